@@ -464,6 +464,7 @@ function SubscribeFromTemplate() {
           body: JSON.stringify({
             intent: payloadIntent,
             signature: submission.signature,
+            chain: selectedChain,
           }),
         },
       );
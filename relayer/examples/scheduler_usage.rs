@@ -1,6 +1,6 @@
 use relayer::{
     AvailClient, BlockchainClient, Config, Database, HyperSyncClient, Metrics, Scheduler,
-    SchedulerContext,
+    SchedulerContext, SchedulerSettings, SchedulerSettingsHandle,
 };
 use std::sync::Arc;
 
@@ -16,12 +16,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let queries = Arc::new(database.queries());
     let pool = database.expect_pool().clone(); // get pool for distributed locking
 
-    let blockchain_client = Arc::new(BlockchainClient::new(&config).await?);
+    let blockchain_client =
+        Arc::new(BlockchainClient::with_queries(&config, database.queries()).await?);
+    blockchain_client.reconcile_pending_transactions().await?;
     let avail_client = Arc::new(AvailClient::new(&config).await?);
     let metrics = Arc::new(Metrics::new());
 
-    let hypersync_client = if let Some((sepolia_url, base_url)) = config.hypersync_urls() {
-        match HyperSyncClient::new(sepolia_url.to_string(), base_url.to_string()) {
+    let hypersync_client = if config.hypersync_enabled() {
+        match HyperSyncClient::new(&config.chains) {
             Ok(client) => Some(Arc::new(client)),
             Err(err) => {
                 eprintln!("failed to initialise HyperSync client: {err}");
@@ -40,6 +42,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         metrics,
         config: config.clone(),
         pool: pool.clone(),
+        settings: SchedulerSettingsHandle::new(SchedulerSettings::from_config(&config)),
     };
 
     let mut scheduler = Scheduler::new(scheduler_context).await?;
@@ -0,0 +1,4 @@
+-- chain names come from the configurable chain registry, not just sepolia/base
+ALTER TABLE subscriptions ALTER COLUMN chain TYPE VARCHAR(64);
+ALTER TABLE executions ALTER COLUMN chain TYPE VARCHAR(64);
+ALTER TABLE intent_cache ALTER COLUMN chain TYPE VARCHAR(64);
@@ -0,0 +1,19 @@
+-- on-chain operations the relayer submitted on a subscriber's behalf and is waiting to confirm
+CREATE TABLE IF NOT EXISTS pending_operations (
+    id BIGSERIAL PRIMARY KEY,
+    subscription_id VARCHAR(66) NOT NULL REFERENCES subscriptions(id),
+    operation VARCHAR(32) NOT NULL,
+    chain VARCHAR(64) NOT NULL,
+    transaction_hash VARCHAR(66) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    error_message TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_operations_open
+    ON pending_operations (created_at)
+    WHERE status = 'PENDING';
+
+CREATE INDEX IF NOT EXISTS idx_pending_operations_subscription_id
+    ON pending_operations (subscription_id);
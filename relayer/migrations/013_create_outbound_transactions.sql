@@ -0,0 +1,36 @@
+-- every transaction the relayer signs, persisted before broadcast so restarts can reconcile
+CREATE TABLE IF NOT EXISTS outbound_transactions (
+    id BIGSERIAL PRIMARY KEY,
+    chain VARCHAR(64) NOT NULL,
+    from_address VARCHAR(42) NOT NULL,
+    to_address VARCHAR(42) NOT NULL,
+    nonce BIGINT NOT NULL,
+    calldata TEXT NOT NULL,
+    value TEXT NOT NULL DEFAULT '0',
+    gas_limit TEXT NOT NULL,
+    max_fee_per_gas TEXT NOT NULL,
+    max_priority_fee_per_gas TEXT NOT NULL,
+    transaction_hash VARCHAR(66) NOT NULL,
+    -- hashes of earlier attempts at the same nonce that were replaced by fee bumps
+    replaced_hashes TEXT[] NOT NULL DEFAULT '{}',
+    attempts INTEGER NOT NULL DEFAULT 1,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    purpose VARCHAR(64) NOT NULL,
+    reference VARCHAR(66) NULL,
+    block_number BIGINT NULL,
+    error_message TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- a nonce can only be live once per sender; failed broadcasts free it up again
+CREATE UNIQUE INDEX IF NOT EXISTS idx_outbound_transactions_live_nonce
+    ON outbound_transactions (chain, from_address, nonce)
+    WHERE status <> 'FAILED';
+
+CREATE INDEX IF NOT EXISTS idx_outbound_transactions_pending
+    ON outbound_transactions (chain, nonce)
+    WHERE status = 'PENDING';
+
+CREATE INDEX IF NOT EXISTS idx_outbound_transactions_hash
+    ON outbound_transactions (transaction_hash);
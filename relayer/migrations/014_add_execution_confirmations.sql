@@ -0,0 +1,15 @@
+-- executions are recorded as SUCCESS when first mined, then become CONFIRMED once buried
+-- under the chain's confirmation depth or ORPHANED if a reorg drops the transaction
+ALTER TABLE executions
+    ADD COLUMN IF NOT EXISTS block_hash VARCHAR(66),
+    ADD COLUMN IF NOT EXISTS confirmed_at TIMESTAMPTZ;
+
+-- an orphaned payment frees its payment number so it can be executed again
+DROP INDEX IF EXISTS idx_executions_unique_payment;
+CREATE UNIQUE INDEX idx_executions_unique_payment
+    ON executions (subscription_id, payment_number)
+    WHERE status IN ('SUCCESS', 'CONFIRMED');
+
+CREATE INDEX IF NOT EXISTS idx_executions_unconfirmed
+    ON executions (chain, block_number)
+    WHERE status = 'SUCCESS';
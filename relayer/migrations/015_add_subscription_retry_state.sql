@@ -0,0 +1,11 @@
+-- failed payments are retried on a per-class backoff instead of every scheduler cycle;
+-- subscriptions that keep failing move to DEAD_LETTER with the last error kept for operators
+ALTER TABLE subscriptions
+    ADD COLUMN IF NOT EXISTS next_retry_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS last_failure_class VARCHAR(32),
+    ADD COLUMN IF NOT EXISTS last_error TEXT,
+    ADD COLUMN IF NOT EXISTS dead_lettered_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_dead_letter
+    ON subscriptions (dead_lettered_at DESC, id)
+    WHERE status = 'DEAD_LETTER';
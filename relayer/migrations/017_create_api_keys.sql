@@ -0,0 +1,22 @@
+-- api keys are stored as sha-256 digests; the plaintext is only shown once on creation
+CREATE TABLE IF NOT EXISTS api_keys (
+    id BIGSERIAL PRIMARY KEY,
+    key_hash CHAR(64) NOT NULL UNIQUE,
+    -- first characters of the key so operators can tell keys apart
+    key_prefix VARCHAR(16) NOT NULL,
+    role VARCHAR(20) NOT NULL,
+    -- set for MERCHANT keys, NULL for ADMIN keys
+    merchant VARCHAR(42) NULL,
+    label VARCHAR(128) NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ NULL,
+    revoked_at TIMESTAMPTZ NULL,
+    CONSTRAINT api_keys_role_scope CHECK (
+        (role = 'MERCHANT' AND merchant IS NOT NULL)
+        OR (role = 'ADMIN' AND merchant IS NULL)
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_merchant
+    ON api_keys (merchant)
+    WHERE revoked_at IS NULL;
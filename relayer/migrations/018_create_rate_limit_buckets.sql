@@ -0,0 +1,11 @@
+-- shared token buckets for RATE_LIMIT_BACKEND=postgres, one row per scope and client
+CREATE TABLE IF NOT EXISTS rate_limit_buckets (
+    bucket_key VARCHAR(160) PRIMARY KEY,
+    tokens DOUBLE PRECISION NOT NULL,
+    -- whether the last request took a token; the upsert can only return the new row
+    last_allowed BOOLEAN NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_buckets_updated_at
+    ON rate_limit_buckets (updated_at);
@@ -0,0 +1,18 @@
+-- operators can hold a subscription back from the scheduler without touching its on-chain state
+ALTER TABLE subscriptions
+    ADD COLUMN IF NOT EXISTS relayer_paused_at TIMESTAMPTZ;
+
+-- one row per action taken through the admin api
+CREATE TABLE IF NOT EXISTS admin_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    -- "admin-token" for ADMIN_API_TOKEN, "api-key:<id>" for stored admin keys
+    actor VARCHAR(64) NOT NULL,
+    action VARCHAR(64) NOT NULL,
+    -- subscription id or chain name, depending on the action
+    target VARCHAR(128) NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_audit_log_created_at
+    ON admin_audit_log (created_at DESC, id DESC);
@@ -0,0 +1,20 @@
+-- outbox of intents waiting to be posted to Avail; the intent endpoint responds before submission
+CREATE TABLE IF NOT EXISTS avail_submissions (
+    id BIGSERIAL PRIMARY KEY,
+    subscription_id VARCHAR(66) NOT NULL UNIQUE REFERENCES subscriptions(id) ON DELETE CASCADE,
+    -- the AvailIntent exactly as it will be submitted
+    payload JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error TEXT NULL,
+    block_number BIGINT NULL,
+    extrinsic_index BIGINT NULL,
+    submitted_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_avail_submissions_due
+    ON avail_submissions (next_attempt_at, id)
+    WHERE status = 'PENDING';
@@ -0,0 +1,19 @@
+-- intents are anchored to Avail in batches: one extrinsic carries a merkle root plus every leaf
+CREATE TABLE IF NOT EXISTS avail_batches (
+    id BIGSERIAL PRIMARY KEY,
+    merkle_root VARCHAR(66) NOT NULL,
+    leaf_count INTEGER NOT NULL,
+    block_number BIGINT NOT NULL,
+    extrinsic_index BIGINT NOT NULL,
+    submitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_avail_batches_reference
+    ON avail_batches (block_number, extrinsic_index);
+
+-- where each intent sits in its batch; proof steps are [{ "position": "left" | "right", "hash": "0x.." }]
+ALTER TABLE avail_submissions
+    ADD COLUMN IF NOT EXISTS batch_id BIGINT NULL REFERENCES avail_batches(id),
+    ADD COLUMN IF NOT EXISTS leaf_index INTEGER NULL,
+    ADD COLUMN IF NOT EXISTS merkle_proof JSONB NULL;
@@ -0,0 +1,4 @@
+-- consecutive transient chain errors (rpc outages, dropped transactions) are counted apart from
+-- failure_count, so an outage can't use up the dead-letter budget meant for subscriber problems
+ALTER TABLE subscriptions
+    ADD COLUMN IF NOT EXISTS chain_error_count BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,239 @@
+use axum::{
+    body::Body,
+    extract::{MatchedPath, State},
+    http::{header::AUTHORIZATION, HeaderMap, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use ethers::core::rand::{thread_rng, RngCore};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::database::models::ApiKeyRole;
+use crate::{AppState, RelayerError, Result};
+
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+const ADMIN_ROUTE_PREFIX: &str = "/api/v1/admin/";
+const MERCHANT_ROUTE_PREFIX: &str = "/api/v1/merchant/:address";
+// characters of the key kept in plaintext so operators can tell keys apart
+const KEY_PREFIX_LENGTH: usize = 11;
+
+// who the presented credential belongs to; inserted into request extensions for handlers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Principal {
+    Admin,
+    // lowercase merchant address
+    Merchant(String),
+}
+
+// the credential behind a request, as written to the admin audit log:
+// "admin-token" for ADMIN_API_TOKEN, "api-key:<id>" for stored keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Actor(pub String);
+
+impl Principal {
+    pub fn can_access_merchant(&self, merchant: &str) -> bool {
+        match self {
+            Principal::Admin => true,
+            Principal::Merchant(scope) => scope.eq_ignore_ascii_case(merchant),
+        }
+    }
+}
+
+pub fn generate_api_key() -> String {
+    let mut bytes = [0u8; 32];
+    thread_rng().fill_bytes(&mut bytes);
+    format!("ak_{}", hex::encode(bytes))
+}
+
+// keys carry 256 bits of entropy, so a plain digest is enough; no salt or slow hash needed
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+pub fn key_prefix(key: &str) -> String {
+    key.chars().take(KEY_PREFIX_LENGTH).collect()
+}
+
+// X-API-Key, or a bearer token for clients that only set Authorization
+fn presented_credential(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| {
+            headers
+                .get(AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+        })
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+async fn resolve_principal(app_state: &AppState, credential: &str) -> Result<(Principal, Actor)> {
+    // the bootstrap token from ADMIN_API_TOKEN; digests compared so timing doesn't leak it
+    if let Some(admin_token) = app_state.config.admin_api_token.as_deref() {
+        if Sha256::digest(credential.as_bytes()) == Sha256::digest(admin_token.as_bytes()) {
+            return Ok((Principal::Admin, Actor("admin-token".to_string())));
+        }
+    }
+
+    let key = app_state
+        .database
+        .queries()
+        .authenticate_api_key(&hash_api_key(credential))
+        .await?
+        .ok_or_else(|| RelayerError::Unauthorized("invalid api key".to_string()))?;
+
+    let principal = match (ApiKeyRole::parse(&key.role), key.merchant) {
+        (Some(ApiKeyRole::Admin), _) => Principal::Admin,
+        (Some(ApiKeyRole::Merchant), Some(merchant)) => Principal::Merchant(merchant),
+        _ => {
+            return Err(RelayerError::InternalError(format!(
+                "api key {} has an invalid role or scope",
+                key.id
+            )))
+        }
+    };
+    Ok((principal, Actor(format!("api-key:{}", key.id))))
+}
+
+// who may call a route; authorize enforces it and the OpenAPI document describes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteAccess {
+    Public,
+    // the merchant's own key, or an admin key
+    Merchant,
+    Admin,
+}
+
+// route is the matched axum path, e.g. "/api/v1/merchant/:address/stats"
+pub fn route_access(route: &str) -> RouteAccess {
+    if route.starts_with(ADMIN_ROUTE_PREFIX) {
+        RouteAccess::Admin
+    } else if route.starts_with(MERCHANT_ROUTE_PREFIX) {
+        RouteAccess::Merchant
+    } else {
+        RouteAccess::Public
+    }
+}
+
+fn authorize(route: &str, path: &str, principal: Option<&Principal>) -> Result<()> {
+    match route_access(route) {
+        RouteAccess::Admin => match principal {
+            Some(Principal::Admin) => Ok(()),
+            Some(Principal::Merchant(_)) => Err(RelayerError::Forbidden(
+                "admin routes need an admin key".to_string(),
+            )),
+            None => Err(RelayerError::Unauthorized(
+                "missing admin credentials".to_string(),
+            )),
+        },
+        RouteAccess::Merchant => {
+            // "/api/v1/merchant/<address>/..." -> the fifth segment
+            let merchant = path.split('/').nth(4).unwrap_or_default();
+            match principal {
+                Some(principal) if principal.can_access_merchant(merchant) => Ok(()),
+                Some(_) => Err(RelayerError::Forbidden(
+                    "api key is not scoped to this merchant".to_string(),
+                )),
+                None => Err(RelayerError::Unauthorized("missing api key".to_string())),
+            }
+        }
+        RouteAccess::Public => Ok(()),
+    }
+}
+
+// resolves the caller on every route (a bad key is rejected even where none is needed),
+// then requires an admin key for /api/v1/admin/* and the merchant's own key for its routes
+pub async fn authenticate(
+    State(app_state): State<Arc<AppState>>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_default();
+
+    let resolved = match presented_credential(req.headers()) {
+        Some(credential) => match resolve_principal(&app_state, credential).await {
+            Ok(resolved) => Some(resolved),
+            Err(e) => {
+                warn!("rejected request to {} with invalid credentials", route);
+                return e.into_response();
+            }
+        },
+        None => None,
+    };
+
+    let principal = resolved.as_ref().map(|(principal, _)| principal);
+    if let Err(e) = authorize(&route, req.uri().path(), principal) {
+        warn!("denied request to {}: {}", route, e);
+        return e.into_response();
+    }
+
+    if let Some((principal, actor)) = resolved {
+        req.extensions_mut().insert(principal);
+        req.extensions_mut().insert(actor);
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MERCHANT: &str = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+
+    #[test]
+    fn test_merchant_routes_are_scoped_to_the_key_merchant() {
+        let route = "/api/v1/merchant/:address/stats";
+        let own = Principal::Merchant(MERCHANT.to_string());
+        let other = Principal::Merchant("0x1234567890123456789012345678901234567890".to_string());
+        // path casing doesn't matter, addresses are compared case-insensitively
+        let path = "/api/v1/merchant/0xAbCdEfabcdefabcdefabcdefabcdefabcdefabcd/stats";
+
+        assert!(authorize(route, path, Some(&own)).is_ok());
+        assert!(authorize(route, path, Some(&Principal::Admin)).is_ok());
+        assert!(matches!(
+            authorize(route, path, Some(&other)),
+            Err(RelayerError::Forbidden(_))
+        ));
+        assert!(matches!(
+            authorize(route, path, None),
+            Err(RelayerError::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    fn test_admin_routes_need_an_admin_principal() {
+        let route = "/api/v1/admin/api-keys";
+        let path = "/api/v1/admin/api-keys";
+
+        assert!(authorize(route, path, Some(&Principal::Admin)).is_ok());
+        assert!(matches!(
+            authorize(
+                route,
+                path,
+                Some(&Principal::Merchant(MERCHANT.to_string()))
+            ),
+            Err(RelayerError::Forbidden(_))
+        ));
+        assert!(authorize("/health", "/health", None).is_ok());
+        assert!(authorize("/api/v1/intent", "/api/v1/intent", None).is_ok());
+    }
+
+    #[test]
+    fn test_generated_keys_hash_deterministically() {
+        let key = generate_api_key();
+        assert!(key.starts_with("ak_"));
+        assert_eq!(key.len(), 67);
+        assert_eq!(hash_api_key(&key), hash_api_key(&key));
+        assert_ne!(hash_api_key(&key), hash_api_key(&generate_api_key()));
+        assert_eq!(key_prefix(&key).len(), KEY_PREFIX_LENGTH);
+    }
+}
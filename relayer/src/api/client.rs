@@ -0,0 +1,476 @@
+use reqwest::{header::RETRY_AFTER, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+use super::auth::API_KEY_HEADER;
+use super::handlers::{
+    ApiKeyQueryParams, AuditLogQueryParams, DeadLetterQueryParams, ExportQueryParams,
+    SubscriberSubscriptionQueryParams, TransactionQueryParams, WebhookDeliveryQueryParams,
+};
+use super::types::{
+    AdminAuditLogResponse, AdminSubscriptionResponse, ApiKeyListResponse, ApiKeyResponse,
+    CreateApiKeyRequest, CrossChainAttestationResponse, DeadLetterListResponse, ErrorResponse,
+    HealthResponse, HyperSyncResyncRequest, HyperSyncResyncResponse, InclusionProofResponse,
+    MerchantStatsResponse, MerchantTransactionsResponse, RegisterWebhookRequest, RequeueResponse,
+    SchedulerLocksResponse, StatusResponse, SubmitIntentRequest, SubmitIntentResponse,
+    SubscriberSubscriptionsResponse, SubscriptionActionRequest, SubscriptionActionResponse,
+    SubscriptionProofResponse, SubscriptionResponse, TriggerCycleRequest, TriggerCycleResponse,
+    WebhookDeliveriesResponse, WebhookListResponse, WebhookResponse,
+};
+use crate::metrics::MetricsSnapshot;
+use crate::scheduler::{SchedulerSettings, SchedulerSettingsUpdate};
+use crate::{RelayerError, Result};
+
+// typed client for the REST API, built on the same request and response types as the server
+#[derive(Debug, Clone)]
+pub struct RelayerApiClient {
+    base_url: String,
+    api_key: Option<String>,
+    http: reqwest::Client,
+}
+
+impl RelayerApiClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    // sent as X-API-Key on every request
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub async fn submit_intent(
+        &self,
+        request: &SubmitIntentRequest,
+    ) -> Result<SubmitIntentResponse> {
+        self.send(self.http.post(self.url("/api/v1/intent")).json(request))
+            .await
+    }
+
+    pub async fn get_subscription(&self, id: &str) -> Result<SubscriptionResponse> {
+        self.send(
+            self.http
+                .get(self.url(&format!("/api/v1/subscription/{}", id))),
+        )
+        .await
+    }
+
+    pub async fn get_subscription_proof(&self, id: &str) -> Result<SubscriptionProofResponse> {
+        self.send(
+            self.http
+                .get(self.url(&format!("/api/v1/subscription/{}/proof", id))),
+        )
+        .await
+    }
+
+    pub async fn get_inclusion_proof(&self, id: &str) -> Result<InclusionProofResponse> {
+        self.send(
+            self.http
+                .get(self.url(&format!("/api/v1/subscription/{}/inclusion-proof", id))),
+        )
+        .await
+    }
+
+    pub async fn pause_subscription(
+        &self,
+        id: &str,
+        request: &SubscriptionActionRequest,
+    ) -> Result<SubscriptionActionResponse> {
+        self.send(
+            self.http
+                .post(self.url(&format!("/api/v1/subscription/{}/pause", id)))
+                .json(request),
+        )
+        .await
+    }
+
+    pub async fn resume_subscription(
+        &self,
+        id: &str,
+        request: &SubscriptionActionRequest,
+    ) -> Result<SubscriptionActionResponse> {
+        self.send(
+            self.http
+                .post(self.url(&format!("/api/v1/subscription/{}/resume", id)))
+                .json(request),
+        )
+        .await
+    }
+
+    pub async fn get_subscriber_subscriptions(
+        &self,
+        subscriber: &str,
+        params: &SubscriberSubscriptionQueryParams,
+    ) -> Result<SubscriberSubscriptionsResponse> {
+        self.send(
+            self.http
+                .get(self.url(&format!("/api/v1/subscriber/{}/subscriptions", subscriber)))
+                .query(params),
+        )
+        .await
+    }
+
+    pub async fn get_merchant_transactions(
+        &self,
+        merchant: &str,
+        params: &TransactionQueryParams,
+    ) -> Result<MerchantTransactionsResponse> {
+        self.send(
+            self.http
+                .get(self.url(&format!("/api/v1/merchant/{}/transactions", merchant)))
+                .query(params),
+        )
+        .await
+    }
+
+    // the raw csv or json lines body, as the server streams it
+    pub async fn export_merchant_settlements(
+        &self,
+        merchant: &str,
+        params: &ExportQueryParams,
+    ) -> Result<String> {
+        self.send_text(
+            self.http
+                .get(self.url(&format!("/api/v1/merchant/{}/export", merchant)))
+                .query(params),
+        )
+        .await
+    }
+
+    pub async fn get_merchant_stats(&self, merchant: &str) -> Result<MerchantStatsResponse> {
+        self.send(
+            self.http
+                .get(self.url(&format!("/api/v1/merchant/{}/stats", merchant))),
+        )
+        .await
+    }
+
+    pub async fn get_cross_chain_attestations(
+        &self,
+        subscription_id: &str,
+    ) -> Result<Vec<CrossChainAttestationResponse>> {
+        self.send(
+            self.http
+                .get(self.url(&format!("/api/v1/cross-chain/{}", subscription_id))),
+        )
+        .await
+    }
+
+    pub async fn list_webhooks(&self, merchant: &str) -> Result<WebhookListResponse> {
+        self.send(
+            self.http
+                .get(self.url(&format!("/api/v1/merchant/{}/webhooks", merchant))),
+        )
+        .await
+    }
+
+    pub async fn register_webhook(
+        &self,
+        merchant: &str,
+        request: &RegisterWebhookRequest,
+    ) -> Result<WebhookResponse> {
+        self.send(
+            self.http
+                .post(self.url(&format!("/api/v1/merchant/{}/webhooks", merchant)))
+                .json(request),
+        )
+        .await
+    }
+
+    pub async fn delete_webhook(&self, merchant: &str, id: i64) -> Result<WebhookListResponse> {
+        self.send(
+            self.http
+                .delete(self.url(&format!("/api/v1/merchant/{}/webhooks/{}", merchant, id))),
+        )
+        .await
+    }
+
+    pub async fn list_webhook_deliveries(
+        &self,
+        merchant: &str,
+        params: &WebhookDeliveryQueryParams,
+    ) -> Result<WebhookDeliveriesResponse> {
+        self.send(
+            self.http
+                .get(self.url(&format!(
+                    "/api/v1/merchant/{}/webhooks/deliveries",
+                    merchant
+                )))
+                .query(params),
+        )
+        .await
+    }
+
+    pub async fn get_scheduler_settings(&self) -> Result<SchedulerSettings> {
+        self.send(self.http.get(self.url("/api/v1/admin/scheduler")))
+            .await
+    }
+
+    pub async fn update_scheduler_settings(
+        &self,
+        update: &SchedulerSettingsUpdate,
+    ) -> Result<SchedulerSettings> {
+        self.send(
+            self.http
+                .put(self.url("/api/v1/admin/scheduler"))
+                .json(update),
+        )
+        .await
+    }
+
+    pub async fn trigger_scheduler_cycle(
+        &self,
+        request: &TriggerCycleRequest,
+    ) -> Result<TriggerCycleResponse> {
+        self.send(
+            self.http
+                .post(self.url("/api/v1/admin/scheduler/run"))
+                .json(request),
+        )
+        .await
+    }
+
+    pub async fn get_scheduler_locks(&self) -> Result<SchedulerLocksResponse> {
+        self.send(self.http.get(self.url("/api/v1/admin/scheduler/locks")))
+            .await
+    }
+
+    pub async fn admin_sync_subscription(&self, id: &str) -> Result<AdminSubscriptionResponse> {
+        self.admin_subscription_action(id, "sync").await
+    }
+
+    pub async fn admin_reset_failures(&self, id: &str) -> Result<AdminSubscriptionResponse> {
+        self.admin_subscription_action(id, "reset-failures").await
+    }
+
+    pub async fn admin_pause_subscription(&self, id: &str) -> Result<AdminSubscriptionResponse> {
+        self.admin_subscription_action(id, "pause").await
+    }
+
+    pub async fn admin_resume_subscription(&self, id: &str) -> Result<AdminSubscriptionResponse> {
+        self.admin_subscription_action(id, "resume").await
+    }
+
+    async fn admin_subscription_action(
+        &self,
+        id: &str,
+        action: &str,
+    ) -> Result<AdminSubscriptionResponse> {
+        self.send(
+            self.http
+                .post(self.url(&format!("/api/v1/admin/subscriptions/{}/{}", id, action))),
+        )
+        .await
+    }
+
+    pub async fn hypersync_resync(
+        &self,
+        request: &HyperSyncResyncRequest,
+    ) -> Result<HyperSyncResyncResponse> {
+        self.send(
+            self.http
+                .post(self.url("/api/v1/admin/hypersync/resync"))
+                .json(request),
+        )
+        .await
+    }
+
+    pub async fn list_dead_letter(
+        &self,
+        params: &DeadLetterQueryParams,
+    ) -> Result<DeadLetterListResponse> {
+        self.send(
+            self.http
+                .get(self.url("/api/v1/admin/dead-letter"))
+                .query(params),
+        )
+        .await
+    }
+
+    pub async fn requeue_dead_letter(&self, id: &str) -> Result<RequeueResponse> {
+        self.send(
+            self.http
+                .post(self.url(&format!("/api/v1/admin/dead-letter/{}/requeue", id))),
+        )
+        .await
+    }
+
+    pub async fn list_api_keys(&self, params: &ApiKeyQueryParams) -> Result<ApiKeyListResponse> {
+        self.send(
+            self.http
+                .get(self.url("/api/v1/admin/api-keys"))
+                .query(params),
+        )
+        .await
+    }
+
+    pub async fn create_api_key(&self, request: &CreateApiKeyRequest) -> Result<ApiKeyResponse> {
+        self.send(
+            self.http
+                .post(self.url("/api/v1/admin/api-keys"))
+                .json(request),
+        )
+        .await
+    }
+
+    pub async fn revoke_api_key(&self, id: i64) -> Result<ApiKeyListResponse> {
+        self.send(
+            self.http
+                .delete(self.url(&format!("/api/v1/admin/api-keys/{}", id))),
+        )
+        .await
+    }
+
+    pub async fn list_admin_audit_log(
+        &self,
+        params: &AuditLogQueryParams,
+    ) -> Result<AdminAuditLogResponse> {
+        self.send(
+            self.http
+                .get(self.url("/api/v1/admin/audit-log"))
+                .query(params),
+        )
+        .await
+    }
+
+    pub async fn health(&self) -> Result<HealthResponse> {
+        self.send(self.http.get(self.url("/health"))).await
+    }
+
+    pub async fn status(&self) -> Result<StatusResponse> {
+        self.send(self.http.get(self.url("/status"))).await
+    }
+
+    // prometheus text exposition
+    pub async fn metrics(&self) -> Result<String> {
+        self.send_text(self.http.get(self.url("/metrics"))).await
+    }
+
+    pub async fn metrics_json(&self) -> Result<MetricsSnapshot> {
+        self.send(self.http.get(self.url("/metrics.json"))).await
+    }
+
+    // the html viewer for the OpenAPI document
+    pub async fn api_documentation(&self) -> Result<String> {
+        self.send_text(self.http.get(self.url("/api/v1/docs")))
+            .await
+    }
+
+    pub async fn openapi_document(&self) -> Result<serde_json::Value> {
+        self.send(self.http.get(self.url(super::openapi::OPENAPI_ROUTE)))
+            .await
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        self.execute(request).await?.json().await.map_err(|e| {
+            RelayerError::InternalError(format!("invalid relayer api response: {}", e))
+        })
+    }
+
+    async fn send_text(&self, request: RequestBuilder) -> Result<String> {
+        self.execute(request).await?.text().await.map_err(|e| {
+            RelayerError::InternalError(format!("invalid relayer api response: {}", e))
+        })
+    }
+
+    async fn execute(&self, request: RequestBuilder) -> Result<Response> {
+        let request = match &self.api_key {
+            Some(api_key) => request.header(API_KEY_HEADER, api_key),
+            None => request,
+        };
+        let response = request.send().await.map_err(|e| {
+            RelayerError::InternalError(format!("relayer api request failed: {}", e))
+        })?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs);
+        let body = response.text().await.unwrap_or_default();
+        Err(api_error(status, &body, retry_after))
+    }
+}
+
+// maps the server's { error, code, details } body back onto the RelayerError it came from
+fn api_error(status: StatusCode, body: &str, retry_after: Option<Duration>) -> RelayerError {
+    let Ok(ErrorResponse {
+        error,
+        code,
+        details,
+    }) = serde_json::from_str::<ErrorResponse>(body)
+    else {
+        return RelayerError::InternalError(format!("relayer api responded {}: {}", status, body));
+    };
+    let details = details.unwrap_or_default();
+
+    let text = |key: &str| details[key].as_str().unwrap_or_default().to_string();
+    let list = |key: &str| -> Vec<String> {
+        details[key]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    match code.as_str() {
+        "VALIDATION_ERROR" => RelayerError::Validation(error),
+        "INVALID_FIELD" => RelayerError::InvalidField {
+            field: text("field"),
+            reason: error,
+        },
+        "INVALID_SIGNATURE" => RelayerError::InvalidSignature(error),
+        "SIGNATURE_MISMATCH" => RelayerError::SignatureMismatch {
+            expected: text("expected"),
+            recovered: text("recovered"),
+        },
+        "NONCE_ALREADY_USED" => RelayerError::NonceAlreadyUsed {
+            subscriber: text("subscriber"),
+            nonce: details["nonce"].as_u64().unwrap_or_default(),
+            chain: text("chain"),
+        },
+        "UNSUPPORTED_CHAIN" => RelayerError::UnsupportedChain {
+            requested: text("chain"),
+            supported: list("supportedChains"),
+        },
+        "UNSUPPORTED_TOKEN" => RelayerError::UnsupportedToken {
+            token: text("token"),
+            chain: text("chain"),
+            supported: list("supportedTokens"),
+        },
+        "INVALID_SUBSCRIPTION_STATE" => RelayerError::InvalidSubscriptionState {
+            subscription_id: text("subscriptionId"),
+            status: text("status"),
+            action: text("action"),
+        },
+        "NOT_FOUND" => RelayerError::NotFound(error),
+        "UNAUTHORIZED" => RelayerError::Unauthorized(error),
+        "FORBIDDEN" => RelayerError::Forbidden(error),
+        "DUPLICATE" => RelayerError::Duplicate(error),
+        "SERVICE_UNAVAILABLE" => RelayerError::Unavailable(error),
+        "RATE_LIMITED" => RelayerError::RateLimited(retry_after.unwrap_or(Duration::from_secs(1))),
+        _ => RelayerError::InternalError(format!(
+            "relayer api responded {} {}: {}",
+            status, code, error
+        )),
+    }
+}
@@ -0,0 +1,409 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use ethers::types::U256;
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::openapi;
+use crate::database::models::{ExecutionFilter, MerchantExecution};
+use crate::database::queries::Queries;
+use crate::utils::tokens;
+use crate::Result;
+
+// rows per round trip; every batch is its own short keyset query, so a slow download
+// never pins a pool connection and memory stays bounded by one batch
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "jsonl" | "ndjson" => Some(ExportFormat::Jsonl),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+// one settled payment as finance books it; amounts are in the token's smallest unit
+// except `amount_formatted`, which is `amount_paid` scaled by `token_decimals`
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct SettlementRow {
+    pub execution_id: i64,
+    #[schema(format = DateTime)]
+    pub executed_at: String,
+    pub chain: String,
+    #[schema(value_type = openapi::Hash)]
+    pub subscription_id: String,
+    #[schema(value_type = openapi::Address)]
+    pub subscriber: String,
+    pub payment_number: i64,
+    #[schema(value_type = openapi::TokenAddress)]
+    pub token_address: String,
+    pub token_symbol: String,
+    pub token_decimals: u8,
+    /// gross amount charged to the subscriber
+    #[schema(value_type = openapi::Wei)]
+    pub amount_paid: String,
+    /// amount_paid in whole tokens, e.g. "1.5"
+    pub amount_formatted: String,
+    #[schema(value_type = openapi::Wei)]
+    pub protocol_fee: String,
+    /// amount_paid minus protocol_fee
+    #[schema(value_type = openapi::Wei)]
+    pub merchant_amount: String,
+    #[schema(value_type = openapi::SettledStatus)]
+    pub status: String,
+    #[schema(value_type = openapi::Hash)]
+    pub transaction_hash: String,
+    pub block_number: i64,
+}
+
+// csv column order, kept in step with `SettlementRow::csv_fields`
+const CSV_COLUMNS: [&str; 16] = [
+    "execution_id",
+    "executed_at",
+    "chain",
+    "subscription_id",
+    "subscriber",
+    "payment_number",
+    "token_address",
+    "token_symbol",
+    "token_decimals",
+    "amount_paid",
+    "amount_formatted",
+    "protocol_fee",
+    "merchant_amount",
+    "status",
+    "transaction_hash",
+    "block_number",
+];
+
+impl From<MerchantExecution> for SettlementRow {
+    fn from(
+        MerchantExecution {
+            execution,
+            subscriber,
+        }: MerchantExecution,
+    ) -> Self {
+        let token_address =
+            tokens::normalize_token_address(execution.token_address.as_deref().unwrap_or("0x0"));
+        let token_decimals = tokens::get_token_decimals(&token_address);
+        let amount_formatted = match U256::from_dec_str(&execution.amount_paid) {
+            Ok(amount) => tokens::format_token_amount(amount, token_decimals),
+            Err(_) => String::new(),
+        };
+
+        Self {
+            execution_id: execution.id,
+            executed_at: execution
+                .executed_at
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+            chain: execution.chain,
+            subscription_id: execution.subscription_id,
+            subscriber,
+            payment_number: execution.payment_number,
+            token_symbol: tokens::get_token_symbol(&token_address).to_string(),
+            token_address,
+            token_decimals,
+            amount_paid: execution.amount_paid,
+            amount_formatted,
+            protocol_fee: execution.protocol_fee,
+            merchant_amount: execution.merchant_amount,
+            status: execution.status,
+            transaction_hash: execution.transaction_hash,
+            block_number: execution.block_number,
+        }
+    }
+}
+
+impl SettlementRow {
+    fn csv_fields(&self) -> [String; 16] {
+        [
+            self.execution_id.to_string(),
+            self.executed_at.clone(),
+            self.chain.clone(),
+            self.subscription_id.clone(),
+            self.subscriber.clone(),
+            self.payment_number.to_string(),
+            self.token_address.clone(),
+            self.token_symbol.clone(),
+            self.token_decimals.to_string(),
+            self.amount_paid.clone(),
+            self.amount_formatted.clone(),
+            self.protocol_fee.clone(),
+            self.merchant_amount.clone(),
+            self.status.clone(),
+            self.transaction_hash.clone(),
+            self.block_number.to_string(),
+        ]
+    }
+
+    fn write_line(&self, format: ExportFormat, out: &mut String) {
+        match format {
+            ExportFormat::Csv => push_csv_record(out, self.csv_fields().iter().map(String::as_str)),
+            ExportFormat::Jsonl => {
+                // a plain struct of strings and integers always serializes
+                out.push_str(&serde_json::to_string(self).unwrap_or_default());
+                out.push('\n');
+            }
+        }
+    }
+}
+
+fn push_csv_record<'a>(out: &mut String, fields: impl Iterator<Item = &'a str>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+struct ExportCursor {
+    queries: Queries,
+    merchant: String,
+    filter: ExecutionFilter,
+    format: ExportFormat,
+    batch_size: i64,
+    // fetched up front so a failing first query is still an error response, not a cut-off body
+    pending: Option<Vec<MerchantExecution>>,
+    after: Option<(DateTime<Utc>, i64)>,
+    done: bool,
+}
+
+impl ExportCursor {
+    async fn next_batch(&mut self) -> Result<Vec<MerchantExecution>> {
+        let rows = self
+            .queries
+            .list_merchant_executions(&self.merchant, &self.filter, self.after, 0, self.batch_size)
+            .await?;
+        self.done = (rows.len() as i64) < self.batch_size;
+        if let Some(last) = rows.last() {
+            self.after = Some((last.execution.executed_at, last.execution.id));
+        }
+        Ok(rows)
+    }
+}
+
+// every settled payment of the merchant within `filter`, newest first, as chunks of csv or
+// jsonl text. payments executed after the export started sort above the cursor and are skipped
+pub async fn settlement_stream(
+    queries: Queries,
+    merchant: &str,
+    filter: ExecutionFilter,
+    format: ExportFormat,
+) -> Result<impl Stream<Item = Result<String>>> {
+    settlement_stream_in_batches(queries, merchant, filter, format, EXPORT_BATCH_SIZE).await
+}
+
+async fn settlement_stream_in_batches(
+    queries: Queries,
+    merchant: &str,
+    filter: ExecutionFilter,
+    format: ExportFormat,
+    batch_size: i64,
+) -> Result<impl Stream<Item = Result<String>>> {
+    let mut cursor = ExportCursor {
+        queries,
+        merchant: merchant.to_lowercase(),
+        filter: ExecutionFilter {
+            settled: true,
+            ..filter
+        },
+        format,
+        batch_size,
+        pending: None,
+        after: None,
+        done: false,
+    };
+    cursor.pending = Some(cursor.next_batch().await?);
+
+    let mut header = String::new();
+    if format == ExportFormat::Csv {
+        push_csv_record(&mut header, CSV_COLUMNS.into_iter());
+    }
+
+    let rows = stream::try_unfold(cursor, |mut cursor| async move {
+        let rows = match cursor.pending.take() {
+            Some(rows) => rows,
+            None if cursor.done => return Ok(None),
+            None => cursor.next_batch().await?,
+        };
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        let mut chunk = String::new();
+        for row in rows {
+            SettlementRow::from(row).write_line(cursor.format, &mut chunk);
+        }
+        Ok(Some((chunk, cursor)))
+    });
+
+    Ok(futures::StreamExt::chain(
+        stream::iter((!header.is_empty()).then_some(Ok(header))),
+        rows,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::models::{Execution, Subscription};
+    use futures::TryStreamExt;
+    use std::collections::BTreeSet;
+
+    const MERCHANT: &str = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+
+    async fn seed(queries: &Queries) {
+        let now = Utc::now();
+        queries
+            .insert_subscription(&Subscription {
+                id: format!("0x{:064x}", 1),
+                subscriber: "0x1234567890123456789012345678901234567890".to_string(),
+                merchant: MERCHANT.to_string(),
+                amount: "1500000000000000000".to_string(),
+                interval_seconds: 60,
+                start_time: now,
+                max_payments: 10,
+                max_total_amount: "15000000000000000000".to_string(),
+                expiry: now,
+                nonce: 1,
+                token_address: "0x0000000000000000000000000000000000000000".to_string(),
+                status: "ACTIVE".to_string(),
+                executed_payments: 0,
+                total_paid: "0".to_string(),
+                next_payment_due: now,
+                failure_count: 0,
+                chain: "sepolia".to_string(),
+                created_at: now,
+                updated_at: now,
+                avail_block_number: None,
+                avail_extrinsic_index: None,
+            })
+            .await
+            .unwrap();
+
+        // five payments, two sharing a timestamp across a batch boundary; one failed
+        for (payment, minute, status) in [
+            (1, 1, "CONFIRMED"),
+            (2, 2, "SUCCESS"),
+            (3, 3, "FAILED"),
+            (4, 4, "SUCCESS"),
+            (5, 4, "SUCCESS"),
+            (6, 5, "SUCCESS"),
+        ] {
+            queries
+                .insert_execution(&Execution {
+                    id: 0,
+                    subscription_id: format!("0x{:064x}", 1),
+                    relayer_address: "0x3333333333333333333333333333333333333333".to_string(),
+                    payment_number: payment,
+                    amount_paid: "1500000000000000000".to_string(),
+                    protocol_fee: "15000000000000000".to_string(),
+                    merchant_amount: "1485000000000000000".to_string(),
+                    transaction_hash: format!("0x{:064x}", payment),
+                    block_number: payment,
+                    gas_used: "21000".to_string(),
+                    gas_price: "1".to_string(),
+                    status: status.to_string(),
+                    error_message: None,
+                    executed_at: now + chrono::Duration::minutes(minute),
+                    chain: "sepolia".to_string(),
+                    nexus_attestation_id: None,
+                    nexus_verified: false,
+                    nexus_submitted_at: None,
+                    token_address: None,
+                    block_hash: None,
+                    confirmed_at: None,
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn export(queries: &Queries, format: ExportFormat, batch_size: i64) -> String {
+        let chunks: Vec<String> = settlement_stream_in_batches(
+            queries.clone(),
+            MERCHANT,
+            ExecutionFilter::default(),
+            format,
+            batch_size,
+        )
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+        chunks.concat()
+    }
+
+    #[tokio::test]
+    async fn test_export_streams_settled_payments_in_batches() {
+        let queries = Queries::in_memory();
+        seed(&queries).await;
+
+        let csv = export(&queries, ExportFormat::Csv, 2).await;
+        let lines: Vec<&str> = csv.split("\r\n").filter(|line| !line.is_empty()).collect();
+        assert_eq!(lines[0], CSV_COLUMNS.join(","));
+        let payments: Vec<&str> = lines[1..]
+            .iter()
+            .map(|line| line.split(',').nth(5).unwrap())
+            .collect();
+        // newest first, nothing lost or repeated at batch edges, the failed attempt left out
+        assert_eq!(payments, vec!["6", "5", "4", "2", "1"]);
+        assert!(lines[1].contains(",ETH,18,1500000000000000000,1.5,15000000000000000,"));
+
+        let jsonl = export(&queries, ExportFormat::Jsonl, 500).await;
+        let rows: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 5);
+        let keys: BTreeSet<&str> = rows[0]
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(keys, CSV_COLUMNS.into_iter().collect());
+        assert_eq!(rows[4]["status"], "CONFIRMED");
+
+        // an empty export is just the header
+        let empty = export(&Queries::in_memory(), ExportFormat::Csv, 2).await;
+        assert_eq!(empty.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_csv_fields_are_quoted_when_needed() {
+        let mut out = String::new();
+        push_csv_record(&mut out, ["plain", "a,b", "say \"hi\""].into_iter());
+        assert_eq!(out, "plain,\"a,b\",\"say \"\"hi\"\"\"\r\n");
+    }
+}
@@ -1,30 +1,70 @@
 use axum::{
-    extract::{rejection::JsonRejection, Path, Query, State},
+    body::Body,
+    extract::{rejection::JsonRejection, Extension, Path, Query, State},
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::IntoResponse,
     Json,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use ethers::types::{Address, U256};
-use serde::Deserialize;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, info, warn};
-
+use utoipa::IntoParams;
+
+use super::auth::{self, Actor};
+use super::export::{self, ExportFormat, SettlementRow};
+use super::openapi;
+use super::pagination::{self, Cursor};
+use super::proof;
+use super::rate_limit::ClientIdentity;
 use super::types::*;
-use super::validation::ValidationService;
-use crate::database::models::{IntentCache, Subscription};
-use crate::integrations::envio::TokenStats;
+use super::validation::{SubscriptionAction, ValidationService};
+use crate::avail::{merkle, AvailClientMode};
+use crate::database::models::{
+    AdminAction, ApiKey, ApiKeyRole, AvailStatus, ExecutionFilter, ExecutionStatus, IntentCache,
+    MerchantExecution, MerchantWebhook, PendingOperationKind, Subscription, SubscriptionFilter,
+    SubscriptionStatus, WebhookDeliveryStatus, WebhookEventType,
+};
+use crate::integrations::envio::{self, PaymentHistoryQuery, TokenStats};
 use crate::integrations::hypersync::{HyperSyncClient, RawPaymentEvent};
-use crate::metrics::MetricsSnapshot;
+use crate::metrics::{MetricsSnapshot, PROMETHEUS_CONTENT_TYPE};
+use crate::scheduler::{self, SchedulerSettings, SchedulerSettingsUpdate};
 use crate::utils::tokens;
+use crate::webhooks;
 use crate::{AppState, RelayerError, Result};
 
 // post /api/v1/intent
+#[utoipa::path(
+    post,
+    path = "/api/v1/intent",
+    tag = "subscriptions",
+    summary = "Submit a signed subscription intent",
+    request_body = SubmitIntentRequest,
+    responses(
+        (status = 200, description = "OK", body = SubmitIntentResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 409, response = openapi::Conflict),
+        (status = 429, response = openapi::RateLimited),
+        (status = 500, response = openapi::InternalError),
+        (status = 502, response = openapi::BadGateway),
+    )
+)]
 pub async fn submit_intent_handler(
     State(app_state): State<Arc<AppState>>,
+    client: ClientIdentity,
     payload: std::result::Result<Json<SubmitIntentRequest>, JsonRejection>,
 ) -> Result<Json<SubmitIntentResponse>> {
+    let limits = app_state.rate_limiter.settings();
+    app_state
+        .rate_limiter
+        .check_client("intent", &client, limits.intent_per_client)
+        .await?;
+
     let Json(request) = payload.map_err(|rejection| {
         RelayerError::Validation(format!("invalid request body: {}", rejection))
     })?;
@@ -38,20 +78,35 @@ pub async fn submit_intent_handler(
         .validate_consistency()
         .map_err(|e| RelayerError::Validation(format!("intent validation failed: {}", e)))?;
 
-    let chain = "sepolia";
-    let verifying_contract = Address::from_str(
-        &app_state.config.subscription_manager_address_sepolia,
-    )
-    .map_err(|_| RelayerError::Validation("invalid subscription manager address".to_string()))?;
-    let chain_id = app_state.blockchain_client.chain_id(chain)?;
+    let (chain, chain_id) = resolve_intent_chain(&app_state, request.chain.as_ref())?;
+    let manager_address = app_state
+        .config
+        .subscription_manager_address_for_chain(&chain)
+        .map_err(|e| RelayerError::Validation(e.to_string()))?;
+    let verifying_contract = Address::from_str(manager_address).map_err(|_| {
+        RelayerError::Validation("invalid subscription manager address".to_string())
+    })?;
+    info!("intent targets {} (chain id {})", chain, chain_id);
 
-    ValidationService::validate_intent_signature(
+    let signer = ValidationService::validate_intent_signature(
         &request.intent,
         &request.signature,
         verifying_contract,
         chain_id,
     )?;
 
+    // only after recovery: an unsigned request could otherwise drain any subscriber's bucket
+    if !client.is_exempt() {
+        app_state
+            .rate_limiter
+            .check(
+                "intent",
+                &format!("subscriber:{:?}", signer),
+                limits.intent_per_subscriber,
+            )
+            .await?;
+    }
+
     ValidationService::validate_timing(
         request.intent.start_time,
         request.intent.expiry,
@@ -60,15 +115,16 @@ pub async fn submit_intent_handler(
 
     let supported_tokens = app_state
         .config
-        .supported_tokens_for_chain(chain)
+        .supported_tokens_for_chain(&chain)
         .map_err(|e| RelayerError::Validation(e.to_string()))?;
 
     let token_address = request.intent.token.clone();
     if !supported_tokens.iter().any(|token| token == &token_address) {
-        return Err(RelayerError::Validation(format!(
-            "unsupported token address: {}",
-            token_address
-        )));
+        return Err(RelayerError::UnsupportedToken {
+            token: token_address,
+            chain,
+            supported: supported_tokens.to_vec(),
+        });
     }
 
     let token_symbol = tokens::get_token_symbol(&token_address);
@@ -91,12 +147,18 @@ pub async fn submit_intent_handler(
     if app_state
         .database
         .queries()
-        .is_nonce_used(&request.intent.subscriber, request.intent.nonce as i64)
+        .is_nonce_used(
+            &request.intent.subscriber,
+            request.intent.nonce as i64,
+            &chain,
+        )
         .await?
     {
-        return Err(RelayerError::Validation(
-            "nonce already used by this subscriber (replay attack prevented)".to_string(),
-        ));
+        return Err(RelayerError::NonceAlreadyUsed {
+            subscriber: request.intent.subscriber.clone(),
+            nonce: request.intent.nonce,
+            chain,
+        });
     }
 
     if let Ok(Some(_)) = app_state
@@ -110,20 +172,20 @@ pub async fn submit_intent_handler(
         ));
     }
 
-    let avail_submission = app_state
-        .avail_client
-        .submit_intent(
+    // posted to avail by the outbox worker, which backfills the block and extrinsic
+    let avail_payload = if app_state.avail_client.mode() == AvailClientMode::Disabled {
+        None
+    } else {
+        let payload = app_state.avail_client.intent_payload(
             &request.intent,
             &request.signature,
             &app_state.config.relayer_address,
             chain_id,
-        )
-        .await?;
-
-    info!(
-        "intent submitted to avail - block: {}, extrinsic: {}",
-        avail_submission.block_number, avail_submission.extrinsic_index
-    );
+        );
+        Some(serde_json::to_value(&payload).map_err(|e| {
+            RelayerError::InternalError(format!("failed to serialize avail intent: {}", e))
+        })?)
+    };
 
     let intent_cache = IntentCache {
         id: 0,
@@ -146,17 +208,18 @@ pub async fn submit_intent_handler(
         processed: false,
         created_at: Utc::now(),
         processed_at: None,
-        chain: chain.to_string(),
-        avail_block_number: Some(avail_submission.block_number as i64),
-        avail_extrinsic_index: Some(avail_submission.extrinsic_index as i64),
+        chain: chain.clone(),
+        avail_block_number: None,
+        avail_extrinsic_index: None,
     };
 
-    let intent_id = app_state
-        .database
-        .queries()
-        .cache_intent(&intent_cache)
-        .await?;
-    info!("cached intent with ID: {}", intent_id);
+    // relayed subscriptions only become ACTIVE once the creation receipt confirms
+    let relay_creation = app_state.config.relay_subscription_creation;
+    let initial_status = if relay_creation {
+        SubscriptionStatus::Pending
+    } else {
+        SubscriptionStatus::Active
+    };
 
     let subscription = Subscription {
         id: subscription_id.clone(),
@@ -172,38 +235,145 @@ pub async fn submit_intent_handler(
             .ok_or_else(|| RelayerError::Validation("invalid expiry time".to_string()))?,
         nonce: request.intent.nonce as i64,
         token_address,
-        status: "ACTIVE".to_string(),
+        status: initial_status.to_string(),
         executed_payments: 0,
         total_paid: "0".to_string(),
         next_payment_due: chrono::DateTime::from_timestamp(request.intent.start_time as i64, 0)
             .ok_or_else(|| RelayerError::Validation("invalid start time".to_string()))?,
         failure_count: 0,
-        chain: chain.to_string(),
+        chain: chain.clone(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
-        avail_block_number: Some(avail_submission.block_number as i64),
-        avail_extrinsic_index: Some(avail_submission.extrinsic_index as i64),
+        avail_block_number: None,
+        avail_extrinsic_index: None,
     };
 
-    app_state
+    let avail_submission = app_state
         .database
         .queries()
-        .insert_subscription(&subscription)
+        .insert_accepted_intent(&intent_cache, &subscription, avail_payload.as_ref())
         .await?;
+    info!(
+        "created subscription {}{}",
+        subscription_id,
+        if avail_submission.is_some() {
+            " and queued it for avail submission"
+        } else {
+            ""
+        }
+    );
 
-    info!("successfully created subscription: {}", subscription_id);
+    let creation_tx_hash = if relay_creation {
+        Some(relay_subscription_creation(&app_state, &request, &subscription_id, &chain).await?)
+    } else {
+        None
+    };
 
     let response = SubmitIntentResponse {
         subscription_id,
-        avail_block: avail_submission.block_number,
-        avail_extrinsic: avail_submission.extrinsic_index,
-        status: "ACTIVE".to_string(),
+        avail_block: None,
+        avail_extrinsic: None,
+        avail_status: avail_submission
+            .map(|submission| submission.status)
+            .unwrap_or_else(|| AvailStatus::Disabled.to_string()),
+        status: initial_status.to_string(),
+        creation_tx_hash,
     };
 
     Ok(Json(response))
 }
 
+// submits createSubscription and tracks it until the scheduler sees the receipt
+async fn relay_subscription_creation(
+    app_state: &AppState,
+    request: &SubmitIntentRequest,
+    subscription_id: &str,
+    chain: &str,
+) -> Result<String> {
+    let signature_bytes = hex::decode(&request.signature[2..])
+        .map_err(|_| RelayerError::Validation("invalid signature hex encoding".to_string()))?;
+
+    let tx_hash = match app_state
+        .blockchain_client
+        .create_subscription(&request.intent, signature_bytes.into(), chain)
+        .await
+    {
+        Ok(tx_hash) => format!("{:?}", tx_hash),
+        Err(e) => {
+            warn!(
+                "failed to relay createSubscription for {}: {}",
+                subscription_id, e
+            );
+            // the contract's nonce never advanced, so the rows must go for the subscriber to
+            // be able to retry with the same nonce
+            app_state
+                .database
+                .queries()
+                .discard_unrelayed_subscription(subscription_id)
+                .await?;
+            return Err(e);
+        }
+    };
+
+    app_state
+        .database
+        .queries()
+        .insert_pending_operation(
+            subscription_id,
+            PendingOperationKind::CreateSubscription,
+            chain,
+            &tx_hash,
+        )
+        .await?;
+
+    info!(
+        "createSubscription for {} pending in tx {}",
+        subscription_id, tx_hash
+    );
+    Ok(tx_hash)
+}
+
+// resolves the requested chain (name or chain id) to a configured chain name and id
+fn resolve_intent_chain(
+    app_state: &AppState,
+    selector: Option<&ChainSelector>,
+) -> Result<(String, u64)> {
+    let registry = &app_state.config.chains;
+    let chain = match selector {
+        None => registry.default_chain(),
+        Some(ChainSelector::Id(chain_id)) => registry.get(*chain_id),
+        Some(ChainSelector::Name(name)) => registry.resolve(name),
+    }
+    .ok_or_else(|| match selector {
+        Some(ChainSelector::Id(chain_id)) => RelayerError::UnsupportedChain {
+            requested: chain_id.to_string(),
+            supported: registry.names(),
+        },
+        Some(ChainSelector::Name(name)) => RelayerError::UnsupportedChain {
+            requested: name.trim().to_string(),
+            supported: registry.names(),
+        },
+        None => RelayerError::InternalError("no chains configured".to_string()),
+    })?;
+
+    let chain_id = app_state.blockchain_client.chain_id(&chain.name)?;
+    Ok((chain.name.clone(), chain_id))
+}
+
 // get /api/v1/subscription/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/subscription/{id}",
+    tag = "subscriptions",
+    summary = "Get a subscription with its on-chain state",
+    params(("id" = openapi::Hash, Path)),
+    responses(
+        (status = 200, description = "OK", body = SubscriptionResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 404, response = openapi::NotFound),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
 pub async fn get_subscription_handler(
     Path(subscription_id): Path<String>,
     State(app_state): State<Arc<AppState>>,
@@ -250,6 +420,19 @@ pub async fn get_subscription_handler(
 
     let token_symbol = tokens::get_token_symbol(&subscription.token_address).to_string();
 
+    // intents accepted before the outbox existed were submitted inline and have no row
+    let avail_status = match app_state
+        .database
+        .queries()
+        .get_avail_submission(&subscription.id)
+        .await?
+    {
+        Some(submission) => Some(submission.status),
+        None => subscription
+            .avail_block_number
+            .map(|_| AvailStatus::Submitted.to_string()),
+    };
+
     let response = SubscriptionResponse {
         id: subscription.id,
         subscriber: subscription.subscriber,
@@ -276,365 +459,1447 @@ pub async fn get_subscription_handler(
         contract_address,
         avail_block: subscription.avail_block_number.map(|v| v as u64),
         avail_extrinsic: subscription.avail_extrinsic_index.map(|v| v as u64),
+        avail_status,
     };
 
     info!("successfully retrieved subscription: {}", subscription_id);
     Ok(Json(response))
 }
 
-// query parameters for transactions endpoint
-#[derive(Debug, Deserialize)]
-pub struct TransactionQueryParams {
-    page: Option<u32>,
-    size: Option<u32>,
-    #[serde(default)]
-    use_hypersync: Option<bool>,
-    #[serde(default)]
-    from_block: Option<u64>,
-    #[serde(default)]
-    to_block: Option<u64>,
-    #[serde(default)]
-    chain: Option<String>,
-}
-
-// get /api/v1/merchant/:address/transactions
-pub async fn get_merchant_transactions_handler(
-    Path(merchant_address): Path<String>,
-    Query(params): Query<TransactionQueryParams>,
+// get /api/v1/subscription/:id/proof
+#[utoipa::path(
+    get,
+    path = "/api/v1/subscription/{id}/proof",
+    tag = "subscriptions",
+    summary = "Check the stored subscription against the intent anchored on Avail",
+    params(("id" = openapi::Hash, Path)),
+    responses(
+        (status = 200, description = "OK", body = SubscriptionProofResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 404, response = openapi::NotFound),
+        (status = 500, response = openapi::InternalError),
+        (status = 502, response = openapi::BadGateway),
+        (status = 503, response = openapi::ServiceUnavailable),
+    )
+)]
+pub async fn get_subscription_proof_handler(
+    Path(subscription_id): Path<String>,
     State(app_state): State<Arc<AppState>>,
-) -> Result<Json<MerchantTransactionsResponse>> {
-    info!("fetching transactions for merchant: {}", merchant_address);
-
-    ValidationService::validate_address_format(&merchant_address)?;
-
-    let page = params.page.unwrap_or(0);
-    let page_size = params.size.unwrap_or(50).min(100);
-    let use_hypersync = params.use_hypersync.unwrap_or(false);
-    let chain_name = params.chain.as_deref().unwrap_or("sepolia");
-
-    // prefer hypersync when available to sidestep heavyweight rpc scans
-    if use_hypersync {
-        let chain_id = app_state.blockchain_client.chain_id(chain_name)?;
-        let contract_address = app_state
-            .config
-            .subscription_manager_address_for_chain(chain_name)?
-            .to_string();
-
-        let current_block = app_state
-            .blockchain_client
-            .get_current_block_number(chain_name)
-            .await?;
-
-        let to_block = params.to_block.unwrap_or(current_block);
-        let default_window = 100_000u64;
-        let from_block = params
-            .from_block
-            .unwrap_or_else(|| to_block.saturating_sub(default_window));
-
-        if from_block > to_block {
-            return Err(RelayerError::Validation(
-                "from_block cannot be greater than to_block".to_string(),
-            ));
-        }
-
-        let merchant_lower = merchant_address.to_lowercase();
-        let start_timer = Instant::now();
-
-        let hyper_result: Result<(Vec<RawPaymentEvent>, &'static str)> =
-            if let Some(hypersync) = app_state.hypersync_client.as_ref() {
-                match hypersync
-                    .get_historical_payments(chain_id, &contract_address, from_block, to_block)
-                    .await
-                {
-                    Ok(events) => Ok((events, "hypersync")),
-                    Err(err) => {
-                        warn!(
-                            "HyperSync query failed for merchant {} on {}: {}; falling back to RPC",
-                            merchant_address, chain_name, err
-                        );
-                        HyperSyncClient::fetch_payments_via_rpc_static(
-                            &app_state.blockchain_client,
-                            chain_name,
-                            chain_id,
-                            &contract_address,
-                            from_block,
-                            to_block,
-                        )
-                        .await
-                        .map(|events| (events, "rpc"))
-                    }
-                }
-            } else {
-                warn!(
-                    "HyperSync client not configured; using RPC fallback for merchant {}",
-                    merchant_address
-                );
-                HyperSyncClient::fetch_payments_via_rpc_static(
-                    &app_state.blockchain_client,
-                    chain_name,
-                    chain_id,
-                    &contract_address,
-                    from_block,
-                    to_block,
-                )
-                .await
-                .map(|events| (events, "rpc"))
-            };
-
-        match hyper_result {
-            Ok((events, data_source)) => {
-                let mut total_revenue = U256::zero();
-                let mut transactions: Vec<TransactionData> = Vec::new();
-
-                for event in events
-                    .into_iter()
-                    .filter(|event| event.merchant == merchant_lower)
-                {
-                    total_revenue = total_revenue.checked_add(event.amount).unwrap_or(U256::MAX);
-
-                    let timestamp = match app_state
-                        .blockchain_client
-                        .get_block_timestamp(chain_name, event.block_number)
-                        .await
-                    {
-                        Ok(value) => value,
-                        Err(err) => {
-                            warn!(
-                                "failed to fetch timestamp for block {} on {}: {}",
-                                event.block_number, chain_name, err
-                            );
-                            continue;
-                        }
-                    };
-
-                    let raw_token = if event.token.trim().is_empty() {
-                        "0x0"
-                    } else {
-                        event.token.as_str()
-                    };
-                    let token_address = tokens::normalize_token_address(raw_token);
-                    let token_symbol = tokens::get_token_symbol(&token_address).to_string();
-
-                    transactions.push(TransactionData {
-                        subscription_id: event.subscription_id.clone(),
-                        subscriber: event.subscriber.clone(),
-                        merchant: event.merchant.clone(),
-                        payment_number: event.payment_number,
-                        amount: event.amount.to_string(),
-                        fee: event.fee.to_string(),
-                        relayer: event.relayer.clone(),
-                        transaction_hash: event.transaction_hash.clone(),
-                        block_number: event.block_number,
-                        timestamp,
-                        chain: chain_name.to_string(),
-                        token_address,
-                        token_symbol,
-                    });
-                }
-
-                let total_count = transactions.len();
-                let start_index = (page as usize).saturating_mul(page_size as usize);
-                let end_index = (start_index + page_size as usize).min(total_count);
-                let has_more = end_index < total_count;
-                let paginated = if start_index < total_count {
-                    transactions[start_index..end_index].to_vec()
-                } else {
-                    Vec::new()
-                };
-
-                let mut page_token_totals: HashMap<String, U256> = HashMap::new();
-                for tx in &paginated {
-                    let amount = U256::from_dec_str(&tx.amount).unwrap_or_else(|_| U256::zero());
-                    let entry = page_token_totals
-                        .entry(tx.token_symbol.clone())
-                        .or_insert(U256::zero());
-                    *entry = entry.checked_add(amount).unwrap_or(U256::MAX);
-                }
+) -> Result<Json<SubscriptionProofResponse>> {
+    ValidationService::validate_subscription_id_format(&subscription_id)?;
 
-                let token_totals = page_token_totals
-                    .into_iter()
-                    .map(|(symbol, amount)| {
-                        let decimals = match symbol.as_str() {
-                            "ETH" => 18,
-                            "PYUSD" => 6,
-                            _ => 18,
-                        };
-                        let formatted = tokens::format_token_amount(amount, decimals);
-                        (symbol, formatted)
-                    })
-                    .collect::<HashMap<_, _>>();
+    // the mock serves the relayer's own memory back, which proves nothing and is gone after a
+    // restart; only a real avail node is an independent copy
+    if app_state.avail_client.mode() != AvailClientMode::Remote {
+        return Err(RelayerError::Unavailable(
+            "subscription proofs need a remote avail node".to_string(),
+        ));
+    }
 
-                app_state
-                    .metrics
-                    .record_hypersync_query(start_timer.elapsed());
-
-                let explorer_url = app_state
-                    .envio_client
-                    .build_explorer_url("merchants", &merchant_lower)
-                    .unwrap_or_else(|| "https://explorer.envio.dev".to_string());
-
-                let response = MerchantTransactionsResponse {
-                    transactions: paginated,
-                    count: total_count as u64,
-                    total_revenue: total_revenue.to_string(),
-                    token_totals,
-                    envio_explorer_url: explorer_url,
-                    page,
-                    has_more,
-                    data_source: data_source.to_string(),
-                };
-
-                info!(
-                    "successfully fetched {} transactions for merchant {} via {}",
-                    response.transactions.len(),
-                    merchant_address,
-                    response.data_source
-                );
+    let subscription = app_state
+        .database
+        .queries()
+        .get_subscription(&subscription_id)
+        .await?
+        .ok_or_else(|| RelayerError::NotFound("subscription not found".to_string()))?;
 
-                return Ok(Json(response));
-            }
-            Err(err) => {
-                warn!(
-                    "failed to fetch merchant transactions via HyperSync/RPC fallback for {}: {}; using Envio",
-                    merchant_address, err
-                );
-            }
+    let (block_number, extrinsic_index) = match (
+        subscription.avail_block_number,
+        subscription.avail_extrinsic_index,
+    ) {
+        (Some(block), Some(extrinsic)) => (block as u64, extrinsic as u64),
+        _ => {
+            return Err(RelayerError::NotFound(
+                "intent is not anchored on avail yet".to_string(),
+            ))
         }
-    }
+    };
 
-    let start_timer = Instant::now();
-    let envio_result = app_state
-        .envio_client
-        .get_merchant_transactions(&merchant_address, page, page_size)
-        .await?;
-    app_state.metrics.record_envio_query(start_timer.elapsed());
+    // the signature has to verify for the chain the relayer stored, not whichever one avail claims
+    let chain_id = app_state.blockchain_client.chain_id(&subscription.chain)?;
+    let verifying_contract = Address::from_str(
+        app_state
+            .config
+            .subscription_manager_address_for_chain(&subscription.chain)
+            .map_err(|e| RelayerError::Validation(e.to_string()))?,
+    )
+    .map_err(|_| RelayerError::Validation("invalid subscription manager address".to_string()))?;
 
-    let response = MerchantTransactionsResponse {
-        transactions: envio_result.transactions,
-        count: envio_result.total_count,
-        total_revenue: envio_result.total_revenue,
-        token_totals: envio_result.token_totals,
-        envio_explorer_url: envio_result
-            .explorer_url
-            .unwrap_or_else(|| "https://explorer.envio.dev".to_string()),
-        page,
-        has_more: envio_result.has_more,
-        data_source: "envio".to_string(),
+    let payload = match app_state
+        .avail_client
+        .retrieve_payload(block_number, extrinsic_index)
+        .await
+    {
+        Ok(payload) => Some(payload),
+        Err(RelayerError::NotFound(_)) => None,
+        Err(e) => return Err(e),
     };
 
+    let response =
+        proof::verify_anchored_intent(&subscription, payload, verifying_contract, chain_id);
     info!(
-        "successfully fetched {} transactions for merchant {} via envio",
-        response.transactions.len(),
-        merchant_address
+        "verified subscription {} against avail: {:?}",
+        subscription_id, response.verdict
     );
-
     Ok(Json(response))
 }
 
-// get /api/v1/merchant/:address/stats
-pub async fn get_merchant_stats_handler(
-    Path(merchant_address): Path<String>,
+// get /api/v1/subscription/:id/inclusion-proof
+#[utoipa::path(
+    get,
+    path = "/api/v1/subscription/{id}/inclusion-proof",
+    tag = "subscriptions",
+    summary = "Merkle proof that the subscription's intent is in an Avail batch",
+    params(("id" = openapi::Hash, Path)),
+    responses(
+        (status = 200, description = "OK", body = InclusionProofResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 404, response = openapi::NotFound),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn get_inclusion_proof_handler(
+    Path(subscription_id): Path<String>,
     State(app_state): State<Arc<AppState>>,
-) -> Result<Json<MerchantStatsResponse>> {
-    info!("fetching stats for merchant: {}", merchant_address);
-
-    ValidationService::validate_address_format(&merchant_address)?;
-
-    let stats = app_state
-        .envio_client
-        .get_merchant_stats(&merchant_address)
-        .await?;
-
-    let explorer_url = app_state
-        .envio_client
-        .build_explorer_url("merchants", &merchant_address.to_lowercase())
-        .unwrap_or_else(|| "https://explorer.envio.dev".to_string());
-
-    let stats_response = if let Some(data) = stats {
-        let mut by_token: HashMap<String, TokenStatsResponse> = HashMap::new();
-        for (token, token_stats) in data.by_token.iter() {
-            by_token.insert(token.clone(), token_stats_to_response(token_stats));
-        }
+) -> Result<Json<InclusionProofResponse>> {
+    ValidationService::validate_subscription_id_format(&subscription_id)?;
 
-        MerchantStatsResponse {
-            merchant: data.merchant,
-            total: token_stats_to_response(&data.total),
-            by_token,
-            envio_explorer_url: explorer_url.clone(),
+    let queries = app_state.database.queries();
+    let submission = queries
+        .get_avail_submission(&subscription_id)
+        .await?
+        .ok_or_else(|| {
+            RelayerError::NotFound("no avail submission for subscription".to_string())
+        })?;
+
+    // intents posted one per extrinsic before batching have no proof; availBlock on the
+    // subscription points straight at them
+    let (batch_id, leaf_index, merkle_proof) = match (
+        submission.batch_id,
+        submission.leaf_index,
+        submission.merkle_proof,
+    ) {
+        (Some(batch_id), Some(leaf_index), Some(merkle_proof)) => {
+            (batch_id, leaf_index, merkle_proof)
         }
-    } else {
-        MerchantStatsResponse {
-            merchant: merchant_address.to_lowercase(),
-            total: TokenStatsResponse {
-                token_address: "aggregate".to_string(),
-                token_symbol: "TOTAL".to_string(),
-                total_subscriptions: 0,
-                active_subscriptions: 0,
-                total_revenue: "0".to_string(),
-                total_payments: 0,
-                average_transaction_value: "0".to_string(),
-                chain_id: 0,
-            },
-            by_token: HashMap::new(),
-            envio_explorer_url: explorer_url.clone(),
+        _ => {
+            return Err(RelayerError::NotFound(format!(
+                "intent is not anchored in an avail batch (status {})",
+                submission.status
+            )))
         }
     };
 
-    info!(
-        "successfully fetched stats for merchant {} (explorer: {})",
-        merchant_address, explorer_url
-    );
+    let batch = queries
+        .get_avail_batch(batch_id)
+        .await?
+        .ok_or_else(|| RelayerError::NotFound("avail batch not found".to_string()))?;
 
-    Ok(Json(stats_response))
+    let proof = serde_json::from_value(merkle_proof).map_err(|e| {
+        RelayerError::InternalError(format!("stored merkle proof is malformed: {}", e))
+    })?;
+    let leaf = merkle::leaf_hash(&subscription_id)?;
+
+    Ok(Json(InclusionProofResponse {
+        subscription_id: subscription_id.to_lowercase(),
+        leaf: merkle::encode_hash(&leaf),
+        leaf_index: leaf_index as u32,
+        proof,
+        merkle_root: batch.merkle_root,
+        leaf_count: batch.leaf_count as u32,
+        avail_block: batch.block_number as u64,
+        avail_extrinsic: batch.extrinsic_index as u64,
+        submitted_at: batch.submitted_at,
+    }))
 }
-pub async fn get_cross_chain_attestations_handler(
-    Path(subscription_id): Path<String>,
+
+// query parameters for a subscriber's subscription list
+#[derive(Debug, Clone, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SubscriberSubscriptionQueryParams {
+    /// only subscriptions in this status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<openapi::SubscriptionStatus>)]
+    pub status: Option<String>,
+    /// chain registry name or chain id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<String>,
+    /// only subscriptions paying in this token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<openapi::TokenAddress>)]
+    pub token: Option<String>,
+    /// only subscriptions to this merchant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<openapi::Address>)]
+    pub merchant: Option<String>,
+    /// nextCursor from the previous page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// page size, 50 by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(minimum = 1, maximum = 200)]
+    pub limit: Option<i64>,
+}
+
+// get /api/v1/subscriber/:address/subscriptions
+#[utoipa::path(
+    get,
+    path = "/api/v1/subscriber/{address}/subscriptions",
+    tag = "subscriptions",
+    summary = "A wallet's subscriptions, newest first, with remaining payments and budget",
+    params(
+        ("address" = openapi::Address, Path),
+        SubscriberSubscriptionQueryParams,
+    ),
+    responses(
+        (status = 200, description = "OK", body = SubscriberSubscriptionsResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 429, response = openapi::RateLimited),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn get_subscriber_subscriptions_handler(
+    Path(subscriber_address): Path<String>,
+    Query(params): Query<SubscriberSubscriptionQueryParams>,
     State(app_state): State<Arc<AppState>>,
-) -> Result<Json<Vec<CrossChainAttestationResponse>>> {
+    client: ClientIdentity,
+) -> Result<Json<SubscriberSubscriptionsResponse>> {
     info!(
-        "fetching cross-chain attestations for subscription {}",
-        subscription_id
+        "listing subscriptions for subscriber: {}",
+        subscriber_address
     );
 
-    ValidationService::validate_subscription_id_format(&subscription_id)?;
+    app_state
+        .rate_limiter
+        .check_client(
+            "analytics",
+            &client,
+            app_state.rate_limiter.settings().analytics,
+        )
+        .await?;
 
-    let attestations = app_state
-        .envio_client
-        .get_cross_chain_attestations(&subscription_id)
+    ValidationService::validate_address_format(&subscriber_address)?;
+    let filter = SubscriptionFilter {
+        status: params
+            .status
+            .as_deref()
+            .map(|status| {
+                SubscriptionStatus::parse(status).ok_or_else(|| {
+                    RelayerError::Validation(format!("unknown subscription status: {}", status))
+                })
+            })
+            .transpose()?,
+        chain: params
+            .chain
+            .as_deref()
+            .map(|chain| {
+                app_state
+                    .config
+                    .chain(chain)
+                    .map(|chain| chain.name.clone())
+                    .map_err(|e| RelayerError::Validation(e.to_string()))
+            })
+            .transpose()?,
+        token_address: params
+            .token
+            .as_deref()
+            .map(|token| {
+                if !tokens::is_eth(token) {
+                    ValidationService::validate_address_format(token)?;
+                }
+                Ok::<_, RelayerError>(tokens::normalize_token_address(token))
+            })
+            .transpose()?,
+        merchant: params
+            .merchant
+            .as_deref()
+            .map(|merchant| {
+                ValidationService::validate_address_format(merchant)?;
+                Ok::<_, RelayerError>(merchant.to_lowercase())
+            })
+            .transpose()?,
+    };
+    let cursor = Cursor::parse_optional(params.cursor.as_deref())?;
+    let limit = pagination::page_size(params.limit);
+
+    // one extra row tells whether another page exists
+    let mut subscriptions = app_state
+        .database
+        .queries()
+        .get_subscriber_subscriptions(
+            &subscriber_address,
+            &filter,
+            cursor
+                .as_ref()
+                .map(|cursor| (cursor.at, cursor.id.as_str())),
+            limit + 1,
+        )
         .await?;
+    let next_cursor = if subscriptions.len() as i64 > limit {
+        subscriptions.truncate(limit as usize);
+        subscriptions
+            .last()
+            .map(|last| Cursor::new(last.created_at, &last.id).encode())
+    } else {
+        None
+    };
 
-    let responses = attestations
+    let subscriptions: Vec<SubscriberSubscriptionResponse> = subscriptions
         .into_iter()
-        .map(|record| CrossChainAttestationResponse {
-            attestation_id: record.id,
-            subscription_id: record.subscription_id,
-            payment_number: record.payment_number.max(0) as u64,
-            chain_id: record.chain_id.max(0) as u64,
-            token: record
-                .token
-                .map(|value| tokens::normalize_token_address(&value))
-                .unwrap_or_else(|| tokens::normalize_token_address("0x0")),
-            amount: record.amount.unwrap_or_else(|| "0".to_string()),
-            timestamp: record.timestamp.max(0) as u64,
-            verified: record.verified,
-        })
+        .map(subscriber_subscription_response)
         .collect();
-
-    Ok(Json(responses))
+    Ok(Json(SubscriberSubscriptionsResponse {
+        subscriber: subscriber_address.to_lowercase(),
+        count: subscriptions.len(),
+        subscriptions,
+        next_cursor,
+    }))
 }
 
-// get /health
-pub async fn health_check_handler(
-    State(app_state): State<Arc<AppState>>,
-) -> Result<Json<HealthResponse>> {
-    debug!("performing comprehensive health check");
-
-    let start_time = std::time::Instant::now();
+fn subscriber_subscription_response(subscription: Subscription) -> SubscriberSubscriptionResponse {
+    let remaining_payments = (subscription.max_payments - subscription.executed_payments).max(0);
+    let remaining_budget = match (
+        U256::from_dec_str(&subscription.max_total_amount),
+        U256::from_dec_str(&subscription.total_paid),
+    ) {
+        (Ok(max_total), Ok(paid)) => max_total.saturating_sub(paid).to_string(),
+        _ => subscription.max_total_amount.clone(),
+    };
+    let still_scheduled = matches!(
+        SubscriptionStatus::parse(&subscription.status),
+        Some(SubscriptionStatus::Active | SubscriptionStatus::Pending)
+    );
+    let next_payment_due = (still_scheduled
+        && remaining_payments > 0
+        && subscription.next_payment_due <= subscription.expiry)
+        .then(|| subscription.next_payment_due.timestamp().max(0) as u64);
 
-    let database_health = match app_state.database.ping().await {
-        Ok(_) => {
-            let response_time = start_time.elapsed().as_millis() as u64;
-            ServiceStatus {
+    SubscriberSubscriptionResponse {
+        token_symbol: tokens::get_token_symbol(&subscription.token_address).to_string(),
+        id: subscription.id,
+        merchant: subscription.merchant,
+        chain: subscription.chain,
+        token_address: subscription.token_address,
+        amount: subscription.amount,
+        interval: subscription.interval_seconds.max(0) as u64,
+        status: subscription.status,
+        executed_payments: subscription.executed_payments.max(0) as u64,
+        max_payments: subscription.max_payments.max(0) as u64,
+        remaining_payments: remaining_payments as u64,
+        total_paid: subscription.total_paid,
+        max_total_amount: subscription.max_total_amount,
+        remaining_budget,
+        next_payment_due,
+        expiry: subscription.expiry.timestamp().max(0) as u64,
+        created_at: subscription.created_at,
+    }
+}
+
+// post /api/v1/subscription/:id/pause
+#[utoipa::path(
+    post,
+    path = "/api/v1/subscription/{id}/pause",
+    tag = "subscriptions",
+    summary = "Pause a subscription with the subscriber's signature",
+    params(("id" = openapi::Hash, Path)),
+    request_body = SubscriptionActionRequest,
+    responses(
+        (status = 200, description = "OK", body = SubscriptionActionResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 404, response = openapi::NotFound),
+        (status = 409, response = openapi::Conflict),
+        (status = 422, response = openapi::Unprocessable),
+        (status = 500, response = openapi::InternalError),
+        (status = 502, response = openapi::BadGateway),
+    )
+)]
+pub async fn pause_subscription_handler(
+    Path(subscription_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    payload: std::result::Result<Json<SubscriptionActionRequest>, JsonRejection>,
+) -> Result<Json<SubscriptionActionResponse>> {
+    let Json(request) = payload.map_err(|rejection| {
+        RelayerError::Validation(format!("invalid request body: {}", rejection))
+    })?;
+    relay_subscription_action(
+        &app_state,
+        &subscription_id,
+        SubscriptionAction::Pause,
+        &request.signature,
+    )
+    .await
+    .map(Json)
+}
+
+// post /api/v1/subscription/:id/resume
+#[utoipa::path(
+    post,
+    path = "/api/v1/subscription/{id}/resume",
+    tag = "subscriptions",
+    summary = "Resume a paused subscription with the subscriber's signature",
+    params(("id" = openapi::Hash, Path)),
+    request_body = SubscriptionActionRequest,
+    responses(
+        (status = 200, description = "OK", body = SubscriptionActionResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 404, response = openapi::NotFound),
+        (status = 409, response = openapi::Conflict),
+        (status = 422, response = openapi::Unprocessable),
+        (status = 500, response = openapi::InternalError),
+        (status = 502, response = openapi::BadGateway),
+    )
+)]
+pub async fn resume_subscription_handler(
+    Path(subscription_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    payload: std::result::Result<Json<SubscriptionActionRequest>, JsonRejection>,
+) -> Result<Json<SubscriptionActionResponse>> {
+    let Json(request) = payload.map_err(|rejection| {
+        RelayerError::Validation(format!("invalid request body: {}", rejection))
+    })?;
+    relay_subscription_action(
+        &app_state,
+        &subscription_id,
+        SubscriptionAction::Resume,
+        &request.signature,
+    )
+    .await
+    .map(Json)
+}
+
+// verifies a subscriber-signed pause/resume, relays it on-chain and mirrors the new status
+async fn relay_subscription_action(
+    app_state: &AppState,
+    subscription_id: &str,
+    action: SubscriptionAction,
+    signature: &str,
+) -> Result<SubscriptionActionResponse> {
+    info!(
+        "received {} request for subscription {}",
+        action, subscription_id
+    );
+
+    ValidationService::validate_subscription_id_format(subscription_id)?;
+
+    let subscription = app_state
+        .database
+        .queries()
+        .get_subscription(subscription_id)
+        .await?
+        .ok_or_else(|| RelayerError::NotFound("subscription not found".to_string()))?;
+
+    let (required_status, new_status) = match action {
+        SubscriptionAction::Pause => (SubscriptionStatus::Active, SubscriptionStatus::Paused),
+        SubscriptionAction::Resume => (SubscriptionStatus::Paused, SubscriptionStatus::Active),
+    };
+    if subscription.status != required_status.to_string() {
+        return Err(RelayerError::InvalidSubscriptionState {
+            subscription_id: subscription_id.to_string(),
+            status: subscription.status,
+            action: action.to_string(),
+        });
+    }
+
+    let subscription_id_bytes: [u8; 32] = hex::decode(&subscription_id[2..])
+        .map_err(|_| RelayerError::Validation("invalid subscription ID format".to_string()))?
+        .try_into()
+        .map_err(|_| RelayerError::Validation("subscription ID must be 32 bytes".to_string()))?;
+
+    let chain = subscription.chain.as_str();
+    let manager_address = app_state
+        .config
+        .subscription_manager_address_for_chain(chain)
+        .map_err(|e| RelayerError::Validation(e.to_string()))?;
+    let verifying_contract = Address::from_str(manager_address).map_err(|_| {
+        RelayerError::Validation("invalid subscription manager address".to_string())
+    })?;
+    let subscriber = Address::from_str(&subscription.subscriber)
+        .map_err(|_| RelayerError::Validation("invalid subscriber address".to_string()))?;
+    let chain_id = app_state.blockchain_client.chain_id(chain)?;
+
+    // signatures commit to the subscriber's on-chain nonce, which advances after every pause/resume
+    let nonce = app_state
+        .blockchain_client
+        .get_subscriber_nonce(subscriber, chain)
+        .await?;
+
+    let signature_bytes = ValidationService::validate_subscription_action_signature(
+        action,
+        subscription_id_bytes,
+        nonce,
+        signature,
+        subscriber,
+        verifying_contract,
+        chain_id,
+    )?;
+
+    let result = match action {
+        SubscriptionAction::Pause => {
+            app_state
+                .blockchain_client
+                .pause_subscription(subscription_id_bytes, signature_bytes.into(), chain)
+                .await?
+        }
+        SubscriptionAction::Resume => {
+            app_state
+                .blockchain_client
+                .resume_subscription(subscription_id_bytes, signature_bytes.into(), chain)
+                .await?
+        }
+    };
+
+    app_state
+        .database
+        .queries()
+        .update_subscription_status_enum(subscription_id, new_status.clone())
+        .await?;
+
+    info!(
+        "{} relayed for subscription {} in tx {:?}",
+        action, subscription_id, result.transaction_hash
+    );
+
+    let transaction_hash = format!("{:?}", result.transaction_hash);
+    if let SubscriptionAction::Pause = action {
+        let paused = Subscription {
+            status: new_status.to_string(),
+            ..subscription.clone()
+        };
+        webhooks::publish_status_change(
+            &app_state.database.queries(),
+            &paused,
+            WebhookEventType::SubscriptionPaused,
+            Some(&transaction_hash),
+        )
+        .await;
+    }
+
+    Ok(SubscriptionActionResponse {
+        subscription_id: subscription_id.to_string(),
+        status: new_status.to_string(),
+        chain: subscription.chain,
+        transaction_hash,
+        block_number: result.block_number,
+    })
+}
+
+// query parameters for transactions endpoint
+#[derive(Debug, Clone, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TransactionQueryParams {
+    /// zero-based page for legacy offset paging; ignored with a cursor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    /// page size, 50 by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(minimum = 1, maximum = 200)]
+    pub size: Option<u32>,
+    /// nextCursor from the previous page; envio and hypersync cursors are interchangeable,
+    /// postgres cursors only work with postgres
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// "envio" (default), "postgres" or "hypersync"; postgres also lists failed and orphaned
+    /// executions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// same as source=hypersync; rate limited separately
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_hypersync: Option<bool>,
+    /// first block of a HyperSync scan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<u64>,
+    /// last block of a HyperSync scan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<u64>,
+    /// earliest payment, unix seconds or RFC 3339, inclusive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_time: Option<String>,
+    /// latest payment, unix seconds or RFC 3339, inclusive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_time: Option<String>,
+    /// chain registry name or chain id; picks the chain a HyperSync scan reads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<String>,
+    /// only payments in this token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<openapi::TokenAddress>)]
+    pub token: Option<String>,
+    /// only payments for this subscription
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<openapi::Hash>)]
+    pub subscription_id: Option<String>,
+    /// only executions in this status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<openapi::ExecutionStatus>)]
+    pub status: Option<String>,
+    /// smallest amount paid, inclusive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<openapi::Wei>)]
+    pub min_amount: Option<String>,
+}
+
+// where merchant transaction history is read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TransactionSource {
+    Postgres,
+    Envio,
+    HyperSync,
+}
+
+impl TransactionSource {
+    fn from_params(params: &TransactionQueryParams) -> Result<Self> {
+        let use_hypersync = params.use_hypersync.unwrap_or(false);
+        let source = match params.source.as_deref().map(str::to_ascii_lowercase) {
+            None if use_hypersync => TransactionSource::HyperSync,
+            None => TransactionSource::Envio,
+            Some(source) => match source.as_str() {
+                "postgres" => TransactionSource::Postgres,
+                "envio" => TransactionSource::Envio,
+                "hypersync" => TransactionSource::HyperSync,
+                _ => {
+                    return Err(RelayerError::Validation(format!(
+                        "unknown transaction source: {}",
+                        source
+                    )))
+                }
+            },
+        };
+        if use_hypersync && source != TransactionSource::HyperSync {
+            return Err(RelayerError::Validation(
+                "use_hypersync conflicts with source".to_string(),
+            ));
+        }
+        Ok(source)
+    }
+}
+
+// one page of history, newest first by (timestamp, id) whatever the source
+struct TransactionPage {
+    transactions: Vec<TransactionData>,
+    count: u64,
+    total_revenue: String,
+    next_cursor: Option<String>,
+    explorer_url: Option<String>,
+    data_source: &'static str,
+}
+
+// get /api/v1/merchant/:address/transactions
+#[utoipa::path(
+    get,
+    path = "/api/v1/merchant/{address}/transactions",
+    tag = "merchants",
+    summary = "Payment history, newest first, from Envio, the relayer's records or a HyperSync scan",
+    params(
+        ("address" = openapi::Address, Path),
+        TransactionQueryParams,
+    ),
+    responses(
+        (status = 200, description = "OK", body = MerchantTransactionsResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 429, response = openapi::RateLimited),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn get_merchant_transactions_handler(
+    Path(merchant_address): Path<String>,
+    Query(params): Query<TransactionQueryParams>,
+    State(app_state): State<Arc<AppState>>,
+    client: ClientIdentity,
+) -> Result<Json<MerchantTransactionsResponse>> {
+    info!("fetching transactions for merchant: {}", merchant_address);
+
+    let limits = app_state.rate_limiter.settings();
+    app_state
+        .rate_limiter
+        .check_client("analytics", &client, limits.analytics)
+        .await?;
+
+    ValidationService::validate_address_format(&merchant_address)?;
+
+    let source = TransactionSource::from_params(&params)?;
+    let filter = transaction_filter(&app_state, &params)?;
+    let cursor = Cursor::parse_optional(params.cursor.as_deref())?;
+    let limit = pagination::page_size(params.size.map(i64::from));
+    let page = if cursor.is_some() {
+        0
+    } else {
+        params.page.unwrap_or(0)
+    };
+    let offset = i64::from(page) * limit;
+
+    let result = match source {
+        TransactionSource::Postgres => {
+            postgres_transactions(
+                &app_state,
+                &merchant_address,
+                &filter,
+                cursor,
+                offset,
+                limit,
+            )
+            .await?
+        }
+        TransactionSource::Envio => {
+            envio_transactions(&app_state, &merchant_address, filter, cursor, offset, limit).await?
+        }
+        // prefer hypersync when available to sidestep heavyweight rpc scans
+        TransactionSource::HyperSync => {
+            app_state
+                .rate_limiter
+                .check_client("hypersync", &client, limits.hypersync_scans)
+                .await?;
+
+            match hypersync_transactions(
+                &app_state,
+                &merchant_address,
+                &params,
+                &filter,
+                cursor.as_ref(),
+                offset,
+                limit,
+            )
+            .await
+            {
+                Ok(result) => result,
+                Err(RelayerError::Validation(message)) => {
+                    return Err(RelayerError::Validation(message))
+                }
+                Err(err) => {
+                    warn!(
+                        "failed to fetch merchant transactions via HyperSync/RPC fallback for {}: {}; using Envio",
+                        merchant_address, err
+                    );
+                    envio_transactions(&app_state, &merchant_address, filter, cursor, offset, limit)
+                        .await?
+                }
+            }
+        }
+    };
+
+    let response = MerchantTransactionsResponse {
+        token_totals: page_token_totals(&result.transactions),
+        transactions: result.transactions,
+        count: result.count,
+        total_revenue: result.total_revenue,
+        envio_explorer_url: result
+            .explorer_url
+            .unwrap_or_else(|| "https://explorer.envio.dev".to_string()),
+        page,
+        has_more: result.next_cursor.is_some(),
+        next_cursor: result.next_cursor,
+        data_source: result.data_source.to_string(),
+    };
+
+    info!(
+        "successfully fetched {} transactions for merchant {} via {}",
+        response.transactions.len(),
+        merchant_address,
+        response.data_source
+    );
+
+    Ok(Json(response))
+}
+
+fn transaction_filter(
+    app_state: &AppState,
+    params: &TransactionQueryParams,
+) -> Result<ExecutionFilter> {
+    let filter = ExecutionFilter {
+        from: params
+            .from_time
+            .as_deref()
+            .map(|value| parse_time_param("from_time", value))
+            .transpose()?,
+        to: params
+            .to_time
+            .as_deref()
+            .map(|value| parse_time_param("to_time", value))
+            .transpose()?,
+        token_address: params
+            .token
+            .as_deref()
+            .map(|token| {
+                if !tokens::is_eth(token) {
+                    ValidationService::validate_address_format(token)?;
+                }
+                Ok::<_, RelayerError>(tokens::normalize_token_address(token))
+            })
+            .transpose()?,
+        chain: params
+            .chain
+            .as_deref()
+            .map(|chain| {
+                app_state
+                    .config
+                    .chain(chain)
+                    .map(|chain| chain.name.clone())
+                    .map_err(|e| RelayerError::Validation(e.to_string()))
+            })
+            .transpose()?,
+        subscription_id: params
+            .subscription_id
+            .as_deref()
+            .map(|id| {
+                ValidationService::validate_subscription_id_format(id)?;
+                Ok::<_, RelayerError>(id.to_lowercase())
+            })
+            .transpose()?,
+        status: params
+            .status
+            .as_deref()
+            .map(|status| {
+                ExecutionStatus::parse(status).ok_or_else(|| {
+                    RelayerError::Validation(format!("unknown execution status: {}", status))
+                })
+            })
+            .transpose()?,
+        min_amount: params
+            .min_amount
+            .as_deref()
+            .map(|amount| {
+                U256::from_dec_str(amount).map_err(|_| {
+                    RelayerError::Validation("min_amount must be a decimal wei amount".to_string())
+                })
+            })
+            .transpose()?,
+        settled: false,
+    };
+
+    if let (Some(from), Some(to)) = (filter.from, filter.to) {
+        if from > to {
+            return Err(RelayerError::Validation(
+                "from_time cannot be later than to_time".to_string(),
+            ));
+        }
+    }
+    Ok(filter)
+}
+
+fn parse_time_param(name: &str, value: &str) -> Result<DateTime<Utc>> {
+    let parsed = match value.parse::<i64>() {
+        Ok(seconds) => DateTime::from_timestamp(seconds, 0),
+        Err(_) => DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|at| at.with_timezone(&Utc)),
+    };
+    parsed.ok_or_else(|| {
+        RelayerError::Validation(format!(
+            "{} must be unix seconds or an RFC 3339 timestamp",
+            name
+        ))
+    })
+}
+
+// the relayer's own execution records, including failed and orphaned attempts
+async fn postgres_transactions(
+    app_state: &AppState,
+    merchant_address: &str,
+    filter: &ExecutionFilter,
+    cursor: Option<Cursor>,
+    offset: i64,
+    limit: i64,
+) -> Result<TransactionPage> {
+    let after = cursor
+        .map(|cursor| {
+            cursor
+                .id
+                .parse::<i64>()
+                .map(|id| (cursor.at, id))
+                .map_err(|_| RelayerError::Validation("invalid cursor".to_string()))
+        })
+        .transpose()?;
+
+    let queries = app_state.database.queries();
+    // one extra row tells whether another page exists
+    let mut rows = queries
+        .list_merchant_executions(merchant_address, filter, after, offset, limit + 1)
+        .await?;
+    let next_cursor = if rows.len() as i64 > limit {
+        rows.truncate(limit as usize);
+        rows.last()
+            .map(|last| Cursor::new(last.execution.executed_at, last.execution.id).encode())
+    } else {
+        None
+    };
+    let (count, total_revenue) = queries
+        .get_merchant_execution_totals(merchant_address, filter)
+        .await?;
+
+    let merchant_lower = merchant_address.to_lowercase();
+    let transactions = rows
+        .into_iter()
+        .map(
+            |MerchantExecution {
+                 execution,
+                 subscriber,
+             }| {
+                let token_address = tokens::normalize_token_address(
+                    execution.token_address.as_deref().unwrap_or("0x0"),
+                );
+                TransactionData {
+                    id: execution.id.to_string(),
+                    subscription_id: execution.subscription_id,
+                    subscriber,
+                    merchant: merchant_lower.clone(),
+                    payment_number: execution.payment_number.max(0) as u64,
+                    amount: execution.amount_paid,
+                    fee: execution.protocol_fee,
+                    relayer: execution.relayer_address,
+                    transaction_hash: execution.transaction_hash,
+                    block_number: execution.block_number.max(0) as u64,
+                    timestamp: execution.executed_at.timestamp().max(0) as u64,
+                    chain: execution.chain,
+                    token_symbol: tokens::get_token_symbol(&token_address).to_string(),
+                    token_address,
+                    status: execution.status,
+                }
+            },
+        )
+        .collect();
+
+    Ok(TransactionPage {
+        transactions,
+        count,
+        total_revenue: total_revenue.to_string(),
+        next_cursor,
+        explorer_url: app_state
+            .envio_client
+            .build_explorer_url("merchants", &merchant_lower),
+        data_source: "postgres",
+    })
+}
+
+async fn envio_transactions(
+    app_state: &AppState,
+    merchant_address: &str,
+    filter: ExecutionFilter,
+    cursor: Option<Cursor>,
+    offset: i64,
+    limit: i64,
+) -> Result<TransactionPage> {
+    let chain_id = match filter.chain.as_deref() {
+        Some(chain) => Some(app_state.config.chain(chain)?.chain_id),
+        None => None,
+    };
+    let query = PaymentHistoryQuery {
+        filter,
+        chain_id,
+        after: cursor.map(|cursor| (cursor.at.timestamp(), cursor.id)),
+        offset: offset.clamp(0, u32::MAX as i64) as u32,
+        limit: limit as u32,
+    };
+
+    let start_timer = Instant::now();
+    let result = app_state
+        .envio_client
+        .get_merchant_transactions(merchant_address, &query)
+        .await?;
+    app_state.metrics.record_envio_query(start_timer.elapsed());
+
+    let next_cursor = if result.has_more {
+        result.transactions.last().map(transaction_cursor)
+    } else {
+        None
+    };
+    Ok(TransactionPage {
+        transactions: result.transactions,
+        count: result.total_count,
+        total_revenue: result.total_revenue,
+        next_cursor,
+        explorer_url: result.explorer_url,
+        data_source: "envio",
+    })
+}
+
+// scans the block window on demand, then filters and pages in memory
+async fn hypersync_transactions(
+    app_state: &AppState,
+    merchant_address: &str,
+    params: &TransactionQueryParams,
+    filter: &ExecutionFilter,
+    cursor: Option<&Cursor>,
+    offset: i64,
+    limit: i64,
+) -> Result<TransactionPage> {
+    let chain_name = match filter.chain.as_deref() {
+        Some(chain) => chain,
+        None => app_state
+            .config
+            .chains
+            .default_chain()
+            .map(|chain| chain.name.as_str())
+            .ok_or_else(|| RelayerError::InternalError("no chains configured".to_string()))?,
+    };
+    let chain_id = app_state.blockchain_client.chain_id(chain_name)?;
+    let contract_address = app_state
+        .config
+        .subscription_manager_address_for_chain(chain_name)?
+        .to_string();
+
+    let current_block = app_state
+        .blockchain_client
+        .get_current_block_number(chain_name)
+        .await?;
+
+    let to_block = params.to_block.unwrap_or(current_block);
+    let default_window = 100_000u64;
+    let from_block = params
+        .from_block
+        .unwrap_or_else(|| to_block.saturating_sub(default_window));
+
+    if from_block > to_block {
+        return Err(RelayerError::Validation(
+            "from_block cannot be greater than to_block".to_string(),
+        ));
+    }
+
+    let merchant_lower = merchant_address.to_lowercase();
+    let start_timer = Instant::now();
+
+    let (events, data_source) = if let Some(hypersync) = app_state.hypersync_client.as_ref() {
+        match hypersync
+            .get_historical_payments(chain_id, &contract_address, from_block, to_block)
+            .await
+        {
+            Ok(events) => (events, "hypersync"),
+            Err(err) => {
+                warn!(
+                    "HyperSync query failed for merchant {} on {}: {}; falling back to RPC",
+                    merchant_address, chain_name, err
+                );
+                let events = HyperSyncClient::fetch_payments_via_rpc_static(
+                    &app_state.blockchain_client,
+                    chain_name,
+                    chain_id,
+                    &contract_address,
+                    from_block,
+                    to_block,
+                )
+                .await?;
+                (events, "rpc")
+            }
+        }
+    } else {
+        warn!(
+            "HyperSync client not configured; using RPC fallback for merchant {}",
+            merchant_address
+        );
+        let events = HyperSyncClient::fetch_payments_via_rpc_static(
+            &app_state.blockchain_client,
+            chain_name,
+            chain_id,
+            &contract_address,
+            from_block,
+            to_block,
+        )
+        .await?;
+        (events, "rpc")
+    };
+
+    let mut transactions: Vec<TransactionData> = Vec::new();
+    for event in events
+        .into_iter()
+        .filter(|event| event.merchant == merchant_lower)
+    {
+        let timestamp = match app_state
+            .blockchain_client
+            .get_block_timestamp(chain_name, event.block_number)
+            .await
+        {
+            Ok(value) => value,
+            Err(err) => {
+                warn!(
+                    "failed to fetch timestamp for block {} on {}: {}",
+                    event.block_number, chain_name, err
+                );
+                continue;
+            }
+        };
+
+        let raw_token = if event.token.trim().is_empty() {
+            "0x0"
+        } else {
+            event.token.as_str()
+        };
+        let token_address = tokens::normalize_token_address(raw_token);
+        let token_symbol = tokens::get_token_symbol(&token_address).to_string();
+
+        transactions.push(hypersync_transaction(
+            event,
+            timestamp,
+            chain_name,
+            token_address,
+            token_symbol,
+        ));
+    }
+
+    transactions.retain(|tx| {
+        filter.matches(
+            DateTime::from_timestamp(tx.timestamp as i64, 0).unwrap_or_default(),
+            &tx.token_address,
+            &tx.chain,
+            &tx.subscription_id,
+            &tx.status,
+            &tx.amount,
+        )
+    });
+    transactions.sort_by(|a, b| (b.timestamp, &b.id).cmp(&(a.timestamp, &a.id)));
+
+    let count = transactions.len() as u64;
+    let total_revenue = transactions
+        .iter()
+        .filter_map(|tx| U256::from_dec_str(&tx.amount).ok())
+        .fold(U256::zero(), |total, amount| total.saturating_add(amount));
+
+    let after = cursor.map(|cursor| (cursor.at.timestamp().max(0) as u64, cursor.id.as_str()));
+    let mut page: Vec<TransactionData> = transactions
+        .into_iter()
+        .filter(|tx| after.is_none_or(|after| (tx.timestamp, tx.id.as_str()) < after))
+        .skip(offset.max(0) as usize)
+        .take(limit as usize + 1)
+        .collect();
+    let next_cursor = if page.len() as i64 > limit {
+        page.truncate(limit as usize);
+        page.last().map(transaction_cursor)
+    } else {
+        None
+    };
+
+    app_state
+        .metrics
+        .record_hypersync_query(start_timer.elapsed());
+
+    Ok(TransactionPage {
+        transactions: page,
+        count,
+        total_revenue: total_revenue.to_string(),
+        next_cursor,
+        explorer_url: app_state
+            .envio_client
+            .build_explorer_url("merchants", &merchant_lower),
+        data_source,
+    })
+}
+
+fn hypersync_transaction(
+    event: RawPaymentEvent,
+    timestamp: u64,
+    chain_name: &str,
+    token_address: String,
+    token_symbol: String,
+) -> TransactionData {
+    TransactionData {
+        id: envio::payment_entity_id(event.chain_id, &event.subscription_id, event.payment_number),
+        subscription_id: event.subscription_id,
+        subscriber: event.subscriber,
+        merchant: event.merchant,
+        payment_number: event.payment_number,
+        amount: event.amount.to_string(),
+        fee: event.fee.to_string(),
+        relayer: event.relayer,
+        transaction_hash: event.transaction_hash,
+        block_number: event.block_number,
+        timestamp,
+        chain: chain_name.to_string(),
+        token_address,
+        token_symbol,
+        status: ExecutionStatus::Success.to_string(),
+    }
+}
+
+fn transaction_cursor(tx: &TransactionData) -> String {
+    let at = DateTime::from_timestamp(tx.timestamp as i64, 0).unwrap_or_default();
+    Cursor::new(at, &tx.id).encode()
+}
+
+fn page_token_totals(transactions: &[TransactionData]) -> HashMap<String, String> {
+    let mut totals: HashMap<String, U256> = HashMap::new();
+    for tx in transactions {
+        let amount = U256::from_dec_str(&tx.amount).unwrap_or_else(|_| U256::zero());
+        let entry = totals
+            .entry(tx.token_symbol.clone())
+            .or_insert(U256::zero());
+        *entry = entry.checked_add(amount).unwrap_or(U256::MAX);
+    }
+
+    totals
+        .into_iter()
+        .map(|(symbol, amount)| {
+            let decimals = match symbol.as_str() {
+                "ETH" => 18,
+                "PYUSD" => 6,
+                _ => 18,
+            };
+            (symbol, tokens::format_token_amount(amount, decimals))
+        })
+        .collect()
+}
+
+// query parameters for the settlement export
+#[derive(Debug, Clone, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQueryParams {
+    /// "csv" (default) or "jsonl"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// earliest execution, unix seconds or RFC 3339, inclusive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// latest execution, unix seconds or RFC 3339, inclusive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+// get /api/v1/merchant/:address/export
+#[utoipa::path(
+    get,
+    path = "/api/v1/merchant/{address}/export",
+    tag = "merchants",
+    summary = "Stream every settled payment as CSV or JSON lines, newest first",
+    params(
+        ("address" = openapi::Address, Path),
+        ExportQueryParams,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "a csv header row then one SettlementRow per line, or one SettlementRow per json line",
+            content(
+                (String = "text/csv"),
+                (SettlementRow = "application/x-ndjson")
+            )
+        ),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 429, response = openapi::RateLimited),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn export_merchant_settlements_handler(
+    Path(merchant_address): Path<String>,
+    Query(params): Query<ExportQueryParams>,
+    State(app_state): State<Arc<AppState>>,
+    client: ClientIdentity,
+) -> Result<impl IntoResponse> {
+    info!("exporting settlements for merchant: {}", merchant_address);
+
+    app_state
+        .rate_limiter
+        .check_client(
+            "analytics",
+            &client,
+            app_state.rate_limiter.settings().analytics,
+        )
+        .await?;
+
+    ValidationService::validate_address_format(&merchant_address)?;
+    let format = match params.format.as_deref() {
+        Some(format) => ExportFormat::parse(format).ok_or_else(|| {
+            RelayerError::Validation(format!("unknown export format: {}", format))
+        })?,
+        None => ExportFormat::Csv,
+    };
+    let filter = ExecutionFilter {
+        from: params
+            .from
+            .as_deref()
+            .map(|value| parse_time_param("from", value))
+            .transpose()?,
+        to: params
+            .to
+            .as_deref()
+            .map(|value| parse_time_param("to", value))
+            .transpose()?,
+        ..ExecutionFilter::default()
+    };
+    if let (Some(from), Some(to)) = (filter.from, filter.to) {
+        if from > to {
+            return Err(RelayerError::Validation(
+                "from cannot be later than to".to_string(),
+            ));
+        }
+    }
+
+    let rows = export::settlement_stream(
+        app_state.database.queries(),
+        &merchant_address,
+        filter,
+        format,
+    )
+    .await?;
+    let merchant = merchant_address.to_lowercase();
+    // headers are already sent once rows flow, so a later failure can only cut the body short
+    let body = Body::from_stream(rows.inspect_err(move |e| {
+        warn!("settlement export for {} aborted: {}", merchant, e);
+    }));
+
+    Ok((
+        [
+            (CONTENT_TYPE, format.content_type().to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"settlements-{}.{}\"",
+                    merchant_address.to_lowercase(),
+                    format.extension()
+                ),
+            ),
+        ],
+        body,
+    ))
+}
+
+// get /api/v1/merchant/:address/stats
+#[utoipa::path(
+    get,
+    path = "/api/v1/merchant/{address}/stats",
+    tag = "merchants",
+    summary = "Revenue and subscription counts by token",
+    params(("address" = openapi::Address, Path)),
+    responses(
+        (status = 200, description = "OK", body = MerchantStatsResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 429, response = openapi::RateLimited),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn get_merchant_stats_handler(
+    Path(merchant_address): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    client: ClientIdentity,
+) -> Result<Json<MerchantStatsResponse>> {
+    info!("fetching stats for merchant: {}", merchant_address);
+
+    app_state
+        .rate_limiter
+        .check_client(
+            "analytics",
+            &client,
+            app_state.rate_limiter.settings().analytics,
+        )
+        .await?;
+
+    ValidationService::validate_address_format(&merchant_address)?;
+
+    let stats = app_state
+        .envio_client
+        .get_merchant_stats(&merchant_address)
+        .await?;
+
+    let explorer_url = app_state
+        .envio_client
+        .build_explorer_url("merchants", &merchant_address.to_lowercase())
+        .unwrap_or_else(|| "https://explorer.envio.dev".to_string());
+
+    let stats_response = if let Some(data) = stats {
+        let mut by_token: HashMap<String, TokenStatsResponse> = HashMap::new();
+        for (token, token_stats) in data.by_token.iter() {
+            by_token.insert(token.clone(), token_stats_to_response(token_stats));
+        }
+
+        MerchantStatsResponse {
+            merchant: data.merchant,
+            total: token_stats_to_response(&data.total),
+            by_token,
+            envio_explorer_url: explorer_url.clone(),
+        }
+    } else {
+        MerchantStatsResponse {
+            merchant: merchant_address.to_lowercase(),
+            total: TokenStatsResponse {
+                token_address: "aggregate".to_string(),
+                token_symbol: "TOTAL".to_string(),
+                total_subscriptions: 0,
+                active_subscriptions: 0,
+                total_revenue: "0".to_string(),
+                total_payments: 0,
+                average_transaction_value: "0".to_string(),
+                chain_id: 0,
+            },
+            by_token: HashMap::new(),
+            envio_explorer_url: explorer_url.clone(),
+        }
+    };
+
+    info!(
+        "successfully fetched stats for merchant {} (explorer: {})",
+        merchant_address, explorer_url
+    );
+
+    Ok(Json(stats_response))
+}
+#[utoipa::path(
+    get,
+    path = "/api/v1/cross-chain/{subscription_id}",
+    tag = "subscriptions",
+    summary = "Cross-chain attestations for a subscription (Envio)",
+    params(("subscription_id" = openapi::Hash, Path)),
+    responses(
+        (status = 200, description = "OK", body = Vec<CrossChainAttestationResponse>),
+        (status = 400, response = openapi::BadRequest),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn get_cross_chain_attestations_handler(
+    Path(subscription_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<Vec<CrossChainAttestationResponse>>> {
+    info!(
+        "fetching cross-chain attestations for subscription {}",
+        subscription_id
+    );
+
+    ValidationService::validate_subscription_id_format(&subscription_id)?;
+
+    let attestations = app_state
+        .envio_client
+        .get_cross_chain_attestations(&subscription_id)
+        .await?;
+
+    let responses = attestations
+        .into_iter()
+        .map(|record| CrossChainAttestationResponse {
+            attestation_id: record.id,
+            subscription_id: record.subscription_id,
+            payment_number: record.payment_number.max(0) as u64,
+            chain_id: record.chain_id.max(0) as u64,
+            token: record
+                .token
+                .map(|value| tokens::normalize_token_address(&value))
+                .unwrap_or_else(|| tokens::normalize_token_address("0x0")),
+            amount: record.amount.unwrap_or_else(|| "0".to_string()),
+            timestamp: record.timestamp.max(0) as u64,
+            verified: record.verified,
+        })
+        .collect();
+
+    Ok(Json(responses))
+}
+
+// get /health
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "service",
+    summary = "Service health with dependency response times",
+    responses(
+        (status = 200, description = "OK", body = HealthResponse),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn health_check_handler(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<HealthResponse>> {
+    debug!("performing comprehensive health check");
+
+    let start_time = std::time::Instant::now();
+
+    let database_health = match app_state.database.ping().await {
+        Ok(_) => {
+            let response_time = start_time.elapsed().as_millis() as u64;
+            ServiceStatus {
                 healthy: true,
                 response_time_ms: Some(response_time),
                 error: None,
@@ -649,15 +1914,38 @@ pub async fn health_check_handler(
 
     let rpc_start = std::time::Instant::now();
     let blockchain_client = app_state.blockchain_client.clone();
-    let rpc_health = match blockchain_client.validate_connection("sepolia").await {
-        Ok(_) => {
-            let response_time = rpc_start.elapsed().as_millis() as u64;
-            ServiceStatus {
-                healthy: true,
-                response_time_ms: Some(response_time),
-                error: None,
-            }
+    let mut rpc_errors = Vec::new();
+    for chain in app_state.config.chains.iter() {
+        if let Err(e) = blockchain_client.validate_connection(&chain.name).await {
+            rpc_errors.push(format!("{}: {}", chain.name, e));
+        }
+    }
+    let rpc_health = if rpc_errors.is_empty() {
+        ServiceStatus {
+            healthy: true,
+            response_time_ms: Some(rpc_start.elapsed().as_millis() as u64),
+            error: None,
+        }
+    } else {
+        ServiceStatus {
+            healthy: false,
+            response_time_ms: None,
+            error: Some(rpc_errors.join("; ")),
         }
+    };
+
+    let envio_start = std::time::Instant::now();
+    let envio_health = match app_state.envio_client.health_check().await {
+        Ok(true) => ServiceStatus {
+            healthy: true,
+            response_time_ms: Some(envio_start.elapsed().as_millis() as u64),
+            error: None,
+        },
+        Ok(false) => ServiceStatus {
+            healthy: false,
+            response_time_ms: Some(envio_start.elapsed().as_millis() as u64),
+            error: Some("Envio health check reported unhealthy".to_string()),
+        },
         Err(e) => ServiceStatus {
             healthy: false,
             response_time_ms: None,
@@ -665,108 +1953,1288 @@ pub async fn health_check_handler(
         },
     };
 
-    let envio_start = std::time::Instant::now();
-    let envio_health = match app_state.envio_client.health_check().await {
-        Ok(true) => ServiceStatus {
-            healthy: true,
-            response_time_ms: Some(envio_start.elapsed().as_millis() as u64),
-            error: None,
-        },
-        Ok(false) => ServiceStatus {
-            healthy: false,
-            response_time_ms: Some(envio_start.elapsed().as_millis() as u64),
-            error: Some("Envio health check reported unhealthy".to_string()),
-        },
-        Err(e) => ServiceStatus {
-            healthy: false,
-            response_time_ms: None,
-            error: Some(e.to_string()),
-        },
+    let overall_healthy = database_health.healthy && rpc_health.healthy && envio_health.healthy;
+    let status = if overall_healthy {
+        "healthy"
+    } else {
+        "degraded"
+    };
+
+    let response = HealthResponse {
+        status: status.to_string(),
+        timestamp: Utc::now(),
+        services: HealthServices {
+            database: database_health,
+            rpc: rpc_health,
+            envio: envio_health,
+        },
+    };
+
+    info!("health check completed - status: {}", status);
+    Ok(Json(response))
+}
+
+// get /status (enhanced version of health check)
+#[utoipa::path(
+    get,
+    path = "/status",
+    tag = "service",
+    summary = "Health plus subscription counts and enabled features",
+    responses(
+        (status = 200, description = "OK", body = StatusResponse),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn status_check_handler(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<StatusResponse>> {
+    info!("status check requested");
+
+    let health_response = health_check_handler(State(app_state.clone())).await?;
+
+    let queries = app_state.database.queries();
+    let mut totals = (0i64, 0i64, 0i64);
+    let mut by_chain = serde_json::Map::new();
+    let mut stats_failed = false;
+    for chain in app_state.config.chains.iter() {
+        match queries.get_subscription_stats(&chain.name).await {
+            Ok((active, paused, total)) => {
+                totals = (totals.0 + active, totals.1 + paused, totals.2 + total);
+                by_chain.insert(
+                    chain.name.clone(),
+                    serde_json::json!({
+                        "active": active,
+                        "paused": paused,
+                        "total": total
+                    }),
+                );
+            }
+            Err(_) => stats_failed = true,
+        }
+    }
+
+    let subscription_stats = if stats_failed {
+        serde_json::json!({
+            "error": "failed to fetch subscription stats"
+        })
+    } else {
+        serde_json::json!({
+            "active": totals.0,
+            "paused": totals.1,
+            "total": totals.2,
+            "by_chain": by_chain
+        })
+    };
+
+    Ok(Json(StatusResponse {
+        service: "aurum-relayer".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        timestamp: Utc::now(),
+        health: health_response.0,
+        subscriptions: subscription_stats,
+        config: serde_json::json!({
+            "chains_supported": app_state.config.chains.names(),
+            "api_version": "v1",
+            "features": [
+                "intent_submission",
+                "subscription_tracking",
+                "merchant_analytics",
+                "envio_integration",
+                "distributed_processing"
+            ]
+        }),
+    }))
+}
+
+fn token_stats_to_response(stats: &TokenStats) -> TokenStatsResponse {
+    TokenStatsResponse {
+        token_address: stats.token_address.clone(),
+        token_symbol: stats.token_symbol.clone(),
+        total_subscriptions: stats.total_subscriptions,
+        active_subscriptions: stats.active_subscriptions,
+        total_revenue: stats.total_revenue.clone(),
+        total_payments: stats.total_payments,
+        average_transaction_value: stats.average_transaction_value.clone(),
+        chain_id: stats.chain_id,
+    }
+}
+
+// get /metrics - prometheus text format
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "service",
+    summary = "Prometheus text exposition",
+    responses(
+        (status = 200, description = "OK", body = String, content_type = "text/plain; version=0.0.4"),
+    )
+)]
+pub async fn metrics_handler(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+        app_state.metrics.render_prometheus(),
+    )
+}
+
+// get /metrics.json
+#[utoipa::path(
+    get,
+    path = "/metrics.json",
+    tag = "service",
+    summary = "HyperSync and Envio query counters as JSON",
+    responses(
+        (status = 200, description = "OK", body = MetricsSnapshot),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn metrics_json_handler(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<MetricsSnapshot>> {
+    let snapshot = app_state.metrics.snapshot();
+    Ok(Json(snapshot))
+}
+
+// get /api/v1/admin/scheduler
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/scheduler",
+    tag = "admin",
+    summary = "Current scheduler settings",
+    responses(
+        (status = 200, description = "OK", body = SchedulerSettings),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn get_scheduler_settings_handler(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<SchedulerSettings>> {
+    Ok(Json(app_state.scheduler_settings.current()))
+}
+
+// put /api/v1/admin/scheduler - applies from the next cycle, reschedules payment jobs on cadence changes
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/scheduler",
+    tag = "admin",
+    summary = "Partially update the scheduler settings from the next cycle",
+    request_body = SchedulerSettingsUpdate,
+    responses(
+        (status = 200, description = "OK", body = SchedulerSettings),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn update_scheduler_settings_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(actor): Extension<Actor>,
+    payload: std::result::Result<Json<SchedulerSettingsUpdate>, JsonRejection>,
+) -> Result<Json<SchedulerSettings>> {
+    let Json(update) = payload.map_err(|rejection| {
+        RelayerError::Validation(format!("invalid request body: {}", rejection))
+    })?;
+
+    let settings = app_state.scheduler_settings.update(&update)?;
+    record_admin_action(
+        &app_state,
+        &actor,
+        AdminAction::UpdateSchedulerSettings,
+        None,
+        serde_json::json!({ "settings": settings }),
+    )
+    .await?;
+    info!(
+        "scheduler settings updated: {}, batch {}, cycle {}, retries {}, dead letter after {} failures",
+        settings.cadence(),
+        settings.max_executions_per_batch,
+        settings.max_executions_per_cycle,
+        settings.max_retry_attempts,
+        settings.max_failures_before_dead_letter
+    );
+    Ok(Json(settings))
+}
+
+// query parameters for the dead letter listing
+#[derive(Debug, Clone, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeadLetterQueryParams {
+    /// page size, at most 500
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(minimum = 1)]
+    pub limit: Option<i64>,
+    /// rows to skip
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(minimum = 0)]
+    pub offset: Option<i64>,
+}
+
+// get /api/v1/admin/dead-letter
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/dead-letter",
+    tag = "admin",
+    summary = "List dead-lettered subscriptions",
+    params(DeadLetterQueryParams),
+    responses(
+        (status = 200, description = "OK", body = DeadLetterListResponse),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn list_dead_letter_handler(
+    Query(params): Query<DeadLetterQueryParams>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<DeadLetterListResponse>> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 500);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let subscriptions: Vec<DeadLetterSubscriptionResponse> = app_state
+        .database
+        .queries()
+        .get_dead_letter_subscriptions(limit, offset)
+        .await?
+        .into_iter()
+        .map(|sub| DeadLetterSubscriptionResponse {
+            id: sub.id,
+            subscriber: sub.subscriber,
+            merchant: sub.merchant,
+            chain: sub.chain,
+            token_address: sub.token_address,
+            amount: sub.amount,
+            executed_payments: sub.executed_payments,
+            failure_count: sub.failure_count,
+            last_failure_class: sub.last_failure_class,
+            last_error: sub.last_error,
+            dead_lettered_at: sub.dead_lettered_at,
+        })
+        .collect();
+
+    Ok(Json(DeadLetterListResponse {
+        count: subscriptions.len(),
+        subscriptions,
+        limit,
+        offset,
+    }))
+}
+
+// post /api/v1/admin/dead-letter/:id/requeue - resets the failure count and schedules it again
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/dead-letter/{id}/requeue",
+    tag = "admin",
+    summary = "Return a dead-lettered subscription to ACTIVE",
+    params(("id" = openapi::Hash, Path)),
+    responses(
+        (status = 200, description = "OK", body = RequeueResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 404, response = openapi::NotFound),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn requeue_dead_letter_handler(
+    Path(subscription_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    Extension(actor): Extension<Actor>,
+) -> Result<Json<RequeueResponse>> {
+    if !app_state
+        .database
+        .queries()
+        .requeue_dead_letter_subscription(&subscription_id)
+        .await?
+    {
+        return Err(RelayerError::NotFound(format!(
+            "no dead-lettered subscription {}",
+            subscription_id
+        )));
+    }
+
+    record_admin_action(
+        &app_state,
+        &actor,
+        AdminAction::RequeueDeadLetter,
+        Some(&subscription_id),
+        serde_json::json!({}),
+    )
+    .await?;
+    info!("requeued dead-lettered subscription {}", subscription_id);
+    Ok(Json(RequeueResponse {
+        subscription_id,
+        status: SubscriptionStatus::Active.to_string(),
+    }))
+}
+
+// post /api/v1/admin/subscriptions/:id/sync - overwrites payments, status and nonce with the
+// contract's view of the subscription
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/subscriptions/{id}/sync",
+    tag = "admin",
+    summary = "Overwrite payments, status and nonce with the on-chain subscription",
+    params(("id" = openapi::Hash, Path)),
+    responses(
+        (status = 200, description = "OK", body = AdminSubscriptionResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 404, response = openapi::NotFound),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn admin_sync_subscription_handler(
+    Path(subscription_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    Extension(actor): Extension<Actor>,
+) -> Result<Json<AdminSubscriptionResponse>> {
+    ValidationService::validate_subscription_id_format(&subscription_id)?;
+    let queries = app_state.database.queries();
+    let subscription = queries
+        .get_subscription(&subscription_id)
+        .await?
+        .ok_or_else(|| RelayerError::NotFound("subscription not found".to_string()))?;
+
+    let subscription_id_bytes = hex::decode(&subscription_id[2..])
+        .map_err(|_| RelayerError::Validation("invalid subscription ID format".to_string()))?
+        .try_into()
+        .map_err(|_| RelayerError::Validation("subscription ID must be 32 bytes".to_string()))?;
+    let on_chain = app_state
+        .blockchain_client
+        .get_subscription(subscription_id_bytes, &subscription.chain)
+        .await?
+        .ok_or_else(|| RelayerError::NotFound("subscription not found on chain".to_string()))?;
+
+    let status = SubscriptionStatus::from_on_chain(on_chain.status).ok_or_else(|| {
+        RelayerError::InternalError(format!("unknown on-chain status {}", on_chain.status))
+    })?;
+    let executed_payments = on_chain_count(on_chain.executed_payments, "executed payments")?;
+    let nonce = on_chain_count(on_chain.nonce, "nonce")?;
+    queries
+        .sync_subscription_with_blockchain(
+            &subscription_id,
+            executed_payments,
+            &status.to_string(),
+            nonce,
+        )
+        .await?;
+
+    let details = serde_json::json!({
+        "before": {
+            "status": subscription.status,
+            "executedPayments": subscription.executed_payments,
+            "nonce": subscription.nonce,
+        },
+        "after": {
+            "status": status.to_string(),
+            "executedPayments": executed_payments,
+            "nonce": nonce,
+        },
+    });
+    admin_subscription_action(
+        &app_state,
+        &actor,
+        AdminAction::SyncSubscription,
+        &subscription_id,
+        details,
+    )
+    .await
+}
+
+// post /api/v1/admin/subscriptions/:id/reset-failures - clears the failure count and retry
+// backoff; dead-lettered subscriptions still need a requeue
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/subscriptions/{id}/reset-failures",
+    tag = "admin",
+    summary = "Clear a subscription's failure count and retry backoff",
+    params(("id" = openapi::Hash, Path)),
+    responses(
+        (status = 200, description = "OK", body = AdminSubscriptionResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 404, response = openapi::NotFound),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn admin_reset_failures_handler(
+    Path(subscription_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    Extension(actor): Extension<Actor>,
+) -> Result<Json<AdminSubscriptionResponse>> {
+    ValidationService::validate_subscription_id_format(&subscription_id)?;
+    let queries = app_state.database.queries();
+    let previous = queries
+        .get_subscription(&subscription_id)
+        .await?
+        .map(|subscription| subscription.failure_count);
+    if !queries
+        .reset_subscription_failures(&subscription_id)
+        .await?
+    {
+        return Err(RelayerError::NotFound("subscription not found".to_string()));
+    }
+
+    admin_subscription_action(
+        &app_state,
+        &actor,
+        AdminAction::ResetFailureCount,
+        &subscription_id,
+        serde_json::json!({ "previousFailureCount": previous }),
+    )
+    .await
+}
+
+// post /api/v1/admin/subscriptions/:id/pause - the scheduler skips it until resumed;
+// the on-chain subscription is untouched
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/subscriptions/{id}/pause",
+    tag = "admin",
+    summary = "Stop the scheduler charging a subscription; on-chain state is untouched",
+    params(("id" = openapi::Hash, Path)),
+    responses(
+        (status = 200, description = "OK", body = AdminSubscriptionResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 404, response = openapi::NotFound),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn admin_pause_subscription_handler(
+    Path(subscription_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    Extension(actor): Extension<Actor>,
+) -> Result<Json<AdminSubscriptionResponse>> {
+    set_relayer_pause(&app_state, &actor, &subscription_id, true).await
+}
+
+// post /api/v1/admin/subscriptions/:id/resume - lifts a relayer-side pause
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/subscriptions/{id}/resume",
+    tag = "admin",
+    summary = "Lift a relayer-side pause",
+    params(("id" = openapi::Hash, Path)),
+    responses(
+        (status = 200, description = "OK", body = AdminSubscriptionResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 404, response = openapi::NotFound),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn admin_resume_subscription_handler(
+    Path(subscription_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    Extension(actor): Extension<Actor>,
+) -> Result<Json<AdminSubscriptionResponse>> {
+    set_relayer_pause(&app_state, &actor, &subscription_id, false).await
+}
+
+async fn set_relayer_pause(
+    app_state: &AppState,
+    actor: &Actor,
+    subscription_id: &str,
+    paused: bool,
+) -> Result<Json<AdminSubscriptionResponse>> {
+    ValidationService::validate_subscription_id_format(subscription_id)?;
+    if !app_state
+        .database
+        .queries()
+        .set_subscription_relayer_pause(subscription_id, paused)
+        .await?
+    {
+        return Err(RelayerError::NotFound("subscription not found".to_string()));
+    }
+
+    let action = if paused {
+        AdminAction::PauseSubscription
+    } else {
+        AdminAction::ResumeSubscription
+    };
+    admin_subscription_action(
+        app_state,
+        actor,
+        action,
+        subscription_id,
+        serde_json::json!({}),
+    )
+    .await
+}
+
+// audits an action on one subscription and responds with the subscription's state after it
+async fn admin_subscription_action(
+    app_state: &AppState,
+    actor: &Actor,
+    action: AdminAction,
+    subscription_id: &str,
+    details: serde_json::Value,
+) -> Result<Json<AdminSubscriptionResponse>> {
+    let queries = app_state.database.queries();
+    let subscription = queries
+        .get_subscription(subscription_id)
+        .await?
+        .ok_or_else(|| RelayerError::NotFound("subscription not found".to_string()))?;
+    let retry_state = queries
+        .get_subscription_retry_state(subscription_id)
+        .await?;
+
+    let audit_id =
+        record_admin_action(app_state, actor, action, Some(subscription_id), details).await?;
+    info!("admin {} on subscription {}", action, subscription_id);
+
+    Ok(Json(AdminSubscriptionResponse {
+        subscription_id: subscription.id,
+        status: subscription.status,
+        executed_payments: subscription.executed_payments.max(0) as u64,
+        nonce: subscription.nonce.max(0) as u64,
+        failure_count: subscription.failure_count.max(0) as u32,
+        next_retry_at: retry_state.next_retry_at,
+        relayer_paused_at: retry_state.relayer_paused_at,
+        audit_id,
+    }))
+}
+
+fn on_chain_count(value: U256, field: &str) -> Result<i64> {
+    u64::try_from(value)
+        .ok()
+        .and_then(|value| i64::try_from(value).ok())
+        .ok_or_else(|| RelayerError::InternalError(format!("on-chain {} out of range", field)))
+}
+
+// post /api/v1/admin/scheduler/run - starts a payment cycle now instead of at the next tick
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/scheduler/run",
+    tag = "admin",
+    summary = "Start a payment cycle now, on one chain or all of them",
+    request_body = TriggerCycleRequest,
+    responses(
+        (status = 200, description = "OK", body = TriggerCycleResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn trigger_scheduler_cycle_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(actor): Extension<Actor>,
+    payload: std::result::Result<Json<TriggerCycleRequest>, JsonRejection>,
+) -> Result<Json<TriggerCycleResponse>> {
+    let Json(request) = payload.map_err(|rejection| {
+        RelayerError::Validation(format!("invalid request body: {}", rejection))
+    })?;
+
+    let chain = match request.chain.as_deref() {
+        Some(chain) => Some(
+            app_state
+                .config
+                .chain(chain)
+                .map_err(|e| RelayerError::Validation(e.to_string()))?
+                .name
+                .clone(),
+        ),
+        None => None,
     };
+    let queued = app_state.scheduler_settings.request_cycle(chain.as_deref());
+    if !queued {
+        warn!("scheduler cycle requested but no scheduler is running in this process");
+    }
 
-    let overall_healthy = database_health.healthy && rpc_health.healthy && envio_health.healthy;
-    let status = if overall_healthy {
-        "healthy"
-    } else {
-        "degraded"
+    let chains = match chain {
+        Some(chain) => vec![chain],
+        None => app_state.config.chains.names(),
     };
+    let audit_id = record_admin_action(
+        &app_state,
+        &actor,
+        AdminAction::TriggerSchedulerCycle,
+        request.chain.as_deref(),
+        serde_json::json!({ "chains": chains, "queued": queued }),
+    )
+    .await?;
 
-    let response = HealthResponse {
-        status: status.to_string(),
-        timestamp: Utc::now(),
-        services: HealthServices {
-            database: database_health,
-            rpc: rpc_health,
-            envio: envio_health,
-        },
+    Ok(Json(TriggerCycleResponse {
+        chains,
+        queued,
+        audit_id,
+    }))
+}
+
+// get /api/v1/admin/scheduler/locks - which database session holds each chain's processing lock
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/scheduler/locks",
+    tag = "admin",
+    summary = "Database sessions holding the per-chain processing locks",
+    responses(
+        (status = 200, description = "OK", body = SchedulerLocksResponse),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn get_scheduler_locks_handler(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<SchedulerLocksResponse>> {
+    let queries = app_state.database.queries();
+    let mut locks = Vec::with_capacity(app_state.config.chains.len());
+
+    for chain in app_state.config.chains.iter() {
+        let lock_key = scheduler::processing_lock_key(chain.chain_id);
+        let holder = queries
+            .get_advisory_lock_holder(lock_key)
+            .await?
+            .map(|holder| LockHolderResponse {
+                pid: holder.pid,
+                application_name: holder.application_name,
+                client_addr: holder.client_addr,
+                backend_start: holder.backend_start,
+                state: holder.state,
+                state_change: holder.state_change,
+            });
+        locks.push(SchedulerLockResponse {
+            chain: chain.name.clone(),
+            chain_id: chain.chain_id,
+            lock_key,
+            held: holder.is_some(),
+            holder,
+        });
+    }
+
+    Ok(Json(SchedulerLocksResponse { locks }))
+}
+
+// post /api/v1/admin/hypersync/resync - rewinds the chain's sync checkpoint and re-runs that
+// chain's historical sync from there in the background; refused while the chain is syncing
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/hypersync/resync",
+    tag = "admin",
+    summary = "Rewind a chain's HyperSync checkpoint and re-sync from a block",
+    request_body = HyperSyncResyncRequest,
+    responses(
+        (status = 200, description = "OK", body = HyperSyncResyncResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 409, response = openapi::Conflict),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn hypersync_resync_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(actor): Extension<Actor>,
+    payload: std::result::Result<Json<HyperSyncResyncRequest>, JsonRejection>,
+) -> Result<Json<HyperSyncResyncResponse>> {
+    let Json(request) = payload.map_err(|rejection| {
+        RelayerError::Validation(format!("invalid request body: {}", rejection))
+    })?;
+
+    let hypersync = app_state
+        .hypersync_client
+        .clone()
+        .ok_or_else(|| RelayerError::Validation("HyperSync is not configured".to_string()))?;
+    let chain = app_state
+        .config
+        .chain(&request.chain)
+        .map_err(|e| RelayerError::Validation(e.to_string()))?;
+    // the checkpoint is the last synced block, so the sync resumes at fromBlock
+    let checkpoint = i64::try_from(request.from_block.saturating_sub(1))
+        .map_err(|_| RelayerError::Validation("fromBlock is out of range".to_string()))?;
+
+    // held until the re-sync finishes, so a running sync can't write its checkpoint over ours
+    let guard = hypersync.try_lock_chain(chain.chain_id)?.ok_or_else(|| {
+        RelayerError::Duplicate(format!(
+            "a HyperSync sync of {} is already running",
+            chain.name
+        ))
+    })?;
+
+    let queries = app_state.database.queries();
+    let (previous_synced_block, audit) = queries
+        .rewind_sync_metadata(
+            chain.chain_id as i64,
+            checkpoint,
+            &actor.0,
+            &chain.name,
+            |previous| {
+                serde_json::json!({
+                    "fromBlock": request.from_block,
+                    "previousSyncedBlock": previous,
+                })
+            },
+        )
+        .await?;
+
+    let chain = chain.clone();
+    let queries = Arc::new(queries);
+    let blockchain_client = Arc::new(app_state.blockchain_client.clone());
+    let response = HyperSyncResyncResponse {
+        chain: chain.name.clone(),
+        chain_id: chain.chain_id,
+        from_block: request.from_block,
+        previous_synced_block,
+        audit_id: audit.id,
     };
+    tokio::spawn(async move {
+        info!(
+            "HyperSync re-sync of {} requested from block {}",
+            chain.name, request.from_block
+        );
+        if let Err(e) = hypersync
+            .sync_chain(&guard, &chain, &queries, &blockchain_client)
+            .await
+        {
+            warn!("HyperSync re-sync of {} failed: {}", chain.name, e);
+        }
+    });
 
-    info!("health check completed - status: {}", status);
     Ok(Json(response))
 }
 
-// get /status (enhanced version of health check)
-pub async fn status_check_handler(
+// query parameters for the admin audit log
+#[derive(Debug, Clone, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditLogQueryParams {
+    /// only this action
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<openapi::AdminAction>)]
+    pub action: Option<String>,
+    /// only actions on this subscription, chain or key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// page size, at most 500
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(minimum = 1)]
+    pub limit: Option<i64>,
+    /// rows to skip
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(minimum = 0)]
+    pub offset: Option<i64>,
+}
+
+// get /api/v1/admin/audit-log - newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit-log",
+    tag = "admin",
+    summary = "Admin actions, newest first",
+    params(AuditLogQueryParams),
+    responses(
+        (status = 200, description = "OK", body = AdminAuditLogResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn list_admin_audit_log_handler(
+    Query(params): Query<AuditLogQueryParams>,
     State(app_state): State<Arc<AppState>>,
-) -> Result<Json<serde_json::Value>> {
-    info!("status check requested");
+) -> Result<Json<AdminAuditLogResponse>> {
+    let action = params
+        .action
+        .as_deref()
+        .map(|action| {
+            AdminAction::parse(action).ok_or_else(|| {
+                RelayerError::Validation(format!("unknown admin action: {}", action))
+            })
+        })
+        .transpose()?;
+    let limit = params.limit.unwrap_or(50).clamp(1, 500);
+    let offset = params.offset.unwrap_or(0).max(0);
 
-    let health_response = health_check_handler(State(app_state.clone())).await?;
+    let entries = app_state
+        .database
+        .queries()
+        .get_admin_audit_log(action, params.target.as_deref(), limit, offset)
+        .await?
+        .into_iter()
+        .map(|entry| AdminAuditEntryResponse {
+            id: entry.id,
+            actor: entry.actor,
+            action: entry.action,
+            target: entry.target,
+            details: entry.details,
+            created_at: entry.created_at,
+        })
+        .collect();
+
+    Ok(Json(AdminAuditLogResponse {
+        entries,
+        limit,
+        offset,
+    }))
+}
 
-    let subscription_stats = match app_state
+// admin actions are recorded once they have succeeded; returns the audit entry id
+async fn record_admin_action(
+    app_state: &AppState,
+    actor: &Actor,
+    action: AdminAction,
+    target: Option<&str>,
+    details: serde_json::Value,
+) -> Result<i64> {
+    let entry = app_state
         .database
         .queries()
-        .get_subscription_stats("sepolia")
-        .await
+        .record_admin_action(&actor.0, action, target, &details)
+        .await?;
+    Ok(entry.id)
+}
+
+const MAX_API_KEY_LABEL_LENGTH: usize = 128;
+
+// query parameters for the api key listing
+#[derive(Debug, Clone, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ApiKeyQueryParams {
+    /// only keys scoped to this merchant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<openapi::Address>)]
+    pub merchant: Option<String>,
+}
+
+// post /api/v1/admin/api-keys - the plaintext key is only returned here
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/api-keys",
+    tag = "admin",
+    summary = "Create an API key; the plaintext key is only returned here",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 200, description = "OK", body = ApiKeyResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn create_api_key_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(actor): Extension<Actor>,
+    payload: std::result::Result<Json<CreateApiKeyRequest>, JsonRejection>,
+) -> Result<Json<ApiKeyResponse>> {
+    let Json(request) = payload.map_err(|rejection| {
+        RelayerError::Validation(format!("invalid request body: {}", rejection))
+    })?;
+
+    let role = ApiKeyRole::parse(&request.role).ok_or_else(|| {
+        RelayerError::Validation(format!("unknown api key role: {}", request.role))
+    })?;
+    match (role, request.merchant.as_deref()) {
+        (ApiKeyRole::Merchant, Some(merchant)) => {
+            ValidationService::validate_address_format(merchant)?;
+        }
+        (ApiKeyRole::Merchant, None) => {
+            return Err(RelayerError::Validation(
+                "merchant keys need a merchant address".to_string(),
+            ));
+        }
+        (ApiKeyRole::Admin, Some(_)) => {
+            return Err(RelayerError::Validation(
+                "admin keys can't be scoped to a merchant".to_string(),
+            ));
+        }
+        (ApiKeyRole::Admin, None) => {}
+    }
+    if request
+        .label
+        .as_ref()
+        .is_some_and(|label| label.len() > MAX_API_KEY_LABEL_LENGTH)
     {
-        Ok((active, paused, total)) => serde_json::json!({
-            "active": active,
-            "paused": paused,
-            "total": total
-        }),
-        Err(_) => serde_json::json!({
-            "error": "failed to fetch subscription stats"
+        return Err(RelayerError::Validation(
+            "api key label is too long".to_string(),
+        ));
+    }
+
+    let key = auth::generate_api_key();
+    let created = app_state
+        .database
+        .queries()
+        .create_api_key(
+            role,
+            request.merchant.as_deref(),
+            request.label.as_deref(),
+            &auth::hash_api_key(&key),
+            &auth::key_prefix(&key),
+        )
+        .await?;
+    record_admin_action(
+        &app_state,
+        &actor,
+        AdminAction::CreateApiKey,
+        Some(&created.id.to_string()),
+        serde_json::json!({
+            "role": created.role,
+            "merchant": created.merchant,
+            "keyPrefix": created.key_prefix,
         }),
-    };
+    )
+    .await?;
+    info!(
+        "created {} api key {} ({})",
+        created.role, created.id, created.key_prefix
+    );
 
-    let status_response = serde_json::json!({
-        "service": "aurum-relayer",
-        "version": env!("CARGO_PKG_VERSION"),
-        "timestamp": Utc::now(),
-        "health": health_response.0,
-        "subscriptions": subscription_stats,
-        "config": {
-            "chains_supported": ["sepolia", "base"],
-            "api_version": "v1",
-            "features": [
-                "intent_submission",
-                "subscription_tracking",
-                "merchant_analytics",
-                "envio_integration",
-                "distributed_processing"
-            ]
+    Ok(Json(ApiKeyResponse {
+        key: Some(key),
+        ..api_key_response(created)
+    }))
+}
+
+// get /api/v1/admin/api-keys
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/api-keys",
+    tag = "admin",
+    summary = "List unrevoked API keys",
+    params(ApiKeyQueryParams),
+    responses(
+        (status = 200, description = "OK", body = ApiKeyListResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn list_api_keys_handler(
+    Query(params): Query<ApiKeyQueryParams>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<ApiKeyListResponse>> {
+    if let Some(merchant) = &params.merchant {
+        ValidationService::validate_address_format(merchant)?;
+    }
+
+    let keys = app_state
+        .database
+        .queries()
+        .get_api_keys(params.merchant.as_deref())
+        .await?
+        .into_iter()
+        .map(api_key_response)
+        .collect();
+
+    Ok(Json(ApiKeyListResponse { keys }))
+}
+
+// delete /api/v1/admin/api-keys/:id - takes effect on the key's next request
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/api-keys/{id}",
+    tag = "admin",
+    summary = "Revoke an API key",
+    params(("id" = i64, Path)),
+    responses(
+        (status = 200, description = "OK", body = ApiKeyListResponse),
+        (status = 403, response = openapi::Forbidden),
+        (status = 404, response = openapi::NotFound),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn revoke_api_key_handler(
+    Path(key_id): Path<i64>,
+    State(app_state): State<Arc<AppState>>,
+    Extension(actor): Extension<Actor>,
+) -> Result<Json<ApiKeyListResponse>> {
+    let queries = app_state.database.queries();
+    if !queries.revoke_api_key(key_id).await? {
+        return Err(RelayerError::NotFound(format!(
+            "api key {} not found",
+            key_id
+        )));
+    }
+    record_admin_action(
+        &app_state,
+        &actor,
+        AdminAction::RevokeApiKey,
+        Some(&key_id.to_string()),
+        serde_json::json!({}),
+    )
+    .await?;
+    info!("revoked api key {}", key_id);
+
+    let keys = queries
+        .get_api_keys(None)
+        .await?
+        .into_iter()
+        .map(api_key_response)
+        .collect();
+
+    Ok(Json(ApiKeyListResponse { keys }))
+}
+
+fn api_key_response(key: ApiKey) -> ApiKeyResponse {
+    ApiKeyResponse {
+        id: key.id,
+        key_prefix: key.key_prefix,
+        role: key.role,
+        merchant: key.merchant,
+        label: key.label,
+        created_at: key.created_at,
+        last_used_at: key.last_used_at,
+        key: None,
+    }
+}
+
+const MAX_WEBHOOKS_PER_MERCHANT: usize = 10;
+const MAX_WEBHOOK_URL_LENGTH: usize = 2048;
+
+// post /api/v1/merchant/:address/webhooks - the signing secret is only returned here
+#[utoipa::path(
+    post,
+    path = "/api/v1/merchant/{address}/webhooks",
+    tag = "webhooks",
+    summary = "Register a webhook endpoint",
+    params(("address" = openapi::Address, Path)),
+    request_body = RegisterWebhookRequest,
+    responses(
+        (status = 200, description = "OK", body = WebhookResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn register_webhook_handler(
+    Path(merchant_address): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    payload: std::result::Result<Json<RegisterWebhookRequest>, JsonRejection>,
+) -> Result<Json<WebhookResponse>> {
+    let Json(request) = payload.map_err(|rejection| {
+        RelayerError::Validation(format!("invalid request body: {}", rejection))
+    })?;
+    ValidationService::validate_address_format(&merchant_address)?;
+
+    if request.url.len() > MAX_WEBHOOK_URL_LENGTH {
+        return Err(RelayerError::Validation(
+            "webhook url is too long".to_string(),
+        ));
+    }
+    let url = url::Url::parse(&request.url)
+        .map_err(|e| RelayerError::Validation(format!("invalid webhook url: {}", e)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(RelayerError::Validation(
+            "webhook url must use http or https".to_string(),
+        ));
+    }
+    if !app_state.config.webhook_allow_private_targets {
+        webhooks::resolve_public_target(&url).await?;
+    }
+
+    let mut events = Vec::new();
+    for event in &request.events {
+        let event_type = WebhookEventType::parse(event).ok_or_else(|| {
+            RelayerError::Validation(format!("unknown webhook event type: {}", event))
+        })?;
+        if !events.contains(&event_type.to_string()) {
+            events.push(event_type.to_string());
         }
-    });
+    }
+
+    let queries = app_state.database.queries();
+    if queries
+        .get_merchant_webhooks(&merchant_address)
+        .await?
+        .len()
+        >= MAX_WEBHOOKS_PER_MERCHANT
+    {
+        return Err(RelayerError::Validation(format!(
+            "a merchant can register at most {} webhooks",
+            MAX_WEBHOOKS_PER_MERCHANT
+        )));
+    }
+
+    let secret = webhooks::generate_secret();
+    let webhook = queries
+        .create_merchant_webhook(&merchant_address, url.as_str(), &secret, &events)
+        .await?;
+    info!(
+        "registered webhook {} for merchant {} -> {}",
+        webhook.id, webhook.merchant, webhook.url
+    );
 
-    Ok(Json(status_response))
+    Ok(Json(WebhookResponse {
+        secret: Some(secret),
+        ..webhook_response(webhook)
+    }))
 }
 
-fn token_stats_to_response(stats: &TokenStats) -> TokenStatsResponse {
-    TokenStatsResponse {
-        token_address: stats.token_address.clone(),
-        token_symbol: stats.token_symbol.clone(),
-        total_subscriptions: stats.total_subscriptions,
-        active_subscriptions: stats.active_subscriptions,
-        total_revenue: stats.total_revenue.clone(),
-        total_payments: stats.total_payments,
-        average_transaction_value: stats.average_transaction_value.clone(),
-        chain_id: stats.chain_id,
+// get /api/v1/merchant/:address/webhooks
+#[utoipa::path(
+    get,
+    path = "/api/v1/merchant/{address}/webhooks",
+    tag = "webhooks",
+    summary = "List webhook endpoints",
+    params(("address" = openapi::Address, Path)),
+    responses(
+        (status = 200, description = "OK", body = WebhookListResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn list_webhooks_handler(
+    Path(merchant_address): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<WebhookListResponse>> {
+    ValidationService::validate_address_format(&merchant_address)?;
+
+    let webhooks = app_state
+        .database
+        .queries()
+        .get_merchant_webhooks(&merchant_address)
+        .await?
+        .into_iter()
+        .map(webhook_response)
+        .collect();
+
+    Ok(Json(WebhookListResponse { webhooks }))
+}
+
+// delete /api/v1/merchant/:address/webhooks/:id - stops new deliveries, keeps the log
+#[utoipa::path(
+    delete,
+    path = "/api/v1/merchant/{address}/webhooks/{id}",
+    tag = "webhooks",
+    summary = "Stop deliveries to a webhook; its delivery log is kept",
+    params(
+        ("address" = openapi::Address, Path),
+        ("id" = i64, Path),
+    ),
+    responses(
+        (status = 200, description = "OK", body = WebhookListResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 404, response = openapi::NotFound),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn delete_webhook_handler(
+    Path((merchant_address, webhook_id)): Path<(String, i64)>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<WebhookListResponse>> {
+    ValidationService::validate_address_format(&merchant_address)?;
+
+    let queries = app_state.database.queries();
+    if !queries
+        .deactivate_merchant_webhook(&merchant_address, webhook_id)
+        .await?
+    {
+        return Err(RelayerError::NotFound(format!(
+            "webhook {} not found",
+            webhook_id
+        )));
     }
+    info!(
+        "removed webhook {} for merchant {}",
+        webhook_id, merchant_address
+    );
+
+    let webhooks = queries
+        .get_merchant_webhooks(&merchant_address)
+        .await?
+        .into_iter()
+        .map(webhook_response)
+        .collect();
+    Ok(Json(WebhookListResponse { webhooks }))
+}
+
+// query parameters for the webhook delivery log
+#[derive(Debug, Clone, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebhookDeliveryQueryParams {
+    /// only deliveries to this webhook
+    #[serde(default, rename = "webhookId", skip_serializing_if = "Option::is_none")]
+    pub webhook_id: Option<i64>,
+    /// only deliveries in this status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(value_type = Option<openapi::WebhookDeliveryStatus>)]
+    pub status: Option<String>,
+    /// page size, at most 500
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(minimum = 1)]
+    pub limit: Option<i64>,
+    /// rows to skip
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(minimum = 0)]
+    pub offset: Option<i64>,
 }
 
-pub async fn metrics_handler(
+// get /api/v1/merchant/:address/webhooks/deliveries
+#[utoipa::path(
+    get,
+    path = "/api/v1/merchant/{address}/webhooks/deliveries",
+    tag = "webhooks",
+    summary = "Webhook delivery log, newest first",
+    params(
+        ("address" = openapi::Address, Path),
+        WebhookDeliveryQueryParams,
+    ),
+    responses(
+        (status = 200, description = "OK", body = WebhookDeliveriesResponse),
+        (status = 400, response = openapi::BadRequest),
+        (status = 403, response = openapi::Forbidden),
+        (status = 500, response = openapi::InternalError),
+    )
+)]
+pub async fn list_webhook_deliveries_handler(
+    Path(merchant_address): Path<String>,
+    Query(params): Query<WebhookDeliveryQueryParams>,
     State(app_state): State<Arc<AppState>>,
-) -> Result<Json<MetricsSnapshot>> {
-    let snapshot = app_state.metrics.snapshot();
-    Ok(Json(snapshot))
+) -> Result<Json<WebhookDeliveriesResponse>> {
+    ValidationService::validate_address_format(&merchant_address)?;
+
+    let status = match params.status.as_deref().map(str::to_uppercase).as_deref() {
+        None => None,
+        Some("PENDING") => Some(WebhookDeliveryStatus::Pending),
+        Some("DELIVERED") => Some(WebhookDeliveryStatus::Delivered),
+        Some("FAILED") => Some(WebhookDeliveryStatus::Failed),
+        Some(other) => {
+            return Err(RelayerError::Validation(format!(
+                "unknown delivery status: {}",
+                other
+            )))
+        }
+    };
+    let limit = params.limit.unwrap_or(50).clamp(1, 500);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let deliveries: Vec<WebhookDeliveryResponse> = app_state
+        .database
+        .queries()
+        .get_webhook_deliveries(&merchant_address, params.webhook_id, status, limit, offset)
+        .await?
+        .into_iter()
+        .map(|delivery| {
+            let pending = delivery.status == WebhookDeliveryStatus::Pending.to_string();
+            WebhookDeliveryResponse {
+                id: delivery.id,
+                webhook_id: delivery.webhook_id,
+                event_id: delivery.event_id.to_string(),
+                event_type: delivery.event_type,
+                payload: delivery.payload,
+                status: delivery.status,
+                attempts: delivery.attempts,
+                next_attempt_at: pending.then_some(delivery.next_attempt_at),
+                response_status: delivery.response_status,
+                last_error: delivery.last_error,
+                delivered_at: delivery.delivered_at,
+                created_at: delivery.created_at,
+            }
+        })
+        .collect();
+
+    Ok(Json(WebhookDeliveriesResponse {
+        count: deliveries.len(),
+        deliveries,
+        limit,
+        offset,
+    }))
+}
+
+fn webhook_response(webhook: MerchantWebhook) -> WebhookResponse {
+    WebhookResponse {
+        id: webhook.id,
+        merchant: webhook.merchant,
+        url: webhook.url,
+        events: webhook.event_types,
+        active: webhook.active,
+        created_at: webhook.created_at,
+        secret: None,
+    }
 }
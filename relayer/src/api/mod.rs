@@ -1,10 +1,18 @@
+pub mod auth;
+pub mod client;
+pub mod export;
 pub mod handlers;
+pub mod openapi;
+pub mod pagination;
+pub mod proof;
+pub mod rate_limit;
 pub mod routes;
 pub mod types;
 pub mod validation;
 
 use axum::{
     body::Body,
+    extract::{MatchedPath, State},
     http::{header, HeaderValue, Method, Request, StatusCode},
     middleware,
     response::Response,
@@ -14,7 +22,7 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use tracing::info;
 
-use crate::{AppState, RelayerError};
+use crate::{AppState, Config, RelayerError};
 
 pub struct ApiServer;
 
@@ -22,8 +30,9 @@ impl ApiServer {
     pub async fn create(app_state: Arc<AppState>) -> Router {
         info!("initializing REST API server");
 
-        let api_router = routes::create_api_routes(app_state)
-            .layer(middleware::from_fn(logging_and_cors_middleware));
+        let api_router = routes::create_api_routes(app_state.clone()).layer(
+            middleware::from_fn_with_state(app_state, logging_and_cors_middleware),
+        );
 
         info!("REST API server initialized successfully");
         api_router
@@ -50,19 +59,27 @@ impl ApiServer {
     }
 }
 
-fn apply_cors_headers(response: &mut Response) {
+// echoes the request origin when it is on CORS_ALLOWED_ORIGINS; other origins get no cors headers
+fn apply_cors_headers(response: &mut Response, config: &Config, origin: Option<&HeaderValue>) {
+    let Some(origin) = origin.filter(|origin| {
+        origin
+            .to_str()
+            .is_ok_and(|origin| config.cors_origin_allowed(origin))
+    }) else {
+        return;
+    };
+
     let headers = response.headers_mut();
-    headers.insert(
-        header::ACCESS_CONTROL_ALLOW_ORIGIN,
-        HeaderValue::from_static("*"),
-    );
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+    headers.insert(header::VARY, HeaderValue::from_static("Origin"));
     headers.insert(
         header::ACCESS_CONTROL_ALLOW_METHODS,
-        HeaderValue::from_static("GET,POST,OPTIONS"),
+        HeaderValue::from_static("GET,POST,PUT,DELETE,OPTIONS"),
     );
+    // listed explicitly: a "*" wildcard doesn't cover Authorization
     headers.insert(
         header::ACCESS_CONTROL_ALLOW_HEADERS,
-        HeaderValue::from_static("*"),
+        HeaderValue::from_static("authorization,content-type,x-api-key"),
     );
     headers.insert(
         header::ACCESS_CONTROL_MAX_AGE,
@@ -70,13 +87,19 @@ fn apply_cors_headers(response: &mut Response) {
     );
 }
 
-async fn logging_and_cors_middleware(req: Request<Body>, next: middleware::Next) -> Response {
+async fn logging_and_cors_middleware(
+    State(app_state): State<Arc<AppState>>,
+    req: Request<Body>,
+    next: middleware::Next,
+) -> Response {
+    let origin = req.headers().get(header::ORIGIN).cloned();
+
     if req.method() == Method::OPTIONS {
         let mut response = Response::builder()
             .status(StatusCode::NO_CONTENT)
             .body(Body::empty())
             .unwrap();
-        apply_cors_headers(&mut response);
+        apply_cors_headers(&mut response, &app_state.config, origin.as_ref());
         response
     } else {
         let method = req.method().clone();
@@ -84,7 +107,7 @@ async fn logging_and_cors_middleware(req: Request<Body>, next: middleware::Next)
         let start = std::time::Instant::now();
 
         let mut response = next.run(req).await;
-        apply_cors_headers(&mut response);
+        apply_cors_headers(&mut response, &app_state.config, origin.as_ref());
 
         info!(
             method = %method,
@@ -97,3 +120,28 @@ async fn logging_and_cors_middleware(req: Request<Body>, next: middleware::Next)
         response
     }
 }
+
+// per-route latency for /metrics; labelled by the route template so ids don't blow up cardinality
+async fn record_request_metrics(
+    State(app_state): State<Arc<AppState>>,
+    req: Request<Body>,
+    next: middleware::Next,
+) -> Response {
+    let method = req.method().clone();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let start = std::time::Instant::now();
+
+    let response = next.run(req).await;
+
+    app_state.metrics.observe_api_request(
+        method.as_str(),
+        &route,
+        response.status().as_u16(),
+        start.elapsed(),
+    );
+    response
+}
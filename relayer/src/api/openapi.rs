@@ -0,0 +1,964 @@
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use utoipa::openapi::path::{Operation, PathItem};
+use utoipa::openapi::response::{Response, ResponseBuilder};
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+use utoipa::openapi::security::{
+    ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+};
+use utoipa::openapi::{ContentBuilder, HeaderBuilder, Ref, RefOr};
+use utoipa::{Modify, OpenApi, PartialSchema, ToResponse, ToSchema};
+
+use super::auth::{self, RouteAccess};
+use super::routes;
+use super::types::ErrorResponse;
+use crate::database::models;
+use crate::RelayerError;
+
+pub const OPENAPI_ROUTE: &str = "/api/v1/openapi.json";
+
+static DOCUMENT: Lazy<Value> = Lazy::new(build_document);
+
+// the OpenAPI 3.1 description of the api. paths come from the #[utoipa::path] annotations the
+// router in routes.rs is built from, schemas from the request and response types
+pub fn document() -> &'static Value {
+    &DOCUMENT
+}
+
+// a component schema for values the api types carry as plain strings or integers, referenced
+// from fields with #[schema(value_type = openapi::...)]
+macro_rules! named_schema {
+    ($name:ident, $schema:expr) => {
+        pub struct $name;
+
+        impl PartialSchema for $name {
+            fn schema() -> RefOr<Schema> {
+                $schema.into()
+            }
+        }
+
+        impl ToSchema for $name {}
+    };
+}
+
+fn string_pattern(pattern: &str) -> ObjectBuilder {
+    ObjectBuilder::new()
+        .schema_type(Type::String)
+        .pattern(Some(pattern))
+}
+
+fn string_enum<V: ToString>(values: impl IntoIterator<Item = V>) -> ObjectBuilder {
+    ObjectBuilder::new()
+        .schema_type(Type::String)
+        .enum_values(Some(values.into_iter().map(|value| value.to_string())))
+}
+
+named_schema!(Address, string_pattern("^0x[0-9a-fA-F]{40}$"));
+named_schema!(
+    TokenAddress,
+    string_pattern("^0x([0-9a-fA-F]{40}|0)$")
+        .description(Some("ERC-20 address, or 0x0 for the native token"))
+);
+named_schema!(
+    Wei,
+    string_pattern("^[1-9][0-9]*$|^0$").description(Some(
+        "integer amount in the token's smallest unit, as a decimal string"
+    ))
+);
+named_schema!(Hash, string_pattern("^0x[0-9a-fA-F]{64}$"));
+named_schema!(Signature, string_pattern("^0x[0-9a-fA-F]{130}$"));
+named_schema!(
+    UnixSeconds,
+    ObjectBuilder::new()
+        .schema_type(Type::Integer)
+        .minimum(Some(0))
+        .description(Some("unix timestamp in seconds"))
+);
+named_schema!(
+    ErrorCode,
+    string_enum(RelayerError::CODES).description(Some(
+        "stable machine readable code; match on this rather than the message"
+    ))
+);
+named_schema!(
+    SubscriptionStatus,
+    string_enum([
+        models::SubscriptionStatus::Pending,
+        models::SubscriptionStatus::Active,
+        models::SubscriptionStatus::Paused,
+        models::SubscriptionStatus::Cancelled,
+        models::SubscriptionStatus::Expired,
+        models::SubscriptionStatus::Completed,
+        models::SubscriptionStatus::DeadLetter,
+    ])
+);
+named_schema!(
+    ExecutionStatus,
+    string_enum([
+        models::ExecutionStatus::Success,
+        models::ExecutionStatus::Confirmed,
+        models::ExecutionStatus::Orphaned,
+        models::ExecutionStatus::Failed,
+        models::ExecutionStatus::Pending,
+    ])
+);
+// settlement exports only carry mined payments
+named_schema!(
+    SettledStatus,
+    string_enum([
+        models::ExecutionStatus::Success,
+        models::ExecutionStatus::Confirmed,
+    ])
+);
+named_schema!(
+    AvailStatus,
+    string_enum([
+        models::AvailStatus::Pending,
+        models::AvailStatus::Submitted,
+        models::AvailStatus::Failed,
+        models::AvailStatus::Disabled,
+    ])
+    .description(Some(
+        "intents are accepted as PENDING and posted to Avail in the background"
+    ))
+);
+named_schema!(
+    AdminAction,
+    string_enum(models::AdminAction::ALL.map(models::AdminAction::as_str))
+);
+named_schema!(
+    ApiKeyRole,
+    string_enum(
+        [models::ApiKeyRole::Merchant, models::ApiKeyRole::Admin].map(models::ApiKeyRole::as_str)
+    )
+);
+named_schema!(
+    WebhookEventType,
+    string_enum(models::WebhookEventType::ALL.map(|event| event.as_str()))
+);
+named_schema!(
+    WebhookDeliveryStatus,
+    string_enum([
+        models::WebhookDeliveryStatus::Pending,
+        models::WebhookDeliveryStatus::Delivered,
+        models::WebhookDeliveryStatus::Failed,
+    ])
+);
+named_schema!(HealthStatus, string_enum(["healthy", "degraded"]));
+named_schema!(
+    TransactionSource,
+    string_enum(["postgres", "hypersync", "rpc", "envio"])
+);
+
+// an error response shared by every route, registered under components.responses
+macro_rules! error_response {
+    ($name:ident, $description:literal) => {
+        error_response!($name, $description, |response| response);
+    };
+    ($name:ident, $description:literal, $extend:expr) => {
+        pub struct $name;
+
+        impl<'r> ToResponse<'r> for $name {
+            fn response() -> (&'r str, RefOr<Response>) {
+                let response = ResponseBuilder::new().description($description).content(
+                    "application/json",
+                    ContentBuilder::new()
+                        .schema(Some(Ref::from_schema_name(ErrorResponse::name())))
+                        .build(),
+                );
+                let extend: fn(ResponseBuilder) -> ResponseBuilder = $extend;
+                (stringify!($name), extend(response).build().into())
+            }
+        }
+    };
+}
+
+error_response!(BadRequest, "the request failed validation");
+error_response!(Unauthorized, "missing or invalid api key");
+error_response!(Forbidden, "the api key isn't allowed to use this route");
+error_response!(NotFound, "the resource doesn't exist");
+error_response!(Conflict, "duplicate or conflicting request");
+error_response!(
+    Unprocessable,
+    "the transaction reverted or couldn't be executed"
+);
+error_response!(
+    RateLimited,
+    "too many requests; retry after the given number of seconds",
+    |response| response.header(
+        "Retry-After",
+        HeaderBuilder::new()
+            .schema(
+                ObjectBuilder::new()
+                    .schema_type(Type::Integer)
+                    .minimum(Some(1))
+            )
+            .build(),
+    )
+);
+error_response!(InternalError, "internal error");
+error_response!(BadGateway, "an upstream rpc call failed");
+error_response!(
+    ServiceUnavailable,
+    "a backend this route needs is disabled on this relayer"
+);
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "apiKey",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(auth::API_KEY_HEADER))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Aurum Relayer API",
+        description = "Relays signed subscription intents and executes recurring payments. \
+            Errors share the ErrorResponse schema; rate limited requests get 429 with Retry-After."
+    ),
+    tags(
+        (name = "subscriptions"),
+        (name = "merchants"),
+        (name = "webhooks"),
+        (name = "admin"),
+        (name = "service")
+    ),
+    components(
+        schemas(ErrorResponse),
+        responses(
+            BadRequest,
+            Unauthorized,
+            Forbidden,
+            NotFound,
+            Conflict,
+            Unprocessable,
+            RateLimited,
+            InternalError,
+            BadGateway,
+            ServiceUnavailable
+        )
+    ),
+    modifiers(&SecuritySchemes)
+)]
+struct ApiDoc;
+
+// security, the access note and the 401 response follow from the route's prefix, the same way
+// auth::authenticate decides who may call it
+fn apply_access(path: &str, item: &mut PathItem) {
+    let access = auth::route_access(&routes::axum_path(path));
+    let (security, note) = match access {
+        RouteAccess::Public => (Vec::new(), "Public."),
+        RouteAccess::Merchant => (
+            vec![
+                SecurityRequirement::new("apiKey", Vec::<String>::new()),
+                SecurityRequirement::new("bearer", Vec::<String>::new()),
+            ],
+            "Requires the merchant's API key or an admin key.",
+        ),
+        RouteAccess::Admin => (
+            vec![
+                SecurityRequirement::new("apiKey", Vec::<String>::new()),
+                SecurityRequirement::new("bearer", Vec::<String>::new()),
+            ],
+            "Requires an admin API key or ADMIN_API_TOKEN.",
+        ),
+    };
+
+    let operations = [
+        &mut item.get,
+        &mut item.put,
+        &mut item.post,
+        &mut item.delete,
+        &mut item.patch,
+    ];
+    for operation in operations.into_iter().flatten() {
+        describe_access(operation, security.clone(), note);
+    }
+}
+
+fn describe_access(operation: &mut Operation, security: Vec<SecurityRequirement>, note: &str) {
+    operation.security = Some(security);
+    operation.description = Some(match operation.description.take() {
+        Some(description) => format!("{} {}", note, description),
+        None => note.to_string(),
+    });
+    // an invalid key is rejected on every route, so 401 is always possible
+    let (name, _) = Unauthorized::response();
+    operation
+        .responses
+        .responses
+        .entry("401".to_string())
+        .or_insert_with(|| RefOr::Ref(Ref::from_response_name(name)));
+}
+
+fn build_document() -> Value {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(routes::ApiPaths::openapi());
+    for (path, item) in openapi.paths.paths.iter_mut() {
+        apply_access(path, item);
+    }
+
+    serde_json::to_value(&openapi).expect("the OpenAPI document serializes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::export::SettlementRow;
+    use crate::api::handlers::TransactionQueryParams;
+    use crate::api::types::*;
+    use crate::avail::merkle::{ProofStep, SiblingPosition};
+    use crate::database::models::{
+        AdminAction, ApiKeyRole, AvailStatus, SubscriptionStatus, WebhookDeliveryStatus,
+        WebhookEventType,
+    };
+    use crate::scheduler::SchedulerSettings;
+    use crate::RelayerError;
+    use axum::response::IntoResponse;
+    use serde::Serialize;
+    use serde_json::json;
+    use std::collections::{BTreeSet, HashMap};
+
+    const HASH: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+    const ADDRESS: &str = "0x2222222222222222222222222222222222222222";
+
+    fn documented_routes() -> BTreeSet<(String, String)> {
+        document()["paths"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(path, item)| {
+                item.as_object()
+                    .unwrap()
+                    .keys()
+                    .map(move |method| (path.clone(), method.clone()))
+            })
+            .collect()
+    }
+
+    fn resolve(schema: &Value) -> &Value {
+        match schema["$ref"].as_str() {
+            Some(reference) => resolve(
+                document()
+                    .pointer(reference.strip_prefix('#').unwrap())
+                    .unwrap_or_else(|| panic!("unresolved {}", reference)),
+            ),
+            None => schema,
+        }
+    }
+
+    fn type_matches(schema_type: &str, value: &Value) -> bool {
+        match schema_type {
+            "null" => value.is_null(),
+            "boolean" => value.is_boolean(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "string" => value.is_string(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            other => panic!("unknown schema type {}", other),
+        }
+    }
+
+    // checks a serialized value against a schema the way a client generated from the
+    // document would read it
+    fn validate(schema: &Value, value: &Value, at: &str) -> std::result::Result<(), String> {
+        let schema = resolve(schema);
+        for key in ["oneOf", "anyOf"] {
+            if let Some(branches) = schema[key].as_array() {
+                let errors: Vec<String> = branches
+                    .iter()
+                    .filter_map(|branch| validate(branch, value, at).err())
+                    .collect();
+                if errors.len() == branches.len() {
+                    return Err(format!("{}: no {} branch matches: {:?}", at, key, errors));
+                }
+            }
+        }
+        if let Some(branches) = schema["allOf"].as_array() {
+            for branch in branches {
+                validate(branch, value, at)?;
+            }
+        }
+
+        let types: Vec<&str> = match &schema["type"] {
+            Value::String(schema_type) => vec![schema_type.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| type_matches(t, value)) {
+            return Err(format!("{}: {} is not {:?}", at, value, types));
+        }
+        if let Some(values) = schema["enum"].as_array() {
+            if !values.contains(value) {
+                return Err(format!("{}: {} is not one of {:?}", at, value, values));
+            }
+        }
+
+        match value {
+            Value::Array(items) if schema.get("items").is_some() => {
+                for (index, item) in items.iter().enumerate() {
+                    validate(&schema["items"], item, &format!("{}[{}]", at, index))?;
+                }
+            }
+            Value::Object(fields) => {
+                let properties = schema["properties"].as_object();
+                for (key, field) in fields {
+                    let at = format!("{}.{}", at, key);
+                    match properties.and_then(|properties| properties.get(key)) {
+                        Some(property) => validate(property, field, &at)?,
+                        None => match &schema["additionalProperties"] {
+                            Value::Object(_) => {
+                                validate(&schema["additionalProperties"], field, &at)?
+                            }
+                            Value::Bool(false) => return Err(format!("{} isn't documented", at)),
+                            _ if properties.is_some() => {
+                                return Err(format!("{} isn't documented", at))
+                            }
+                            _ => {}
+                        },
+                    }
+                }
+                for required in schema["required"].as_array().into_iter().flatten() {
+                    let required = required.as_str().unwrap();
+                    if !fields.contains_key(required) {
+                        return Err(format!("{}.{} is required but missing", at, required));
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // the sample validates against the schema, and every documented property is serialized;
+    // samples set every optional field
+    fn assert_documents(schema: &str, sample: impl Serialize) {
+        let value = serde_json::to_value(sample).unwrap();
+        let reference = json!({ "$ref": format!("#/components/schemas/{}", schema) });
+        if let Err(error) = validate(&reference, &value, schema) {
+            panic!("{} schema is out of date: {}", schema, error);
+        }
+        let fields: BTreeSet<&String> = value.as_object().unwrap().keys().collect();
+        let properties: BTreeSet<&String> = resolve(&reference)["properties"]
+            .as_object()
+            .unwrap_or_else(|| panic!("{} has no properties", schema))
+            .keys()
+            .collect();
+        assert_eq!(fields, properties, "{} schema is out of date", schema);
+    }
+
+    #[test]
+    fn test_documented_routes_are_served() {
+        // panics if any documented operation has no handler
+        let _router = routes::api_router();
+        let served: BTreeSet<(String, String)> = routes::ApiPaths::openapi()
+            .paths
+            .paths
+            .iter()
+            .flat_map(|(path, item)| {
+                let item = serde_json::to_value(item).unwrap();
+                item.as_object()
+                    .unwrap()
+                    .keys()
+                    .map(|method| (path.clone(), method.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert!(served.len() > 30);
+        assert_eq!(served, documented_routes());
+
+        for (path, method) in documented_routes() {
+            let operation = &document()["paths"][&path][&method];
+            let declared: BTreeSet<&str> = operation["parameters"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|param| param["in"] == "path")
+                .map(|param| param["name"].as_str().unwrap())
+                .collect();
+            let route = routes::axum_path(&path);
+            let in_path: BTreeSet<&str> = route
+                .split('/')
+                .filter_map(|segment| segment.strip_prefix(':'))
+                .collect();
+            assert_eq!(declared, in_path, "{} {} path parameters", method, path);
+
+            let public = auth::route_access(&route) == RouteAccess::Public;
+            assert_eq!(
+                operation["security"].as_array().unwrap().is_empty(),
+                public,
+                "{} {} security",
+                method,
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_reference_resolves() {
+        fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+            match value {
+                Value::Object(map) => {
+                    if let Some(Value::String(reference)) = map.get("$ref") {
+                        refs.push(reference.clone());
+                    }
+                    map.values().for_each(|value| collect_refs(value, refs));
+                }
+                Value::Array(items) => items.iter().for_each(|value| collect_refs(value, refs)),
+                _ => {}
+            }
+        }
+
+        let mut refs = Vec::new();
+        collect_refs(document(), &mut refs);
+        assert!(!refs.is_empty());
+        for reference in refs {
+            let pointer = reference.strip_prefix('#').unwrap();
+            assert!(
+                document().pointer(pointer).is_some(),
+                "unresolved {}",
+                reference
+            );
+        }
+    }
+
+    #[test]
+    fn test_transaction_query_params_are_documented() {
+        let params = TransactionQueryParams {
+            page: Some(0),
+            size: Some(20),
+            cursor: Some("00".to_string()),
+            source: Some("hypersync".to_string()),
+            use_hypersync: Some(true),
+            from_block: Some(1),
+            to_block: Some(2),
+            from_time: Some("1".to_string()),
+            to_time: Some("2".to_string()),
+            chain: Some("sepolia".to_string()),
+            token: Some(ADDRESS.to_string()),
+            subscription_id: Some(HASH.to_string()),
+            status: Some("SUCCESS".to_string()),
+            min_amount: Some("1".to_string()),
+        };
+        let fields: BTreeSet<String> = serde_json::to_value(params)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        let documented: BTreeSet<String> = document()["paths"]
+            ["/api/v1/merchant/{address}/transactions"]["get"]["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|param| param["in"] == "query")
+            .map(|param| param["name"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(fields, documented);
+    }
+
+    #[tokio::test]
+    async fn test_error_schema_matches_error_responses() {
+        let error = RelayerError::UnsupportedToken {
+            token: ADDRESS.to_string(),
+            chain: "sepolia".to_string(),
+            supported: vec![ADDRESS.to_string()],
+        };
+        let response = error.into_response();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_documents("ErrorResponse", body);
+        assert_eq!(
+            document()["components"]["schemas"]["ErrorCode"]["enum"],
+            json!(RelayerError::CODES)
+        );
+    }
+
+    #[test]
+    fn test_schemas_match_api_types() {
+        let intent = SubscriptionIntent {
+            subscriber: ADDRESS.to_string(),
+            merchant: ADDRESS.to_string(),
+            amount: "1000".to_string(),
+            interval: 60,
+            start_time: 1,
+            max_payments: 2,
+            max_total_amount: "2000".to_string(),
+            expiry: 2,
+            nonce: 1,
+            token: "0x0".to_string(),
+        };
+        assert_documents("SubscriptionIntent", &intent);
+        assert_documents(
+            "SubmitIntentRequest",
+            SubmitIntentRequest {
+                intent: intent.clone(),
+                signature: "0x00".to_string(),
+                chain: Some(ChainSelector::Id(1)),
+            },
+        );
+        assert_documents(
+            "SubmitIntentResponse",
+            SubmitIntentResponse {
+                subscription_id: HASH.to_string(),
+                avail_block: Some(1),
+                avail_extrinsic: Some(0),
+                avail_status: AvailStatus::Submitted.to_string(),
+                status: "ACTIVE".to_string(),
+                creation_tx_hash: Some(HASH.to_string()),
+            },
+        );
+        assert_documents(
+            "SubscriptionResponse",
+            SubscriptionResponse {
+                id: HASH.to_string(),
+                subscriber: ADDRESS.to_string(),
+                merchant: ADDRESS.to_string(),
+                amount: "1".to_string(),
+                interval: 60,
+                start_time: 1,
+                max_payments: 2,
+                max_total_amount: "2".to_string(),
+                expiry: 2,
+                nonce: 1,
+                token_address: ADDRESS.to_string(),
+                token_symbol: "PYUSD".to_string(),
+                status: "ACTIVE".to_string(),
+                executed_payments: 0,
+                total_paid: "0".to_string(),
+                next_payment_time: 1,
+                failure_count: 0,
+                chain: "sepolia".to_string(),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                on_chain_status: 0,
+                on_chain_payments: 0,
+                contract_address: ADDRESS.to_string(),
+                avail_block: Some(1),
+                avail_extrinsic: Some(0),
+                avail_status: Some(AvailStatus::Submitted.to_string()),
+            },
+        );
+        let difference = FieldDifference {
+            field: "amount".to_string(),
+            anchored: "1".to_string(),
+            stored: "2".to_string(),
+        };
+        assert_documents("FieldDifference", &difference);
+        assert_documents(
+            "SubscriptionProofResponse",
+            SubscriptionProofResponse {
+                subscription_id: HASH.to_string(),
+                verdict: ProofVerdict::Mismatch,
+                avail_block: 1,
+                avail_extrinsic: 0,
+                anchored_intent: Some(intent.clone()),
+                merkle_root_valid: Some(true),
+                signature_valid: false,
+                signature_error: Some("invalid signature".to_string()),
+                recomputed_subscription_id: Some(HASH.to_string()),
+                subscription_id_matches: true,
+                differences: vec![difference],
+            },
+        );
+        assert_eq!(
+            document()["components"]["schemas"]["ProofVerdict"]["enum"],
+            json!(ProofVerdict::ALL)
+        );
+        let step = ProofStep {
+            position: SiblingPosition::Left,
+            hash: HASH.to_string(),
+        };
+        assert_documents("ProofStep", &step);
+        assert_documents(
+            "InclusionProofResponse",
+            InclusionProofResponse {
+                subscription_id: HASH.to_string(),
+                leaf: HASH.to_string(),
+                leaf_index: 1,
+                proof: vec![step],
+                merkle_root: HASH.to_string(),
+                leaf_count: 2,
+                avail_block: 1,
+                avail_extrinsic: 0,
+                submitted_at: chrono::Utc::now(),
+            },
+        );
+        let transaction = TransactionData {
+            id: "1".to_string(),
+            subscription_id: HASH.to_string(),
+            subscriber: ADDRESS.to_string(),
+            merchant: ADDRESS.to_string(),
+            payment_number: 1,
+            amount: "1".to_string(),
+            fee: "0".to_string(),
+            relayer: ADDRESS.to_string(),
+            transaction_hash: HASH.to_string(),
+            block_number: 1,
+            timestamp: 1,
+            chain: "sepolia".to_string(),
+            token_address: ADDRESS.to_string(),
+            token_symbol: "PYUSD".to_string(),
+            status: "SUCCESS".to_string(),
+        };
+        assert_documents("TransactionData", &transaction);
+        assert_documents(
+            "MerchantTransactionsResponse",
+            MerchantTransactionsResponse {
+                transactions: vec![transaction],
+                count: 1,
+                total_revenue: "1".to_string(),
+                token_totals: HashMap::new(),
+                envio_explorer_url: String::new(),
+                page: 0,
+                has_more: false,
+                next_cursor: None,
+                data_source: "rpc".to_string(),
+            },
+        );
+        assert_documents("TokenStatsResponse", TokenStatsResponse::default());
+        assert_documents(
+            "SettlementRow",
+            SettlementRow {
+                execution_id: 1,
+                executed_at: "2026-01-01T00:00:00Z".to_string(),
+                chain: "sepolia".to_string(),
+                subscription_id: HASH.to_string(),
+                subscriber: ADDRESS.to_string(),
+                payment_number: 1,
+                token_address: ADDRESS.to_string(),
+                token_symbol: "PYUSD".to_string(),
+                token_decimals: 6,
+                amount_paid: "1500000".to_string(),
+                amount_formatted: "1.5".to_string(),
+                protocol_fee: "15000".to_string(),
+                merchant_amount: "1485000".to_string(),
+                status: "SUCCESS".to_string(),
+                transaction_hash: HASH.to_string(),
+                block_number: 1,
+            },
+        );
+        assert_documents(
+            "MerchantStatsResponse",
+            MerchantStatsResponse {
+                merchant: ADDRESS.to_string(),
+                total: TokenStatsResponse::default(),
+                by_token: HashMap::new(),
+                envio_explorer_url: String::new(),
+            },
+        );
+        let entry = SubscriberSubscriptionResponse {
+            id: HASH.to_string(),
+            merchant: ADDRESS.to_string(),
+            chain: "sepolia".to_string(),
+            token_address: ADDRESS.to_string(),
+            token_symbol: "PYUSD".to_string(),
+            amount: "1".to_string(),
+            interval: 60,
+            status: "ACTIVE".to_string(),
+            executed_payments: 1,
+            max_payments: 2,
+            remaining_payments: 1,
+            total_paid: "1".to_string(),
+            max_total_amount: "2".to_string(),
+            remaining_budget: "1".to_string(),
+            next_payment_due: Some(1),
+            expiry: 2,
+            created_at: chrono::Utc::now(),
+        };
+        assert_documents("SubscriberSubscriptionResponse", &entry);
+        assert_documents(
+            "SubscriberSubscriptionsResponse",
+            SubscriberSubscriptionsResponse {
+                subscriber: ADDRESS.to_string(),
+                subscriptions: vec![entry],
+                count: 1,
+                next_cursor: Some("00".to_string()),
+            },
+        );
+        assert_documents(
+            "AdminSubscriptionResponse",
+            AdminSubscriptionResponse {
+                subscription_id: HASH.to_string(),
+                status: "ACTIVE".to_string(),
+                executed_payments: 1,
+                nonce: 1,
+                failure_count: 0,
+                next_retry_at: None,
+                relayer_paused_at: Some(chrono::Utc::now()),
+                audit_id: 1,
+            },
+        );
+        assert_documents(
+            "TriggerCycleResponse",
+            TriggerCycleResponse {
+                chains: vec!["sepolia".to_string()],
+                queued: true,
+                audit_id: 1,
+            },
+        );
+        assert_documents(
+            "HyperSyncResyncResponse",
+            HyperSyncResyncResponse {
+                chain: "sepolia".to_string(),
+                chain_id: 11155111,
+                from_block: 100,
+                previous_synced_block: 500,
+                audit_id: 1,
+            },
+        );
+        assert_documents(
+            "LockHolderResponse",
+            LockHolderResponse {
+                pid: 42,
+                application_name: None,
+                client_addr: Some("127.0.0.1".to_string()),
+                backend_start: Some(chrono::Utc::now()),
+                state: Some("idle".to_string()),
+                state_change: None,
+            },
+        );
+        assert_documents(
+            "AdminAuditLogResponse",
+            AdminAuditLogResponse {
+                entries: Vec::new(),
+                limit: 50,
+                offset: 0,
+            },
+        );
+        assert_documents(
+            "AdminAuditEntryResponse",
+            AdminAuditEntryResponse {
+                id: 1,
+                actor: "admin-token".to_string(),
+                action: AdminAction::PauseSubscription.to_string(),
+                target: Some(HASH.to_string()),
+                details: json!({}),
+                created_at: chrono::Utc::now(),
+            },
+        );
+        let actions: Vec<&str> = AdminAction::ALL.map(AdminAction::as_str).to_vec();
+        assert_eq!(
+            document()["components"]["schemas"]["AdminAction"]["enum"],
+            json!(actions)
+        );
+        assert_documents(
+            "SchedulerSettings",
+            SchedulerSettings {
+                execution_interval_seconds: 60,
+                cron: None,
+                max_executions_per_batch: 1,
+                max_executions_per_cycle: 1,
+                max_retry_attempts: 1,
+                max_failures_before_dead_letter: 1,
+            },
+        );
+        let dead_letter = DeadLetterSubscriptionResponse {
+            id: HASH.to_string(),
+            subscriber: ADDRESS.to_string(),
+            merchant: ADDRESS.to_string(),
+            chain: "sepolia".to_string(),
+            token_address: ADDRESS.to_string(),
+            amount: "1".to_string(),
+            executed_payments: 1,
+            failure_count: 3,
+            last_failure_class: Some("chain".to_string()),
+            last_error: Some("execution reverted".to_string()),
+            dead_lettered_at: Some(chrono::Utc::now()),
+        };
+        assert_documents(
+            "DeadLetterListResponse",
+            DeadLetterListResponse {
+                subscriptions: vec![dead_letter],
+                count: 1,
+                limit: 50,
+                offset: 0,
+            },
+        );
+        assert_documents(
+            "RequeueResponse",
+            RequeueResponse {
+                subscription_id: HASH.to_string(),
+                status: SubscriptionStatus::Active.to_string(),
+            },
+        );
+        assert_documents(
+            "ApiKeyListResponse",
+            ApiKeyListResponse {
+                keys: vec![ApiKeyResponse {
+                    id: 1,
+                    key_prefix: "ak_1234".to_string(),
+                    role: ApiKeyRole::Merchant.as_str().to_string(),
+                    merchant: Some(ADDRESS.to_string()),
+                    label: Some("billing".to_string()),
+                    created_at: chrono::Utc::now(),
+                    last_used_at: Some(chrono::Utc::now()),
+                    key: Some("ak_1234secret".to_string()),
+                }],
+            },
+        );
+        assert_documents(
+            "SchedulerLocksResponse",
+            SchedulerLocksResponse {
+                locks: vec![SchedulerLockResponse {
+                    chain: "sepolia".to_string(),
+                    chain_id: 11155111,
+                    lock_key: 1,
+                    held: true,
+                    holder: None,
+                }],
+            },
+        );
+        let event = WebhookEventType::ALL[0].as_str().to_string();
+        assert_documents(
+            "WebhookListResponse",
+            WebhookListResponse {
+                webhooks: vec![WebhookResponse {
+                    id: 1,
+                    merchant: ADDRESS.to_string(),
+                    url: "https://example.com/hook".to_string(),
+                    events: vec![event.clone()],
+                    active: true,
+                    created_at: chrono::Utc::now(),
+                    secret: Some("whsec".to_string()),
+                }],
+            },
+        );
+        assert_documents(
+            "WebhookDeliveriesResponse",
+            WebhookDeliveriesResponse {
+                deliveries: vec![WebhookDeliveryResponse {
+                    id: 1,
+                    webhook_id: 1,
+                    event_id: uuid::Uuid::new_v4().to_string(),
+                    event_type: event,
+                    payload: json!({}),
+                    status: WebhookDeliveryStatus::Delivered.to_string(),
+                    attempts: 1,
+                    next_attempt_at: None,
+                    response_status: Some(200),
+                    last_error: None,
+                    delivered_at: Some(chrono::Utc::now()),
+                    created_at: chrono::Utc::now(),
+                }],
+                count: 1,
+                limit: 50,
+                offset: 0,
+            },
+        );
+    }
+}
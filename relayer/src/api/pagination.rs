@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+
+use crate::{RelayerError, Result};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+pub const MAX_PAGE_SIZE: i64 = 200;
+
+// keyset cursor: the (timestamp, id) sort key of the last row already returned.
+// rows are listed newest first, so the next page holds rows strictly below it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub at: DateTime<Utc>,
+    pub id: String,
+}
+
+impl Cursor {
+    pub fn new(at: DateTime<Utc>, id: impl ToString) -> Self {
+        Self {
+            // postgres keeps microseconds; truncating keeps stub and postgres cursors identical
+            at: DateTime::from_timestamp_micros(at.timestamp_micros()).unwrap_or(at),
+            id: id.to_string(),
+        }
+    }
+
+    // opaque to clients; hex so it survives query strings unescaped
+    pub fn encode(&self) -> String {
+        hex::encode(format!("{}:{}", self.at.timestamp_micros(), self.id))
+    }
+
+    pub fn decode(value: &str) -> Result<Self> {
+        let invalid = || RelayerError::Validation("invalid cursor".to_string());
+        let decoded =
+            String::from_utf8(hex::decode(value).map_err(|_| invalid())?).map_err(|_| invalid())?;
+        let (micros, id) = decoded.split_once(':').ok_or_else(invalid)?;
+        let at = micros
+            .parse()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        if id.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            at,
+            id: id.to_string(),
+        })
+    }
+
+    pub fn parse_optional(value: Option<&str>) -> Result<Option<Self>> {
+        value
+            .filter(|value| !value.is_empty())
+            .map(Self::decode)
+            .transpose()
+    }
+}
+
+pub fn page_size(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trips_and_rejects_garbage() {
+        let at = DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap();
+        let cursor = Cursor::new(at, "0xabc");
+        let decoded = Cursor::decode(&cursor.encode()).unwrap();
+        assert_eq!(decoded, cursor);
+        assert_eq!(Cursor::new(at, 42).id, "42");
+
+        assert!(Cursor::decode("zz").is_err());
+        assert!(Cursor::decode(&hex::encode("not-a-cursor")).is_err());
+        assert!(Cursor::decode(&hex::encode("123:")).is_err());
+        assert_eq!(Cursor::parse_optional(Some("")).unwrap(), None);
+        assert_eq!(page_size(Some(10_000)), MAX_PAGE_SIZE);
+        assert_eq!(page_size(Some(0)), 1);
+    }
+}
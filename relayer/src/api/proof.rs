@@ -0,0 +1,247 @@
+use ethers::types::Address;
+
+use super::types::{FieldDifference, ProofVerdict, SubscriptionIntent, SubscriptionProofResponse};
+use super::validation::ValidationService;
+use crate::avail::{AvailIntent, AvailPayload};
+use crate::database::models::Subscription;
+use crate::utils::tokens;
+
+// checks a subscriptions row against what was anchored for it on avail. payload is the extrinsic
+// the row points at, or None when avail doesn't have it; verifying_contract and chain_id are the
+// eip-712 domain of the row's chain. an extrinsic that exists but doesn't carry the subscription
+// is a mismatch, not a missing anchor
+pub fn verify_anchored_intent(
+    subscription: &Subscription,
+    payload: Option<AvailPayload>,
+    verifying_contract: Address,
+    chain_id: u64,
+) -> SubscriptionProofResponse {
+    let anchored = payload.is_some();
+    let (record, merkle_root_valid) = match payload {
+        Some(AvailPayload::Intent(record)) => (Some(record), None),
+        Some(AvailPayload::Batch(batch)) => {
+            let root_valid = batch.root_matches_leaves().unwrap_or(false);
+            let record = batch.leaf(&subscription.id).map(|leaf| leaf.record.clone());
+            (record, Some(root_valid))
+        }
+        None => (None, None),
+    };
+
+    let mut response = SubscriptionProofResponse {
+        subscription_id: subscription.id.clone(),
+        verdict: ProofVerdict::NotAnchored,
+        avail_block: subscription.avail_block_number.unwrap_or_default() as u64,
+        avail_extrinsic: subscription.avail_extrinsic_index.unwrap_or_default() as u64,
+        anchored_intent: None,
+        merkle_root_valid,
+        signature_valid: false,
+        signature_error: None,
+        recomputed_subscription_id: None,
+        subscription_id_matches: false,
+        differences: Vec::new(),
+    };
+    let Some(AvailIntent {
+        intent,
+        signature,
+        metadata,
+    }) = record
+    else {
+        if anchored {
+            response.verdict = ProofVerdict::Mismatch;
+        }
+        return response;
+    };
+
+    match ValidationService::validate_intent_signature(
+        &intent,
+        &signature,
+        verifying_contract,
+        chain_id,
+    ) {
+        Ok(_) => response.signature_valid = true,
+        Err(e) => response.signature_error = Some(e.to_string()),
+    }
+    response.recomputed_subscription_id =
+        ValidationService::generate_subscription_id(&intent, &signature).ok();
+    response.subscription_id_matches = response
+        .recomputed_subscription_id
+        .as_deref()
+        .is_some_and(|id| id.eq_ignore_ascii_case(&subscription.id));
+    response.differences = field_differences(&intent, metadata.chain_id, subscription, chain_id);
+
+    let verified = response.merkle_root_valid != Some(false)
+        && response.signature_valid
+        && response.subscription_id_matches
+        && response.differences.is_empty();
+    response.verdict = if verified {
+        ProofVerdict::Verified
+    } else {
+        ProofVerdict::Mismatch
+    };
+    response.anchored_intent = Some(intent);
+    response
+}
+
+// field names follow the api's camelCase; addresses compare case-insensitively
+pub fn field_differences(
+    intent: &SubscriptionIntent,
+    anchored_chain_id: u64,
+    subscription: &Subscription,
+    stored_chain_id: u64,
+) -> Vec<FieldDifference> {
+    let fields = [
+        (
+            "subscriber",
+            intent.subscriber.to_lowercase(),
+            subscription.subscriber.to_lowercase(),
+        ),
+        (
+            "merchant",
+            intent.merchant.to_lowercase(),
+            subscription.merchant.to_lowercase(),
+        ),
+        ("amount", intent.amount.clone(), subscription.amount.clone()),
+        (
+            "interval",
+            intent.interval.to_string(),
+            subscription.interval_seconds.to_string(),
+        ),
+        (
+            "startTime",
+            intent.start_time.to_string(),
+            subscription.start_time.timestamp().to_string(),
+        ),
+        (
+            "maxPayments",
+            intent.max_payments.to_string(),
+            subscription.max_payments.to_string(),
+        ),
+        (
+            "maxTotalAmount",
+            intent.max_total_amount.clone(),
+            subscription.max_total_amount.clone(),
+        ),
+        (
+            "expiry",
+            intent.expiry.to_string(),
+            subscription.expiry.timestamp().to_string(),
+        ),
+        (
+            "nonce",
+            intent.nonce.to_string(),
+            subscription.nonce.to_string(),
+        ),
+        (
+            "token",
+            tokens::normalize_token_address(&intent.token),
+            tokens::normalize_token_address(&subscription.token_address),
+        ),
+        (
+            "chainId",
+            anchored_chain_id.to_string(),
+            stored_chain_id.to_string(),
+        ),
+    ];
+
+    fields
+        .into_iter()
+        .filter(|(_, anchored, stored)| anchored != stored)
+        .map(|(field, anchored, stored)| FieldDifference {
+            field: field.to_string(),
+            anchored,
+            stored,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn intent() -> SubscriptionIntent {
+        SubscriptionIntent {
+            subscriber: "0x1234567890123456789012345678901234567890".to_string(),
+            merchant: "0xABCDEFABCDEFABCDEFABCDEFABCDEFABCDEFABCD".to_string(),
+            amount: "1000".to_string(),
+            interval: 3600,
+            start_time: 1_700_000_000,
+            max_payments: 12,
+            max_total_amount: "12000".to_string(),
+            expiry: 1_800_000_000,
+            nonce: 7,
+            token: "0x0".to_string(),
+        }
+    }
+
+    fn subscription() -> Subscription {
+        let intent = intent();
+        Subscription {
+            id: "0x4444444444444444444444444444444444444444444444444444444444444444".to_string(),
+            subscriber: intent.subscriber,
+            merchant: intent.merchant.to_lowercase(),
+            amount: intent.amount,
+            interval_seconds: 3600,
+            start_time: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+            max_payments: 12,
+            max_total_amount: intent.max_total_amount,
+            expiry: Utc.timestamp_opt(1_800_000_000, 0).unwrap(),
+            nonce: 7,
+            token_address: "0x0000000000000000000000000000000000000000".to_string(),
+            status: "ACTIVE".to_string(),
+            executed_payments: 0,
+            total_paid: "0".to_string(),
+            next_payment_due: Utc::now(),
+            failure_count: 0,
+            chain: "sepolia".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            avail_block_number: Some(1),
+            avail_extrinsic_index: Some(2),
+        }
+    }
+
+    #[test]
+    fn test_identical_terms_have_no_differences() {
+        assert!(field_differences(&intent(), 1, &subscription(), 1).is_empty());
+    }
+
+    #[test]
+    fn test_differences_name_each_changed_term() {
+        let mut stored = subscription();
+        stored.amount = "2000".to_string();
+        stored.expiry = Utc.timestamp_opt(1_900_000_000, 0).unwrap();
+
+        let differences = field_differences(&intent(), 11_155_111, &stored, 84_532);
+        let fields: Vec<&str> = differences.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(fields, ["amount", "expiry", "chainId"]);
+        assert_eq!(differences[0].anchored, "1000");
+        assert_eq!(differences[0].stored, "2000");
+    }
+
+    #[test]
+    fn test_missing_extrinsic_is_not_anchored() {
+        let response = verify_anchored_intent(&subscription(), None, Address::zero(), 1);
+        assert_eq!(response.verdict, ProofVerdict::NotAnchored);
+        assert_eq!(response.avail_block, 1);
+        assert_eq!(response.avail_extrinsic, 2);
+        assert!(response.anchored_intent.is_none());
+    }
+
+    #[test]
+    fn test_batch_without_the_leaf_is_a_mismatch() {
+        let batch = crate::avail::AvailBatch {
+            version: 1,
+            merkle_root: "0x00".to_string(),
+            leaves: Vec::new(),
+        };
+        let response = verify_anchored_intent(
+            &subscription(),
+            Some(AvailPayload::Batch(batch)),
+            Address::zero(),
+            1,
+        );
+        assert_eq!(response.verdict, ProofVerdict::Mismatch);
+        assert!(response.anchored_intent.is_none());
+    }
+}
@@ -0,0 +1,450 @@
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use super::auth::Principal;
+use crate::database::queries::Queries;
+use crate::database::Database;
+use crate::{RelayerError, Result};
+
+// in-memory buckets are pruned once the map grows past this many clients, never holding more
+const MAX_MEMORY_BUCKETS: usize = 10_000;
+// postgres buckets idle this long are deleted, checked every PRUNE_EVERY_CHECKS requests
+const POSTGRES_BUCKET_IDLE_SECONDS: i64 = 3_600;
+const PRUNE_EVERY_CHECKS: u64 = 1_000;
+
+// a token bucket holding `burst` tokens that refills at `per_minute`; 0 per minute disables it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitPolicy {
+    pub per_minute: u32,
+    pub burst: u32,
+}
+
+impl RateLimitPolicy {
+    pub const fn new(per_minute: u32, burst: u32) -> Self {
+        Self { per_minute, burst }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.per_minute > 0
+    }
+
+    fn capacity(&self) -> f64 {
+        self.burst.max(1) as f64
+    }
+
+    fn refill_per_second(&self) -> f64 {
+        self.per_minute as f64 / 60.0
+    }
+
+    // "<per minute>" or "<per minute>:<burst>"; burst defaults to the per-minute rate
+    pub fn parse(value: &str) -> Option<Self> {
+        let (rate, burst) = match value.trim().split_once(':') {
+            Some((rate, burst)) => (rate, Some(burst)),
+            None => (value.trim(), None),
+        };
+        let per_minute = rate.trim().parse().ok()?;
+        let burst = match burst {
+            Some(burst) => burst.trim().parse().ok()?,
+            None => per_minute,
+        };
+        Some(Self::new(per_minute, burst))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RateLimitBackend {
+    // per instance
+    Memory,
+    // shared by every instance through the rate_limit_buckets table
+    Postgres,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitSettings {
+    pub backend: RateLimitBackend,
+    // intent submissions per api key, or per ip when anonymous
+    pub intent_per_client: RateLimitPolicy,
+    // intent submissions per subscriber address, charged to the recovered signer
+    pub intent_per_subscriber: RateLimitPolicy,
+    // merchant transaction/stats and subscriber listing reads per api key (per ip when anonymous)
+    pub analytics: RateLimitPolicy,
+    // on-demand HyperSync scans (use_hypersync=true), on top of `analytics`
+    pub hypersync_scans: RateLimitPolicy,
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            backend: RateLimitBackend::Memory,
+            intent_per_client: RateLimitPolicy::new(30, 10),
+            intent_per_subscriber: RateLimitPolicy::new(10, 5),
+            analytics: RateLimitPolicy::new(120, 30),
+            hypersync_scans: RateLimitPolicy::new(6, 2),
+        }
+    }
+}
+
+// who a request is charged to: the api key's scope when one was presented, otherwise the peer ip
+#[derive(Debug, Clone, Default)]
+pub struct ClientIdentity {
+    pub ip: Option<IpAddr>,
+    pub principal: Option<Principal>,
+}
+
+impl ClientIdentity {
+    // operators using an admin key aren't limited
+    pub fn is_exempt(&self) -> bool {
+        matches!(self.principal, Some(Principal::Admin))
+    }
+
+    // None when there is nothing to key on: admin keys, or no peer address
+    pub fn client_key(&self) -> Option<String> {
+        match &self.principal {
+            Some(Principal::Merchant(merchant)) => Some(format!("merchant:{}", merchant)),
+            Some(Principal::Admin) => None,
+            None => self.ip.map(ip_key),
+        }
+    }
+}
+
+// ipv6 clients are keyed by their /64, the smallest block a single host is usually handed
+fn ip_key(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => format!("ip:{}", v4),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => format!("ip:{}", v4),
+            None => {
+                let prefix = u128::from(v6) & !(u128::from(u64::MAX));
+                format!("ip:{}/64", Ipv6Addr::from(prefix))
+            }
+        },
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIdentity {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        Ok(Self {
+            // missing when the router isn't served with connect info, e.g. in tests
+            ip: parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip()),
+            principal: parts.extensions.get::<Principal>().cloned(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+    // the policy the bucket was last charged under; scopes differ, so eviction can't use the
+    // policy of whichever request happens to trigger it
+    policy: RateLimitPolicy,
+}
+
+impl TokenBucket {
+    fn refilled(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        (self.tokens + elapsed * self.policy.refill_per_second()).min(self.policy.capacity())
+    }
+
+    // a full bucket behaves exactly like a missing one
+    fn is_full(&self, now: Instant) -> bool {
+        self.refilled(now) >= self.policy.capacity()
+    }
+}
+
+enum Backend {
+    Memory(Mutex<HashMap<String, TokenBucket>>),
+    Postgres(Queries),
+}
+
+pub struct RateLimiter {
+    settings: RateLimitSettings,
+    backend: Backend,
+    checks: AtomicU64,
+}
+
+impl std::fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("settings", &self.settings)
+            .finish()
+    }
+}
+
+impl RateLimiter {
+    pub fn new(settings: RateLimitSettings, database: &Database) -> Self {
+        let backend = match settings.backend {
+            RateLimitBackend::Postgres if database.pool().is_some() => {
+                Backend::Postgres(database.queries())
+            }
+            RateLimitBackend::Postgres => {
+                warn!(
+                    "postgres rate limiting needs a database connection, using in-memory buckets"
+                );
+                Backend::Memory(Mutex::new(HashMap::new()))
+            }
+            RateLimitBackend::Memory => Backend::Memory(Mutex::new(HashMap::new())),
+        };
+
+        Self {
+            settings,
+            backend,
+            checks: AtomicU64::new(0),
+        }
+    }
+
+    pub fn settings(&self) -> &RateLimitSettings {
+        &self.settings
+    }
+
+    pub async fn check_client(
+        &self,
+        scope: &str,
+        client: &ClientIdentity,
+        policy: RateLimitPolicy,
+    ) -> Result<()> {
+        match client.client_key() {
+            Some(key) if !client.is_exempt() => self.check(scope, &key, policy).await,
+            _ => Ok(()),
+        }
+    }
+
+    // takes a token from the client's bucket for `scope`, or fails with RateLimited
+    pub async fn check(&self, scope: &str, client: &str, policy: RateLimitPolicy) -> Result<()> {
+        if !policy.enabled() {
+            return Ok(());
+        }
+
+        let key = format!("{}:{}", scope, client);
+        let (allowed, tokens) = match &self.backend {
+            Backend::Memory(buckets) => take_memory_token(buckets, &key, policy),
+            Backend::Postgres(queries) => {
+                self.prune_postgres_buckets(queries).await;
+                queries
+                    .take_rate_limit_token(&key, policy.capacity(), policy.refill_per_second())
+                    .await?
+            }
+        };
+
+        if allowed {
+            return Ok(());
+        }
+
+        let retry_after = ((1.0 - tokens) / policy.refill_per_second())
+            .ceil()
+            .max(1.0) as u64;
+        debug!("rate limited {} (retry in {}s)", key, retry_after);
+        Err(RelayerError::RateLimited(Duration::from_secs(retry_after)))
+    }
+
+    async fn prune_postgres_buckets(&self, queries: &Queries) {
+        if !self
+            .checks
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(PRUNE_EVERY_CHECKS)
+        {
+            return;
+        }
+        match queries
+            .prune_rate_limit_buckets(chrono::Duration::seconds(POSTGRES_BUCKET_IDLE_SECONDS))
+            .await
+        {
+            Ok(pruned) => debug!("pruned {} idle rate limit buckets", pruned),
+            Err(e) => warn!("failed to prune rate limit buckets: {}", e),
+        }
+    }
+}
+
+fn take_memory_token(
+    buckets: &Mutex<HashMap<String, TokenBucket>>,
+    key: &str,
+    policy: RateLimitPolicy,
+) -> (bool, f64) {
+    let now = Instant::now();
+    let mut buckets = buckets.lock().unwrap();
+
+    if buckets.len() >= MAX_MEMORY_BUCKETS && !buckets.contains_key(key) {
+        buckets.retain(|_, bucket| !bucket.is_full(now));
+        // every client is still draining: drop the least recently used tenth instead
+        if buckets.len() >= MAX_MEMORY_BUCKETS {
+            let mut by_age: Vec<(Instant, String)> = buckets
+                .iter()
+                .map(|(key, bucket)| (bucket.updated_at, key.clone()))
+                .collect();
+            by_age.sort_unstable();
+            let excess = buckets.len() + 1 - MAX_MEMORY_BUCKETS * 9 / 10;
+            for (_, key) in by_age.into_iter().take(excess) {
+                buckets.remove(&key);
+            }
+        }
+    }
+
+    let bucket = buckets.entry(key.to_string()).or_insert(TokenBucket {
+        tokens: policy.capacity(),
+        updated_at: now,
+        policy,
+    });
+    bucket.policy = policy;
+    let tokens = bucket.refilled(now);
+    let allowed = tokens >= 1.0;
+    bucket.tokens = if allowed { tokens - 1.0 } else { tokens };
+    bucket.updated_at = now;
+    (allowed, tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_limiter() -> RateLimiter {
+        RateLimiter {
+            settings: RateLimitSettings::default(),
+            backend: Backend::Memory(Mutex::new(HashMap::new())),
+            checks: AtomicU64::new(0),
+        }
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!(
+            RateLimitPolicy::parse("30"),
+            Some(RateLimitPolicy::new(30, 30))
+        );
+        assert_eq!(
+            RateLimitPolicy::parse(" 60:5 "),
+            Some(RateLimitPolicy::new(60, 5))
+        );
+        assert!(!RateLimitPolicy::parse("0").unwrap().enabled());
+        assert_eq!(RateLimitPolicy::parse("fast"), None);
+        assert_eq!(RateLimitPolicy::parse("10:"), None);
+    }
+
+    #[tokio::test]
+    async fn test_bucket_allows_burst_then_limits_per_client() {
+        let limiter = memory_limiter();
+        let policy = RateLimitPolicy::new(1, 2);
+
+        assert!(limiter.check("intent", "ip:1", policy).await.is_ok());
+        assert!(limiter.check("intent", "ip:1", policy).await.is_ok());
+        match limiter.check("intent", "ip:1", policy).await {
+            // one token per minute, so the next one is about a minute away
+            Err(RelayerError::RateLimited(retry_after)) => {
+                assert!(retry_after > Duration::from_secs(50));
+                assert!(retry_after <= Duration::from_secs(60));
+            }
+            other => panic!("expected rate limit, got {:?}", other),
+        }
+
+        // separate buckets per client and per scope
+        assert!(limiter.check("intent", "ip:2", policy).await.is_ok());
+        assert!(limiter.check("analytics", "ip:1", policy).await.is_ok());
+        // disabled policies never limit
+        for _ in 0..5 {
+            assert!(limiter
+                .check("intent", "ip:1", RateLimitPolicy::new(0, 0))
+                .await
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let policy = RateLimitPolicy::new(60, 2);
+        let start = Instant::now();
+        let bucket = TokenBucket {
+            tokens: 0.0,
+            updated_at: start,
+            policy,
+        };
+        let refilled = bucket.refilled(start + Duration::from_millis(1_500));
+        assert!((refilled - 1.5).abs() < 1e-9);
+        // never above the burst size
+        assert_eq!(bucket.refilled(start + Duration::from_secs(60)), 2.0);
+    }
+
+    #[test]
+    fn test_eviction_checks_each_bucket_against_its_own_policy() {
+        let strict = RateLimitPolicy::new(1, 1);
+        let generous = RateLimitPolicy::new(1, 100);
+        let now = Instant::now();
+        let mut map = HashMap::new();
+        // half drained under a generous policy, yet above a strict policy's capacity
+        map.insert(
+            "analytics:ip:busy".to_string(),
+            TokenBucket {
+                tokens: 50.0,
+                updated_at: now,
+                policy: generous,
+            },
+        );
+        for i in 1..MAX_MEMORY_BUCKETS {
+            map.insert(
+                format!("intent:ip:{}", i),
+                TokenBucket {
+                    tokens: 1.0,
+                    updated_at: now,
+                    policy: strict,
+                },
+            );
+        }
+        let buckets = Mutex::new(map);
+
+        let (allowed, _) = take_memory_token(&buckets, "intent:ip:new", strict);
+        assert!(allowed);
+        let buckets = buckets.lock().unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets["analytics:ip:busy"].tokens, 50.0);
+    }
+
+    #[test]
+    fn test_drained_buckets_never_grow_past_the_cap() {
+        let policy = RateLimitPolicy::new(1, 5);
+        let buckets = Mutex::new(HashMap::new());
+        // every client takes one token, so no bucket is ever full again
+        for i in 0..MAX_MEMORY_BUCKETS + 500 {
+            take_memory_token(&buckets, &format!("intent:ip:{}", i), policy);
+        }
+        let buckets = buckets.lock().unwrap();
+        assert!(buckets.len() <= MAX_MEMORY_BUCKETS);
+        // the newest client is kept, the oldest evicted
+        assert!(buckets.contains_key(&format!("intent:ip:{}", MAX_MEMORY_BUCKETS + 499)));
+        assert!(!buckets.contains_key("intent:ip:0"));
+    }
+
+    #[test]
+    fn test_ipv6_clients_share_a_key_per_slash_64() {
+        let key = |ip: &str| {
+            ClientIdentity {
+                ip: Some(ip.parse().unwrap()),
+                principal: None,
+            }
+            .client_key()
+            .unwrap()
+        };
+        assert_eq!(key("2001:db8:1:2::1"), "ip:2001:db8:1:2::/64");
+        assert_eq!(key("2001:db8:1:2:ffff:1:2:3"), key("2001:db8:1:2::1"));
+        assert_ne!(key("2001:db8:1:3::1"), key("2001:db8:1:2::1"));
+        assert_eq!(key("::ffff:192.0.2.1"), key("192.0.2.1"));
+        assert_eq!(key("192.0.2.1"), "ip:192.0.2.1");
+    }
+}
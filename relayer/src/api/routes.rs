@@ -1,143 +1,248 @@
+use super::auth;
 use super::handlers::*;
+use super::openapi;
+use super::record_request_metrics;
 use crate::AppState;
 use axum::{
-    routing::{get, post},
-    Router,
+    middleware,
+    response::Html,
+    routing::{on, MethodFilter},
+    Json, Router,
 };
+use once_cell::sync::Lazy;
+use serde_json::Value;
 use std::sync::Arc;
+use utoipa::OpenApi;
 
 pub fn create_api_routes(app_state: Arc<AppState>) -> Router {
-    Router::new()
-        // api v1 routes
-        .route("/api/v1/intent", post(submit_intent_handler))
-        .route("/api/v1/subscription/:id", get(get_subscription_handler))
-        .route(
-            "/api/v1/merchant/:address/transactions",
-            get(get_merchant_transactions_handler),
-        )
-        .route(
-            "/api/v1/merchant/:address/stats",
-            get(get_merchant_stats_handler),
-        )
-        .route(
-            "/api/v1/cross-chain/:subscription_id",
-            get(get_cross_chain_attestations_handler),
-        )
-        // health and status routes
-        .route("/health", get(health_check_handler))
-        .route("/status", get(status_check_handler))
-        .route("/metrics", get(metrics_handler))
-        // docs routes (optional)
-        .route("/api/v1/docs", get(api_documentation_handler))
+    api_router()
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            auth::authenticate,
+        ))
+        // outside the auth layer so rejected requests are measured too
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            record_request_metrics,
+        ))
         .with_state(app_state)
 }
 
-// handler for api docs
-pub async fn api_documentation_handler() -> axum::response::Html<&'static str> {
-    axum::response::Html(
-        r#"
-        <!DOCTYPE html>
-        <html>
-        <head>
-            <title>Aurum Relayer API Documentation</title>
-            <style>
-                body { font-family: Arial, sans-serif; margin: 40px; }
-                .endpoint { margin: 20px 0; padding: 15px; border: 1px solid #ddd; }
-                .method { color: white; padding: 4px 8px; border-radius: 3px; font-weight: bold; }
-                .post { background-color: #49cc90; }
-                .get { background-color: #61affe; }
-                code { background-color: #f5f5f5; padding: 2px 4px; border-radius: 3px; }
-                .example { background-color: #f8f8f8; padding: 10px; border-radius: 3px; margin: 10px 0; }
-            </style>
-        </head>
-        <body>
-            <h1>Aurum Relayer API v1</h1>
-            
-            <div class="endpoint">
-                <h3><span class="method post">POST</span> /api/v1/intent</h3>
-                <p>Submit a subscription intent for processing</p>
-                <div class="example">
-                    <strong>Request:</strong><br>
-                    <code>
-                    {<br>
-                    &nbsp;&nbsp;"intent": {<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"subscriber": "0x...",<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"merchant": "0x...",<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"amount": "1000000000000000000",<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"interval": 86400,<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"startTime": 1698123456,<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"maxPayments": 12,<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"maxTotalAmount": "12000000000000000000",<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"expiry": 1729659456,<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"nonce": 1<br>
-                    &nbsp;&nbsp;},<br>
-                    &nbsp;&nbsp;"signature": "0x..."<br>
-                    }
-                    </code>
-                </div>
-            </div>
-            
-            <div class="endpoint">
-                <h3><span class="method get">GET</span> /api/v1/subscription/:id</h3>
-                <p>Get subscription details by ID</p>
-                <div class="example">
-                    <strong>Example:</strong> <code>GET /api/v1/subscription/0x123...</code>
-                </div>
-            </div>
-            
-            <div class="endpoint">
-                <h3><span class="method get">GET</span> /api/v1/merchant/:address/transactions</h3>
-                <p>Get transaction history for a merchant</p>
-                <div class="example">
-                    <strong>Query Parameters:</strong><br>
-                    <code>?page=0&size=50</code>
-                    <br><em>Optional:</em> <code>use_hypersync=true&from_block=1000&to_block=2000&chain=base</code>
-                </div>
-            </div>
-            
-            <div class="endpoint">
-                <h3><span class="method get">GET</span> /api/v1/merchant/:address/stats</h3>
-                <p>Get aggregated statistics for a merchant</p>
-                <div class="example">
-                    <strong>Example:</strong> <code>GET /api/v1/merchant/0x123.../stats</code>
-                </div>
-            </div>
+// one list of handlers feeds both the OpenAPI paths and the router, so the two can't drift
+macro_rules! api_routes {
+    ($($handler:ident),+ $(,)?) => {
+        // paths and the schemas they reference, merged into the document by openapi::document
+        #[derive(OpenApi)]
+        #[openapi(paths($($handler),+))]
+        pub(super) struct ApiPaths;
 
-            <div class="endpoint">
-                <h3><span class="method get">GET</span> /health</h3>
-                <p>Health check endpoint</p>
-                <div class="example">
-                    <strong>Response:</strong><br>
-                    <code>
-                    {<br>
-                    &nbsp;&nbsp;"status": "healthy",<br>
-                    &nbsp;&nbsp;"services": {<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"database": {"healthy": true},<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"rpc": {"healthy": true},<br>
-                    &nbsp;&nbsp;&nbsp;&nbsp;"envio": {"healthy": true}<br>
-                    &nbsp;&nbsp;}<br>
-                    }
-                    </code>
-                </div>
-            </div>
-            
-            <h2>Error Responses</h2>
-            <p>All endpoints return errors in the format:</p>
-            <div class="example">
-                <code>
-                {<br>
-                &nbsp;&nbsp;"error": "error message",<br>
-                &nbsp;&nbsp;"code": "ERROR_CODE"<br>
-                }
-                </code>
-            </div>
-            
-            <h2>Data Verification</h2>
-            <p>All merchant transaction and statistics data is sourced from the Envio HyperIndex. 
-            Each response includes an <code>envioExplorerUrl</code> field linking to the Envio Explorer 
-            for independent data verification.</p>
-        </body>
-        </html>
-        "#,
+        // operation ids default to the handler's name
+        fn add_route(
+            router: Router<Arc<AppState>>,
+            operation_id: &str,
+            path: &str,
+            method: MethodFilter,
+        ) -> Router<Arc<AppState>> {
+            $(if operation_id == stringify!($handler) {
+                return router.route(path, on(method, $handler));
+            })+
+            panic!("no handler registered for operation {}", operation_id)
+        }
+    };
+}
+
+api_routes!(
+    // api v1 routes
+    submit_intent_handler,
+    get_subscription_handler,
+    get_subscription_proof_handler,
+    get_inclusion_proof_handler,
+    pause_subscription_handler,
+    resume_subscription_handler,
+    get_subscriber_subscriptions_handler,
+    get_merchant_transactions_handler,
+    export_merchant_settlements_handler,
+    get_merchant_stats_handler,
+    list_webhooks_handler,
+    register_webhook_handler,
+    list_webhook_deliveries_handler,
+    delete_webhook_handler,
+    get_cross_chain_attestations_handler,
+    // admin routes, need an ADMIN api key or ADMIN_API_TOKEN
+    get_scheduler_settings_handler,
+    update_scheduler_settings_handler,
+    trigger_scheduler_cycle_handler,
+    get_scheduler_locks_handler,
+    admin_sync_subscription_handler,
+    admin_reset_failures_handler,
+    admin_pause_subscription_handler,
+    admin_resume_subscription_handler,
+    hypersync_resync_handler,
+    list_dead_letter_handler,
+    requeue_dead_letter_handler,
+    list_api_keys_handler,
+    create_api_key_handler,
+    revoke_api_key_handler,
+    list_admin_audit_log_handler,
+    // health and status routes
+    health_check_handler,
+    status_check_handler,
+    metrics_handler,
+    metrics_json_handler,
+    // docs routes
+    api_documentation_handler,
+    openapi_handler,
+);
+
+// every documented operation, routed at its documented path and method
+pub(super) fn api_router() -> Router<Arc<AppState>> {
+    let mut router = Router::new();
+    for (path, item) in ApiPaths::openapi().paths.paths {
+        let operations = [
+            (MethodFilter::GET, item.get),
+            (MethodFilter::PUT, item.put),
+            (MethodFilter::POST, item.post),
+            (MethodFilter::DELETE, item.delete),
+            (MethodFilter::OPTIONS, item.options),
+            (MethodFilter::HEAD, item.head),
+            (MethodFilter::PATCH, item.patch),
+            (MethodFilter::TRACE, item.trace),
+        ];
+        for (method, operation) in operations {
+            let Some(operation) = operation else {
+                continue;
+            };
+            let operation_id = operation
+                .operation_id
+                .expect("utoipa gives every operation an id");
+            router = add_route(router, &operation_id, &axum_path(&path), method);
+        }
+    }
+    router
+}
+
+// "/api/v1/subscription/{id}" -> "/api/v1/subscription/:id"
+pub(crate) fn axum_path(path: &str) -> String {
+    path.replace('{', ":").replace('}', "")
+}
+
+// viewer for the OpenAPI document, which is embedded so the page needs no further requests
+static DOCS_PAGE: Lazy<String> = Lazy::new(|| {
+    // "</" would close the script element early
+    let spec = openapi::document().to_string().replace("</", "<\\/");
+    DOCS_TEMPLATE.replace("__OPENAPI_DOCUMENT__", &spec)
+});
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/docs",
+    tag = "service",
+    summary = "Interactive viewer for this document",
+    responses(
+        (status = 200, description = "OK", body = String, content_type = "text/html"),
     )
+)]
+pub async fn api_documentation_handler() -> Html<&'static str> {
+    Html(DOCS_PAGE.as_str())
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/openapi.json",
+    tag = "service",
+    summary = "This document",
+    responses(
+        (status = 200, description = "OK", body = Object),
+    )
+)]
+pub async fn openapi_handler() -> Json<&'static Value> {
+    Json(openapi::document())
+}
+
+const DOCS_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <title>Aurum Relayer API Documentation</title>
+    <style>
+        body { font-family: Arial, sans-serif; margin: 40px; max-width: 1100px; }
+        .endpoint { margin: 12px 0; border: 1px solid #ddd; border-radius: 4px; }
+        .endpoint summary { padding: 10px 15px; cursor: pointer; }
+        .endpoint .body { padding: 0 15px 15px; }
+        .method { color: white; padding: 4px 8px; border-radius: 3px; font-weight: bold; display: inline-block; min-width: 52px; text-align: center; }
+        .get { background-color: #61affe; }
+        .post { background-color: #49cc90; }
+        .put { background-color: #fca130; }
+        .delete { background-color: #f93e3e; }
+        .path { font-family: monospace; font-size: 15px; margin: 0 10px; }
+        .muted { color: #666; }
+        code, pre { background-color: #f5f5f5; padding: 2px 4px; border-radius: 3px; }
+        pre { padding: 10px; overflow-x: auto; }
+        table { border-collapse: collapse; }
+        td, th { text-align: left; padding: 4px 12px 4px 0; vertical-align: top; }
+    </style>
+</head>
+<body>
+    <h1 id="title"></h1>
+    <p id="description"></p>
+    <p>Machine readable document: <a href="/api/v1/openapi.json"><code>/api/v1/openapi.json</code></a></p>
+    <div id="operations"></div>
+    <script id="openapi" type="application/json">__OPENAPI_DOCUMENT__</script>
+    <script>
+        const spec = JSON.parse(document.getElementById("openapi").textContent);
+        const el = (tag, attrs, ...children) => {
+            const node = Object.assign(document.createElement(tag), attrs || {});
+            children.forEach((child) => node.append(child));
+            return node;
+        };
+        const resolve = (schema, depth = 0) => {
+            if (!schema || depth > 6) return schema;
+            if (schema.$ref) return resolve(spec.components.schemas[schema.$ref.split("/").pop()], depth + 1);
+            const copy = Array.isArray(schema) ? [] : {};
+            for (const [key, value] of Object.entries(schema)) {
+                copy[key] = typeof value === "object" && value !== null ? resolve(value, depth + 1) : value;
+            }
+            return copy;
+        };
+        const schemaBlock = (label, schema) =>
+            el("div", {}, el("strong", { textContent: label }), el("pre", { textContent: JSON.stringify(resolve(schema), null, 2) }));
+
+        document.getElementById("title").textContent = spec.info.title + " " + spec.info.version;
+        document.getElementById("description").textContent = spec.info.description;
+
+        const operations = document.getElementById("operations");
+        for (const tag of spec.tags.map((tag) => tag.name)) {
+            operations.append(el("h2", { textContent: tag }));
+            for (const [path, item] of Object.entries(spec.paths)) {
+                for (const [method, op] of Object.entries(item)) {
+                    if (!op.tags.includes(tag)) continue;
+                    const body = el("div", { className: "body" }, el("p", { className: "muted", textContent: op.description }));
+                    if (op.parameters) {
+                        const rows = op.parameters.map((param) =>
+                            el("tr", {}, el("td", {}, el("code", { textContent: param.name })), el("td", { textContent: param.in }),
+                                el("td", { textContent: param.schema.type || "" }), el("td", { textContent: param.description || "" })));
+                        body.append(el("strong", { textContent: "Parameters" }), el("table", {}, ...rows));
+                    }
+                    if (op.requestBody) {
+                        body.append(schemaBlock("Request body", op.requestBody.content["application/json"].schema));
+                    }
+                    for (const [status, response] of Object.entries(op.responses)) {
+                        const resolved = response.$ref ? spec.components.responses[response.$ref.split("/").pop()] : response;
+                        if (status === "200") {
+                            const [type, media] = Object.entries(resolved.content)[0];
+                            body.append(schemaBlock("200 " + type, media.schema));
+                        } else {
+                            body.append(el("div", { className: "muted", textContent: status + " " + resolved.description }));
+                        }
+                    }
+                    operations.append(el("details", { className: "endpoint" },
+                        el("summary", {}, el("span", { className: "method " + method, textContent: method.toUpperCase() }),
+                            el("span", { className: "path", textContent: path }), op.summary),
+                        body));
+                }
+            }
+        }
+    </script>
+</body>
+</html>
+"#;
@@ -1,18 +1,56 @@
+use super::openapi;
+use crate::avail::merkle::ProofStep;
 use crate::utils::tokens;
 use chrono::{DateTime, Utc};
 use ethers::types::{Address, U256};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 use std::str::FromStr;
+use utoipa::ToSchema;
+
+// body of every error response, see RelayerError::into_response
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    /// human readable message
+    pub error: String,
+    #[schema(value_type = openapi::ErrorCode)]
+    pub code: String,
+    /// structured fields for the code, e.g. expected and recovered for SIGNATURE_MISMATCH or
+    /// supportedTokens for UNSUPPORTED_TOKEN
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<serde_json::Value>,
+}
 
 // request types
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct SubmitIntentRequest {
     pub intent: SubscriptionIntent,
+    /// the subscriber's EIP-712 signature over the intent
+    #[serde(deserialize_with = "deserialize_signature")]
+    #[schema(value_type = openapi::Signature)]
+    pub signature: String,
+    /// target chain by registry name or chain id; defaults to the first configured chain
+    #[serde(default)]
+    pub chain: Option<ChainSelector>,
+}
+
+// body for pause/resume; signature is the subscriber's eip-712 PauseRequest/ResumeRequest
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct SubscriptionActionRequest {
+    /// the subscriber's EIP-712 PauseRequest or ResumeRequest signature
     #[serde(deserialize_with = "deserialize_signature")]
+    #[schema(value_type = openapi::Signature)]
     pub signature: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(untagged)]
+pub enum ChainSelector {
+    Id(u64),
+    Name(String),
+}
+
 /// subscription intent - must match client-side signing format exactly
 ///
 /// critical: for signature verification to work, the intent data must be
@@ -27,69 +65,116 @@ pub struct SubmitIntentRequest {
 /// 3. numbers: big-endian byte representation for hashing
 /// 4. field order: fixed order in hash calculation (subscriber, merchant, amount, ...)
 /// 5. encoding: all string fields as utf-8 bytes
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct SubscriptionIntent {
     #[serde(deserialize_with = "deserialize_address")]
+    #[schema(value_type = openapi::Address)]
     pub subscriber: String,
     #[serde(deserialize_with = "deserialize_address")]
+    #[schema(value_type = openapi::Address)]
     pub merchant: String,
     /// amount in wei as decimal string (no leading zeros, no scientific notation)
     #[serde(deserialize_with = "deserialize_amount")]
+    #[schema(value_type = openapi::Wei)]
     pub amount: String,
+    /// seconds between payments
     #[serde(deserialize_with = "deserialize_positive_number")]
+    #[schema(minimum = 1)]
     pub interval: u64,
     #[serde(deserialize_with = "deserialize_timestamp")]
+    #[schema(value_type = openapi::UnixSeconds)]
     pub start_time: u64,
     #[serde(deserialize_with = "deserialize_positive_number")]
+    #[schema(minimum = 1)]
     pub max_payments: u64,
     /// maximum total amount in wei as decimal string (no leading zeros)
     #[serde(deserialize_with = "deserialize_amount")]
+    #[schema(value_type = openapi::Wei)]
     pub max_total_amount: String,
     #[serde(deserialize_with = "deserialize_timestamp")]
+    #[schema(value_type = openapi::UnixSeconds)]
     pub expiry: u64,
     #[serde(deserialize_with = "deserialize_positive_number")]
+    #[schema(minimum = 1)]
     pub nonce: u64,
     #[serde(deserialize_with = "deserialize_token_address")]
+    #[schema(value_type = openapi::TokenAddress)]
     pub token: String,
 }
 
 // response types
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct SubmitIntentResponse {
     #[serde(rename = "subscriptionId")]
+    #[schema(value_type = openapi::Hash)]
     pub subscription_id: String,
+    /// null until the intent is included on Avail; see availStatus
     #[serde(rename = "availBlock")]
-    pub avail_block: u64,
+    pub avail_block: Option<u64>,
     #[serde(rename = "availExtrinsic")]
-    pub avail_extrinsic: u64,
+    pub avail_extrinsic: Option<u64>,
+    /// PENDING when accepted; follow GET /subscription/{id} for SUBMITTED or FAILED
+    #[serde(rename = "availStatus")]
+    #[schema(value_type = openapi::AvailStatus)]
+    pub avail_status: String,
     pub status: String,
+    /// set when RELAY_SUBSCRIPTION_CREATION is enabled and the relayer submitted createSubscription
+    #[serde(rename = "creationTxHash", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<openapi::Hash>)]
+    pub creation_tx_hash: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct SubscriptionActionResponse {
+    #[serde(rename = "subscriptionId")]
+    #[schema(value_type = openapi::Hash)]
+    pub subscription_id: String,
+    #[schema(value_type = openapi::SubscriptionStatus)]
+    pub status: String,
+    pub chain: String,
+    #[serde(rename = "transactionHash")]
+    #[schema(value_type = openapi::Hash)]
+    pub transaction_hash: String,
+    #[serde(rename = "blockNumber")]
+    pub block_number: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct SubscriptionResponse {
+    #[schema(value_type = openapi::Hash)]
     pub id: String,
+    #[schema(value_type = openapi::Address)]
     pub subscriber: String,
+    #[schema(value_type = openapi::Address)]
     pub merchant: String,
+    #[schema(value_type = openapi::Wei)]
     pub amount: String,
     pub interval: u64,
     #[serde(rename = "startTime")]
+    #[schema(value_type = openapi::UnixSeconds)]
     pub start_time: u64,
     #[serde(rename = "maxPayments")]
     pub max_payments: u64,
     #[serde(rename = "maxTotalAmount")]
+    #[schema(value_type = openapi::Wei)]
     pub max_total_amount: String,
+    #[schema(value_type = openapi::UnixSeconds)]
     pub expiry: u64,
     pub nonce: u64,
     #[serde(rename = "tokenAddress")]
+    #[schema(value_type = openapi::TokenAddress)]
     pub token_address: String,
     #[serde(rename = "tokenSymbol")]
     pub token_symbol: String,
+    #[schema(value_type = openapi::SubscriptionStatus)]
     pub status: String,
     #[serde(rename = "executedPayments")]
     pub executed_payments: u64,
     #[serde(rename = "totalPaid")]
+    #[schema(value_type = openapi::Wei)]
     pub total_paid: String,
     #[serde(rename = "nextPaymentTime")]
+    #[schema(value_type = openapi::UnixSeconds)]
     pub next_payment_time: u64,
     #[serde(rename = "failureCount")]
     pub failure_count: u32,
@@ -99,72 +184,159 @@ pub struct SubscriptionResponse {
     #[serde(rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
     // blockchain verification
+    /// SubscriptionManager status enum, read from the contract
     #[serde(rename = "onChainStatus")]
     pub on_chain_status: u8,
     #[serde(rename = "onChainPayments")]
     pub on_chain_payments: u64,
     #[serde(rename = "contractAddress")]
+    #[schema(value_type = openapi::Address)]
     pub contract_address: String,
     #[serde(rename = "availBlock")]
     pub avail_block: Option<u64>,
     #[serde(rename = "availExtrinsic")]
     pub avail_extrinsic: Option<u64>,
+    /// null for subscriptions the relayer never posted to Avail, e.g. ones imported from chain
+    #[serde(rename = "availStatus")]
+    #[schema(value_type = Option<openapi::AvailStatus>)]
+    pub avail_status: Option<String>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+// one entry of a subscriber's subscription list, with what is left of its schedule and budget
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct SubscriberSubscriptionResponse {
+    #[schema(value_type = openapi::Hash)]
+    pub id: String,
+    #[schema(value_type = openapi::Address)]
+    pub merchant: String,
+    pub chain: String,
+    #[serde(rename = "tokenAddress")]
+    #[schema(value_type = openapi::TokenAddress)]
+    pub token_address: String,
+    #[serde(rename = "tokenSymbol")]
+    pub token_symbol: String,
+    #[schema(value_type = openapi::Wei)]
+    pub amount: String,
+    pub interval: u64,
+    #[schema(value_type = openapi::SubscriptionStatus)]
+    pub status: String,
+    #[serde(rename = "executedPayments")]
+    pub executed_payments: u64,
+    #[serde(rename = "maxPayments")]
+    pub max_payments: u64,
+    #[serde(rename = "remainingPayments")]
+    pub remaining_payments: u64,
+    #[serde(rename = "totalPaid")]
+    #[schema(value_type = openapi::Wei)]
+    pub total_paid: String,
+    #[serde(rename = "maxTotalAmount")]
+    #[schema(value_type = openapi::Wei)]
+    pub max_total_amount: String,
+    /// maxTotalAmount minus totalPaid
+    #[serde(rename = "remainingBudget")]
+    #[schema(value_type = openapi::Wei)]
+    pub remaining_budget: String,
+    /// null once no further payment will be taken, or while paused
+    #[serde(rename = "nextPaymentDue")]
+    #[schema(value_type = Option<openapi::UnixSeconds>)]
+    pub next_payment_due: Option<u64>,
+    #[schema(value_type = openapi::UnixSeconds)]
+    pub expiry: u64,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SubscriberSubscriptionsResponse {
+    #[schema(value_type = openapi::Address)]
+    pub subscriber: String,
+    pub subscriptions: Vec<SubscriberSubscriptionResponse>,
+    pub count: usize,
+    /// pass back as cursor for the next page; null on the last page
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct TransactionData {
+    /// execution id for source=postgres, the indexer's payment id otherwise
+    pub id: String,
     #[serde(rename = "subscriptionId")]
+    #[schema(value_type = openapi::Hash)]
     pub subscription_id: String,
+    #[schema(value_type = openapi::Address)]
     pub subscriber: String,
+    #[schema(value_type = openapi::Address)]
     pub merchant: String,
     #[serde(rename = "paymentNumber")]
     pub payment_number: u64,
+    #[schema(value_type = openapi::Wei)]
     pub amount: String,
+    #[schema(value_type = openapi::Wei)]
     pub fee: String,
+    #[schema(value_type = openapi::Address)]
     pub relayer: String,
     #[serde(rename = "transactionHash")]
+    #[schema(value_type = openapi::Hash)]
     pub transaction_hash: String,
     #[serde(rename = "blockNumber")]
     pub block_number: u64,
+    #[schema(value_type = openapi::UnixSeconds)]
     pub timestamp: u64,
     pub chain: String,
     #[serde(rename = "tokenAddress")]
+    #[schema(value_type = openapi::TokenAddress)]
     pub token_address: String,
     #[serde(rename = "tokenSymbol")]
     pub token_symbol: String,
+    /// indexed payments are always SUCCESS
+    #[schema(value_type = openapi::ExecutionStatus)]
+    pub status: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct MerchantTransactionsResponse {
     pub transactions: Vec<TransactionData>,
+    /// matching transactions across all pages
     pub count: u64,
+    /// paid by matching successful transactions across all pages
     #[serde(rename = "totalRevenue")]
+    #[schema(value_type = openapi::Wei)]
     pub total_revenue: String,
+    /// revenue per token symbol on this page
     #[serde(rename = "tokenTotals")]
+    #[schema(value_type = HashMap<String, openapi::Wei>)]
     pub token_totals: HashMap<String, String>,
     #[serde(rename = "envioExplorerUrl")]
     pub envio_explorer_url: String,
     pub page: u32,
     #[serde(rename = "hasMore")]
     pub has_more: bool,
+    /// pass back as cursor for the next page; null on the last page
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<String>,
     #[serde(rename = "dataSource")]
+    #[schema(value_type = openapi::TransactionSource)]
     pub data_source: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MerchantStatsResponse {
+    #[schema(value_type = openapi::Address)]
     pub merchant: String,
     #[serde(rename = "total")]
     pub total: TokenStatsResponse,
+    /// stats per token symbol
     #[serde(rename = "byToken")]
     pub by_token: HashMap<String, TokenStatsResponse>,
     #[serde(rename = "envioExplorerUrl")]
     pub envio_explorer_url: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct TokenStatsResponse {
     #[serde(rename = "tokenAddress")]
+    #[schema(value_type = openapi::TokenAddress)]
     pub token_address: String,
     #[serde(rename = "tokenSymbol")]
     pub token_symbol: String,
@@ -173,52 +345,419 @@ pub struct TokenStatsResponse {
     #[serde(rename = "activeSubscriptions")]
     pub active_subscriptions: u64,
     #[serde(rename = "totalRevenue")]
+    #[schema(value_type = openapi::Wei)]
     pub total_revenue: String,
     #[serde(rename = "totalPayments")]
     pub total_payments: u64,
     #[serde(rename = "averageTransactionValue")]
+    #[schema(value_type = openapi::Wei)]
     pub average_transaction_value: String,
     #[serde(rename = "chainId")]
     pub chain_id: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct HealthResponse {
+    #[schema(value_type = openapi::HealthStatus)]
     pub status: String,
     pub timestamp: DateTime<Utc>,
     pub services: HealthServices,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StatusResponse {
+    pub service: String,
+    pub version: String,
+    pub timestamp: DateTime<Utc>,
+    pub health: HealthResponse,
+    /// active, paused and total counts, with a by_chain breakdown
+    #[schema(value_type = Object)]
+    pub subscriptions: serde_json::Value,
+    #[schema(value_type = Object)]
+    pub config: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct HealthServices {
     pub database: ServiceStatus,
     pub rpc: ServiceStatus,
     pub envio: ServiceStatus,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ServiceStatus {
     pub healthy: bool,
     pub response_time_ms: Option<u64>,
     pub error: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CrossChainAttestationResponse {
     #[serde(rename = "attestationId")]
     pub attestation_id: String,
     #[serde(rename = "subscriptionId")]
+    #[schema(value_type = openapi::Hash)]
     pub subscription_id: String,
     #[serde(rename = "paymentNumber")]
     pub payment_number: u64,
     #[serde(rename = "chainId")]
     pub chain_id: u64,
+    #[schema(value_type = openapi::TokenAddress)]
     pub token: String,
+    #[schema(value_type = openapi::Wei)]
     pub amount: String,
+    #[schema(value_type = openapi::UnixSeconds)]
     pub timestamp: u64,
     pub verified: bool,
 }
 
+/// everything needed to check an intent's inclusion against the Avail extrinsic without trusting
+/// the relayer: fold the proof over leaf and compare with merkleRoot, then fetch the extrinsic at
+/// availBlock/availExtrinsic, which carries merkleRoot and every leaf's intent
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InclusionProofResponse {
+    #[serde(rename = "subscriptionId")]
+    #[schema(value_type = openapi::Hash)]
+    pub subscription_id: String,
+    /// keccak256(0x00 ‖ subscriptionId)
+    #[schema(value_type = openapi::Hash)]
+    pub leaf: String,
+    #[serde(rename = "leafIndex")]
+    pub leaf_index: u32,
+    /// fold from the leaf: keccak256(0x01 ‖ left ‖ right) at each step
+    pub proof: Vec<ProofStep>,
+    #[serde(rename = "merkleRoot")]
+    #[schema(value_type = openapi::Hash)]
+    pub merkle_root: String,
+    #[serde(rename = "leafCount")]
+    pub leaf_count: u32,
+    #[serde(rename = "availBlock")]
+    pub avail_block: u64,
+    #[serde(rename = "availExtrinsic")]
+    pub avail_extrinsic: u64,
+    #[serde(rename = "submittedAt")]
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// VERIFIED only when every check passes and no stored term differs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProofVerdict {
+    // the anchored intent is signed by the subscriber, hashes to the id and matches the stored terms
+    Verified,
+    // includes a batch at the referenced extrinsic that has no leaf for the subscription
+    Mismatch,
+    // avail has no extrinsic at the block/extrinsic the subscription points to
+    NotAnchored,
+}
+
+impl ProofVerdict {
+    pub const ALL: [ProofVerdict; 3] = [
+        ProofVerdict::Verified,
+        ProofVerdict::Mismatch,
+        ProofVerdict::NotAnchored,
+    ];
+}
+
+// a term that differs between the intent on avail and the relayer's subscriptions row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FieldDifference {
+    pub field: String,
+    /// the value in the intent on Avail
+    pub anchored: String,
+    /// the value in the relayer's database
+    pub stored: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SubscriptionProofResponse {
+    #[serde(rename = "subscriptionId")]
+    #[schema(value_type = openapi::Hash)]
+    pub subscription_id: String,
+    pub verdict: ProofVerdict,
+    #[serde(rename = "availBlock")]
+    pub avail_block: u64,
+    #[serde(rename = "availExtrinsic")]
+    pub avail_extrinsic: u64,
+    /// the intent as read back from Avail; null when NOT_ANCHORED
+    #[serde(rename = "anchoredIntent")]
+    pub anchored_intent: Option<SubscriptionIntent>,
+    /// whether the batch root recomputes from its leaves; null for single-intent extrinsics
+    /// posted before batching
+    #[serde(rename = "merkleRootValid")]
+    pub merkle_root_valid: Option<bool>,
+    /// the EIP-712 signature recovers to the subscriber for the stored chain
+    #[serde(rename = "signatureValid")]
+    pub signature_valid: bool,
+    #[serde(rename = "signatureError")]
+    pub signature_error: Option<String>,
+    #[serde(rename = "recomputedSubscriptionId")]
+    #[schema(value_type = Option<openapi::Hash>)]
+    pub recomputed_subscription_id: Option<String>,
+    #[serde(rename = "subscriptionIdMatches")]
+    pub subscription_id_matches: bool,
+    pub differences: Vec<FieldDifference>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct DeadLetterSubscriptionResponse {
+    #[schema(value_type = openapi::Hash)]
+    pub id: String,
+    #[schema(value_type = openapi::Address)]
+    pub subscriber: String,
+    #[schema(value_type = openapi::Address)]
+    pub merchant: String,
+    pub chain: String,
+    #[serde(rename = "tokenAddress")]
+    #[schema(value_type = openapi::TokenAddress)]
+    pub token_address: String,
+    #[schema(value_type = openapi::Wei)]
+    pub amount: String,
+    #[serde(rename = "executedPayments")]
+    pub executed_payments: i64,
+    #[serde(rename = "failureCount")]
+    pub failure_count: i64,
+    #[serde(rename = "lastFailureClass")]
+    pub last_failure_class: Option<String>,
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+    #[serde(rename = "deadLetteredAt")]
+    pub dead_lettered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct DeadLetterListResponse {
+    pub subscriptions: Vec<DeadLetterSubscriptionResponse>,
+    pub count: usize,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct RequeueResponse {
+    #[serde(rename = "subscriptionId")]
+    #[schema(value_type = openapi::Hash)]
+    pub subscription_id: String,
+    #[schema(value_type = openapi::SubscriptionStatus)]
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CreateApiKeyRequest {
+    #[schema(value_type = openapi::ApiKeyRole)]
+    pub role: String,
+    /// required for MERCHANT keys, rejected for ADMIN keys
+    #[schema(value_type = Option<openapi::Address>)]
+    pub merchant: Option<String>,
+    #[schema(max_length = 128)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct ApiKeyResponse {
+    pub id: i64,
+    #[serde(rename = "keyPrefix")]
+    pub key_prefix: String,
+    #[schema(value_type = openapi::ApiKeyRole)]
+    pub role: String,
+    #[schema(value_type = Option<openapi::Address>)]
+    pub merchant: Option<String>,
+    pub label: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "lastUsedAt")]
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// the plaintext key, only returned when it is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ApiKeyListResponse {
+    pub keys: Vec<ApiKeyResponse>,
+}
+
+// relayer-side state of a subscription after an admin action
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AdminSubscriptionResponse {
+    #[serde(rename = "subscriptionId")]
+    #[schema(value_type = openapi::Hash)]
+    pub subscription_id: String,
+    #[schema(value_type = openapi::SubscriptionStatus)]
+    pub status: String,
+    #[serde(rename = "executedPayments")]
+    pub executed_payments: u64,
+    pub nonce: u64,
+    #[serde(rename = "failureCount")]
+    pub failure_count: u32,
+    #[serde(rename = "nextRetryAt")]
+    pub next_retry_at: Option<DateTime<Utc>>,
+    /// set while an operator holds the subscription back from the scheduler
+    #[serde(rename = "relayerPausedAt")]
+    pub relayer_paused_at: Option<DateTime<Utc>>,
+    #[serde(rename = "auditId")]
+    pub audit_id: i64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TriggerCycleRequest {
+    /// chain name or id; every chain when omitted
+    pub chain: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct TriggerCycleResponse {
+    pub chains: Vec<String>,
+    /// false when no scheduler runs in the instance that served the request
+    pub queued: bool,
+    #[serde(rename = "auditId")]
+    pub audit_id: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HyperSyncResyncRequest {
+    /// chain name or id
+    pub chain: String,
+    #[serde(rename = "fromBlock")]
+    pub from_block: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct HyperSyncResyncResponse {
+    pub chain: String,
+    #[serde(rename = "chainId")]
+    pub chain_id: u64,
+    #[serde(rename = "fromBlock")]
+    pub from_block: u64,
+    #[serde(rename = "previousSyncedBlock")]
+    pub previous_synced_block: i64,
+    #[serde(rename = "auditId")]
+    pub audit_id: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct LockHolderResponse {
+    pub pid: i32,
+    #[serde(rename = "applicationName")]
+    pub application_name: Option<String>,
+    #[serde(rename = "clientAddr")]
+    pub client_addr: Option<String>,
+    #[serde(rename = "backendStart")]
+    pub backend_start: Option<DateTime<Utc>>,
+    pub state: Option<String>,
+    #[serde(rename = "stateChange")]
+    pub state_change: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SchedulerLockResponse {
+    pub chain: String,
+    #[serde(rename = "chainId")]
+    pub chain_id: u64,
+    #[serde(rename = "lockKey")]
+    pub lock_key: i64,
+    pub held: bool,
+    pub holder: Option<LockHolderResponse>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SchedulerLocksResponse {
+    pub locks: Vec<SchedulerLockResponse>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AdminAuditEntryResponse {
+    pub id: i64,
+    /// "admin-token" or "api-key:<id>"
+    pub actor: String,
+    #[schema(value_type = openapi::AdminAction)]
+    pub action: String,
+    pub target: Option<String>,
+    #[schema(value_type = Object)]
+    pub details: serde_json::Value,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AdminAuditLogResponse {
+    pub entries: Vec<AdminAuditEntryResponse>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RegisterWebhookRequest {
+    #[schema(format = Uri)]
+    pub url: String,
+    /// omitted or empty subscribes to every event type
+    #[serde(default)]
+    #[schema(value_type = Vec<openapi::WebhookEventType>)]
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct WebhookResponse {
+    pub id: i64,
+    #[schema(value_type = openapi::Address)]
+    pub merchant: String,
+    pub url: String,
+    #[schema(value_type = Vec<openapi::WebhookEventType>)]
+    pub events: Vec<String>,
+    pub active: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    /// the signing secret, only returned when the webhook is registered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct WebhookListResponse {
+    pub webhooks: Vec<WebhookResponse>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct WebhookDeliveryResponse {
+    pub id: i64,
+    #[serde(rename = "webhookId")]
+    pub webhook_id: i64,
+    #[serde(rename = "eventId")]
+    #[schema(format = Uuid)]
+    pub event_id: String,
+    #[serde(rename = "eventType")]
+    #[schema(value_type = openapi::WebhookEventType)]
+    pub event_type: String,
+    /// the signed event body
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+    #[schema(value_type = openapi::WebhookDeliveryStatus)]
+    pub status: String,
+    pub attempts: i32,
+    #[serde(rename = "nextAttemptAt")]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    #[serde(rename = "responseStatus")]
+    pub response_status: Option<i32>,
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+    #[serde(rename = "deliveredAt")]
+    pub delivered_at: Option<DateTime<Utc>>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct WebhookDeliveriesResponse {
+    pub deliveries: Vec<WebhookDeliveryResponse>,
+    pub count: usize,
+    pub limit: i64,
+    pub offset: i64,
+}
+
 // envio graphql types
 #[derive(Debug, Deserialize)]
 pub struct EnvioResponse<T> {
@@ -8,24 +8,44 @@ use std::str::FromStr;
 
 pub struct ValidationService;
 
+// subscriber-signed state changes accepted by SubscriptionManager
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionAction {
+    Pause,
+    Resume,
+}
+
+impl SubscriptionAction {
+    fn type_hash(self) -> [u8; 32] {
+        match self {
+            SubscriptionAction::Pause => {
+                keccak256(b"PauseRequest(bytes32 subscriptionId,uint256 nonce)")
+            }
+            SubscriptionAction::Resume => {
+                keccak256(b"ResumeRequest(bytes32 subscriptionId,uint256 nonce)")
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for SubscriptionAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubscriptionAction::Pause => write!(f, "pause"),
+            SubscriptionAction::Resume => write!(f, "resume"),
+        }
+    }
+}
+
 impl ValidationService {
+    // returns the recovered signer, which is the intent's subscriber once this succeeds
     pub fn validate_intent_signature(
         intent: &SubscriptionIntent,
         signature: &str,
         verifying_contract: Address,
         chain_id: u64,
-    ) -> Result<()> {
-        if !signature.starts_with("0x") || signature.len() != 132 {
-            return Err(RelayerError::Validation(
-                "signature must be 0x followed by 130 hex characters".to_string(),
-            ));
-        }
-
-        let signature_bytes = hex::decode(&signature[2..])
-            .map_err(|_| RelayerError::Validation("invalid signature hex encoding".to_string()))?;
-
-        let sig = Signature::try_from(signature_bytes.as_slice())
-            .map_err(|_| RelayerError::Validation("invalid signature format".to_string()))?;
+    ) -> Result<Address> {
+        let (_, sig) = Self::parse_signature(signature)?;
 
         let struct_hash = Self::hash_subscription_intent(intent)?;
         let domain_separator = Self::domain_separator(chain_id, verifying_contract);
@@ -33,18 +53,50 @@ impl ValidationService {
 
         let recovered_address = sig
             .recover(message_hash)
-            .map_err(|_| RelayerError::Validation("signature recovery failed".to_string()))?;
+            .map_err(|_| RelayerError::InvalidSignature("signature recovery failed".to_string()))?;
 
         let subscriber_address = Address::from_str(&intent.subscriber)
-            .map_err(|_| RelayerError::Validation("invalid subscriber address".to_string()))?;
+            .map_err(|_| RelayerError::invalid_field("subscriber", "invalid subscriber address"))?;
 
         if recovered_address != subscriber_address {
-            return Err(RelayerError::Validation(
-                "signature does not match subscriber address".to_string(),
+            return Err(Self::signature_mismatch(
+                subscriber_address,
+                recovered_address,
             ));
         }
 
-        Ok(())
+        Ok(recovered_address)
+    }
+
+    // verifies a PauseRequest/ResumeRequest signature and returns the raw bytes to relay
+    pub fn validate_subscription_action_signature(
+        action: SubscriptionAction,
+        subscription_id: [u8; 32],
+        nonce: U256,
+        signature: &str,
+        subscriber: Address,
+        verifying_contract: Address,
+        chain_id: u64,
+    ) -> Result<Vec<u8>> {
+        let (signature_bytes, sig) = Self::parse_signature(signature)?;
+
+        let struct_hash = keccak256(encode(&[
+            Token::FixedBytes(action.type_hash().to_vec()),
+            Token::FixedBytes(subscription_id.to_vec()),
+            Token::Uint(nonce),
+        ]));
+        let domain_separator = Self::domain_separator(chain_id, verifying_contract);
+        let message_hash = Self::eip712_digest(domain_separator, struct_hash);
+
+        let recovered_address = sig
+            .recover(message_hash)
+            .map_err(|_| RelayerError::InvalidSignature("signature recovery failed".to_string()))?;
+
+        if recovered_address != subscriber {
+            return Err(Self::signature_mismatch(subscriber, recovered_address));
+        }
+
+        Ok(signature_bytes)
     }
 
     pub fn generate_subscription_id(
@@ -53,8 +105,9 @@ impl ValidationService {
     ) -> Result<String> {
         let struct_hash = Self::hash_subscription_intent(intent)?;
 
-        let signature_bytes = hex::decode(&signature[2..])
-            .map_err(|_| RelayerError::Validation("invalid signature encoding".to_string()))?;
+        let signature_bytes = hex::decode(&signature[2..]).map_err(|_| {
+            RelayerError::InvalidSignature("invalid signature encoding".to_string())
+        })?;
 
         let mut combined = Vec::with_capacity(32 + signature_bytes.len());
         combined.extend_from_slice(&struct_hash);
@@ -94,19 +147,25 @@ impl ValidationService {
     }
 
     pub fn validate_amount(amount: &str) -> Result<U256> {
+        Self::validate_amount_field("amount", amount)
+    }
+
+    fn validate_amount_field(field: &str, amount: &str) -> Result<U256> {
         let amount_u256 = U256::from_str(amount)
-            .map_err(|_| RelayerError::Validation("invalid amount format".to_string()))?;
+            .map_err(|_| RelayerError::invalid_field(field, "invalid amount format"))?;
 
         if amount_u256.is_zero() {
-            return Err(RelayerError::Validation(
-                "amount must be greater than zero".to_string(),
+            return Err(RelayerError::invalid_field(
+                field,
+                "amount must be greater than zero",
             ));
         }
 
         let max_amount = U256::from(10).pow(U256::from(30));
         if amount_u256 > max_amount {
-            return Err(RelayerError::Validation(
-                "amount exceeds maximum allowed value".to_string(),
+            return Err(RelayerError::invalid_field(
+                field,
+                "amount exceeds maximum allowed value",
             ));
         }
 
@@ -117,40 +176,46 @@ impl ValidationService {
         let now = chrono::Utc::now().timestamp() as u64;
 
         if start_time < now {
-            return Err(RelayerError::Validation(
-                "start time cannot be in the past".to_string(),
+            return Err(RelayerError::invalid_field(
+                "startTime",
+                "start time cannot be in the past",
             ));
         }
 
         if expiry <= start_time {
-            return Err(RelayerError::Validation(
-                "expiry must be after start time".to_string(),
+            return Err(RelayerError::invalid_field(
+                "expiry",
+                "expiry must be after start time",
             ));
         }
 
         let max_future = now + (10 * 365 * 24 * 60 * 60);
         if expiry > max_future {
-            return Err(RelayerError::Validation(
-                "expiry too far in the future".to_string(),
+            return Err(RelayerError::invalid_field(
+                "expiry",
+                "expiry too far in the future",
             ));
         }
 
         if interval < 3600 {
-            return Err(RelayerError::Validation(
-                "interval must be at least 1 hour (3600 seconds)".to_string(),
+            return Err(RelayerError::invalid_field(
+                "interval",
+                "interval must be at least 1 hour (3600 seconds)",
             ));
         }
 
         if interval > (365 * 24 * 60 * 60) {
-            return Err(RelayerError::Validation(
-                "interval cannot exceed 1 year".to_string(),
+            return Err(RelayerError::invalid_field(
+                "interval",
+                "interval cannot exceed 1 year",
             ));
         }
 
         let total_duration = expiry - start_time;
         if total_duration < interval {
-            return Err(RelayerError::Validation(
-                "subscription duration must allow for at least one payment".to_string(),
+            return Err(RelayerError::invalid_field(
+                "interval",
+                "subscription duration must allow for at least one payment",
             ));
         }
 
@@ -164,27 +229,32 @@ impl ValidationService {
         token_address: &str,
     ) -> Result<()> {
         let amount_u256 = Self::validate_amount(amount)?;
-        let max_total_u256 = Self::validate_amount(max_total_amount)?;
+        let max_total_u256 = Self::validate_amount_field("maxTotalAmount", max_total_amount)?;
 
         if max_payments == 0 {
-            return Err(RelayerError::Validation(
-                "max payments must be greater than zero".to_string(),
+            return Err(RelayerError::invalid_field(
+                "maxPayments",
+                "max payments must be greater than zero",
             ));
         }
 
         if max_payments > 10000 {
-            return Err(RelayerError::Validation(
-                "max payments cannot exceed 10,000".to_string(),
+            return Err(RelayerError::invalid_field(
+                "maxPayments",
+                "max payments cannot exceed 10,000",
             ));
         }
 
         let calculated_total = amount_u256
             .checked_mul(U256::from(max_payments))
-            .ok_or_else(|| RelayerError::Validation("payment calculation overflow".to_string()))?;
+            .ok_or_else(|| {
+                RelayerError::invalid_field("maxPayments", "payment calculation overflow")
+            })?;
 
         if calculated_total > max_total_u256 {
-            return Err(RelayerError::Validation(
-                "max total amount insufficient for max payments * amount".to_string(),
+            return Err(RelayerError::invalid_field(
+                "maxTotalAmount",
+                "max total amount insufficient for max payments * amount",
             ));
         }
 
@@ -199,32 +269,57 @@ impl ValidationService {
             } else {
                 "payment amount must be at least one base unit for the selected token"
             };
-            return Err(RelayerError::Validation(message.to_string()));
+            return Err(RelayerError::invalid_field("amount", message));
         }
 
         Ok(())
     }
 
+    fn signature_mismatch(expected: Address, recovered: Address) -> RelayerError {
+        RelayerError::SignatureMismatch {
+            expected: format!("{:?}", expected),
+            recovered: format!("{:?}", recovered),
+        }
+    }
+
+    fn parse_signature(signature: &str) -> Result<(Vec<u8>, Signature)> {
+        if !signature.starts_with("0x") || signature.len() != 132 {
+            return Err(RelayerError::InvalidSignature(
+                "signature must be 0x followed by 130 hex characters".to_string(),
+            ));
+        }
+
+        let signature_bytes = hex::decode(&signature[2..]).map_err(|_| {
+            RelayerError::InvalidSignature("invalid signature hex encoding".to_string())
+        })?;
+
+        let sig = Signature::try_from(signature_bytes.as_slice())
+            .map_err(|_| RelayerError::InvalidSignature("invalid signature format".to_string()))?;
+
+        Ok((signature_bytes, sig))
+    }
+
     fn hash_subscription_intent(intent: &SubscriptionIntent) -> Result<[u8; 32]> {
         let type_hash = keccak256(
             b"SubscriptionIntent(address subscriber,address merchant,uint256 amount,uint256 interval,uint256 startTime,uint256 maxPayments,uint256 maxTotalAmount,uint256 expiry,uint256 nonce,address token)",
         );
 
         let subscriber = Address::from_str(&intent.subscriber)
-            .map_err(|_| RelayerError::Validation("invalid subscriber address".to_string()))?;
+            .map_err(|_| RelayerError::invalid_field("subscriber", "invalid subscriber address"))?;
         let merchant = Address::from_str(&intent.merchant)
-            .map_err(|_| RelayerError::Validation("invalid merchant address".to_string()))?;
+            .map_err(|_| RelayerError::invalid_field("merchant", "invalid merchant address"))?;
         let amount = U256::from_dec_str(&intent.amount)
-            .map_err(|_| RelayerError::Validation("invalid amount format".to_string()))?;
+            .map_err(|_| RelayerError::invalid_field("amount", "invalid amount format"))?;
         let interval = U256::from(intent.interval);
         let start_time = U256::from(intent.start_time);
         let max_payments = U256::from(intent.max_payments);
-        let max_total_amount = U256::from_dec_str(&intent.max_total_amount)
-            .map_err(|_| RelayerError::Validation("invalid max total amount format".to_string()))?;
+        let max_total_amount = U256::from_dec_str(&intent.max_total_amount).map_err(|_| {
+            RelayerError::invalid_field("maxTotalAmount", "invalid max total amount format")
+        })?;
         let expiry = U256::from(intent.expiry);
         let nonce = U256::from(intent.nonce);
         let token_addr = Address::from_str(&intent.token)
-            .map_err(|_| RelayerError::Validation("invalid token address".to_string()))?;
+            .map_err(|_| RelayerError::invalid_field("token", "invalid token address"))?;
 
         let encoded = encode(&[
             Token::FixedBytes(type_hash.to_vec()),
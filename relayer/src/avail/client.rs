@@ -1,45 +1,54 @@
 use std::{convert::TryFrom, env};
 
 use avail_rust_client::{
-    block_api::BlockWithExt, extensions::KeypairExt, prelude::*, Client as SdkClient,
-    Error as SdkError, UserError as SdkUserError,
+    block_api::{BlockExtOptionsSimple, BlockWithExt},
+    extensions::KeypairExt,
+    prelude::*,
+    Client as SdkClient, Error as SdkError, UserError as SdkUserError,
 };
 use chrono::Utc;
 use tracing::{info, warn};
 
 use crate::{
-    api::types::SubscriptionIntent,
-    avail::types::{AvailIntent, AvailMetadata, AvailSubmissionResult},
-    config::Config,
+    api::{types::SubscriptionIntent, validation::ValidationService},
+    avail::{
+        mock::AvailMock,
+        types::{
+            AppExtrinsic, AvailBatch, AvailIntent, AvailMetadata, AvailPayload,
+            AvailSubmissionResult,
+        },
+    },
+    config::{AvailMode, Config},
     error::{RelayerError, Result},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AvailClientMode {
+    // avail isn't configured; nothing is anchored and there is nothing to read back
+    Disabled,
     Stub,
     Remote,
 }
 
-#[derive(Clone, Default)]
-struct StubClient;
-
 #[derive(Clone)]
 struct RemoteClient {
     client: SdkClient,
-    signer: avail_rust_client::subxt_signer::sr25519::Keypair,
+    // None for read-only clients, e.g. recovery, which never submit
+    signer: Option<avail_rust_client::subxt_signer::sr25519::Keypair>,
     app_id: u32,
 }
 
 impl RemoteClient {
-    async fn submit_intent(&self, payload: &AvailIntent) -> Result<AvailSubmissionResult> {
-        let data = serde_json::to_vec(payload).map_err(|e| {
-            RelayerError::InternalError(format!("failed to serialize avail intent: {}", e))
-        })?;
+    async fn submit_data(&self, payload: &AvailPayload) -> Result<AvailSubmissionResult> {
+        let data = encode_payload(payload)?;
 
+        let signer = self.signer.as_ref().ok_or_else(|| {
+            RelayerError::InternalError("avail client is read-only; cannot submit".to_string())
+        })?;
         let submittable = self.client.tx().data_availability().submit_data(data);
 
         let submitted = submittable
-            .sign_and_submit(&self.signer, Options::new(self.app_id))
+            .sign_and_submit(signer, Options::new(self.app_id))
             .await
             .map_err(map_sdk_error)?;
 
@@ -60,7 +69,7 @@ impl RemoteClient {
         })
     }
 
-    async fn fetch_intent(&self, block_number: u64, extrinsic_index: u64) -> Result<AvailIntent> {
+    async fn fetch_payload(&self, block_number: u64, extrinsic_index: u64) -> Result<AvailPayload> {
         let block_number = u32::try_from(block_number).map_err(|_| {
             RelayerError::Validation("avail block number exceeds 32-bit range".to_string())
         })?;
@@ -77,12 +86,40 @@ impl RemoteClient {
             RelayerError::NotFound("avail extrinsic not found for provided references".to_string())
         })?;
 
-        serde_json::from_slice::<AvailIntent>(&extrinsic.call.data).map_err(|e| {
-            RelayerError::InternalError(format!(
-                "failed to decode avail intent payload from extrinsic: {}",
-                e
-            ))
-        })
+        decode_payload(&extrinsic.call.data)
+    }
+
+    async fn app_extrinsics(&self, block_number: u64) -> Result<Vec<AppExtrinsic>> {
+        let height = u32::try_from(block_number).map_err(|_| {
+            RelayerError::Validation("avail block number exceeds 32-bit range".to_string())
+        })?;
+
+        let block = BlockWithExt::new(self.client.clone(), height);
+        let extrinsics = block
+            .all::<avail::data_availability::tx::SubmitData>(BlockExtOptionsSimple {
+                app_id: Some(self.app_id),
+                ..Default::default()
+            })
+            .await
+            .map_err(map_sdk_error)?;
+
+        Ok(extrinsics
+            .into_iter()
+            .map(|extrinsic| AppExtrinsic {
+                block_number,
+                extrinsic_index: extrinsic.ext_index() as u64,
+                data: extrinsic.call.data,
+            })
+            .collect())
+    }
+
+    async fn finalized_block_number(&self) -> Result<u64> {
+        self.client
+            .finalized()
+            .block_height()
+            .await
+            .map(u64::from)
+            .map_err(|e| RelayerError::InternalError(format!("avail rpc error: {}", e)))
     }
 }
 
@@ -93,124 +130,220 @@ pub struct AvailClient {
 
 #[derive(Clone)]
 enum AvailClientModeInner {
-    Stub(StubClient),
+    Disabled,
+    Stub(AvailMock),
     Remote(Box<RemoteClient>),
 }
 
 impl AvailClient {
     pub async fn new(config: &Config) -> Result<Self> {
-        if config.avail_enabled() {
-            let endpoint = config
-                .avail_rpc_url
-                .as_ref()
-                .expect("avail_enabled implies rpc url present")
-                .clone();
-            let app_id = config
-                .avail_application_id
-                .expect("avail_enabled implies application id present");
-            let signing_key = env::var("AVAIL_SIGNING_KEY")
-                .ok()
-                .map(|value| value.trim().to_string())
-                .filter(|value| !value.is_empty());
-
-            if let Some(secret_uri) = signing_key {
-                let client = SdkClient::new(endpoint.trim())
-                    .await
-                    .map_err(map_sdk_error)?;
+        match config.avail_mode {
+            AvailMode::Remote => {
+                let secret_uri = env::var("AVAIL_SIGNING_KEY")
+                    .ok()
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+                    .ok_or_else(|| {
+                        RelayerError::Config(anyhow::anyhow!(
+                            "AVAIL_SIGNING_KEY is required to submit to avail in remote mode"
+                        ))
+                    })?;
                 let signer =
                     avail_rust_client::subxt_signer::sr25519::Keypair::from_str(&secret_uri)
                         .map_err(map_user_error)?;
-
-                info!(
-                    "initialised Avail client in remote mode targeting {} (app_id={})",
-                    endpoint, app_id
-                );
-
-                return Ok(Self {
-                    inner: AvailClientModeInner::Remote(Box::new(RemoteClient {
-                        client,
-                        signer,
-                        app_id,
-                    })),
-                });
-            } else {
+                Self::connect(config, Some(signer)).await
+            }
+            AvailMode::Mock => {
                 warn!(
-                    "AVAIL_RPC_URL configured but AVAIL_SIGNING_KEY is missing; falling back to stub mode"
+                    "AVAIL_MODE=mock: intents are anchored in process memory and lost on restart"
                 );
+                Ok(Self::new_stub())
+            }
+            AvailMode::Disabled => {
+                info!("avail is disabled; accepted intents will not be anchored");
+                Ok(Self::disabled())
             }
         }
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            inner: AvailClientModeInner::Disabled,
+        }
+    }
+
+    // remote client without a signer, for reading what was posted; needs no AVAIL_SIGNING_KEY
+    pub async fn new_read_only(config: &Config) -> Result<Self> {
+        if !config.avail_enabled() {
+            return Err(RelayerError::Validation(
+                "AVAIL_RPC_URL and AVAIL_APPLICATION_ID are required to read from avail"
+                    .to_string(),
+            ));
+        }
+        Self::connect(config, None).await
+    }
+
+    async fn connect(
+        config: &Config,
+        signer: Option<avail_rust_client::subxt_signer::sr25519::Keypair>,
+    ) -> Result<Self> {
+        let endpoint = config
+            .avail_rpc_url
+            .as_ref()
+            .expect("avail_enabled implies rpc url present")
+            .clone();
+        let app_id = config
+            .avail_application_id
+            .expect("avail_enabled implies application id present");
+
+        let client = SdkClient::new(endpoint.trim())
+            .await
+            .map_err(map_sdk_error)?;
+
+        info!(
+            "initialised Avail client in remote mode targeting {} (app_id={}{})",
+            endpoint,
+            app_id,
+            if signer.is_some() { "" } else { ", read-only" }
+        );
 
-        info!("initialised Avail client in stub mode");
         Ok(Self {
-            inner: AvailClientModeInner::Stub(StubClient),
+            inner: AvailClientModeInner::Remote(Box::new(RemoteClient {
+                client,
+                signer,
+                app_id,
+            })),
         })
     }
 
+    // stub mode keeps everything it is sent in an in-memory avail mock
+    pub fn new_stub() -> Self {
+        Self::with_mock(AvailMock::new())
+    }
+
+    // stub client over a given mock; keep a clone of the mock to inject faults
+    pub fn with_mock(mock: AvailMock) -> Self {
+        Self {
+            inner: AvailClientModeInner::Stub(mock),
+        }
+    }
+
+    pub fn mock(&self) -> Option<&AvailMock> {
+        match &self.inner {
+            AvailClientModeInner::Stub(mock) => Some(mock),
+            AvailClientModeInner::Disabled | AvailClientModeInner::Remote(_) => None,
+        }
+    }
+
     pub fn mode(&self) -> AvailClientMode {
         match self.inner {
+            AvailClientModeInner::Disabled => AvailClientMode::Disabled,
             AvailClientModeInner::Stub(_) => AvailClientMode::Stub,
             AvailClientModeInner::Remote(_) => AvailClientMode::Remote,
         }
     }
 
-    pub async fn submit_intent(
+    // the record posted to avail for an accepted intent; built when the intent is accepted
+    pub fn intent_payload(
         &self,
         intent: &SubscriptionIntent,
         signature: &str,
         relayer: &str,
         chain_id: u64,
-    ) -> Result<AvailSubmissionResult> {
+    ) -> AvailIntent {
         let metadata = AvailMetadata {
             application_id: match &self.inner {
                 AvailClientModeInner::Remote(remote) => remote.app_id.to_string(),
-                AvailClientModeInner::Stub(_) => "aurum-stub".to_string(),
+                AvailClientModeInner::Stub(_) | AvailClientModeInner::Disabled => {
+                    "aurum-stub".to_string()
+                }
             },
             chain_id,
             submitted_at: Utc::now(),
             relayer: relayer.to_string(),
         };
 
-        let payload = AvailIntent {
+        AvailIntent {
             intent: intent.clone(),
             signature: signature.to_string(),
             metadata,
-        };
+        }
+    }
 
+    // waits for finality in remote mode, so only the outbox worker should call this
+    pub async fn submit_batch(&self, batch: &AvailBatch) -> Result<AvailSubmissionResult> {
         match &self.inner {
-            AvailClientModeInner::Stub(stub) => Ok(stub.stub_submission_result(&payload)),
-            AvailClientModeInner::Remote(remote) => remote.submit_intent(&payload).await,
+            AvailClientModeInner::Disabled => Err(disabled_error()),
+            AvailClientModeInner::Stub(mock) => {
+                mock.submit(encode_payload(&AvailPayload::Batch(batch.clone()))?)
+                    .await
+            }
+            AvailClientModeInner::Remote(remote) => {
+                remote
+                    .submit_data(&AvailPayload::Batch(batch.clone()))
+                    .await
+            }
         }
     }
 
-    pub async fn retrieve_intent(
+    // the raw extrinsic contents, unchecked
+    pub async fn retrieve_payload(
         &self,
         block_number: u64,
         extrinsic_index: u64,
-    ) -> Result<AvailIntent> {
+    ) -> Result<AvailPayload> {
         match &self.inner {
-            AvailClientModeInner::Stub(_) => Err(RelayerError::InternalError(
-                "avail client running in stub mode; retrieval unavailable".to_string(),
-            )),
+            AvailClientModeInner::Disabled => Err(disabled_error()),
+            AvailClientModeInner::Stub(mock) => {
+                decode_payload(&mock.fetch(block_number, extrinsic_index).await?)
+            }
             AvailClientModeInner::Remote(remote) => {
-                remote.fetch_intent(block_number, extrinsic_index).await
+                remote.fetch_payload(block_number, extrinsic_index).await
             }
         }
     }
 
+    // the intent for one subscription, from either a batch or a pre-batching single-intent extrinsic
+    pub async fn retrieve_intent(
+        &self,
+        block_number: u64,
+        extrinsic_index: u64,
+        subscription_id: &str,
+    ) -> Result<AvailIntent> {
+        let payload = self.retrieve_payload(block_number, extrinsic_index).await?;
+        intent_from_payload(payload, subscription_id)
+    }
+
+    // every data submission under our app id in one block, undecoded
+    pub async fn app_extrinsics(&self, block_number: u64) -> Result<Vec<AppExtrinsic>> {
+        match &self.inner {
+            AvailClientModeInner::Disabled => Err(disabled_error()),
+            AvailClientModeInner::Stub(mock) => mock.block(block_number).await,
+            AvailClientModeInner::Remote(remote) => remote.app_extrinsics(block_number).await,
+        }
+    }
+
+    pub async fn finalized_block_number(&self) -> Result<u64> {
+        match &self.inner {
+            AvailClientModeInner::Disabled => Err(disabled_error()),
+            AvailClientModeInner::Stub(mock) => Ok(mock.finalized_block_number()),
+            AvailClientModeInner::Remote(remote) => remote.finalized_block_number().await,
+        }
+    }
+
     pub async fn verify_data_availability(
         &self,
         block_number: u64,
         extrinsic_index: u64,
+        subscription_id: &str,
     ) -> Result<bool> {
-        match &self.inner {
-            AvailClientModeInner::Stub(_) => Ok(true),
-            AvailClientModeInner::Remote(remote) => {
-                match remote.fetch_intent(block_number, extrinsic_index).await {
-                    Ok(_) => Ok(true),
-                    Err(RelayerError::NotFound(_)) => Ok(false),
-                    Err(err) => Err(err),
-                }
-            }
+        match self
+            .retrieve_intent(block_number, extrinsic_index, subscription_id)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(RelayerError::NotFound(_)) => Ok(false),
+            Err(err) => Err(err),
         }
     }
 
@@ -218,31 +351,70 @@ impl AvailClient {
         &self,
         block_number: u64,
         extrinsic_index: u64,
+        subscription_id: &str,
     ) -> Result<Option<AvailIntent>> {
-        match self.mode() {
-            AvailClientMode::Stub => Ok(None),
-            AvailClientMode::Remote => {
-                match self.retrieve_intent(block_number, extrinsic_index).await {
-                    Ok(intent) => Ok(Some(intent)),
-                    Err(RelayerError::NotFound(_)) => Ok(None),
-                    Err(err) => Err(err),
-                }
-            }
+        match self
+            .retrieve_intent(block_number, extrinsic_index, subscription_id)
+            .await
+        {
+            Ok(intent) => Ok(Some(intent)),
+            Err(RelayerError::NotFound(_)) => Ok(None),
+            Err(err) => Err(err),
         }
     }
 }
 
-impl StubClient {
-    fn stub_submission_result(&self, payload: &AvailIntent) -> AvailSubmissionResult {
-        let seed = payload.intent.nonce + payload.metadata.chain_id;
-        let block_number = 1_000_000 + (seed % 10_000);
-        let extrinsic_index = payload.intent.nonce % 100;
+// picks the subscription's intent out of a payload; a batch leaf is only trusted once the
+// batch root recomputes and the intent hashes to the subscription id it is filed under
+fn intent_from_payload(payload: AvailPayload, subscription_id: &str) -> Result<AvailIntent> {
+    let batch = match payload {
+        AvailPayload::Intent(intent) => return Ok(intent),
+        AvailPayload::Batch(batch) => batch,
+    };
+
+    if !batch.root_matches_leaves()? {
+        return Err(RelayerError::InternalError(format!(
+            "avail batch merkle root {} does not match its leaves",
+            batch.merkle_root
+        )));
+    }
 
-        AvailSubmissionResult {
-            block_number,
-            extrinsic_index,
-        }
+    let leaf = batch.leaf(subscription_id).ok_or_else(|| {
+        RelayerError::NotFound(format!(
+            "subscription {} is not part of the avail batch",
+            subscription_id
+        ))
+    })?;
+
+    let recomputed =
+        ValidationService::generate_subscription_id(&leaf.record.intent, &leaf.record.signature)?;
+    if !recomputed.eq_ignore_ascii_case(subscription_id) {
+        return Err(RelayerError::InternalError(format!(
+            "avail batch leaf for {} hashes to {}",
+            subscription_id, recomputed
+        )));
     }
+
+    Ok(leaf.record.clone())
+}
+
+fn encode_payload(payload: &AvailPayload) -> Result<Vec<u8>> {
+    serde_json::to_vec(payload).map_err(|e| {
+        RelayerError::InternalError(format!("failed to serialize avail payload: {}", e))
+    })
+}
+
+fn decode_payload(data: &[u8]) -> Result<AvailPayload> {
+    serde_json::from_slice::<AvailPayload>(data).map_err(|e| {
+        RelayerError::InternalError(format!(
+            "failed to decode avail payload from extrinsic: {}",
+            e
+        ))
+    })
+}
+
+fn disabled_error() -> RelayerError {
+    RelayerError::Unavailable("avail is disabled on this relayer".to_string())
 }
 
 fn map_sdk_error(error: SdkError) -> RelayerError {
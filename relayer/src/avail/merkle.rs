@@ -0,0 +1,165 @@
+use ethers::core::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::{RelayerError, Result};
+
+// domain prefixes keep a leaf from ever being passed off as an inner node
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SiblingPosition {
+    Left,
+    Right,
+}
+
+// one level of an inclusion proof: the sibling to hash with and which side it sits on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ProofStep {
+    /// which side of the running hash the sibling goes on
+    pub position: SiblingPosition,
+    #[schema(value_type = crate::api::openapi::Hash)]
+    pub hash: String,
+}
+
+// leaf for an intent: keccak256(0x00 ‖ subscriptionId)
+pub fn leaf_hash(subscription_id: &str) -> Result<[u8; 32]> {
+    let id = decode_hash(subscription_id)?;
+    let mut preimage = Vec::with_capacity(33);
+    preimage.push(LEAF_PREFIX);
+    preimage.extend_from_slice(&id);
+    Ok(keccak256(&preimage))
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(65);
+    preimage.push(NODE_PREFIX);
+    preimage.extend_from_slice(left);
+    preimage.extend_from_slice(right);
+    keccak256(&preimage)
+}
+
+// an odd node at the end of a level is carried up unchanged
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!("chunks(2) yields one or two nodes"),
+        })
+        .collect()
+}
+
+pub fn root(leaves: &[[u8; 32]]) -> Option<[u8; 32]> {
+    if leaves.is_empty() {
+        return None;
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    Some(level[0])
+}
+
+pub fn proof(leaves: &[[u8; 32]], index: usize) -> Option<Vec<ProofStep>> {
+    if index >= leaves.len() {
+        return None;
+    }
+    let mut steps = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut index = index;
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            steps.push(ProofStep {
+                position: if sibling < index {
+                    SiblingPosition::Left
+                } else {
+                    SiblingPosition::Right
+                },
+                hash: encode_hash(&level[sibling]),
+            });
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    Some(steps)
+}
+
+// folds the proof over the leaf and compares against the root
+pub fn verify(leaf: &[u8; 32], proof: &[ProofStep], root: &[u8; 32]) -> Result<bool> {
+    let mut current = *leaf;
+    for step in proof {
+        let sibling = decode_hash(&step.hash)?;
+        current = match step.position {
+            SiblingPosition::Left => node_hash(&sibling, &current),
+            SiblingPosition::Right => node_hash(&current, &sibling),
+        };
+    }
+    Ok(&current == root)
+}
+
+pub fn encode_hash(hash: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(hash))
+}
+
+pub fn decode_hash(value: &str) -> Result<[u8; 32]> {
+    let bytes = value
+        .strip_prefix("0x")
+        .and_then(|hex_part| hex::decode(hex_part).ok())
+        .ok_or_else(|| RelayerError::Validation(format!("invalid 32-byte hash: {}", value)))?;
+    bytes
+        .try_into()
+        .map_err(|_| RelayerError::Validation(format!("invalid 32-byte hash: {}", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: u8) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| leaf_hash(&encode_hash(&[i; 32])).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_single_leaf_is_its_own_root() {
+        let leaves = leaves(1);
+        assert_eq!(root(&leaves), Some(leaves[0]));
+        assert!(proof(&leaves, 0).unwrap().is_empty());
+        assert_eq!(root(&[]), None);
+    }
+
+    #[test]
+    fn test_every_leaf_proves_against_the_root() {
+        for count in 1..=9 {
+            let leaves = leaves(count);
+            let root = root(&leaves).unwrap();
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = proof(&leaves, index).unwrap();
+                assert!(verify(leaf, &proof, &root).unwrap(), "{}/{}", index, count);
+            }
+        }
+    }
+
+    #[test]
+    fn test_proof_rejects_other_leaves_and_roots() {
+        let leaves = leaves(5);
+        let root = root(&leaves).unwrap();
+        let proof = proof(&leaves, 2).unwrap();
+        assert!(!verify(&leaves[3], &proof, &root).unwrap());
+        assert!(!verify(&leaves[2], &proof, &leaves[0]).unwrap());
+        assert!(super::proof(&leaves, 5).is_none());
+    }
+
+    #[test]
+    fn test_leaf_is_domain_separated_from_subscription_id() {
+        let id = [7u8; 32];
+        assert_ne!(leaf_hash(&encode_hash(&id)).unwrap(), keccak256(id));
+        assert!(leaf_hash("0x1234").is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    avail::types::{AppExtrinsic, AvailSubmissionResult},
+    error::{RelayerError, Result},
+};
+
+// block numbers are handed out in order from here, so tests can predict them
+pub const MOCK_FIRST_BLOCK: u64 = 1;
+
+// what the next submission runs into instead of being included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockFault {
+    // accepted but never finalized, like a remote transaction dropped from the pool
+    Drop,
+    // the node refuses the call
+    Unavailable,
+}
+
+// in-process stand-in for an avail node: every submission is stored in a block of its own and
+// can be read back by block and extrinsic. clones share the same chain, so a test can keep a
+// handle and inject faults into the client it handed out
+#[derive(Clone, Default)]
+pub struct AvailMock {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    // block number -> extrinsic data in index order; a forgotten extrinsic leaves a gap
+    blocks: BTreeMap<u64, Vec<Option<Vec<u8>>>>,
+    faults: VecDeque<MockFault>,
+    latency: Duration,
+}
+
+impl AvailMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // applied before every submission and read
+    pub fn set_latency(&self, latency: Duration) {
+        self.state.lock().unwrap().latency = latency;
+    }
+
+    // queued faults are consumed one per submission, in order
+    pub fn fail_next_submission(&self, fault: MockFault) {
+        self.state.lock().unwrap().faults.push_back(fault);
+    }
+
+    // drops a stored extrinsic so reads of it return NotFound, as for a pruned or wrong reference
+    pub fn forget(&self, block_number: u64, extrinsic_index: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state
+            .blocks
+            .get_mut(&block_number)
+            .and_then(|block| block.get_mut(extrinsic_index as usize))
+            .and_then(Option::take)
+            .is_some()
+    }
+
+    // how many extrinsics the mock chain holds, forgotten ones excluded
+    pub fn extrinsic_count(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state
+            .blocks
+            .values()
+            .flatten()
+            .filter(|data| data.is_some())
+            .count()
+    }
+
+    pub async fn submit(&self, data: Vec<u8>) -> Result<AvailSubmissionResult> {
+        self.wait().await;
+        let mut state = self.state.lock().unwrap();
+        match state.faults.pop_front() {
+            Some(MockFault::Drop) => Err(RelayerError::InternalError(
+                "avail transaction dropped before inclusion".to_string(),
+            )),
+            Some(MockFault::Unavailable) => Err(RelayerError::InternalError(
+                "avail sdk error: mock node unavailable".to_string(),
+            )),
+            None => {
+                let block_number = state
+                    .blocks
+                    .last_key_value()
+                    .map_or(MOCK_FIRST_BLOCK, |(block, _)| block + 1);
+                state.blocks.insert(block_number, vec![Some(data)]);
+                Ok(AvailSubmissionResult {
+                    block_number,
+                    extrinsic_index: 0,
+                })
+            }
+        }
+    }
+
+    pub async fn fetch(&self, block_number: u64, extrinsic_index: u64) -> Result<Vec<u8>> {
+        self.wait().await;
+        let state = self.state.lock().unwrap();
+        state
+            .blocks
+            .get(&block_number)
+            .and_then(|block| block.get(extrinsic_index as usize))
+            .and_then(Clone::clone)
+            .ok_or_else(|| {
+                RelayerError::NotFound(
+                    "avail extrinsic not found for provided references".to_string(),
+                )
+            })
+    }
+
+    pub async fn block(&self, block_number: u64) -> Result<Vec<AppExtrinsic>> {
+        self.wait().await;
+        let state = self.state.lock().unwrap();
+        let Some(block) = state.blocks.get(&block_number) else {
+            return Ok(Vec::new());
+        };
+        Ok(block
+            .iter()
+            .enumerate()
+            .filter_map(|(index, data)| {
+                data.clone().map(|data| AppExtrinsic {
+                    block_number,
+                    extrinsic_index: index as u64,
+                    data,
+                })
+            })
+            .collect())
+    }
+
+    // every block is final as soon as it exists
+    pub fn finalized_block_number(&self) -> u64 {
+        let state = self.state.lock().unwrap();
+        state
+            .blocks
+            .last_key_value()
+            .map_or(MOCK_FIRST_BLOCK - 1, |(block, _)| *block)
+    }
+
+    async fn wait(&self) {
+        let latency = self.state.lock().unwrap().latency;
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stores_submissions_in_consecutive_blocks() {
+        let mock = AvailMock::new();
+        let first = mock.submit(b"one".to_vec()).await.unwrap();
+        let second = mock.submit(b"two".to_vec()).await.unwrap();
+        assert_eq!(first.block_number, MOCK_FIRST_BLOCK);
+        assert_eq!(second.block_number, MOCK_FIRST_BLOCK + 1);
+        assert_eq!(mock.finalized_block_number(), second.block_number);
+
+        assert_eq!(mock.fetch(second.block_number, 0).await.unwrap(), b"two");
+        let block = mock.block(first.block_number).await.unwrap();
+        assert_eq!(block.len(), 1);
+        assert_eq!(block[0].data, b"one");
+        assert!(mock.block(99).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_faults_are_consumed_in_order() {
+        let mock = AvailMock::new();
+        mock.fail_next_submission(MockFault::Drop);
+        mock.fail_next_submission(MockFault::Unavailable);
+
+        assert!(mock.submit(b"a".to_vec()).await.is_err());
+        assert!(mock.submit(b"b".to_vec()).await.is_err());
+        let included = mock.submit(b"c".to_vec()).await.unwrap();
+        assert_eq!(included.block_number, MOCK_FIRST_BLOCK);
+        assert_eq!(mock.extrinsic_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_forgotten_extrinsic_is_not_found() {
+        let mock = AvailMock::new();
+        let included = mock.submit(b"gone".to_vec()).await.unwrap();
+        assert!(mock.forget(included.block_number, included.extrinsic_index));
+        assert!(!mock.forget(included.block_number, included.extrinsic_index));
+
+        assert!(matches!(
+            mock.fetch(included.block_number, included.extrinsic_index)
+                .await,
+            Err(RelayerError::NotFound(_))
+        ));
+        assert!(mock.block(included.block_number).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_latency_delays_every_call() {
+        let mock = AvailMock::new();
+        mock.set_latency(Duration::from_millis(20));
+
+        let started = std::time::Instant::now();
+        let included = mock.submit(b"slow".to_vec()).await.unwrap();
+        mock.fetch(included.block_number, 0).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(40));
+    }
+}
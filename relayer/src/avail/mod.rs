@@ -1,5 +1,14 @@
 pub mod client;
+pub mod merkle;
+pub mod mock;
+pub mod outbox;
+pub mod recovery;
 pub mod types;
 
 pub use client::{AvailClient, AvailClientMode};
-pub use types::{AvailIntent, AvailMetadata, AvailSubmissionResult};
+pub use mock::{AvailMock, MockFault};
+pub use recovery::{Recovery, RecoveryReport};
+pub use types::{
+    AppExtrinsic, AvailBatch, AvailBatchLeaf, AvailIntent, AvailMetadata, AvailPayload,
+    AvailSubmissionResult,
+};
@@ -0,0 +1,562 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::{
+    avail::{merkle, AvailBatch, AvailBatchLeaf, AvailClient, AvailIntent},
+    database::{
+        models::{AvailBatchMember, AvailStatus, AvailSubmission},
+        queries::Queries,
+    },
+    error::{RelayerError, Result},
+    utils::backoff::backoff,
+};
+
+pub const BATCH_VERSION: u32 = 1;
+const MAX_SUBMISSION_ATTEMPTS: i32 = 12;
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(15);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(3_600);
+// bounds one run when a backlog has built up; the rest waits for the next window
+const MAX_BATCHES_PER_RUN: usize = 10;
+// remote submissions wait for finality; the lease has to outlast a whole run
+const SUBMISSION_TIMEOUT_SECONDS: u64 = 180;
+const SUBMISSION_LEASE_SECONDS: i64 = 30 * 60;
+// avail rejects extrinsics over 512 KiB; the rest is headroom for the batch envelope
+const MAX_BATCH_BYTES: usize = 448 * 1024;
+
+// anchors everything queued since the last run in batches of up to max_intents, split further
+// when they'd exceed MAX_BATCH_BYTES; returns how many intents were included
+pub async fn submit_pending_intents(
+    queries: &Queries,
+    avail_client: &AvailClient,
+    max_intents: i64,
+) -> Result<usize> {
+    let mut submitted = 0;
+    // one batch at a time: every extrinsic is signed by the same account
+    for _ in 0..MAX_BATCHES_PER_RUN {
+        let submissions = queries
+            .claim_due_avail_submissions(
+                max_intents,
+                chrono::Duration::seconds(SUBMISSION_LEASE_SECONDS),
+            )
+            .await?;
+        let claimed = submissions.len() as i64;
+        if claimed == 0 {
+            break;
+        }
+
+        submitted += submit_batch(queries, avail_client, submissions).await?;
+
+        if claimed < max_intents {
+            break;
+        }
+    }
+    Ok(submitted)
+}
+
+async fn submit_batch(
+    queries: &Queries,
+    avail_client: &AvailClient,
+    submissions: Vec<AvailSubmission>,
+) -> Result<usize> {
+    let mut submitted = 0;
+    let mut members = Vec::new();
+    let mut leaves = Vec::new();
+    let mut hashes = Vec::new();
+    let mut batch_bytes = 0;
+    for submission in submissions {
+        let decoded = serde_json::from_value::<AvailIntent>(submission.payload.clone())
+            .map_err(|e| format!("invalid avail payload: {}", e))
+            .and_then(|record| {
+                let leaf = AvailBatchLeaf {
+                    subscription_id: submission.subscription_id.clone(),
+                    record,
+                };
+                // the leaf plus the comma separating it from the next
+                let size = serde_json::to_vec(&leaf)
+                    .map(|encoded| encoded.len() + 1)
+                    .map_err(|e| format!("invalid avail payload: {}", e))?;
+                merkle::leaf_hash(&submission.subscription_id)
+                    .map(|hash| (leaf, hash, size))
+                    .map_err(|e| format!("invalid avail payload: {}", e))
+            })
+            .and_then(|(leaf, hash, size)| {
+                if size > MAX_BATCH_BYTES {
+                    return Err(format!(
+                        "avail payload is {} bytes, over the {} byte batch limit",
+                        size, MAX_BATCH_BYTES
+                    ));
+                }
+                Ok((leaf, hash, size))
+            });
+        match decoded {
+            Ok((leaf, hash, size)) => {
+                // the claim is split into several extrinsics when it doesn't fit in one
+                if batch_bytes + size > MAX_BATCH_BYTES {
+                    submitted += anchor_batch(
+                        queries,
+                        avail_client,
+                        std::mem::take(&mut members),
+                        std::mem::take(&mut leaves),
+                        std::mem::take(&mut hashes),
+                    )
+                    .await?;
+                    batch_bytes = 0;
+                }
+                batch_bytes += size;
+                leaves.push(leaf);
+                hashes.push(hash);
+                members.push(submission);
+            }
+            Err(error) => {
+                // retrying can't fix a payload that doesn't decode or fit
+                warn!(
+                    "dropping avail submission {} for {}: {}",
+                    submission.id, submission.subscription_id, error
+                );
+                queries
+                    .record_avail_submission_failure(
+                        submission.id,
+                        AvailStatus::Failed,
+                        &error,
+                        Utc::now(),
+                    )
+                    .await?;
+            }
+        }
+    }
+
+    submitted += anchor_batch(queries, avail_client, members, leaves, hashes).await?;
+    Ok(submitted)
+}
+
+// posts one extrinsic; on failure every member is rescheduled
+async fn anchor_batch(
+    queries: &Queries,
+    avail_client: &AvailClient,
+    members: Vec<AvailSubmission>,
+    leaves: Vec<AvailBatchLeaf>,
+    hashes: Vec<[u8; 32]>,
+) -> Result<usize> {
+    let Some(root) = merkle::root(&hashes) else {
+        return Ok(0);
+    };
+    let batch = AvailBatch {
+        version: BATCH_VERSION,
+        merkle_root: merkle::encode_hash(&root),
+        leaves,
+    };
+
+    // a timed out submission may still land; a retry then anchors the intents a second time,
+    // which is harmless for data availability
+    let outcome = tokio::time::timeout(
+        Duration::from_secs(SUBMISSION_TIMEOUT_SECONDS),
+        avail_client.submit_batch(&batch),
+    )
+    .await
+    .unwrap_or_else(|_| {
+        Err(RelayerError::InternalError(format!(
+            "avail submission timed out after {}s",
+            SUBMISSION_TIMEOUT_SECONDS
+        )))
+    });
+
+    let error = match outcome {
+        Ok(result) => {
+            let batch_members = members
+                .iter()
+                .enumerate()
+                .map(|(index, submission)| {
+                    let proof = merkle::proof(&hashes, index).expect("index is within the batch");
+                    Ok(AvailBatchMember {
+                        submission_id: submission.id,
+                        leaf_index: index as i32,
+                        merkle_proof: serde_json::to_value(proof).map_err(|e| {
+                            RelayerError::InternalError(format!(
+                                "failed to serialize merkle proof: {}",
+                                e
+                            ))
+                        })?,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            queries
+                .complete_avail_batch(
+                    &batch.merkle_root,
+                    batch.leaves.len(),
+                    result.block_number,
+                    result.extrinsic_index,
+                    &batch_members,
+                )
+                .await?;
+            info!(
+                "anchored {} intents on avail - root: {}, block: {}, extrinsic: {}",
+                members.len(),
+                batch.merkle_root,
+                result.block_number,
+                result.extrinsic_index
+            );
+            return Ok(members.len());
+        }
+        Err(e) => e.to_string(),
+    };
+
+    for submission in &members {
+        if let Err(e) = record_failure(queries, submission, &error).await {
+            warn!(
+                "failed to record avail submission {} for {}: {}",
+                submission.id, submission.subscription_id, e
+            );
+        }
+    }
+    Ok(0)
+}
+
+async fn record_failure(
+    queries: &Queries,
+    submission: &AvailSubmission,
+    error: &str,
+) -> Result<()> {
+    let attempts = submission.attempts + 1;
+    let (status, next_attempt_at) = if attempts >= MAX_SUBMISSION_ATTEMPTS {
+        warn!(
+            "giving up on avail submission for {} after {} attempts: {}",
+            submission.subscription_id, attempts, error
+        );
+        (AvailStatus::Failed, Utc::now())
+    } else {
+        let delay = backoff(BASE_RETRY_DELAY, MAX_RETRY_DELAY, attempts.into());
+        warn!(
+            "avail submission for {} failed (attempt {}), retrying in {}s: {}",
+            submission.subscription_id,
+            attempts,
+            delay.as_secs(),
+            error
+        );
+        (
+            AvailStatus::Pending,
+            Utc::now() + chrono::Duration::seconds(delay.as_secs() as i64),
+        )
+    };
+
+    queries
+        .record_avail_submission_failure(submission.id, status, error, next_attempt_at)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::SubscriptionIntent;
+    use crate::avail::{AvailMock, MockFault};
+    use crate::database::models::{IntentCache, Subscription};
+    use crate::database::Database;
+
+    const SUBSCRIPTION_ID: &str =
+        "0x4444444444444444444444444444444444444444444444444444444444444444";
+    const MAX_INTENTS: i64 = 100;
+
+    fn intent() -> SubscriptionIntent {
+        SubscriptionIntent {
+            subscriber: "0x1234567890123456789012345678901234567890".to_string(),
+            merchant: "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd".to_string(),
+            amount: "1000".to_string(),
+            interval: 3600,
+            start_time: 1,
+            max_payments: 2,
+            max_total_amount: "2000".to_string(),
+            expiry: 2,
+            nonce: 7,
+            token: "0x0000000000000000000000000000000000000000".to_string(),
+        }
+    }
+
+    async fn seed(queries: &Queries) {
+        seed_subscription(queries, SUBSCRIPTION_ID).await;
+    }
+
+    async fn seed_subscription(queries: &Queries, subscription_id: &str) {
+        let now = Utc::now();
+        queries
+            .insert_subscription(&Subscription {
+                id: subscription_id.to_string(),
+                subscriber: intent().subscriber,
+                merchant: intent().merchant,
+                amount: "1000".to_string(),
+                interval_seconds: 3600,
+                start_time: now,
+                max_payments: 2,
+                max_total_amount: "2000".to_string(),
+                expiry: now + chrono::Duration::days(1),
+                nonce: 7,
+                token_address: intent().token,
+                status: "ACTIVE".to_string(),
+                executed_payments: 0,
+                total_paid: "0".to_string(),
+                next_payment_due: now,
+                failure_count: 0,
+                chain: "sepolia".to_string(),
+                created_at: now,
+                updated_at: now,
+                avail_block_number: None,
+                avail_extrinsic_index: None,
+            })
+            .await
+            .unwrap();
+        queries
+            .cache_intent(&IntentCache {
+                id: 0,
+                subscription_intent: serde_json::to_value(intent()).unwrap(),
+                signature: "0x00".to_string(),
+                subscription_id: subscription_id.to_string(),
+                subscriber: intent().subscriber,
+                merchant: intent().merchant,
+                amount: "1000".to_string(),
+                interval_seconds: 3600,
+                start_time: now,
+                max_payments: 2,
+                max_total_amount: "2000".to_string(),
+                expiry: now + chrono::Duration::days(1),
+                nonce: 7,
+                processed: false,
+                created_at: now,
+                processed_at: None,
+                chain: "sepolia".to_string(),
+                avail_block_number: None,
+                avail_extrinsic_index: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_submits_queued_intents_and_backfills_references() {
+        let database = Database::new("stub").await.unwrap();
+        let queries = database.queries();
+        let avail_client = AvailClient::new_stub();
+        seed(&queries).await;
+
+        let payload = avail_client.intent_payload(&intent(), "0x00", "0x01", 11_155_111);
+        queries
+            .enqueue_avail_submission(SUBSCRIPTION_ID, &serde_json::to_value(&payload).unwrap())
+            .await
+            .unwrap();
+        let pending = queries
+            .get_avail_submission(SUBSCRIPTION_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(pending.status, "PENDING");
+
+        assert_eq!(
+            submit_pending_intents(&queries, &avail_client, MAX_INTENTS)
+                .await
+                .unwrap(),
+            1
+        );
+
+        let submitted = queries
+            .get_avail_submission(SUBSCRIPTION_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(submitted.status, "SUBMITTED");
+        assert_eq!(submitted.attempts, 1);
+        let block = submitted.block_number.unwrap();
+        let subscription = queries
+            .get_subscription(SUBSCRIPTION_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(subscription.avail_block_number, Some(block));
+        assert_eq!(
+            subscription.avail_extrinsic_index,
+            submitted.extrinsic_index
+        );
+        let cached = queries
+            .get_cached_intent(SUBSCRIPTION_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.avail_block_number, Some(block));
+        assert_eq!(submitted.leaf_index, Some(0));
+
+        // nothing left to submit
+        assert_eq!(
+            submit_pending_intents(&queries, &avail_client, MAX_INTENTS)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dropped_batch_is_retried_with_backoff() {
+        let database = Database::new("stub").await.unwrap();
+        let queries = database.queries();
+        let mock = AvailMock::new();
+        let avail_client = AvailClient::with_mock(mock.clone());
+        seed(&queries).await;
+
+        let payload = avail_client.intent_payload(&intent(), "0x00", "0x01", 11_155_111);
+        queries
+            .enqueue_avail_submission(SUBSCRIPTION_ID, &serde_json::to_value(&payload).unwrap())
+            .await
+            .unwrap();
+        mock.fail_next_submission(MockFault::Drop);
+
+        assert_eq!(
+            submit_pending_intents(&queries, &avail_client, MAX_INTENTS)
+                .await
+                .unwrap(),
+            0
+        );
+        let retrying = queries
+            .get_avail_submission(SUBSCRIPTION_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(retrying.status, "PENDING");
+        assert_eq!(retrying.attempts, 1);
+        assert!(retrying
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("dropped before inclusion"));
+        assert!(retrying.next_attempt_at > Utc::now());
+        assert_eq!(mock.extrinsic_count(), 0);
+
+        // not due again until the backoff has passed
+        assert_eq!(
+            submit_pending_intents(&queries, &avail_client, MAX_INTENTS)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_undecodable_payload_fails_without_retry() {
+        let database = Database::new("stub").await.unwrap();
+        let queries = database.queries();
+        let avail_client = AvailClient::new_stub();
+        seed(&queries).await;
+
+        queries
+            .enqueue_avail_submission(SUBSCRIPTION_ID, &serde_json::json!({ "intent": 1 }))
+            .await
+            .unwrap();
+        assert_eq!(
+            submit_pending_intents(&queries, &avail_client, MAX_INTENTS)
+                .await
+                .unwrap(),
+            0
+        );
+
+        let failed = queries
+            .get_avail_submission(SUBSCRIPTION_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(failed.status, "FAILED");
+        assert!(failed
+            .last_error
+            .as_deref()
+            .unwrap()
+            .starts_with("invalid avail payload"));
+    }
+
+    #[tokio::test]
+    async fn test_batches_intents_with_verifiable_proofs() {
+        let database = Database::new("stub").await.unwrap();
+        let queries = database.queries();
+        let avail_client = AvailClient::new_stub();
+
+        let ids: Vec<String> = (1..=5u8).map(|i| merkle::encode_hash(&[i; 32])).collect();
+        for id in &ids {
+            seed_subscription(&queries, id).await;
+            let payload = avail_client.intent_payload(&intent(), "0x00", "0x01", 11_155_111);
+            queries
+                .enqueue_avail_submission(id, &serde_json::to_value(&payload).unwrap())
+                .await
+                .unwrap();
+        }
+
+        // two full batches and a remainder
+        assert_eq!(
+            submit_pending_intents(&queries, &avail_client, 2)
+                .await
+                .unwrap(),
+            5
+        );
+
+        let mut batch_ids = std::collections::HashSet::new();
+        for id in &ids {
+            let submission = queries.get_avail_submission(id).await.unwrap().unwrap();
+            let batch = queries
+                .get_avail_batch(submission.batch_id.unwrap())
+                .await
+                .unwrap()
+                .unwrap();
+            batch_ids.insert(batch.id);
+            assert_eq!(submission.block_number, Some(batch.block_number));
+
+            let proof: Vec<merkle::ProofStep> =
+                serde_json::from_value(submission.merkle_proof.unwrap()).unwrap();
+            let leaf = merkle::leaf_hash(id).unwrap();
+            let root = merkle::decode_hash(&batch.merkle_root).unwrap();
+            assert!(merkle::verify(&leaf, &proof, &root).unwrap());
+        }
+        assert_eq!(batch_ids.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_claims_are_split_by_encoded_size() {
+        let database = Database::new("stub").await.unwrap();
+        let queries = database.queries();
+        let mock = AvailMock::new();
+        let avail_client = AvailClient::with_mock(mock.clone());
+
+        // two of these fit in one extrinsic, three don't; the last fits in none
+        let sizes = [200 * 1024, 200 * 1024, 200 * 1024, MAX_BATCH_BYTES];
+        let ids: Vec<String> = (1..=4u8).map(|i| merkle::encode_hash(&[i; 32])).collect();
+        for (id, size) in ids.iter().zip(sizes) {
+            seed_subscription(&queries, id).await;
+            let signature = format!("0x{}", "ab".repeat(size / 2));
+            let payload = avail_client.intent_payload(&intent(), &signature, "0x01", 11_155_111);
+            queries
+                .enqueue_avail_submission(id, &serde_json::to_value(&payload).unwrap())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            submit_pending_intents(&queries, &avail_client, MAX_INTENTS)
+                .await
+                .unwrap(),
+            3
+        );
+        assert_eq!(mock.extrinsic_count(), 2);
+
+        let mut batch_ids = std::collections::HashSet::new();
+        for id in &ids[..3] {
+            let submission = queries.get_avail_submission(id).await.unwrap().unwrap();
+            assert_eq!(submission.status, "SUBMITTED");
+            batch_ids.insert(submission.batch_id.unwrap());
+        }
+        assert_eq!(batch_ids.len(), 2);
+
+        let oversized = queries
+            .get_avail_submission(&ids[3])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(oversized.status, "FAILED");
+        assert!(oversized
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("over the"));
+    }
+}
@@ -0,0 +1,391 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use tracing::{info, warn};
+
+use crate::{
+    api::validation::ValidationService,
+    avail::{merkle, AppExtrinsic, AvailBatch, AvailClient, AvailIntent, AvailPayload},
+    blockchain::BlockchainClient,
+    chains::ChainRegistry,
+    database::{
+        models::{AvailBatchMember, IntentCache, Subscription, SubscriptionStatus},
+        queries::Queries,
+    },
+    error::{RelayerError, Result},
+};
+
+const PROGRESS_EVERY_BLOCKS: u64 = 1_000;
+
+// rebuilds subscriptions and intent_cache from what this relayer posted to avail; nothing is
+// restored unless the subscriber's signature still verifies
+pub struct Recovery<'a> {
+    pub queries: &'a Queries,
+    pub chains: &'a ChainRegistry,
+    // when set, status and payment counts come from the contract and intents it never created
+    // are skipped; without it everything is restored as PENDING
+    pub blockchain_client: Option<&'a BlockchainClient>,
+    // count what would be restored without writing anything
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct RecoveryReport {
+    pub blocks_scanned: u64,
+    pub extrinsics: usize,
+    // extrinsics under our app id that aren't an intent or a batch
+    pub undecodable: usize,
+    pub intents_found: usize,
+    pub restored: Vec<String>,
+    pub already_present: usize,
+    pub rejected: usize,
+    // signed and anchored but never created on chain, so there is nothing to restore
+    pub not_on_chain: usize,
+}
+
+enum Outcome {
+    Restored,
+    AlreadyPresent,
+    Rejected,
+    NotOnChain,
+}
+
+impl Recovery<'_> {
+    // scans from..=to; stops at the first avail or rpc error so a rerun can pick up from that block
+    pub async fn scan(
+        &self,
+        avail_client: &AvailClient,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<RecoveryReport> {
+        let mut report = RecoveryReport::default();
+        for block_number in from_block..=to_block {
+            let extrinsics = avail_client.app_extrinsics(block_number).await?;
+            for extrinsic in &extrinsics {
+                self.restore_extrinsic(extrinsic, &mut report).await?;
+            }
+            report.blocks_scanned += 1;
+            if report.blocks_scanned % PROGRESS_EVERY_BLOCKS == 0 {
+                info!(
+                    "avail recovery at block {} of {}: {} intents found, {} restored",
+                    block_number,
+                    to_block,
+                    report.intents_found,
+                    report.restored.len()
+                );
+            }
+        }
+        Ok(report)
+    }
+
+    pub async fn restore_extrinsic(
+        &self,
+        extrinsic: &AppExtrinsic,
+        report: &mut RecoveryReport,
+    ) -> Result<()> {
+        report.extrinsics += 1;
+        let payload = match serde_json::from_slice::<AvailPayload>(&extrinsic.data) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!(
+                    "skipping undecodable avail extrinsic {}/{}: {}",
+                    extrinsic.block_number, extrinsic.extrinsic_index, e
+                );
+                report.undecodable += 1;
+                return Ok(());
+            }
+        };
+
+        match payload {
+            AvailPayload::Intent(record) => {
+                report.intents_found += 1;
+                let subscription_id = match ValidationService::generate_subscription_id(
+                    &record.intent,
+                    &record.signature,
+                ) {
+                    Ok(id) => id,
+                    Err(e) => {
+                        warn!(
+                            "rejecting intent in avail extrinsic {}/{}: {}",
+                            extrinsic.block_number, extrinsic.extrinsic_index, e
+                        );
+                        report.rejected += 1;
+                        return Ok(());
+                    }
+                };
+                let outcome = self
+                    .restore_intent(&subscription_id, &record, extrinsic)
+                    .await?;
+                report.record(outcome, subscription_id);
+            }
+            AvailPayload::Batch(batch) => self.restore_batch(batch, extrinsic, report).await?,
+        }
+        Ok(())
+    }
+
+    async fn restore_batch(
+        &self,
+        batch: AvailBatch,
+        extrinsic: &AppExtrinsic,
+        report: &mut RecoveryReport,
+    ) -> Result<()> {
+        report.intents_found += batch.leaves.len();
+        let hashes = batch
+            .leaves
+            .iter()
+            .map(|leaf| merkle::leaf_hash(&leaf.subscription_id))
+            .collect::<Result<Vec<_>>>();
+        let hashes = match hashes {
+            Ok(hashes) if batch.root_matches_leaves().unwrap_or(false) => hashes,
+            _ => {
+                warn!(
+                    "rejecting avail batch {}/{}: merkle root {} does not match its leaves",
+                    extrinsic.block_number, extrinsic.extrinsic_index, batch.merkle_root
+                );
+                report.rejected += batch.leaves.len();
+                return Ok(());
+            }
+        };
+
+        let mut members = Vec::new();
+        for (index, leaf) in batch.leaves.iter().enumerate() {
+            let recomputed = ValidationService::generate_subscription_id(
+                &leaf.record.intent,
+                &leaf.record.signature,
+            );
+            if !recomputed.is_ok_and(|id| id.eq_ignore_ascii_case(&leaf.subscription_id)) {
+                warn!(
+                    "rejecting {} in avail batch {}/{}: intent does not hash to its subscription id",
+                    leaf.subscription_id, extrinsic.block_number, extrinsic.extrinsic_index
+                );
+                report.rejected += 1;
+                continue;
+            }
+
+            let subscription_id = leaf.subscription_id.to_lowercase();
+            let outcome = self
+                .restore_intent(&subscription_id, &leaf.record, extrinsic)
+                .await?;
+            if matches!(outcome, Outcome::Restored) && !self.dry_run {
+                // restore the outbox row too so inclusion proofs keep working
+                let payload = serde_json::to_value(&leaf.record).map_err(|e| {
+                    RelayerError::InternalError(format!("failed to serialize avail intent: {}", e))
+                })?;
+                let submission = self
+                    .queries
+                    .enqueue_avail_submission(&subscription_id, &payload)
+                    .await?;
+                let proof = merkle::proof(&hashes, index).expect("index is within the batch");
+                members.push(AvailBatchMember {
+                    submission_id: submission.id,
+                    leaf_index: index as i32,
+                    merkle_proof: serde_json::to_value(proof).map_err(|e| {
+                        RelayerError::InternalError(format!(
+                            "failed to serialize merkle proof: {}",
+                            e
+                        ))
+                    })?,
+                });
+            }
+            report.record(outcome, subscription_id);
+        }
+
+        if !members.is_empty() {
+            self.queries
+                .complete_avail_batch(
+                    &batch.merkle_root,
+                    batch.leaves.len(),
+                    extrinsic.block_number,
+                    extrinsic.extrinsic_index,
+                    &members,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn restore_intent(
+        &self,
+        subscription_id: &str,
+        record: &AvailIntent,
+        extrinsic: &AppExtrinsic,
+    ) -> Result<Outcome> {
+        // the same intent can be anchored twice when a submission timed out but still landed
+        if self
+            .queries
+            .get_subscription(subscription_id)
+            .await?
+            .is_some()
+        {
+            return Ok(Outcome::AlreadyPresent);
+        }
+
+        let Some(chain) = self.chains.get(record.metadata.chain_id) else {
+            warn!(
+                "rejecting {}: chain id {} is not configured",
+                subscription_id, record.metadata.chain_id
+            );
+            return Ok(Outcome::Rejected);
+        };
+        let verifying_contract =
+            Address::from_str(&chain.subscription_manager_address).map_err(|_| {
+                RelayerError::Validation("invalid subscription manager address".to_string())
+            })?;
+        if let Err(e) = ValidationService::validate_intent_signature(
+            &record.intent,
+            &record.signature,
+            verifying_contract,
+            chain.chain_id,
+        ) {
+            warn!("rejecting {}: {}", subscription_id, e);
+            return Ok(Outcome::Rejected);
+        }
+
+        let intent = &record.intent;
+        let (Some(start_time), Some(expiry)) =
+            (timestamp(intent.start_time), timestamp(intent.expiry))
+        else {
+            warn!("rejecting {}: timestamps out of range", subscription_id);
+            return Ok(Outcome::Rejected);
+        };
+        let block_number = Some(extrinsic.block_number as i64);
+        let extrinsic_index = Some(extrinsic.extrinsic_index as i64);
+        let now = Utc::now();
+
+        let mut subscription = Subscription {
+            id: subscription_id.to_string(),
+            subscriber: intent.subscriber.clone(),
+            merchant: intent.merchant.clone(),
+            amount: intent.amount.clone(),
+            interval_seconds: intent.interval as i64,
+            start_time,
+            max_payments: intent.max_payments as i64,
+            max_total_amount: intent.max_total_amount.clone(),
+            expiry,
+            nonce: intent.nonce as i64,
+            token_address: intent.token.clone(),
+            // nothing says the contract has it until apply_on_chain_state does
+            status: SubscriptionStatus::Pending.to_string(),
+            executed_payments: 0,
+            total_paid: "0".to_string(),
+            next_payment_due: start_time,
+            failure_count: 0,
+            chain: chain.name.clone(),
+            created_at: record.metadata.submitted_at,
+            updated_at: now,
+            avail_block_number: block_number,
+            avail_extrinsic_index: extrinsic_index,
+        };
+        if let Some(blockchain_client) = self.blockchain_client {
+            if !self
+                .apply_on_chain_state(blockchain_client, &mut subscription)
+                .await?
+            {
+                info!("skipping {}: not created on chain", subscription_id);
+                return Ok(Outcome::NotOnChain);
+            }
+        }
+
+        if self.dry_run {
+            return Ok(Outcome::Restored);
+        }
+
+        let subscription_intent = serde_json::to_value(intent).map_err(|e| {
+            RelayerError::InternalError(format!("failed to serialize intent: {}", e))
+        })?;
+        if let Err(e) = self.queries.insert_subscription(&subscription).await {
+            warn!("failed to restore subscription {}: {}", subscription_id, e);
+            return Ok(Outcome::Rejected);
+        }
+        self.queries
+            .cache_intent(&IntentCache {
+                id: 0,
+                subscription_intent,
+                signature: record.signature.clone(),
+                subscription_id: subscription_id.to_string(),
+                subscriber: intent.subscriber.clone(),
+                merchant: intent.merchant.clone(),
+                amount: intent.amount.clone(),
+                interval_seconds: intent.interval as i64,
+                start_time,
+                max_payments: intent.max_payments as i64,
+                max_total_amount: intent.max_total_amount.clone(),
+                expiry,
+                nonce: intent.nonce as i64,
+                processed: false,
+                created_at: record.metadata.submitted_at,
+                processed_at: None,
+                chain: chain.name.clone(),
+                avail_block_number: block_number,
+                avail_extrinsic_index: extrinsic_index,
+            })
+            .await?;
+
+        Ok(Outcome::Restored)
+    }
+
+    // false when the contract doesn't know the subscription. rpc errors fail the scan rather than
+    // guess, since a stale status could revive a cancelled subscription
+    async fn apply_on_chain_state(
+        &self,
+        blockchain_client: &BlockchainClient,
+        subscription: &mut Subscription,
+    ) -> Result<bool> {
+        let id = merkle::decode_hash(&subscription.id)?;
+        let Some(on_chain) = blockchain_client
+            .get_subscription(id, &subscription.chain)
+            .await
+            .map_err(|e| {
+                RelayerError::InternalError(format!(
+                    "could not read on-chain state for {}: {}",
+                    subscription.id, e
+                ))
+            })?
+        else {
+            return Ok(false);
+        };
+
+        let status = SubscriptionStatus::from_on_chain(on_chain.status).ok_or_else(|| {
+            RelayerError::InternalError(format!(
+                "unknown on-chain status {} for {}",
+                on_chain.status, subscription.id
+            ))
+        })?;
+        let executed_payments = u64::try_from(on_chain.executed_payments)
+            .ok()
+            .and_then(|count| i64::try_from(count).ok())
+            .ok_or_else(|| {
+                RelayerError::InternalError(format!(
+                    "executed payment count {} for {} is out of range",
+                    on_chain.executed_payments, subscription.id
+                ))
+            })?;
+
+        subscription.status = status.to_string();
+        subscription.executed_payments = executed_payments;
+        subscription.total_paid = on_chain.total_paid.to_string();
+        subscription.next_payment_due = subscription.start_time
+            + chrono::Duration::seconds(
+                subscription
+                    .interval_seconds
+                    .saturating_mul(executed_payments),
+            );
+        Ok(true)
+    }
+}
+
+impl RecoveryReport {
+    fn record(&mut self, outcome: Outcome, subscription_id: String) {
+        match outcome {
+            Outcome::Restored => self.restored.push(subscription_id),
+            Outcome::AlreadyPresent => self.already_present += 1,
+            Outcome::Rejected => self.rejected += 1,
+            Outcome::NotOnChain => self.not_on_chain += 1,
+        }
+    }
+}
+
+fn timestamp(seconds: u64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(i64::try_from(seconds).ok()?, 0)
+}
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::api::types::SubscriptionIntent;
+use crate::{api::types::SubscriptionIntent, avail::merkle, error::Result};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AvailMetadata {
@@ -28,3 +28,55 @@ pub struct AvailSubmissionResult {
     #[serde(rename = "extrinsicIndex")]
     pub extrinsic_index: u64,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailBatchLeaf {
+    #[serde(rename = "subscriptionId")]
+    pub subscription_id: String,
+    #[serde(flatten)]
+    pub record: AvailIntent,
+}
+
+// one extrinsic anchoring many intents; leaves are in merkle order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailBatch {
+    pub version: u32,
+    #[serde(rename = "merkleRoot")]
+    pub merkle_root: String,
+    pub leaves: Vec<AvailBatchLeaf>,
+}
+
+impl AvailBatch {
+    // whether merkleRoot is really the root of the listed leaves
+    pub fn root_matches_leaves(&self) -> Result<bool> {
+        let leaves = self
+            .leaves
+            .iter()
+            .map(|leaf| merkle::leaf_hash(&leaf.subscription_id))
+            .collect::<Result<Vec<_>>>()?;
+        let root = merkle::root(&leaves).map(|root| merkle::encode_hash(&root));
+        Ok(root.as_deref() == Some(self.merkle_root.to_lowercase().as_str()))
+    }
+
+    pub fn leaf(&self, subscription_id: &str) -> Option<&AvailBatchLeaf> {
+        self.leaves
+            .iter()
+            .find(|leaf| leaf.subscription_id.eq_ignore_ascii_case(subscription_id))
+    }
+}
+
+// what an extrinsic can hold: a batch, or a single intent posted before batching
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AvailPayload {
+    Batch(AvailBatch),
+    Intent(AvailIntent),
+}
+
+// raw data from one of our extrinsics, as found when scanning a block
+#[derive(Debug, Clone)]
+pub struct AppExtrinsic {
+    pub block_number: u64,
+    pub extrinsic_index: u64,
+    pub data: Vec<u8>,
+}
@@ -0,0 +1,128 @@
+// rebuilds subscriptions and intent_cache from the intents this relayer anchored on avail, then
+// replays contract history so payment state catches up. run it against an empty (or partially
+// restored) database before starting the relayer:
+//
+//   recover_from_avail --from <avail block> [--to <avail block>] [--dry-run]
+//
+// --to defaults to the latest finalized block; reruns skip subscriptions that already exist
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use tracing::{error, info, warn, Level};
+
+use relayer::avail::Recovery;
+use relayer::{AvailClient, BlockchainClient, Config, Database, HyperSyncClient};
+
+struct Args {
+    from_block: u64,
+    to_block: Option<u64>,
+    dry_run: bool,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut from_block = None;
+    let mut to_block = None;
+    let mut dry_run = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => from_block = Some(parse_block(args.next(), "--from")?),
+            "--to" => to_block = Some(parse_block(args.next(), "--to")?),
+            "--dry-run" => dry_run = true,
+            other => bail!(
+                "unknown argument {}; usage: recover_from_avail --from <block> [--to <block>] [--dry-run]",
+                other
+            ),
+        }
+    }
+
+    Ok(Args {
+        from_block: from_block.ok_or_else(|| anyhow!("--from <block> is required"))?,
+        to_block,
+        dry_run,
+    })
+}
+
+fn parse_block(value: Option<String>, flag: &str) -> Result<u64> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| anyhow!("{} expects an avail block number", flag))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_max_level(Level::INFO)
+        .with_target(false)
+        .init();
+
+    let args = parse_args()?;
+    let config = Config::from_env()?;
+
+    let database = Database::new(&config.database_url).await?;
+    database.run_migrations().await?;
+    let queries = database.queries();
+
+    let blockchain_client = BlockchainClient::with_queries(&config, queries.clone()).await?;
+    let avail_client = AvailClient::new_read_only(&config).await?;
+
+    let to_block = match args.to_block {
+        Some(block) => block,
+        None => avail_client.finalized_block_number().await?,
+    };
+    if to_block < args.from_block {
+        bail!("--to {} is before --from {}", to_block, args.from_block);
+    }
+    info!(
+        "scanning avail blocks {}..={} for app id {}{}",
+        args.from_block,
+        to_block,
+        config.avail_application_id.unwrap_or_default(),
+        if args.dry_run { " (dry run)" } else { "" }
+    );
+
+    let recovery = Recovery {
+        queries: &queries,
+        chains: &config.chains,
+        blockchain_client: Some(&blockchain_client),
+        dry_run: args.dry_run,
+    };
+    let report = recovery
+        .scan(&avail_client, args.from_block, to_block)
+        .await?;
+    info!(
+        "avail recovery finished: {} blocks, {} extrinsics ({} undecodable), {} intents, {} restored, {} already present, {} rejected, {} never created on chain",
+        report.blocks_scanned,
+        report.extrinsics,
+        report.undecodable,
+        report.intents_found,
+        report.restored.len(),
+        report.already_present,
+        report.rejected,
+        report.not_on_chain
+    );
+    for subscription_id in &report.restored {
+        info!("restored {}", subscription_id);
+    }
+
+    if args.dry_run {
+        return Ok(());
+    }
+
+    // executions only live on-chain; hypersync replays them into the payments tables
+    if config.hypersync_enabled() {
+        let hypersync = HyperSyncClient::new(&config.chains)?;
+        hypersync
+            .sync_historical_data(&config, Arc::new(queries), Arc::new(blockchain_client))
+            .await
+            .inspect_err(|e| error!("hypersync replay failed: {}", e))?;
+        info!("hypersync replay finished");
+    } else {
+        // status and payment counts were already read from the contract during the scan
+        warn!("hypersync not configured; individual payment records were not rebuilt");
+    }
+
+    Ok(())
+}
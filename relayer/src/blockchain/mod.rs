@@ -1,5 +1,6 @@
 pub mod contract_bindings;
 pub mod rpc_client;
+pub mod tx_manager;
 
 pub use contract_bindings::*;
 pub use rpc_client::*;
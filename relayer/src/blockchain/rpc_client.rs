@@ -1,38 +1,68 @@
-use super::contract_bindings::{SubscriptionManager, IERC20};
+use super::contract_bindings::{
+    Subscription as OnChainSubscription, SubscriptionIntent as OnChainSubscriptionIntent,
+    SubscriptionManager, IERC20,
+};
+use super::tx_manager::{OutboundCall, ReconcileSummary, TxManager};
+use crate::api::types::SubscriptionIntent;
+use crate::chains::GasPolicy;
 use crate::config::Config;
+use crate::database::queries::Queries;
 use crate::error::{RelayerError, Result};
+use crate::metrics::Metrics;
 use chrono::Utc;
+use ethers::abi::{Token, Tokenizable};
 use ethers::prelude::*;
 use ethers::providers::{Http, Middleware, Provider};
 use ethers::signers::{LocalWallet, Signer};
-use ethers::types::{Address, Filter, Log as EthersLog, TransactionReceipt, H256, U256};
-use std::sync::Arc;
+use ethers::types::{Address, Bytes, Filter, Log as EthersLog, TransactionReceipt, H256, U256};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tracing::{info, warn};
 
-const STUB_SEPOLIA_CHAIN_ID: u64 = 11155111;
-const STUB_BASE_CHAIN_ID: u64 = 8453;
+pub(crate) type SignerClient = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+// spacing between synthetic block heights handed out per chain in stub mode
+const STUB_BLOCK_SPACING: u64 = 4_000_000;
+// subscriptions per aggregate3 call; each contributes up to four inner calls
+const MULTICALL_MAX_SUBSCRIPTIONS: usize = 50;
 
 #[derive(Clone)]
 pub struct BlockchainClient {
     real: Option<Arc<RealBlockchainClient>>,
     stub: Option<Arc<StubBlockchainClient>>,
+    metrics: Option<Arc<Metrics>>,
+}
+
+struct ChainHandle {
+    name: String,
+    provider: Arc<SignerClient>,
+    subscription_manager: SubscriptionManager<SignerClient>,
+    chain_id: u64,
+    gas_policy: GasPolicy,
+    multicall: Address,
 }
 
 struct RealBlockchainClient {
-    sepolia_provider: Arc<SignerMiddleware<Provider<Http>, LocalWallet>>,
-    base_provider: Arc<SignerMiddleware<Provider<Http>, LocalWallet>>,
-    sepolia_subscription_manager:
-        SubscriptionManager<SignerMiddleware<Provider<Http>, LocalWallet>>,
-    base_subscription_manager: SubscriptionManager<SignerMiddleware<Provider<Http>, LocalWallet>>,
+    chains: HashMap<String, ChainHandle>,
     relayer_address: Address,
-    sepolia_chain_id: u64,
-    base_chain_id: u64,
+    tx_manager: Arc<TxManager>,
+}
+
+struct StubChain {
+    chain_id: u64,
+    block_number: u64,
 }
 
 struct StubBlockchainClient {
     relayer_address: Address,
-    sepolia_chain_id: u64,
-    base_chain_id: u64,
+    chains: HashMap<String, StubChain>,
+    // synthetic transactions handed out by this stub, keyed by hash -> block
+    submitted: Mutex<HashMap<H256, u64>>,
+    // revert reasons for upcoming createSubscription calls, consumed in order
+    creation_reverts: Mutex<VecDeque<String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -53,50 +83,107 @@ pub struct SubscriptionData {
     pub total_paid: U256,
 }
 
+// identifies one subscription whose on-chain state the scheduler needs before charging it
+#[derive(Debug, Clone, Copy)]
+pub struct PreflightRequest {
+    pub subscription_id: [u8; 32],
+    pub subscriber: Address,
+    pub token: Address,
+}
+
+// on-chain state for one PreflightRequest; each lookup succeeds or fails on its own
+#[derive(Debug, Clone)]
+pub struct PaymentPreflight {
+    pub subscription: std::result::Result<Option<SubscriptionData>, String>,
+    pub payment_count: std::result::Result<u64, String>,
+    pub balance: std::result::Result<U256, String>,
+    // None for native-token subscriptions, which need no allowance
+    pub allowance: std::result::Result<Option<U256>, String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ExecutionResult {
     pub transaction_hash: H256,
     pub block_number: u64,
+    pub block_hash: Option<H256>,
     pub gas_used: U256,
     pub gas_price: U256,
     pub status: bool,
 }
 
 impl BlockchainClient {
+    // outbound transactions are only tracked in memory; use with_queries to persist them
     pub async fn new(config: &Config) -> Result<Self> {
-        let eth_stub = is_stub_endpoint(&config.ethereum_rpc_url);
-        let base_stub = is_stub_endpoint(&config.base_rpc_url);
+        Self::with_queries(config, Queries::in_memory()).await
+    }
+
+    pub async fn with_queries(config: &Config, queries: Queries) -> Result<Self> {
+        let stub_count = config
+            .chains
+            .iter()
+            .filter(|chain| is_stub_endpoint(&chain.rpc_url))
+            .count();
 
-        if eth_stub ^ base_stub {
+        if stub_count > 0 && stub_count < config.chains.len() {
             warn!("detected mixed stub and real rpc urls; running blockchain client in stub mode");
         }
 
-        if eth_stub || base_stub {
+        if stub_count > 0 || config.chains.is_empty() {
             // treat any stub endpoint as a hint to stay in deterministic client mode
             info!("initializing blockchain client in stub mode");
             let stub = StubBlockchainClient::new(config)?;
             Ok(Self {
                 real: None,
                 stub: Some(Arc::new(stub)),
+                metrics: None,
             })
         } else {
-            info!("initializing blockchain client with providers for sepolia and base");
-            let real = RealBlockchainClient::new(config).await?;
+            info!(
+                "initializing blockchain client with providers for {}",
+                config.chains.names().join(", ")
+            );
+            let real = RealBlockchainClient::new(config, queries).await?;
             info!("blockchain client initialized successfully");
             Ok(Self {
                 real: Some(Arc::new(real)),
                 stub: None,
+                metrics: None,
             })
         }
     }
 
+    // records rpc latency for every call that reaches a real provider
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    async fn timed<T>(
+        &self,
+        chain: &str,
+        method: &'static str,
+        call: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let started = Instant::now();
+        let result = call.await;
+        if let Some(metrics) = &self.metrics {
+            metrics.observe_rpc_call(chain, method, started.elapsed(), result.is_ok());
+        }
+        result
+    }
+
     pub async fn get_subscription(
         &self,
         subscription_id: [u8; 32],
         chain: &str,
     ) -> Result<Option<SubscriptionData>> {
         if let Some(real) = &self.real {
-            real.get_subscription(subscription_id, chain).await
+            self.timed(
+                chain,
+                "get_subscription",
+                real.get_subscription(subscription_id, chain),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.get_subscription(subscription_id, chain).await
         } else {
@@ -114,7 +201,12 @@ impl BlockchainClient {
         chain: &str,
     ) -> Result<bool> {
         if let Some(real) = &self.real {
-            real.check_allowance(subscriber, token, amount, chain).await
+            self.timed(
+                chain,
+                "check_allowance",
+                real.check_allowance(subscriber, token, amount, chain),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.check_allowance(subscriber, token, amount, chain).await
         } else {
@@ -131,7 +223,12 @@ impl BlockchainClient {
         chain: &str,
     ) -> Result<U256> {
         if let Some(real) = &self.real {
-            real.check_balance(subscriber, token, chain).await
+            self.timed(
+                chain,
+                "check_balance",
+                real.check_balance(subscriber, token, chain),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.check_balance(subscriber, token, chain).await
         } else {
@@ -147,7 +244,12 @@ impl BlockchainClient {
         chain: &str,
     ) -> Result<ExecutionResult> {
         if let Some(real) = &self.real {
-            real.execute_subscription(subscription_id, chain).await
+            self.timed(
+                chain,
+                "execute_subscription",
+                real.execute_subscription(subscription_id, chain),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.execute_subscription(subscription_id, chain).await
         } else {
@@ -157,13 +259,41 @@ impl BlockchainClient {
         }
     }
 
+    // sends createSubscription and returns the tx hash without waiting for the receipt
+    pub async fn create_subscription(
+        &self,
+        intent: &SubscriptionIntent,
+        signature: Bytes,
+        chain: &str,
+    ) -> Result<H256> {
+        if let Some(real) = &self.real {
+            self.timed(
+                chain,
+                "create_subscription",
+                real.create_subscription(intent, signature, chain),
+            )
+            .await
+        } else if let Some(stub) = &self.stub {
+            stub.create_subscription(intent, signature, chain).await
+        } else {
+            Err(RelayerError::InternalError(
+                "blockchain client not initialised".to_string(),
+            ))
+        }
+    }
+
     pub async fn get_transaction_receipt(
         &self,
         tx_hash: H256,
         chain: &str,
     ) -> Result<Option<TransactionReceipt>> {
         if let Some(real) = &self.real {
-            real.get_transaction_receipt(tx_hash, chain).await
+            self.timed(
+                chain,
+                "get_transaction_receipt",
+                real.get_transaction_receipt(tx_hash, chain),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.get_transaction_receipt(tx_hash, chain).await
         } else {
@@ -175,7 +305,12 @@ impl BlockchainClient {
 
     pub async fn get_current_block_number(&self, chain: &str) -> Result<u64> {
         if let Some(real) = &self.real {
-            real.get_current_block_number(chain).await
+            self.timed(
+                chain,
+                "get_current_block_number",
+                real.get_current_block_number(chain),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.get_current_block_number(chain).await
         } else {
@@ -187,7 +322,12 @@ impl BlockchainClient {
 
     pub async fn get_block_timestamp(&self, chain: &str, block_number: u64) -> Result<u64> {
         if let Some(real) = &self.real {
-            real.get_block_timestamp(chain, block_number).await
+            self.timed(
+                chain,
+                "get_block_timestamp",
+                real.get_block_timestamp(chain, block_number),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.get_block_timestamp(chain, block_number).await
         } else {
@@ -199,7 +339,12 @@ impl BlockchainClient {
 
     pub async fn validate_connection(&self, chain: &str) -> Result<()> {
         if let Some(real) = &self.real {
-            real.validate_connection(chain).await
+            self.timed(
+                chain,
+                "validate_connection",
+                real.validate_connection(chain),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.validate_connection(chain).await
         } else {
@@ -211,7 +356,12 @@ impl BlockchainClient {
 
     pub async fn get_payment_count(&self, subscription_id: [u8; 32], chain: &str) -> Result<u64> {
         if let Some(real) = &self.real {
-            real.get_payment_count(subscription_id, chain).await
+            self.timed(
+                chain,
+                "get_payment_count",
+                real.get_payment_count(subscription_id, chain),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.get_payment_count(subscription_id, chain).await
         } else {
@@ -221,13 +371,40 @@ impl BlockchainClient {
         }
     }
 
+    // resolves the state for a batch of subscriptions on one chain; results follow request order
+    pub async fn preflight_payments(
+        &self,
+        chain: &str,
+        requests: &[PreflightRequest],
+    ) -> Result<Vec<PaymentPreflight>> {
+        if let Some(real) = &self.real {
+            self.timed(
+                chain,
+                "preflight_payments",
+                real.preflight_payments(chain, requests),
+            )
+            .await
+        } else if let Some(stub) = &self.stub {
+            stub.preflight_payments(chain, requests).await
+        } else {
+            Err(RelayerError::InternalError(
+                "blockchain client not initialised".to_string(),
+            ))
+        }
+    }
+
     pub async fn get_subscription_nonce(
         &self,
         subscription_id: [u8; 32],
         chain: &str,
     ) -> Result<u64> {
         if let Some(real) = &self.real {
-            real.get_subscription_nonce(subscription_id, chain).await
+            self.timed(
+                chain,
+                "get_subscription_nonce",
+                real.get_subscription_nonce(subscription_id, chain),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.get_subscription_nonce(subscription_id, chain).await
         } else {
@@ -237,14 +414,81 @@ impl BlockchainClient {
         }
     }
 
+    pub async fn get_subscriber_nonce(&self, subscriber: Address, chain: &str) -> Result<U256> {
+        if let Some(real) = &self.real {
+            self.timed(
+                chain,
+                "get_subscriber_nonce",
+                real.get_subscriber_nonce(subscriber, chain),
+            )
+            .await
+        } else if let Some(stub) = &self.stub {
+            stub.get_subscriber_nonce(subscriber, chain).await
+        } else {
+            Err(RelayerError::InternalError(
+                "blockchain client not initialised".to_string(),
+            ))
+        }
+    }
+
+    pub async fn pause_subscription(
+        &self,
+        subscription_id: [u8; 32],
+        signature: Bytes,
+        chain: &str,
+    ) -> Result<ExecutionResult> {
+        if let Some(real) = &self.real {
+            self.timed(
+                chain,
+                "pause_subscription",
+                real.pause_subscription(subscription_id, signature, chain),
+            )
+            .await
+        } else if let Some(stub) = &self.stub {
+            stub.submit_subscriber_action(subscription_id, "pause", chain)
+                .await
+        } else {
+            Err(RelayerError::InternalError(
+                "blockchain client not initialised".to_string(),
+            ))
+        }
+    }
+
+    pub async fn resume_subscription(
+        &self,
+        subscription_id: [u8; 32],
+        signature: Bytes,
+        chain: &str,
+    ) -> Result<ExecutionResult> {
+        if let Some(real) = &self.real {
+            self.timed(
+                chain,
+                "resume_subscription",
+                real.resume_subscription(subscription_id, signature, chain),
+            )
+            .await
+        } else if let Some(stub) = &self.stub {
+            stub.submit_subscriber_action(subscription_id, "resume", chain)
+                .await
+        } else {
+            Err(RelayerError::InternalError(
+                "blockchain client not initialised".to_string(),
+            ))
+        }
+    }
+
     pub async fn validate_subscription_state(
         &self,
         subscription_id: [u8; 32],
         chain: &str,
     ) -> Result<SubscriptionData> {
         if let Some(real) = &self.real {
-            real.validate_subscription_state(subscription_id, chain)
-                .await
+            self.timed(
+                chain,
+                "validate_subscription_state",
+                real.validate_subscription_state(subscription_id, chain),
+            )
+            .await
         } else if let Some(stub) = &self.stub {
             stub.validate_subscription_state(subscription_id, chain)
                 .await
@@ -257,7 +501,8 @@ impl BlockchainClient {
 
     pub async fn fetch_logs(&self, chain: &str, filter: Filter) -> Result<Vec<EthersLog>> {
         if let Some(real) = &self.real {
-            real.fetch_logs(chain, filter).await
+            self.timed(chain, "fetch_logs", real.fetch_logs(chain, filter))
+                .await
         } else if let Some(stub) = &self.stub {
             stub.fetch_logs(chain, filter).await
         } else {
@@ -267,6 +512,34 @@ impl BlockchainClient {
         }
     }
 
+    // settles transactions left pending by a previous run; a no-op in stub mode
+    pub async fn reconcile_pending_transactions(&self) -> Result<()> {
+        if let Some(real) = &self.real {
+            real.reconcile_pending_transactions().await
+        } else {
+            Ok(())
+        }
+    }
+
+    // native gas token balance of the account that signs relayed transactions
+    pub async fn get_relayer_balance(&self, chain: &str) -> Result<U256> {
+        if let Some(real) = &self.real {
+            self.timed(
+                chain,
+                "check_balance",
+                real.check_balance(real.relayer_address, Address::zero(), chain),
+            )
+            .await
+        } else if let Some(stub) = &self.stub {
+            stub.check_balance(stub.relayer_address, Address::zero(), chain)
+                .await
+        } else {
+            Err(RelayerError::InternalError(
+                "blockchain client not initialised".to_string(),
+            ))
+        }
+    }
+
     pub fn chain_id(&self, chain: &str) -> Result<u64> {
         if let Some(real) = &self.real {
             real.chain_id(chain)
@@ -278,18 +551,24 @@ impl BlockchainClient {
             ))
         }
     }
+
+    // stub mode only: the next createSubscription reverts with this reason; false on a real client
+    pub fn revert_next_stub_creation(&self, reason: &str) -> bool {
+        match &self.stub {
+            Some(stub) => {
+                stub.creation_reverts
+                    .lock()
+                    .unwrap()
+                    .push_back(reason.to_string());
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl RealBlockchainClient {
-    async fn new(config: &Config) -> Result<Self> {
-        let sepolia_provider =
-            Provider::<Http>::try_from(&config.ethereum_rpc_url).map_err(|e| {
-                RelayerError::RpcConnectionFailed(format!("sepolia rpc connection failed: {}", e))
-            })?;
-        let base_provider = Provider::<Http>::try_from(&config.base_rpc_url).map_err(|e| {
-            RelayerError::RpcConnectionFailed(format!("base rpc connection failed: {}", e))
-        })?;
-
+    async fn new(config: &Config, queries: Queries) -> Result<Self> {
         let wallet: LocalWallet = config
             .relayer_private_key
             .parse()
@@ -298,71 +577,137 @@ impl RealBlockchainClient {
         let relayer_address = wallet.address();
         info!("relayer address: {:?}", relayer_address);
 
-        let sepolia_chain_id = sepolia_provider.get_chainid().await.map_err(|e| {
-            RelayerError::RpcConnectionFailed(format!("failed to get sepolia chain id: {}", e))
-        })?;
-        let base_chain_id = base_provider.get_chainid().await.map_err(|e| {
-            RelayerError::RpcConnectionFailed(format!("failed to get base chain id: {}", e))
-        })?;
-
-        let sepolia_chain_id_u64 = sepolia_chain_id.as_u64();
-        let base_chain_id_u64 = base_chain_id.as_u64();
+        let mut chains = HashMap::new();
+        for chain in config.chains.iter() {
+            let provider = Provider::<Http>::try_from(chain.rpc_url.as_str()).map_err(|e| {
+                RelayerError::RpcConnectionFailed(format!(
+                    "{} rpc connection failed: {}",
+                    chain.name, e
+                ))
+            })?;
 
-        let sepolia_wallet = wallet.clone().with_chain_id(sepolia_chain_id_u64);
-        let base_wallet = wallet.with_chain_id(base_chain_id_u64);
+            let reported_chain_id = provider
+                .get_chainid()
+                .await
+                .map_err(|e| {
+                    RelayerError::RpcConnectionFailed(format!(
+                        "failed to get {} chain id: {}",
+                        chain.name, e
+                    ))
+                })?
+                .as_u64();
+
+            if reported_chain_id != chain.chain_id {
+                return Err(RelayerError::Config(anyhow::anyhow!(
+                    "{} rpc reports chain id {} but the registry expects {}",
+                    chain.name,
+                    reported_chain_id,
+                    chain.chain_id
+                )));
+            }
 
-        let sepolia_client = Arc::new(SignerMiddleware::new(sepolia_provider, sepolia_wallet));
-        let base_client = Arc::new(SignerMiddleware::new(base_provider, base_wallet));
+            let client = Arc::new(SignerMiddleware::new(
+                provider,
+                wallet.clone().with_chain_id(chain.chain_id),
+            ));
 
-        let sepolia_sm_address: Address = config
-            .subscription_manager_address_sepolia
-            .parse()
-            .map_err(|_| {
-                RelayerError::Config(anyhow::anyhow!(
-                    "invalid sepolia subscription manager address"
-                ))
-            })?;
-        let base_sm_address: Address =
-            config
-                .subscription_manager_address_base
-                .parse()
-                .map_err(|_| {
+            let manager_address: Address =
+                chain.subscription_manager_address.parse().map_err(|_| {
                     RelayerError::Config(anyhow::anyhow!(
-                        "invalid base subscription manager address"
+                        "invalid {} subscription manager address",
+                        chain.name
                     ))
                 })?;
 
-        let sepolia_subscription_manager =
-            SubscriptionManager::new(sepolia_sm_address, sepolia_client.clone());
-        let base_subscription_manager =
-            SubscriptionManager::new(base_sm_address, base_client.clone());
+            let multicall: Address = chain.multicall_address.parse().map_err(|_| {
+                RelayerError::Config(anyhow::anyhow!("invalid {} multicall address", chain.name))
+            })?;
+
+            chains.insert(
+                chain.name.to_ascii_lowercase(),
+                ChainHandle {
+                    name: chain.name.clone(),
+                    subscription_manager: SubscriptionManager::new(manager_address, client.clone()),
+                    provider: client,
+                    chain_id: chain.chain_id,
+                    gas_policy: chain.gas_policy.clone(),
+                    multicall,
+                },
+            );
+        }
 
         Ok(Self {
-            sepolia_provider: sepolia_client,
-            base_provider: base_client,
-            sepolia_subscription_manager,
-            base_subscription_manager,
+            chains,
             relayer_address,
-            sepolia_chain_id: sepolia_chain_id_u64,
-            base_chain_id: base_chain_id_u64,
+            tx_manager: Arc::new(TxManager::new(queries)),
         })
     }
 
+    async fn reconcile_pending_transactions(&self) -> Result<()> {
+        for handle in self.chains.values() {
+            let summary: ReconcileSummary = self
+                .tx_manager
+                .reconcile(&handle.name, &handle.provider, &handle.gas_policy)
+                .await?;
+            if summary.outstanding > 0 {
+                warn!(
+                    "{} outbound transactions still pending on {}",
+                    summary.outstanding, handle.name
+                );
+            }
+        }
+        Ok(())
+    }
+
+    // signs and broadcasts through the tx manager, then waits (bumping fees) for the receipt
+    async fn send_and_confirm(
+        &self,
+        handle: &ChainHandle,
+        call: OutboundCall,
+    ) -> Result<ExecutionResult> {
+        let record = self
+            .tx_manager
+            .submit(&handle.name, &handle.provider, &handle.gas_policy, call)
+            .await?;
+        let max_fee_per_gas = U256::from_dec_str(&record.max_fee_per_gas).unwrap_or_default();
+
+        let receipt = self
+            .tx_manager
+            .wait_for_receipt(&handle.name, &handle.provider, &handle.gas_policy, record)
+            .await?;
+
+        Ok(ExecutionResult {
+            transaction_hash: receipt.transaction_hash,
+            block_number: receipt.block_number.unwrap_or_default().as_u64(),
+            block_hash: receipt.block_hash,
+            gas_used: receipt.gas_used.unwrap_or_default(),
+            gas_price: receipt.effective_gas_price.unwrap_or(max_fee_per_gas),
+            status: true,
+        })
+    }
+
+    fn chain_handle(&self, chain: &str) -> Result<&ChainHandle> {
+        let normalized = chain.trim().to_ascii_lowercase();
+        self.chains
+            .get(&normalized)
+            .or_else(|| {
+                normalized
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(|id| self.chains.values().find(|handle| handle.chain_id == id))
+            })
+            .ok_or_else(|| RelayerError::UnsupportedChain {
+                requested: chain.to_string(),
+                supported: sorted_chain_names(&self.chains),
+            })
+    }
+
     fn get_provider_and_contracts(
         &self,
         chain: &str,
-    ) -> Result<(
-        &Arc<SignerMiddleware<Provider<Http>, LocalWallet>>,
-        &SubscriptionManager<SignerMiddleware<Provider<Http>, LocalWallet>>,
-    )> {
-        match chain.to_lowercase().as_str() {
-            "sepolia" => Ok((&self.sepolia_provider, &self.sepolia_subscription_manager)),
-            "base" => Ok((&self.base_provider, &self.base_subscription_manager)),
-            _ => Err(RelayerError::Validation(format!(
-                "unsupported chain: {}",
-                chain
-            ))),
-        }
+    ) -> Result<(&Arc<SignerClient>, &SubscriptionManager<SignerClient>)> {
+        let handle = self.chain_handle(chain)?;
+        Ok((&handle.provider, &handle.subscription_manager))
     }
 
     async fn get_subscription(
@@ -401,25 +746,12 @@ impl RealBlockchainClient {
                 RelayerError::ContractRevert(format!("failed to get executed payments: {}", e))
             })?;
 
-        let data = SubscriptionData {
-            id: subscription_id,
-            subscriber: subscription.subscriber,
-            merchant: subscription.merchant,
-            amount: subscription.amount,
-            interval: subscription.interval,
-            start_time: subscription.start_time,
-            max_payments: subscription.max_payments,
-            max_total_amount: subscription.max_total_amount,
-            expiry: subscription.expiry,
-            nonce: subscription.nonce,
-            token: subscription.token,
-            status: subscription.status,
-            executed_payments,
-            total_paid: executed_payments * subscription.amount,
-        };
-
         info!("successfully fetched subscription {:?}", subscription_id);
-        Ok(Some(data))
+        Ok(subscription_data(
+            subscription_id,
+            subscription,
+            executed_payments,
+        ))
     }
 
     async fn check_allowance(
@@ -504,7 +836,7 @@ impl RealBlockchainClient {
             subscription_id, chain
         );
 
-        let (provider, subscription_manager) = self.get_provider_and_contracts(chain)?;
+        let (_, subscription_manager) = self.get_provider_and_contracts(chain)?;
 
         let subscription_data = self
             .get_subscription(subscription_id, chain)
@@ -536,58 +868,23 @@ impl RealBlockchainClient {
             }
         }
 
-        let gas_estimate = subscription_manager
-            .execute_subscription(subscription_id, self.relayer_address)
+        let call = subscription_manager.execute_subscription(subscription_id, self.relayer_address);
+        let gas_estimate = call
             .estimate_gas()
             .await
             .map_err(|e| RelayerError::InsufficientGas(format!("gas estimation failed: {}", e)))?;
 
-        let gas_buffer_multiplier = 120;
-        let gas_limit = gas_estimate * gas_buffer_multiplier / 100;
+        let handle = self.chain_handle(chain)?;
+        let gas_limit = gas_estimate * handle.gas_policy.gas_limit_multiplier_percent / 100;
         info!("gas estimate: {}, using limit: {}", gas_estimate, gas_limit);
 
-        let gas_price = provider.get_gas_price().await.map_err(|e| {
-            RelayerError::RpcConnectionFailed(format!("failed to get gas price: {}", e))
-        })?;
-
-        info!("current gas price: {}", gas_price);
-
-        let tx = subscription_manager
-            .execute_subscription(subscription_id, self.relayer_address)
-            .gas(gas_limit)
-            .gas_price(gas_price);
-
-        let pending_tx = tx.send().await.map_err(|e| {
-            RelayerError::TransactionFailed(format!("failed to send transaction: {}", e))
-        })?;
-
-        info!("transaction sent, hash: {:?}", pending_tx.tx_hash());
-
-        let receipt = pending_tx
-            .await
-            .map_err(|e| {
-                RelayerError::TransactionFailed(format!("transaction confirmation failed: {}", e))
-            })?
-            .ok_or_else(|| {
-                RelayerError::InternalError("transaction receipt not found".to_string())
-            })?;
-
-        let transaction_succeeded = receipt.status == Some(1u64.into());
-
-        if !transaction_succeeded {
-            warn!("transaction failed: {:?}", receipt.transaction_hash);
-            return Err(RelayerError::InternalError(
-                "transaction execution failed".to_string(),
-            ));
-        }
-
-        let result = ExecutionResult {
-            transaction_hash: receipt.transaction_hash,
-            block_number: receipt.block_number.unwrap_or_default().as_u64(),
-            gas_used: receipt.gas_used.unwrap_or_default(),
-            gas_price,
-            status: transaction_succeeded,
-        };
+        let call = outbound_call(
+            &call,
+            gas_limit,
+            "EXECUTE_SUBSCRIPTION",
+            Some(format!("0x{}", hex::encode(subscription_id))),
+        )?;
+        let result = self.send_and_confirm(handle, call).await?;
 
         info!(
             "subscription executed successfully: {:?}",
@@ -596,6 +893,54 @@ impl RealBlockchainClient {
         Ok(result)
     }
 
+    async fn create_subscription(
+        &self,
+        intent: &SubscriptionIntent,
+        signature: Bytes,
+        chain: &str,
+    ) -> Result<H256> {
+        info!(
+            "relaying createSubscription for subscriber {} on chain {}",
+            intent.subscriber, chain
+        );
+
+        let handle = self.chain_handle(chain)?;
+        let call = handle
+            .subscription_manager
+            .create_subscription(to_on_chain_intent(intent)?, signature);
+
+        let gas_estimate = call.estimate_gas().await.map_err(|e| {
+            RelayerError::ContractRevert(format!("createSubscription would revert: {}", e))
+        })?;
+        let gas_limit = gas_estimate * handle.gas_policy.gas_limit_multiplier_percent / 100;
+
+        let call = outbound_call(&call, gas_limit, "CREATE_SUBSCRIPTION", None)?;
+        let record = self
+            .tx_manager
+            .submit(&handle.name, &handle.provider, &handle.gas_policy, call)
+            .await?;
+        let tx_hash = H256::from_str(&record.transaction_hash).map_err(|_| {
+            RelayerError::InternalError("tx manager returned a malformed hash".to_string())
+        })?;
+
+        // callers only get the hash; keep bumping in the background until it is mined
+        let tx_manager = Arc::clone(&self.tx_manager);
+        let chain_name = handle.name.clone();
+        let provider = Arc::clone(&handle.provider);
+        let gas_policy = handle.gas_policy.clone();
+        tokio::spawn(async move {
+            if let Err(e) = tx_manager
+                .wait_for_receipt(&chain_name, &provider, &gas_policy, record)
+                .await
+            {
+                warn!("createSubscription {:?} did not confirm: {}", tx_hash, e);
+            }
+        });
+
+        info!("createSubscription sent, hash: {:?}", tx_hash);
+        Ok(tx_hash)
+    }
+
     async fn get_transaction_receipt(
         &self,
         tx_hash: H256,
@@ -698,6 +1043,98 @@ impl RealBlockchainClient {
         Ok(())
     }
 
+    async fn preflight_payments(
+        &self,
+        chain: &str,
+        requests: &[PreflightRequest],
+    ) -> Result<Vec<PaymentPreflight>> {
+        let handle = self.chain_handle(chain)?;
+        let manager_address = handle.subscription_manager.address();
+        let mut preflights = Vec::with_capacity(requests.len());
+
+        for batch in requests.chunks(MULTICALL_MAX_SUBSCRIPTIONS) {
+            let mut multicall = Multicall::new_with_chain_id(
+                handle.provider.clone(),
+                Some(handle.multicall),
+                None::<u64>,
+            )
+            .map_err(|e| {
+                RelayerError::InternalError(format!(
+                    "failed to build multicall for {}: {}",
+                    chain, e
+                ))
+            })?;
+
+            for request in batch {
+                multicall
+                    .add_call(
+                        handle
+                            .subscription_manager
+                            .get_subscription(request.subscription_id),
+                        true,
+                    )
+                    .add_call(
+                        handle
+                            .subscription_manager
+                            .executed_payments(request.subscription_id),
+                        true,
+                    );
+                if request.token == Address::zero() {
+                    multicall.add_get_eth_balance(request.subscriber, true);
+                } else {
+                    let erc20 = IERC20::new(request.token, handle.provider.clone());
+                    multicall
+                        .add_call(erc20.balance_of(request.subscriber), true)
+                        .add_call(erc20.allowance(request.subscriber, manager_address), true);
+                }
+            }
+
+            let mut returned = multicall
+                .call_raw()
+                .await
+                .map_err(|e| {
+                    RelayerError::RpcConnectionFailed(format!(
+                        "multicall pre-flight failed on {}: {}",
+                        chain, e
+                    ))
+                })?
+                .into_iter();
+
+            for request in batch {
+                let subscription =
+                    decode_multicall::<OnChainSubscription>(returned.next(), "getSubscription");
+                let executed_payments =
+                    decode_multicall::<U256>(returned.next(), "executedPayments");
+                let balance = decode_multicall::<U256>(returned.next(), "balance");
+                let allowance = if request.token == Address::zero() {
+                    Ok(None)
+                } else {
+                    decode_multicall::<U256>(returned.next(), "allowance").map(Some)
+                };
+
+                preflights.push(PaymentPreflight {
+                    subscription: subscription.map(|subscription| {
+                        subscription_data(
+                            request.subscription_id,
+                            subscription,
+                            executed_payments.clone().unwrap_or_default(),
+                        )
+                    }),
+                    payment_count: executed_payments.map(|count| count.as_u64()),
+                    balance,
+                    allowance,
+                });
+            }
+        }
+
+        info!(
+            "resolved pre-flight state for {} subscriptions on {} via multicall",
+            preflights.len(),
+            chain
+        );
+        Ok(preflights)
+    }
+
     async fn get_payment_count(&self, subscription_id: [u8; 32], chain: &str) -> Result<u64> {
         info!(
             "fetching payment count for subscription {:?} on chain {}",
@@ -737,6 +1174,74 @@ impl RealBlockchainClient {
         Ok(subscription_data.nonce.as_u64())
     }
 
+    async fn get_subscriber_nonce(&self, subscriber: Address, chain: &str) -> Result<U256> {
+        let (_, subscription_manager) = self.get_provider_and_contracts(chain)?;
+
+        subscription_manager
+            .current_nonce(subscriber)
+            .call()
+            .await
+            .map_err(|e| {
+                RelayerError::ContractRevert(format!("failed to get subscriber nonce: {}", e))
+            })
+    }
+
+    async fn pause_subscription(
+        &self,
+        subscription_id: [u8; 32],
+        signature: Bytes,
+        chain: &str,
+    ) -> Result<ExecutionResult> {
+        info!(
+            "relaying pause for subscription {:?} on chain {}",
+            subscription_id, chain
+        );
+        let (_, subscription_manager) = self.get_provider_and_contracts(chain)?;
+        let call = subscription_manager.pause_subscription(subscription_id, signature);
+        self.send_subscriber_action(call, "PAUSE_SUBSCRIPTION", subscription_id, chain)
+            .await
+    }
+
+    async fn resume_subscription(
+        &self,
+        subscription_id: [u8; 32],
+        signature: Bytes,
+        chain: &str,
+    ) -> Result<ExecutionResult> {
+        info!(
+            "relaying resume for subscription {:?} on chain {}",
+            subscription_id, chain
+        );
+        let (_, subscription_manager) = self.get_provider_and_contracts(chain)?;
+        let call = subscription_manager.resume_subscription(subscription_id, signature);
+        self.send_subscriber_action(call, "RESUME_SUBSCRIPTION", subscription_id, chain)
+            .await
+    }
+
+    // relays a subscriber-signed call on their behalf and waits for it to be mined
+    async fn send_subscriber_action(
+        &self,
+        call: ContractCall<SignerClient, ()>,
+        purpose: &str,
+        subscription_id: [u8; 32],
+        chain: &str,
+    ) -> Result<ExecutionResult> {
+        let handle = self.chain_handle(chain)?;
+
+        let gas_estimate = call.estimate_gas().await.map_err(|e| {
+            RelayerError::ContractRevert(format!("relayed call would revert: {}", e))
+        })?;
+        let gas_limit = gas_estimate * handle.gas_policy.gas_limit_multiplier_percent / 100;
+
+        let call = outbound_call(
+            &call,
+            gas_limit,
+            purpose,
+            Some(format!("0x{}", hex::encode(subscription_id))),
+        )?;
+        self.send_and_confirm(handle, call).await
+    }
+
     async fn validate_subscription_state(
         &self,
         subscription_id: [u8; 32],
@@ -782,17 +1287,17 @@ impl RealBlockchainClient {
     }
 
     fn chain_id(&self, chain: &str) -> Result<u64> {
-        match chain.to_lowercase().as_str() {
-            "sepolia" => Ok(self.sepolia_chain_id),
-            "base" => Ok(self.base_chain_id),
-            _ => Err(RelayerError::Validation(format!(
-                "unsupported chain: {}",
-                chain
-            ))),
-        }
+        Ok(self.chain_handle(chain)?.chain_id)
     }
 }
 
+// configured chain names, for unsupported chain errors
+fn sorted_chain_names<T>(chains: &HashMap<String, T>) -> Vec<String> {
+    let mut names: Vec<String> = chains.keys().cloned().collect();
+    names.sort();
+    names
+}
+
 impl StubBlockchainClient {
     fn new(config: &Config) -> Result<Self> {
         let relayer_address = config
@@ -800,22 +1305,48 @@ impl StubBlockchainClient {
             .parse()
             .unwrap_or_else(|_| Address::zero());
 
+        let chains = config
+            .chains
+            .iter()
+            .enumerate()
+            .map(|(index, chain)| {
+                (
+                    chain.name.to_ascii_lowercase(),
+                    StubChain {
+                        chain_id: chain.chain_id,
+                        block_number: 1_000_000 + STUB_BLOCK_SPACING * index as u64,
+                    },
+                )
+            })
+            .collect();
+
         Ok(Self {
             relayer_address,
-            sepolia_chain_id: STUB_SEPOLIA_CHAIN_ID,
-            base_chain_id: STUB_BASE_CHAIN_ID,
+            chains,
+            submitted: Mutex::new(HashMap::new()),
+            creation_reverts: Mutex::new(VecDeque::new()),
         })
     }
 
-    fn normalize_chain(chain: &str) -> Result<String> {
+    fn stub_chain(&self, chain: &str) -> Result<&StubChain> {
         let normalized = chain.trim().to_ascii_lowercase();
-        match normalized.as_str() {
-            "sepolia" | "base" => Ok(normalized),
-            _ => Err(RelayerError::Validation(format!(
-                "unsupported chain: {}",
-                chain
-            ))),
-        }
+        self.chains
+            .get(&normalized)
+            .or_else(|| {
+                normalized
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(|id| self.chains.values().find(|stub| stub.chain_id == id))
+            })
+            .ok_or_else(|| RelayerError::UnsupportedChain {
+                requested: chain.to_string(),
+                supported: sorted_chain_names(&self.chains),
+            })
+    }
+
+    fn normalize_chain(&self, chain: &str) -> Result<String> {
+        self.stub_chain(chain)?;
+        Ok(chain.trim().to_ascii_lowercase())
     }
 
     async fn get_subscription(
@@ -823,7 +1354,7 @@ impl StubBlockchainClient {
         subscription_id: [u8; 32],
         chain: &str,
     ) -> Result<Option<SubscriptionData>> {
-        let normalized = Self::normalize_chain(chain)?;
+        let normalized = self.normalize_chain(chain)?;
         info!(
             "stub blockchain client returning no on-chain subscription data for {:?} on {}",
             subscription_id, normalized
@@ -832,7 +1363,7 @@ impl StubBlockchainClient {
     }
 
     async fn fetch_logs(&self, chain: &str, _filter: Filter) -> Result<Vec<EthersLog>> {
-        let normalized = Self::normalize_chain(chain)?;
+        let normalized = self.normalize_chain(chain)?;
         info!(
             "stub blockchain client returning empty log set for {}",
             normalized
@@ -847,7 +1378,7 @@ impl StubBlockchainClient {
         _amount: U256,
         chain: &str,
     ) -> Result<bool> {
-        let normalized = Self::normalize_chain(chain)?;
+        let normalized = self.normalize_chain(chain)?;
         info!(
             "stub blockchain client assuming allowance sufficient on {}",
             normalized
@@ -861,7 +1392,7 @@ impl StubBlockchainClient {
         _token: Address,
         chain: &str,
     ) -> Result<U256> {
-        let normalized = Self::normalize_chain(chain)?;
+        let normalized = self.normalize_chain(chain)?;
         info!(
             "stub blockchain client returning large balance for chain {}",
             normalized
@@ -874,16 +1405,19 @@ impl StubBlockchainClient {
         subscription_id: [u8; 32],
         chain: &str,
     ) -> Result<ExecutionResult> {
-        let normalized = Self::normalize_chain(chain)?;
-        let block_number = match normalized.as_str() {
-            "sepolia" => 1_000_000,
-            "base" => 5_000_000,
-            _ => unreachable!(),
-        };
+        let normalized = self.normalize_chain(chain)?;
+        let block_number = self.stub_chain(chain)?.block_number;
+
+        let transaction_hash = H256::from(subscription_id);
+        self.submitted
+            .lock()
+            .unwrap()
+            .insert(transaction_hash, block_number);
 
         let result = ExecutionResult {
-            transaction_hash: H256::from(subscription_id),
+            transaction_hash,
             block_number,
+            block_hash: Some(stub_block_hash(block_number)),
             gas_used: U256::from(21_000u64),
             gas_price: U256::from(1_000_000_000u64),
             status: true,
@@ -896,12 +1430,50 @@ impl StubBlockchainClient {
         Ok(result)
     }
 
+    async fn create_subscription(
+        &self,
+        intent: &SubscriptionIntent,
+        signature: Bytes,
+        chain: &str,
+    ) -> Result<H256> {
+        let normalized = self.normalize_chain(chain)?;
+        let block_number = self.stub_chain(chain)?.block_number;
+        to_on_chain_intent(intent)?;
+        if let Some(reason) = self.creation_reverts.lock().unwrap().pop_front() {
+            return Err(RelayerError::ContractRevert(reason));
+        }
+
+        let mut preimage = signature.to_vec();
+        preimage.extend_from_slice(b"createSubscription");
+        let tx_hash = H256::from(ethers::utils::keccak256(preimage));
+        self.submitted.lock().unwrap().insert(tx_hash, block_number);
+
+        info!(
+            "stub blockchain client accepted createSubscription {:?} on {}",
+            tx_hash, normalized
+        );
+        Ok(tx_hash)
+    }
+
     async fn get_transaction_receipt(
         &self,
         tx_hash: H256,
         chain: &str,
     ) -> Result<Option<TransactionReceipt>> {
-        let normalized = Self::normalize_chain(chain)?;
+        let normalized = self.normalize_chain(chain)?;
+
+        // transactions this stub handed out always confirm successfully
+        if let Some(block_number) = self.submitted.lock().unwrap().get(&tx_hash).copied() {
+            return Ok(Some(TransactionReceipt {
+                transaction_hash: tx_hash,
+                block_number: Some(block_number.into()),
+                block_hash: Some(stub_block_hash(block_number)),
+                gas_used: Some(U256::from(21_000u64)),
+                status: Some(1u64.into()),
+                ..Default::default()
+            }));
+        }
+
         info!(
             "stub blockchain client has no receipt for {:?} on {}",
             tx_hash, normalized
@@ -910,12 +1482,8 @@ impl StubBlockchainClient {
     }
 
     async fn get_current_block_number(&self, chain: &str) -> Result<u64> {
-        let normalized = Self::normalize_chain(chain)?;
-        let block_number = match normalized.as_str() {
-            "sepolia" => 1_000_000,
-            "base" => 5_000_000,
-            _ => unreachable!(),
-        };
+        let normalized = self.normalize_chain(chain)?;
+        let block_number = self.stub_chain(chain)?.block_number;
         info!(
             "stub blockchain client returning block {} for {}",
             block_number, normalized
@@ -924,7 +1492,7 @@ impl StubBlockchainClient {
     }
 
     async fn get_block_timestamp(&self, chain: &str, _block_number: u64) -> Result<u64> {
-        let normalized = Self::normalize_chain(chain)?;
+        let normalized = self.normalize_chain(chain)?;
         info!(
             "stub blockchain client providing synthetic timestamp for {}",
             normalized
@@ -933,7 +1501,7 @@ impl StubBlockchainClient {
     }
 
     async fn validate_connection(&self, chain: &str) -> Result<()> {
-        let normalized = Self::normalize_chain(chain)?;
+        let normalized = self.normalize_chain(chain)?;
         info!(
             "stub blockchain client connection validated for {}",
             normalized
@@ -941,8 +1509,32 @@ impl StubBlockchainClient {
         Ok(())
     }
 
+    async fn preflight_payments(
+        &self,
+        chain: &str,
+        requests: &[PreflightRequest],
+    ) -> Result<Vec<PaymentPreflight>> {
+        let normalized = self.normalize_chain(chain)?;
+        info!(
+            "stub blockchain client returning synthetic pre-flight state for {} subscriptions on {}",
+            requests.len(),
+            normalized
+        );
+
+        // mirrors the single-call stubs: unknown subscription, ample balance and allowance
+        Ok(requests
+            .iter()
+            .map(|request| PaymentPreflight {
+                subscription: Ok(None),
+                payment_count: Ok(0),
+                balance: Ok(U256::from(u128::MAX)),
+                allowance: Ok((request.token != Address::zero()).then(U256::max_value)),
+            })
+            .collect())
+    }
+
     async fn get_payment_count(&self, subscription_id: [u8; 32], chain: &str) -> Result<u64> {
-        let normalized = Self::normalize_chain(chain)?;
+        let normalized = self.normalize_chain(chain)?;
         info!(
             "stub blockchain client returning zero payments for {:?} on {}",
             subscription_id, normalized
@@ -951,7 +1543,7 @@ impl StubBlockchainClient {
     }
 
     async fn get_subscription_nonce(&self, subscription_id: [u8; 32], chain: &str) -> Result<u64> {
-        let normalized = Self::normalize_chain(chain)?;
+        let normalized = self.normalize_chain(chain)?;
         info!(
             "stub blockchain client returning nonce 0 for {:?} on {}",
             subscription_id, normalized
@@ -959,12 +1551,53 @@ impl StubBlockchainClient {
         Ok(0)
     }
 
+    async fn get_subscriber_nonce(&self, subscriber: Address, chain: &str) -> Result<U256> {
+        let normalized = self.normalize_chain(chain)?;
+        info!(
+            "stub blockchain client returning nonce 0 for subscriber {:?} on {}",
+            subscriber, normalized
+        );
+        Ok(U256::zero())
+    }
+
+    async fn submit_subscriber_action(
+        &self,
+        subscription_id: [u8; 32],
+        action: &str,
+        chain: &str,
+    ) -> Result<ExecutionResult> {
+        let normalized = self.normalize_chain(chain)?;
+        let block_number = self.stub_chain(chain)?.block_number;
+
+        let mut preimage = subscription_id.to_vec();
+        preimage.extend_from_slice(action.as_bytes());
+
+        info!(
+            "stub blockchain client returning synthetic {} result for {:?} on {}",
+            action, subscription_id, normalized
+        );
+        let transaction_hash = H256::from(ethers::utils::keccak256(preimage));
+        self.submitted
+            .lock()
+            .unwrap()
+            .insert(transaction_hash, block_number);
+
+        Ok(ExecutionResult {
+            transaction_hash,
+            block_number,
+            block_hash: Some(stub_block_hash(block_number)),
+            gas_used: U256::from(21_000u64),
+            gas_price: U256::from(1_000_000_000u64),
+            status: true,
+        })
+    }
+
     async fn validate_subscription_state(
         &self,
         subscription_id: [u8; 32],
         chain: &str,
     ) -> Result<SubscriptionData> {
-        let normalized = Self::normalize_chain(chain)?;
+        let normalized = self.normalize_chain(chain)?;
         info!(
             "stub blockchain client returning synthetic subscription state for {:?} on {}",
             subscription_id, normalized
@@ -989,12 +1622,94 @@ impl StubBlockchainClient {
     }
 
     fn chain_id(&self, chain: &str) -> Result<u64> {
-        let normalized = Self::normalize_chain(chain)?;
-        Ok(match normalized.as_str() {
-            "sepolia" => self.sepolia_chain_id,
-            "base" => self.base_chain_id,
-            _ => unreachable!(),
-        })
+        Ok(self.stub_chain(chain)?.chain_id)
+    }
+}
+
+fn outbound_call<D>(
+    call: &ContractCall<SignerClient, D>,
+    gas_limit: U256,
+    purpose: &str,
+    reference: Option<String>,
+) -> Result<OutboundCall> {
+    let to =
+        call.tx.to_addr().copied().ok_or_else(|| {
+            RelayerError::InternalError("contract call has no target".to_string())
+        })?;
+
+    Ok(OutboundCall {
+        to,
+        data: call.tx.data().cloned().unwrap_or_default(),
+        value: call.tx.value().copied().unwrap_or_default(),
+        gas_limit,
+        purpose: purpose.to_string(),
+        reference,
+    })
+}
+
+fn to_on_chain_intent(intent: &SubscriptionIntent) -> Result<OnChainSubscriptionIntent> {
+    let address = |value: &str, field: &str| {
+        Address::from_str(value)
+            .map_err(|_| RelayerError::Validation(format!("invalid {} address", field)))
+    };
+    let amount = |value: &str, field: &str| {
+        U256::from_dec_str(value)
+            .map_err(|_| RelayerError::Validation(format!("invalid {} format", field)))
+    };
+
+    Ok(OnChainSubscriptionIntent {
+        subscriber: address(&intent.subscriber, "subscriber")?,
+        merchant: address(&intent.merchant, "merchant")?,
+        amount: amount(&intent.amount, "amount")?,
+        interval: U256::from(intent.interval),
+        start_time: U256::from(intent.start_time),
+        max_payments: U256::from(intent.max_payments),
+        max_total_amount: amount(&intent.max_total_amount, "max total amount")?,
+        expiry: U256::from(intent.expiry),
+        nonce: U256::from(intent.nonce),
+        token: address(&intent.token, "token")?,
+    })
+}
+
+// a zero nonce means the manager has never seen this subscription id
+fn subscription_data(
+    subscription_id: [u8; 32],
+    subscription: OnChainSubscription,
+    executed_payments: U256,
+) -> Option<SubscriptionData> {
+    if subscription.nonce.is_zero() {
+        return None;
+    }
+
+    Some(SubscriptionData {
+        id: subscription_id,
+        subscriber: subscription.subscriber,
+        merchant: subscription.merchant,
+        amount: subscription.amount,
+        interval: subscription.interval,
+        start_time: subscription.start_time,
+        max_payments: subscription.max_payments,
+        max_total_amount: subscription.max_total_amount,
+        expiry: subscription.expiry,
+        nonce: subscription.nonce,
+        token: subscription.token,
+        status: subscription.status,
+        executed_payments,
+        total_paid: executed_payments.saturating_mul(subscription.amount),
+    })
+}
+
+// allow_failure calls come back as Err(revert data); decode successes into the expected type
+fn decode_multicall<D: Tokenizable>(
+    result: Option<std::result::Result<Token, Bytes>>,
+    call: &str,
+) -> std::result::Result<D, String> {
+    match result {
+        Some(Ok(token)) => {
+            D::from_token(token).map_err(|e| format!("failed to decode {} result: {}", call, e))
+        }
+        Some(Err(revert)) => Err(format!("{} reverted: {}", call, revert)),
+        None => Err(format!("multicall returned no result for {}", call)),
     }
 }
 
@@ -1002,3 +1717,8 @@ fn is_stub_endpoint(endpoint: &str) -> bool {
     let normalized = endpoint.trim().to_ascii_lowercase();
     normalized == "stub" || normalized.starts_with("stub://")
 }
+
+// stub chains never reorg, so each block number maps to one fixed hash
+fn stub_block_hash(block_number: u64) -> H256 {
+    H256::from(ethers::utils::keccak256(block_number.to_be_bytes()))
+}
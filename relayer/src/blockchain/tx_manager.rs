@@ -0,0 +1,531 @@
+use super::rpc_client::SignerClient;
+use crate::chains::GasPolicy;
+use crate::database::models::{OutboundTransaction, OutboundTxStatus};
+use crate::database::queries::Queries;
+use crate::error::{RelayerError, Result};
+use chrono::Utc;
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{
+    Address, BlockNumber, Bytes, Eip1559TransactionRequest, TransactionReceipt, H256, U256,
+};
+use ethers::utils::keccak256;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+// resubmit with higher fees once an attempt has sat unmined this long
+const BUMP_AFTER: Duration = Duration::from_secs(45);
+// callers stop waiting after this; the row stays PENDING for reconciliation
+const MAX_RECEIPT_WAIT: Duration = Duration::from_secs(180);
+// mempools reject replacements that raise fees by less than ~10%
+pub const FEE_BUMP_PERCENT: u64 = 125;
+const MIN_REPLACEMENT_PERCENT: u64 = 110;
+const WEI_PER_GWEI: u64 = 1_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeQuote {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+pub fn fee_cap_wei(policy: &GasPolicy) -> U256 {
+    U256::from(policy.max_gas_price_gwei) * U256::from(WEI_PER_GWEI)
+}
+
+// clamps suggested eip-1559 fees to the chain's max_gas_price_gwei
+pub fn cap_fees(
+    max_fee_per_gas: U256,
+    max_priority_fee_per_gas: U256,
+    policy: &GasPolicy,
+) -> FeeQuote {
+    let max_fee_per_gas = max_fee_per_gas.min(fee_cap_wei(policy));
+    FeeQuote {
+        max_fee_per_gas,
+        max_priority_fee_per_gas: max_priority_fee_per_gas.min(max_fee_per_gas),
+    }
+}
+
+// fees for a replacement attempt, or None when the cap leaves no room for a valid bump
+pub fn bump_fees(previous: &FeeQuote, current: &FeeQuote, policy: &GasPolicy) -> Option<FeeQuote> {
+    let bumped = cap_fees(
+        (previous.max_fee_per_gas * FEE_BUMP_PERCENT / 100).max(current.max_fee_per_gas),
+        (previous.max_priority_fee_per_gas * FEE_BUMP_PERCENT / 100)
+            .max(current.max_priority_fee_per_gas),
+        policy,
+    );
+
+    let min_max_fee = previous.max_fee_per_gas * MIN_REPLACEMENT_PERCENT / 100;
+    let min_priority_fee = previous.max_priority_fee_per_gas * MIN_REPLACEMENT_PERCENT / 100;
+    if bumped.max_fee_per_gas < min_max_fee || bumped.max_priority_fee_per_gas < min_priority_fee {
+        return None;
+    }
+
+    Some(bumped)
+}
+
+// a contract call ready to be signed; gas_limit already includes the chain's buffer
+pub(crate) struct OutboundCall {
+    pub to: Address,
+    pub data: Bytes,
+    pub value: U256,
+    pub gas_limit: U256,
+    pub purpose: String,
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReconcileSummary {
+    pub confirmed: usize,
+    pub reverted: usize,
+    pub dropped: usize,
+    pub outstanding: usize,
+}
+
+// signs, persists and tracks every relayer transaction; nonces are assigned locally per chain
+pub(crate) struct TxManager {
+    queries: Queries,
+    // next nonce per chain; the slot lock is held across sign/persist/broadcast
+    nonces: Mutex<HashMap<String, Arc<tokio::sync::Mutex<Option<U256>>>>>,
+}
+
+impl TxManager {
+    pub fn new(queries: Queries) -> Self {
+        Self {
+            queries,
+            nonces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn submit(
+        &self,
+        chain: &str,
+        client: &SignerClient,
+        policy: &GasPolicy,
+        call: OutboundCall,
+    ) -> Result<OutboundTransaction> {
+        let from = client.address();
+        let fees = self.current_fees(chain, client, policy, true).await?;
+
+        let slot = self.nonce_slot(chain);
+        let mut next_nonce = slot.lock().await;
+        let nonce = match *next_nonce {
+            Some(nonce) => nonce,
+            None => self.seed_nonce(chain, client).await?,
+        };
+
+        let request = Eip1559TransactionRequest::new()
+            .from(from)
+            .to(call.to)
+            .data(call.data.clone())
+            .value(call.value)
+            .gas(call.gas_limit)
+            .nonce(nonce)
+            .max_fee_per_gas(fees.max_fee_per_gas)
+            .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+            .chain_id(client.signer().chain_id());
+        let (raw, hash) = sign(client, request).await?;
+
+        let now = Utc::now();
+        let mut record = OutboundTransaction {
+            id: 0,
+            chain: chain.to_string(),
+            from_address: format!("{:?}", from),
+            to_address: format!("{:?}", call.to),
+            nonce: nonce.as_u64() as i64,
+            calldata: format!("0x{}", hex::encode(&call.data)),
+            value: call.value.to_string(),
+            gas_limit: call.gas_limit.to_string(),
+            max_fee_per_gas: fees.max_fee_per_gas.to_string(),
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas.to_string(),
+            transaction_hash: format!("{:?}", hash),
+            replaced_hashes: Vec::new(),
+            attempts: 1,
+            status: OutboundTxStatus::Pending.to_string(),
+            purpose: call.purpose,
+            reference: call.reference,
+            block_number: None,
+            error_message: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        // persist before broadcasting so a crash never leaves an untracked transaction
+        record.id = self.queries.insert_outbound_transaction(&record).await?;
+
+        if let Err(e) = client.send_raw_transaction(raw).await {
+            // our view of the nonce may be stale; reseed from the node next time
+            *next_nonce = None;
+            self.queries
+                .resolve_outbound_transaction(
+                    record.id,
+                    OutboundTxStatus::Failed,
+                    None,
+                    None,
+                    Some(&e.to_string()),
+                )
+                .await?;
+            return Err(RelayerError::TransactionFailed(format!(
+                "failed to send transaction: {}",
+                e
+            )));
+        }
+
+        *next_nonce = Some(nonce + 1);
+        info!(
+            "sent {} tx {:?} on {} (nonce {}, max fee {} wei)",
+            record.purpose, hash, chain, nonce, fees.max_fee_per_gas
+        );
+        Ok(record)
+    }
+
+    // polls for a receipt, replacing the transaction with bumped fees while it sits unmined
+    pub async fn wait_for_receipt(
+        &self,
+        chain: &str,
+        client: &SignerClient,
+        policy: &GasPolicy,
+        mut record: OutboundTransaction,
+    ) -> Result<TransactionReceipt> {
+        let started = Instant::now();
+        let mut last_attempt = Instant::now();
+
+        loop {
+            match find_receipt(client, &record).await {
+                Ok(Some((hash, receipt))) => {
+                    return self.record_outcome(&record, hash, receipt).await;
+                }
+                Ok(None) => {}
+                Err(e) => warn!(
+                    "failed to poll receipt for outbound tx {}: {}",
+                    record.id, e
+                ),
+            }
+
+            if started.elapsed() >= MAX_RECEIPT_WAIT {
+                // non-retryable on purpose: resending would only queue a second tx behind this nonce
+                return Err(RelayerError::TransactionFailed(format!(
+                    "transaction {} still pending at nonce {} after {}s",
+                    record.transaction_hash,
+                    record.nonce,
+                    MAX_RECEIPT_WAIT.as_secs()
+                )));
+            }
+
+            if last_attempt.elapsed() >= BUMP_AFTER {
+                match self.replace(chain, client, policy, &record).await {
+                    Ok(Some(replacement)) => record = replacement,
+                    Ok(None) => {}
+                    Err(e) => warn!("failed to bump outbound tx {}: {}", record.id, e),
+                }
+                last_attempt = Instant::now();
+            }
+
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+
+    // settles rows left PENDING by a previous run and rebroadcasts whatever is still outstanding
+    pub async fn reconcile(
+        &self,
+        chain: &str,
+        client: &SignerClient,
+        policy: &GasPolicy,
+    ) -> Result<ReconcileSummary> {
+        let from = client.address();
+        let pending = self
+            .queries
+            .get_pending_outbound_transactions(chain, &format!("{:?}", from))
+            .await?;
+        let mut summary = ReconcileSummary::default();
+
+        if pending.is_empty() {
+            return Ok(summary);
+        }
+
+        let mined_nonce = client
+            .get_transaction_count(from, Some(BlockNumber::Latest.into()))
+            .await
+            .map_err(|e| {
+                RelayerError::RpcConnectionFailed(format!(
+                    "failed to get {} transaction count: {}",
+                    chain, e
+                ))
+            })?;
+
+        for record in pending {
+            if let Some((hash, receipt)) = find_receipt(client, &record).await? {
+                match self.record_outcome(&record, hash, receipt).await {
+                    Ok(_) => summary.confirmed += 1,
+                    Err(_) => summary.reverted += 1,
+                }
+                continue;
+            }
+
+            if U256::from(record.nonce as u64) < mined_nonce {
+                self.queries
+                    .resolve_outbound_transaction(
+                        record.id,
+                        OutboundTxStatus::Failed,
+                        None,
+                        None,
+                        Some("nonce consumed by another transaction"),
+                    )
+                    .await?;
+                summary.dropped += 1;
+                continue;
+            }
+
+            // the node may have forgotten it across the restart; bump if fees moved, else resend as-is
+            match self.replace(chain, client, policy, &record).await {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    let (raw, _) = sign(client, request_from_record(client, &record)?).await?;
+                    if let Err(e) = client.send_raw_transaction(raw).await {
+                        warn!("rebroadcast of outbound tx {} failed: {}", record.id, e);
+                    }
+                }
+                Err(e) => warn!("failed to replace outbound tx {}: {}", record.id, e),
+            }
+            summary.outstanding += 1;
+        }
+
+        // pending rows may hold nonces above what the node reports; reseed on next submit
+        *self.nonce_slot(chain).lock().await = None;
+
+        info!(
+            "reconciled outbound transactions on {}: {} confirmed, {} reverted, {} dropped, {} outstanding",
+            chain, summary.confirmed, summary.reverted, summary.dropped, summary.outstanding
+        );
+        Ok(summary)
+    }
+
+    async fn replace(
+        &self,
+        chain: &str,
+        client: &SignerClient,
+        policy: &GasPolicy,
+        record: &OutboundTransaction,
+    ) -> Result<Option<OutboundTransaction>> {
+        let previous = FeeQuote {
+            max_fee_per_gas: parse_u256(&record.max_fee_per_gas)?,
+            max_priority_fee_per_gas: parse_u256(&record.max_priority_fee_per_gas)?,
+        };
+        let current = self.current_fees(chain, client, policy, false).await?;
+
+        let Some(bumped) = bump_fees(&previous, &current, policy) else {
+            warn!(
+                "outbound tx {} on {} is at the {} gwei cap; waiting instead of bumping",
+                record.id, chain, policy.max_gas_price_gwei
+            );
+            return Ok(None);
+        };
+
+        let request = request_from_record(client, record)?
+            .max_fee_per_gas(bumped.max_fee_per_gas)
+            .max_priority_fee_per_gas(bumped.max_priority_fee_per_gas);
+        let (raw, hash) = sign(client, request).await?;
+        let hash = format!("{:?}", hash);
+
+        // record first: earlier hashes stay candidates, so a failed send loses nothing
+        self.queries
+            .record_outbound_replacement(
+                record.id,
+                &hash,
+                &bumped.max_fee_per_gas.to_string(),
+                &bumped.max_priority_fee_per_gas.to_string(),
+            )
+            .await?;
+
+        if let Err(e) = client.send_raw_transaction(raw).await {
+            warn!(
+                "replacement {} for outbound tx {} rejected: {}",
+                hash, record.id, e
+            );
+        } else {
+            info!(
+                "bumped outbound tx {} on {} to max fee {} wei ({})",
+                record.id, chain, bumped.max_fee_per_gas, hash
+            );
+        }
+
+        let mut replacement = record.clone();
+        replacement
+            .replaced_hashes
+            .push(std::mem::replace(&mut replacement.transaction_hash, hash));
+        replacement.max_fee_per_gas = bumped.max_fee_per_gas.to_string();
+        replacement.max_priority_fee_per_gas = bumped.max_priority_fee_per_gas.to_string();
+        replacement.attempts += 1;
+        Ok(Some(replacement))
+    }
+
+    async fn record_outcome(
+        &self,
+        record: &OutboundTransaction,
+        hash: H256,
+        receipt: TransactionReceipt,
+    ) -> Result<TransactionReceipt> {
+        let hash_str = format!("{:?}", hash);
+        let block_number = receipt.block_number.map(|block| block.as_u64() as i64);
+
+        if receipt.status == Some(1u64.into()) {
+            self.queries
+                .resolve_outbound_transaction(
+                    record.id,
+                    OutboundTxStatus::Confirmed,
+                    Some(&hash_str),
+                    block_number,
+                    None,
+                )
+                .await?;
+            return Ok(receipt);
+        }
+
+        warn!("transaction failed: {:?}", hash);
+        self.queries
+            .resolve_outbound_transaction(
+                record.id,
+                OutboundTxStatus::Reverted,
+                Some(&hash_str),
+                block_number,
+                Some("transaction reverted"),
+            )
+            .await?;
+        Err(RelayerError::ContractRevert(format!(
+            "transaction {} reverted",
+            hash_str
+        )))
+    }
+
+    // current network fees clamped to the cap; new sends refuse to go out below the base fee
+    async fn current_fees(
+        &self,
+        chain: &str,
+        client: &SignerClient,
+        policy: &GasPolicy,
+        require_base_fee_under_cap: bool,
+    ) -> Result<FeeQuote> {
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            client.estimate_eip1559_fees(None).await.map_err(|e| {
+                RelayerError::RpcConnectionFailed(format!(
+                    "failed to estimate {} fees: {}",
+                    chain, e
+                ))
+            })?;
+
+        if require_base_fee_under_cap {
+            let base_fee = client
+                .get_block(BlockNumber::Latest)
+                .await
+                .map_err(|e| {
+                    RelayerError::RpcConnectionFailed(format!(
+                        "failed to fetch latest {} block: {}",
+                        chain, e
+                    ))
+                })?
+                .and_then(|block| block.base_fee_per_gas)
+                .unwrap_or_default();
+
+            // a tx priced below the base fee would only block every later nonce
+            if base_fee > fee_cap_wei(policy) {
+                return Err(RelayerError::InsufficientGas(format!(
+                    "{} base fee {} wei exceeds the {} gwei cap",
+                    chain, base_fee, policy.max_gas_price_gwei
+                )));
+            }
+        }
+
+        Ok(cap_fees(max_fee_per_gas, max_priority_fee_per_gas, policy))
+    }
+
+    fn nonce_slot(&self, chain: &str) -> Arc<tokio::sync::Mutex<Option<U256>>> {
+        self.nonces
+            .lock()
+            .unwrap()
+            .entry(chain.to_string())
+            .or_default()
+            .clone()
+    }
+
+    // the higher of the node's pending count and one past our highest live nonce
+    async fn seed_nonce(&self, chain: &str, client: &SignerClient) -> Result<U256> {
+        let from = client.address();
+        let node_nonce = client
+            .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(|e| {
+                RelayerError::RpcConnectionFailed(format!(
+                    "failed to get {} transaction count: {}",
+                    chain, e
+                ))
+            })?;
+        let persisted_next = self
+            .queries
+            .get_max_outbound_nonce(chain, &format!("{:?}", from))
+            .await?
+            .map(|nonce| U256::from(nonce as u64 + 1))
+            .unwrap_or_default();
+
+        Ok(node_nonce.max(persisted_next))
+    }
+}
+
+async fn sign(client: &SignerClient, request: Eip1559TransactionRequest) -> Result<(Bytes, H256)> {
+    let typed: TypedTransaction = request.into();
+    let signature = client
+        .signer()
+        .sign_transaction(&typed)
+        .await
+        .map_err(|e| RelayerError::InternalError(format!("failed to sign transaction: {}", e)))?;
+    let raw = typed.rlp_signed(&signature);
+    let hash = H256::from(keccak256(&raw));
+    Ok((raw, hash))
+}
+
+async fn find_receipt(
+    client: &SignerClient,
+    record: &OutboundTransaction,
+) -> Result<Option<(H256, TransactionReceipt)>> {
+    for candidate in record.candidate_hashes() {
+        let hash = H256::from_str(&candidate).map_err(|_| {
+            RelayerError::InternalError(format!("malformed transaction hash {}", candidate))
+        })?;
+        let receipt = client.get_transaction_receipt(hash).await.map_err(|e| {
+            RelayerError::RpcConnectionFailed(format!("failed to get transaction receipt: {}", e))
+        })?;
+        if let Some(receipt) = receipt {
+            return Ok(Some((hash, receipt)));
+        }
+    }
+    Ok(None)
+}
+
+fn request_from_record(
+    client: &SignerClient,
+    record: &OutboundTransaction,
+) -> Result<Eip1559TransactionRequest> {
+    let to = Address::from_str(&record.to_address).map_err(|_| {
+        RelayerError::InternalError(format!("malformed to address {}", record.to_address))
+    })?;
+    let data = Bytes::from_str(&record.calldata)
+        .map_err(|_| RelayerError::InternalError("malformed stored calldata".to_string()))?;
+
+    Ok(Eip1559TransactionRequest::new()
+        .from(client.address())
+        .to(to)
+        .data(data)
+        .value(parse_u256(&record.value)?)
+        .gas(parse_u256(&record.gas_limit)?)
+        .nonce(record.nonce as u64)
+        .max_fee_per_gas(parse_u256(&record.max_fee_per_gas)?)
+        .max_priority_fee_per_gas(parse_u256(&record.max_priority_fee_per_gas)?)
+        .chain_id(client.signer().chain_id()))
+}
+
+fn parse_u256(value: &str) -> Result<U256> {
+    U256::from_dec_str(value)
+        .map_err(|_| RelayerError::InternalError(format!("malformed stored amount {}", value)))
+}
@@ -0,0 +1,127 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+pub const SEPOLIA_CHAIN_ID: u64 = 11155111;
+pub const BASE_SEPOLIA_CHAIN_ID: u64 = 84532;
+pub const DEFAULT_CONFIRMATIONS: u64 = 3;
+pub const DEFAULT_GAS_LIMIT_MULTIPLIER_PERCENT: u64 = 120;
+// canonical Multicall3 deployment, present at the same address on most EVM chains
+pub const DEFAULT_MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GasPolicy {
+    pub max_gas_price_gwei: u64,
+    // buffer applied on top of eth_estimateGas, e.g. 120 = +20%
+    pub gas_limit_multiplier_percent: u64,
+}
+
+impl GasPolicy {
+    pub fn new(max_gas_price_gwei: u64) -> Self {
+        Self {
+            max_gas_price_gwei,
+            gas_limit_multiplier_percent: DEFAULT_GAS_LIMIT_MULTIPLIER_PERCENT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainConfig {
+    // short lowercase name stored in subscriptions.chain ("sepolia", "base", ...)
+    pub name: String,
+    pub chain_id: u64,
+    pub rpc_url: String,
+    pub subscription_manager_address: String,
+    pub pyusd_address: Option<String>,
+    pub supported_tokens: Vec<String>,
+    pub hypersync_url: Option<String>,
+    pub confirmations: u64,
+    pub gas_policy: GasPolicy,
+    // Multicall3 contract used to batch the scheduler's pre-flight reads
+    pub multicall_address: String,
+    // first block the historical sync scans, usually the manager's deployment block
+    pub start_block: Option<u64>,
+}
+
+impl ChainConfig {
+    pub fn hypersync_endpoint(&self) -> Option<&str> {
+        self.hypersync_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty() && !url.eq_ignore_ascii_case("stub"))
+    }
+}
+
+// chains the relayer serves, keyed by chain id and kept in configuration order
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChainRegistry {
+    chains: Vec<ChainConfig>,
+}
+
+impl ChainRegistry {
+    pub fn new(chains: Vec<ChainConfig>) -> Result<Self> {
+        let mut names = HashSet::new();
+        let mut ids = HashSet::new();
+
+        for chain in &chains {
+            if chain.name.trim().is_empty() {
+                return Err(anyhow!("chain {} has an empty name", chain.chain_id));
+            }
+            if !names.insert(chain.name.to_ascii_lowercase()) {
+                return Err(anyhow!("chain name {} configured twice", chain.name));
+            }
+            if !ids.insert(chain.chain_id) {
+                return Err(anyhow!("chain id {} configured twice", chain.chain_id));
+            }
+        }
+
+        Ok(Self { chains })
+    }
+
+    pub fn get(&self, chain_id: u64) -> Option<&ChainConfig> {
+        self.chains.iter().find(|chain| chain.chain_id == chain_id)
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&ChainConfig> {
+        let name = name.trim();
+        self.chains
+            .iter()
+            .find(|chain| chain.name.eq_ignore_ascii_case(name))
+    }
+
+    // accepts either a chain name or a decimal chain id
+    pub fn resolve(&self, name_or_id: &str) -> Option<&ChainConfig> {
+        let trimmed = name_or_id.trim();
+        match trimmed.parse::<u64>() {
+            Ok(chain_id) => self.get(chain_id),
+            Err(_) => self.by_name(trimmed),
+        }
+    }
+
+    pub fn require(&self, name_or_id: &str) -> Result<&ChainConfig> {
+        self.resolve(name_or_id)
+            .ok_or_else(|| anyhow!("unsupported chain: {}", name_or_id))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ChainConfig> {
+        self.chains.iter()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.chains.iter().map(|chain| chain.name.clone()).collect()
+    }
+
+    // first configured chain; used where older endpoints assumed sepolia
+    pub fn default_chain(&self) -> Option<&ChainConfig> {
+        self.chains.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.chains.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chains.is_empty()
+    }
+}
@@ -1,3 +1,9 @@
+use crate::api::rate_limit::{RateLimitBackend, RateLimitPolicy, RateLimitSettings};
+use crate::chains::{
+    ChainConfig, ChainRegistry, GasPolicy, BASE_SEPOLIA_CHAIN_ID, DEFAULT_CONFIRMATIONS,
+    DEFAULT_MULTICALL3_ADDRESS, SEPOLIA_CHAIN_ID,
+};
+use crate::scheduler::SchedulerSettings;
 use crate::utils::tokens;
 use anyhow::{Context, Result};
 use ethers::types::Address;
@@ -6,6 +12,15 @@ use std::collections::HashSet;
 use std::env;
 use std::str::FromStr;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AvailMode {
+    // intents are accepted without being anchored anywhere
+    Disabled,
+    // in-process avail that keeps every blob in memory; tests and local development only
+    Mock,
+    Remote,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub database_url: String,
@@ -22,14 +37,34 @@ pub struct Config {
     pub server_port: u16,
     pub execution_interval_seconds: u64,
     pub max_executions_per_batch: i64,
+    // cron expression (with seconds) overriding execution_interval_seconds
+    pub scheduler_cron: Option<String>,
+    pub max_executions_per_cycle: i64,
+    pub max_retry_attempts: u32,
+    pub max_failures_before_dead_letter: i64,
+    // bootstrap admin credential, accepted alongside ADMIN-role api keys
+    pub admin_api_token: Option<String>,
+    // browser origins allowed by cors; "*" allows any, empty allows none
+    pub cors_allowed_origins: Vec<String>,
+    pub rate_limits: RateLimitSettings,
     pub max_gas_price_gwei: u64,
     pub relayer_address: String,
     pub envio_graphql_endpoint: Option<String>,
     pub envio_explorer_url: Option<String>,
+    pub avail_mode: AvailMode,
     pub avail_rpc_url: Option<String>,
     pub avail_application_id: Option<u32>,
+    // how long the outbox collects intents before anchoring them as one avail batch
+    pub avail_batch_window_seconds: u64,
+    pub avail_batch_max_intents: i64,
     pub hypersync_url_sepolia: Option<String>,
     pub hypersync_url_base: Option<String>,
+    // submit createSubscription on-chain for accepted intents instead of leaving it to the dapp
+    pub relay_subscription_creation: bool,
+    // lets webhooks target loopback and private networks; local development only
+    pub webhook_allow_private_targets: bool,
+    // sepolia/base from the fields above plus anything listed in ADDITIONAL_CHAINS
+    pub chains: ChainRegistry,
 }
 
 impl Config {
@@ -86,6 +121,29 @@ impl Config {
             .unwrap_or_else(|_| "10".to_string())
             .parse()
             .context("MAX_EXECUTIONS_PER_BATCH must be a valid number")?;
+        let scheduler_cron = env::var("SCHEDULER_CRON")
+            .ok()
+            .map(|cron| cron.trim().to_string())
+            .filter(|cron| !cron.is_empty());
+        let max_executions_per_cycle = env::var("MAX_EXECUTIONS_PER_CYCLE")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .context("MAX_EXECUTIONS_PER_CYCLE must be a valid number")?;
+        let max_retry_attempts = env::var("MAX_RETRY_ATTEMPTS")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()
+            .context("MAX_RETRY_ATTEMPTS must be a valid number")?;
+        let max_failures_before_dead_letter = env::var("MAX_FAILURES_BEFORE_DEAD_LETTER")
+            .unwrap_or_else(|_| "8".to_string())
+            .parse()
+            .context("MAX_FAILURES_BEFORE_DEAD_LETTER must be a valid number")?;
+        let admin_api_token = env::var("ADMIN_API_TOKEN")
+            .ok()
+            .filter(|token| !token.trim().is_empty());
+        let cors_allowed_origins = env::var("CORS_ALLOWED_ORIGINS")
+            .map(|raw| Self::parse_cors_origins(&raw))
+            .unwrap_or_default();
+        let rate_limits = Self::load_rate_limits()?;
         let max_gas_price_gwei = env::var("MAX_GAS_PRICE_GWEI")
             .unwrap_or_else(|_| "50".to_string())
             .parse()
@@ -103,9 +161,50 @@ impl Config {
                 })
             })
             .transpose()?;
+        let avail_mode = match env::var("AVAIL_MODE")
+            .ok()
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| !value.is_empty())
+        {
+            Some(mode) => match mode.as_str() {
+                "disabled" => AvailMode::Disabled,
+                "mock" => AvailMode::Mock,
+                "remote" => AvailMode::Remote,
+                other => {
+                    return Err(anyhow::anyhow!(
+                        "AVAIL_MODE must be 'disabled', 'mock' or 'remote', got '{}'",
+                        other
+                    ))
+                }
+            },
+            // a configured rpc url means remote; the mock is never picked implicitly
+            None if avail_rpc_url
+                .as_ref()
+                .is_some_and(|url| url.to_lowercase() != "stub" && !url.trim().is_empty()) =>
+            {
+                AvailMode::Remote
+            }
+            None => AvailMode::Disabled,
+        };
+        let avail_batch_window_seconds = env::var("AVAIL_BATCH_WINDOW_SECONDS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .context("AVAIL_BATCH_WINDOW_SECONDS must be a valid number")?;
+        let avail_batch_max_intents = env::var("AVAIL_BATCH_MAX_INTENTS")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse()
+            .context("AVAIL_BATCH_MAX_INTENTS must be a valid number")?;
         let hypersync_url_sepolia = env::var("HYPERSYNC_URL_SEPOLIA").ok();
         let hypersync_url_base = env::var("HYPERSYNC_URL_BASE").ok();
-        let config = Config {
+        let relay_subscription_creation = env::var("RELAY_SUBSCRIPTION_CREATION")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .context("RELAY_SUBSCRIPTION_CREATION must be true or false")?;
+        let webhook_allow_private_targets = env::var("WEBHOOK_ALLOW_PRIVATE_TARGETS")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .context("WEBHOOK_ALLOW_PRIVATE_TARGETS must be true or false")?;
+        let mut config = Config {
             database_url,
             ethereum_rpc_url,
             base_rpc_url,
@@ -120,27 +219,230 @@ impl Config {
             server_port,
             execution_interval_seconds,
             max_executions_per_batch,
+            scheduler_cron,
+            max_executions_per_cycle,
+            max_retry_attempts,
+            max_failures_before_dead_letter,
+            admin_api_token,
+            cors_allowed_origins,
+            rate_limits,
             max_gas_price_gwei,
             relayer_address,
             envio_graphql_endpoint,
             envio_explorer_url,
+            avail_mode,
             avail_rpc_url,
             avail_application_id,
+            avail_batch_window_seconds,
+            avail_batch_max_intents,
             hypersync_url_sepolia,
             hypersync_url_base,
+            relay_subscription_creation,
+            webhook_allow_private_targets,
+            chains: ChainRegistry::default(),
         };
 
         // validate eth addresses
         config.validate()?;
+        config.chains = config.load_chain_registry()?;
 
-        tokens::register_pyusd_addresses(&[
-            config.pyusd_address_sepolia.clone(),
-            config.pyusd_address_base.clone(),
-        ]);
+        let pyusd_addresses: Vec<String> = config
+            .chains
+            .iter()
+            .filter_map(|chain| chain.pyusd_address.clone())
+            .collect();
+        tokens::register_pyusd_addresses(&pyusd_addresses);
 
         Ok(config)
     }
 
+    // registry entries for the two chains configured through the legacy sepolia/base fields
+    pub fn legacy_chains(&self) -> Vec<ChainConfig> {
+        vec![
+            ChainConfig {
+                name: "sepolia".to_string(),
+                chain_id: SEPOLIA_CHAIN_ID,
+                rpc_url: self.ethereum_rpc_url.clone(),
+                subscription_manager_address: self.subscription_manager_address_sepolia.clone(),
+                pyusd_address: Some(self.pyusd_address_sepolia.clone()),
+                supported_tokens: self.supported_tokens_sepolia.clone(),
+                hypersync_url: self.hypersync_url_sepolia.clone(),
+                confirmations: DEFAULT_CONFIRMATIONS,
+                gas_policy: GasPolicy::new(self.max_gas_price_gwei),
+                multicall_address: DEFAULT_MULTICALL3_ADDRESS.to_string(),
+                start_block: None,
+            },
+            ChainConfig {
+                name: "base".to_string(),
+                chain_id: BASE_SEPOLIA_CHAIN_ID,
+                rpc_url: self.base_rpc_url.clone(),
+                subscription_manager_address: self.subscription_manager_address_base.clone(),
+                pyusd_address: Some(self.pyusd_address_base.clone()),
+                supported_tokens: self.supported_tokens_base.clone(),
+                hypersync_url: self.hypersync_url_base.clone(),
+                confirmations: DEFAULT_CONFIRMATIONS,
+                gas_policy: GasPolicy::new(self.max_gas_price_gwei),
+                multicall_address: DEFAULT_MULTICALL3_ADDRESS.to_string(),
+                start_block: None,
+            },
+        ]
+    }
+
+    fn load_chain_registry(&self) -> Result<ChainRegistry> {
+        let mut chains = self.legacy_chains();
+
+        if let Ok(raw) = env::var("ADDITIONAL_CHAINS") {
+            for name in raw
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                chains.push(self.chain_from_env(name)?);
+            }
+        }
+
+        for chain in chains.iter_mut() {
+            Self::apply_chain_overrides(chain)?;
+        }
+
+        ChainRegistry::new(chains)
+    }
+
+    fn chain_env_key(name: &str, suffix: &str) -> String {
+        format!(
+            "CHAIN_{}_{}",
+            name.to_ascii_uppercase().replace('-', "_"),
+            suffix
+        )
+    }
+
+    fn chain_from_env(&self, name: &str) -> Result<ChainConfig> {
+        let name = name.to_ascii_lowercase();
+        if name.len() > 64
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(anyhow::anyhow!(
+                "ADDITIONAL_CHAINS entry '{}' must be at most 64 alphanumeric, '_' or '-' characters",
+                name
+            ));
+        }
+
+        let key = |suffix: &str| Self::chain_env_key(&name, suffix);
+
+        let chain_id = env::var(key("CHAIN_ID"))
+            .with_context(|| format!("{} environment variable is required", key("CHAIN_ID")))?
+            .trim()
+            .parse::<u64>()
+            .with_context(|| format!("{} must be a valid number", key("CHAIN_ID")))?;
+
+        let rpc_url = env::var(key("RPC_URL"))
+            .with_context(|| format!("{} environment variable is required", key("RPC_URL")))?;
+        if !rpc_url.starts_with("http") && !rpc_url.trim().eq_ignore_ascii_case("stub") {
+            return Err(anyhow::anyhow!(
+                "{} must be a valid http(s) url",
+                key("RPC_URL")
+            ));
+        }
+
+        let manager_key = key("SUBSCRIPTION_MANAGER_ADDRESS");
+        let subscription_manager_address = Self::normalize_contract_address(
+            &env::var(&manager_key)
+                .with_context(|| format!("{} environment variable is required", manager_key))?,
+            &manager_key,
+        )?;
+
+        let pyusd_key = key("PYUSD_ADDRESS");
+        let pyusd_address = env::var(&pyusd_key)
+            .ok()
+            .map(|value| Self::normalize_contract_address(&value, &pyusd_key))
+            .transpose()?;
+
+        let tokens_key = key("SUPPORTED_TOKENS");
+        let supported_tokens = Self::parse_supported_tokens_var(&tokens_key)?;
+        Self::validate_supported_tokens(&tokens_key, &supported_tokens)?;
+
+        let hypersync_url = env::var(key("HYPERSYNC_URL")).ok();
+        // without hypersync the history is scanned over rpc, which from genesis takes days
+        let has_hypersync = hypersync_url
+            .as_deref()
+            .map(str::trim)
+            .is_some_and(|url| !url.is_empty() && !url.eq_ignore_ascii_case("stub"));
+        if !has_hypersync && env::var(key("START_BLOCK")).is_err() {
+            return Err(anyhow::anyhow!(
+                "{} is required when {} is not set",
+                key("START_BLOCK"),
+                key("HYPERSYNC_URL")
+            ));
+        }
+
+        Ok(ChainConfig {
+            name,
+            chain_id,
+            rpc_url,
+            subscription_manager_address,
+            pyusd_address,
+            supported_tokens,
+            hypersync_url,
+            confirmations: DEFAULT_CONFIRMATIONS,
+            gas_policy: GasPolicy::new(self.max_gas_price_gwei),
+            multicall_address: DEFAULT_MULTICALL3_ADDRESS.to_string(),
+            start_block: None,
+        })
+    }
+
+    // per-chain tuning knobs, applied to legacy and additional chains alike
+    fn apply_chain_overrides(chain: &mut ChainConfig) -> Result<()> {
+        let parse = |suffix: &str| -> Result<Option<u64>> {
+            let key = Self::chain_env_key(&chain.name, suffix);
+            env::var(&key)
+                .ok()
+                .map(|value| {
+                    value
+                        .trim()
+                        .parse::<u64>()
+                        .with_context(|| format!("{} must be a valid number", key))
+                })
+                .transpose()
+        };
+
+        let chain_id = parse("CHAIN_ID")?;
+        let confirmations = parse("CONFIRMATIONS")?;
+        let max_gas_price_gwei = parse("MAX_GAS_PRICE_GWEI")?;
+        let gas_limit_multiplier_percent = parse("GAS_LIMIT_MULTIPLIER_PERCENT")?;
+        let start_block = parse("START_BLOCK")?;
+
+        if let Some(chain_id) = chain_id {
+            chain.chain_id = chain_id;
+        }
+        if let Some(confirmations) = confirmations {
+            chain.confirmations = confirmations;
+        }
+        if start_block.is_some() {
+            chain.start_block = start_block;
+        }
+        if let Some(max_gas_price_gwei) = max_gas_price_gwei {
+            chain.gas_policy.max_gas_price_gwei = max_gas_price_gwei;
+        }
+        if let Some(multiplier) = gas_limit_multiplier_percent {
+            if multiplier < 100 {
+                return Err(anyhow::anyhow!(
+                    "{} must be at least 100",
+                    Self::chain_env_key(&chain.name, "GAS_LIMIT_MULTIPLIER_PERCENT")
+                ));
+            }
+            chain.gas_policy.gas_limit_multiplier_percent = multiplier;
+        }
+
+        let multicall_key = Self::chain_env_key(&chain.name, "MULTICALL_ADDRESS");
+        if let Ok(value) = env::var(&multicall_key) {
+            chain.multicall_address = Self::normalize_contract_address(&value, &multicall_key)?;
+        }
+
+        Ok(())
+    }
+
     fn validate(&self) -> Result<()> {
         // validate that addresses are valid eth addresses (42 chars starting with 0x)
         let addresses = [
@@ -222,23 +524,45 @@ impl Config {
             ));
         }
 
-        let hypersync_sepolia_provided = self
-            .hypersync_url_sepolia
-            .as_ref()
-            .map(|url| !url.trim().is_empty())
-            .unwrap_or(false);
-        let hypersync_base_provided = self
-            .hypersync_url_base
-            .as_ref()
-            .map(|url| !url.trim().is_empty())
-            .unwrap_or(false);
+        if self.avail_mode == AvailMode::Remote && !self.avail_enabled() {
+            return Err(anyhow::anyhow!(
+                "AVAIL_MODE=remote requires AVAIL_RPC_URL and AVAIL_APPLICATION_ID"
+            ));
+        }
+
+        if !(1..=3_600).contains(&self.avail_batch_window_seconds) {
+            return Err(anyhow::anyhow!(
+                "AVAIL_BATCH_WINDOW_SECONDS must be between 1 and 3600"
+            ));
+        }
 
-        if hypersync_sepolia_provided ^ hypersync_base_provided {
+        if !(1..=1_000).contains(&self.avail_batch_max_intents) {
             return Err(anyhow::anyhow!(
-                "both HYPERSYNC_URL_SEPOLIA and HYPERSYNC_URL_BASE must be provided together"
+                "AVAIL_BATCH_MAX_INTENTS must be between 1 and 1000"
             ));
         }
 
+        SchedulerSettings::from_config(self)
+            .validate()
+            .map_err(|e| anyhow::anyhow!("invalid scheduler settings: {}", e))?;
+
+        if let Some(token) = &self.admin_api_token {
+            if token.len() < 32 {
+                return Err(anyhow::anyhow!(
+                    "ADMIN_API_TOKEN must be at least 32 characters"
+                ));
+            }
+        }
+
+        for origin in &self.cors_allowed_origins {
+            if origin != "*" && !origin.starts_with("http://") && !origin.starts_with("https://") {
+                return Err(anyhow::anyhow!(
+                    "CORS_ALLOWED_ORIGINS entry '{}' must be '*' or an http(s) origin",
+                    origin
+                ));
+            }
+        }
+
         let envio_graphql_provided = self
             .envio_graphql_endpoint
             .as_ref()
@@ -259,6 +583,61 @@ impl Config {
         Ok(())
     }
 
+    fn load_rate_limits() -> Result<RateLimitSettings> {
+        let defaults = RateLimitSettings::default();
+        let policy = |key: &str, default: RateLimitPolicy| -> Result<RateLimitPolicy> {
+            match env::var(key) {
+                Ok(value) => RateLimitPolicy::parse(&value).ok_or_else(|| {
+                    anyhow::anyhow!("{} must be <per minute> or <per minute>:<burst>", key)
+                }),
+                Err(_) => Ok(default),
+            }
+        };
+
+        let backend = match env::var("RATE_LIMIT_BACKEND")
+            .unwrap_or_else(|_| "memory".to_string())
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "memory" => RateLimitBackend::Memory,
+            "postgres" => RateLimitBackend::Postgres,
+            other => {
+                return Err(anyhow::anyhow!(
+                    "RATE_LIMIT_BACKEND must be 'memory' or 'postgres', got '{}'",
+                    other
+                ))
+            }
+        };
+
+        Ok(RateLimitSettings {
+            backend,
+            intent_per_client: policy("RATE_LIMIT_INTENT_PER_CLIENT", defaults.intent_per_client)?,
+            intent_per_subscriber: policy(
+                "RATE_LIMIT_INTENT_PER_SUBSCRIBER",
+                defaults.intent_per_subscriber,
+            )?,
+            analytics: policy("RATE_LIMIT_ANALYTICS", defaults.analytics)?,
+            hypersync_scans: policy("RATE_LIMIT_HYPERSYNC_SCANS", defaults.hypersync_scans)?,
+        })
+    }
+
+    // comma-separated origins, compared the way browsers send them: no trailing slash, lowercase
+    pub fn parse_cors_origins(value: &str) -> Vec<String> {
+        value
+            .split(',')
+            .map(|origin| origin.trim().trim_end_matches('/').to_ascii_lowercase())
+            .filter(|origin| !origin.is_empty())
+            .collect()
+    }
+
+    pub fn cors_origin_allowed(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/').to_ascii_lowercase();
+        self.cors_allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || *allowed == origin)
+    }
+
     fn normalize_contract_address(value: &str, var_name: &str) -> Result<String> {
         let trimmed = value.trim();
         if !trimmed.starts_with("0x") || trimmed.len() != 42 {
@@ -353,50 +732,36 @@ impl Config {
         Ok(())
     }
 
+    pub fn chain(&self, chain: &str) -> Result<&ChainConfig> {
+        self.chains.require(chain)
+    }
+
     pub fn subscription_manager_address_for_chain(&self, chain: &str) -> Result<&str> {
-        match chain.to_lowercase().as_str() {
-            "sepolia" => Ok(&self.subscription_manager_address_sepolia),
-            "base" => Ok(&self.subscription_manager_address_base),
-            _ => Err(anyhow::anyhow!("unsupported chain: {}", chain)),
-        }
+        Ok(&self.chain(chain)?.subscription_manager_address)
     }
 
     pub fn rpc_url_for_chain(&self, chain: &str) -> Result<&str> {
-        match chain.to_lowercase().as_str() {
-            "sepolia" => Ok(&self.ethereum_rpc_url),
-            "base" => Ok(&self.base_rpc_url),
-            _ => Err(anyhow::anyhow!("unsupported chain: {}", chain)),
-        }
+        Ok(&self.chain(chain)?.rpc_url)
     }
 
     pub fn pyusd_address_for_chain(&self, chain: &str) -> Result<&str> {
-        match chain.to_lowercase().as_str() {
-            "sepolia" => Ok(&self.pyusd_address_sepolia),
-            "base" => Ok(&self.pyusd_address_base),
-            _ => Err(anyhow::anyhow!("unsupported chain: {}", chain)),
-        }
+        self.chain(chain)?
+            .pyusd_address
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("no PYUSD address configured for chain: {}", chain))
     }
 
     pub fn supported_tokens_for_chain(&self, chain: &str) -> Result<&[String]> {
-        match chain.to_lowercase().as_str() {
-            "sepolia" => Ok(self.supported_tokens_sepolia.as_slice()),
-            "base" => Ok(self.supported_tokens_base.as_slice()),
-            _ => Err(anyhow::anyhow!("unsupported chain: {}", chain)),
-        }
+        Ok(self.chain(chain)?.supported_tokens.as_slice())
     }
 
+    // whether an avail node is configured to connect to; submitting also needs AVAIL_SIGNING_KEY
     pub fn avail_enabled(&self) -> bool {
-        let signing_key_present = env::var("AVAIL_SIGNING_KEY")
-            .ok()
-            .map(|value| !value.trim().is_empty())
-            .unwrap_or(false);
-
         self.avail_rpc_url
             .as_ref()
             .map(|url| url.to_lowercase() != "stub" && !url.trim().is_empty())
             .unwrap_or(false)
             && self.avail_application_id.is_some()
-            && signing_key_present
     }
 
     pub fn envio_enabled(&self) -> bool {
@@ -412,38 +777,8 @@ impl Config {
     }
 
     pub fn hypersync_enabled(&self) -> bool {
-        self.hypersync_url_sepolia
-            .as_ref()
-            .map(|url| {
-                let trimmed = url.trim();
-                !trimmed.is_empty() && !trimmed.eq_ignore_ascii_case("stub")
-            })
-            .unwrap_or(false)
-            && self
-                .hypersync_url_base
-                .as_ref()
-                .map(|url| {
-                    let trimmed = url.trim();
-                    !trimmed.is_empty() && !trimmed.eq_ignore_ascii_case("stub")
-                })
-                .unwrap_or(false)
-    }
-
-    pub fn hypersync_urls(&self) -> Option<(&str, &str)> {
-        match (&self.hypersync_url_sepolia, &self.hypersync_url_base) {
-            (Some(sepolia), Some(base))
-                if {
-                    let s = sepolia.trim();
-                    let b = base.trim();
-                    !s.is_empty()
-                        && !b.is_empty()
-                        && !s.eq_ignore_ascii_case("stub")
-                        && !b.eq_ignore_ascii_case("stub")
-                } =>
-            {
-                Some((sepolia.as_str(), base.as_str()))
-            }
-            _ => None,
-        }
+        self.chains
+            .iter()
+            .any(|chain| chain.hypersync_endpoint().is_some())
     }
 }
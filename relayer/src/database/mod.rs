@@ -3,7 +3,11 @@ pub mod queries;
 
 use crate::database::queries::Queries;
 use anyhow::Result;
-use models::{Execution, ExecutionRecord, IntentCache, Subscription, SyncMetadata};
+use models::{
+    AdminAuditEntry, ApiKey, AvailBatchRecord, AvailSubmission, Execution, ExecutionRecord,
+    IntentCache, MerchantWebhook, OutboundTransaction, PendingOperation, Subscription,
+    SubscriptionRetryState, SyncMetadata, WebhookDelivery,
+};
 use sqlx::{postgres::PgPoolOptions, PgPool};
 use std::{
     collections::HashMap,
@@ -22,8 +26,25 @@ pub(crate) struct StubStorage {
     pub execution_records: Mutex<Vec<ExecutionRecord>>,
     pub intent_cache: Mutex<Vec<IntentCache>>,
     pub sync_metadata: Mutex<HashMap<i64, SyncMetadata>>,
+    pub pending_operations: Mutex<Vec<PendingOperation>>,
+    pub outbound_transactions: Mutex<Vec<OutboundTransaction>>,
+    pub retry_states: Mutex<HashMap<String, SubscriptionRetryState>>,
+    pub merchant_webhooks: Mutex<Vec<MerchantWebhook>>,
+    pub webhook_deliveries: Mutex<Vec<WebhookDelivery>>,
+    pub api_keys: Mutex<Vec<ApiKey>>,
+    pub admin_audit_log: Mutex<Vec<AdminAuditEntry>>,
+    pub avail_submissions: Mutex<Vec<AvailSubmission>>,
+    pub avail_batches: Mutex<Vec<AvailBatchRecord>>,
     next_intent_id: AtomicI64,
     next_execution_id: AtomicI64,
+    next_pending_operation_id: AtomicI64,
+    next_outbound_transaction_id: AtomicI64,
+    next_webhook_id: AtomicI64,
+    next_webhook_delivery_id: AtomicI64,
+    next_api_key_id: AtomicI64,
+    next_admin_audit_id: AtomicI64,
+    next_avail_submission_id: AtomicI64,
+    next_avail_batch_id: AtomicI64,
 }
 
 impl StubStorage {
@@ -34,6 +55,42 @@ impl StubStorage {
     fn next_execution_id(&self) -> i64 {
         self.next_execution_id.fetch_add(1, Ordering::SeqCst) + 1
     }
+
+    fn next_pending_operation_id(&self) -> i64 {
+        self.next_pending_operation_id
+            .fetch_add(1, Ordering::SeqCst)
+            + 1
+    }
+
+    fn next_outbound_transaction_id(&self) -> i64 {
+        self.next_outbound_transaction_id
+            .fetch_add(1, Ordering::SeqCst)
+            + 1
+    }
+
+    fn next_webhook_id(&self) -> i64 {
+        self.next_webhook_id.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn next_webhook_delivery_id(&self) -> i64 {
+        self.next_webhook_delivery_id.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn next_api_key_id(&self) -> i64 {
+        self.next_api_key_id.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn next_admin_audit_id(&self) -> i64 {
+        self.next_admin_audit_id.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn next_avail_submission_id(&self) -> i64 {
+        self.next_avail_submission_id.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn next_avail_batch_id(&self) -> i64 {
+        self.next_avail_batch_id.fetch_add(1, Ordering::SeqCst) + 1
+    }
 }
 
 #[derive(Clone)]
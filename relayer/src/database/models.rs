@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use ethers::types::U256;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -15,14 +16,14 @@ pub struct Subscription {
     pub expiry: DateTime<Utc>,
     pub nonce: i64,
     pub token_address: String,
-    pub status: String, // "ACTIVE", "PAUSED", "CANCELLED", "EXPIRED", "COMPLETED"
+    pub status: String, // "PENDING", "ACTIVE", "PAUSED", "CANCELLED", "EXPIRED", "COMPLETED", "DEAD_LETTER"
     pub executed_payments: i64,
     pub total_paid: String, // large numbers
     pub next_payment_due: DateTime<Utc>,
     pub failure_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-    pub chain: String, // "sepolia" or "base"
+    pub chain: String, // chain registry name, e.g. "sepolia"
     pub avail_block_number: Option<i64>,
     pub avail_extrinsic_index: Option<i64>,
 }
@@ -40,7 +41,7 @@ pub struct Execution {
     pub block_number: i64,
     pub gas_used: String,
     pub gas_price: String,
-    pub status: String, // "SUCCESS", "FAILED", "PENDING"
+    pub status: String, // "SUCCESS", "CONFIRMED", "ORPHANED", "FAILED", "PENDING"
     pub error_message: Option<String>,
     pub executed_at: DateTime<Utc>,
     pub chain: String,
@@ -48,6 +49,17 @@ pub struct Execution {
     pub nexus_verified: bool,
     pub nexus_submitted_at: Option<DateTime<Utc>>,
     pub token_address: Option<String>,
+    pub block_hash: Option<String>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+// an execution with the subscriber it charged, as listed in merchant transaction history
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MerchantExecution {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub execution: Execution,
+    pub subscriber: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +79,7 @@ pub struct ExecutionRecord {
     pub nexus_verified: bool,
     pub nexus_submitted_at: Option<DateTime<Utc>>,
     pub token_address: Option<String>,
+    pub block_hash: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -101,30 +114,516 @@ pub struct SyncMetadata {
     pub sync_method: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PendingOperation {
+    pub id: i64,
+    pub subscription_id: String,
+    pub operation: String, // "CREATE_SUBSCRIPTION"
+    pub chain: String,
+    pub transaction_hash: String,
+    pub status: String, // "PENDING", "CONFIRMED", "FAILED"
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OutboundTransaction {
+    pub id: i64,
+    pub chain: String,
+    pub from_address: String,
+    pub to_address: String,
+    pub nonce: i64,
+    pub calldata: String, // 0x-prefixed hex
+    pub value: String,
+    pub gas_limit: String,
+    pub max_fee_per_gas: String,
+    pub max_priority_fee_per_gas: String,
+    pub transaction_hash: String, // latest attempt
+    pub replaced_hashes: Vec<String>,
+    pub attempts: i32,
+    pub status: String, // "PENDING", "CONFIRMED", "REVERTED", "FAILED"
+    pub purpose: String,
+    pub reference: Option<String>,
+    pub block_number: Option<i64>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OutboundTransaction {
+    // every hash that could still be mined for this nonce, newest first
+    pub fn candidate_hashes(&self) -> Vec<String> {
+        let mut hashes = vec![self.transaction_hash.clone()];
+        hashes.extend(self.replaced_hashes.iter().rev().cloned());
+        hashes
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutboundTxStatus {
+    Pending,
+    Confirmed,
+    Reverted,
+    // never reached the mempool, or its nonce was consumed by another transaction
+    Failed,
+}
+
+impl std::fmt::Display for OutboundTxStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutboundTxStatus::Pending => write!(f, "PENDING"),
+            OutboundTxStatus::Confirmed => write!(f, "CONFIRMED"),
+            OutboundTxStatus::Reverted => write!(f, "REVERTED"),
+            OutboundTxStatus::Failed => write!(f, "FAILED"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SubscriptionStatus {
+    // awaiting confirmation of a relayed createSubscription
+    Pending,
     Active,
     Paused,
     Cancelled,
     Expired,
     Completed,
+    // kept failing past the retry budget; needs an operator to re-queue it
+    DeadLetter,
 }
 
 impl std::fmt::Display for SubscriptionStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            SubscriptionStatus::Pending => write!(f, "PENDING"),
             SubscriptionStatus::Active => write!(f, "ACTIVE"),
             SubscriptionStatus::Paused => write!(f, "PAUSED"),
             SubscriptionStatus::Cancelled => write!(f, "CANCELLED"),
             SubscriptionStatus::Expired => write!(f, "EXPIRED"),
             SubscriptionStatus::Completed => write!(f, "COMPLETED"),
+            SubscriptionStatus::DeadLetter => write!(f, "DEAD_LETTER"),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl SubscriptionStatus {
+    pub const ALL: [SubscriptionStatus; 7] = [
+        SubscriptionStatus::Pending,
+        SubscriptionStatus::Active,
+        SubscriptionStatus::Paused,
+        SubscriptionStatus::Cancelled,
+        SubscriptionStatus::Expired,
+        SubscriptionStatus::Completed,
+        SubscriptionStatus::DeadLetter,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.to_string().eq_ignore_ascii_case(value))
+    }
+
+    // the SubscriptionManager contract's status enum
+    pub fn from_on_chain(status: u8) -> Option<Self> {
+        match status {
+            0 => Some(SubscriptionStatus::Active),
+            1 => Some(SubscriptionStatus::Paused),
+            2 => Some(SubscriptionStatus::Cancelled),
+            3 => Some(SubscriptionStatus::Expired),
+            4 => Some(SubscriptionStatus::Completed),
+            _ => None,
+        }
+    }
+}
+
+// optional filters when listing a subscriber's subscriptions; addresses are lowercase
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionFilter {
+    pub status: Option<SubscriptionStatus>,
+    pub chain: Option<String>,
+    pub token_address: Option<String>,
+    pub merchant: Option<String>,
+}
+
+// why a scheduled payment failed; each class retries on its own backoff curve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FailureClass {
+    InsufficientBalance,
+    MissingAllowance,
+    ChainError,
+    ContractRevert,
+    // the relayer's own database, configuration or bookkeeping failed, not the subscription
+    RelayerError,
+}
+
+impl std::fmt::Display for FailureClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailureClass::InsufficientBalance => write!(f, "INSUFFICIENT_BALANCE"),
+            FailureClass::MissingAllowance => write!(f, "MISSING_ALLOWANCE"),
+            FailureClass::ChainError => write!(f, "CHAIN_ERROR"),
+            FailureClass::ContractRevert => write!(f, "CONTRACT_REVERT"),
+            FailureClass::RelayerError => write!(f, "RELAYER_ERROR"),
+        }
+    }
+}
+
+// retry bookkeeping kept in subscriptions columns outside the Subscription row type
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubscriptionRetryState {
+    pub next_retry_at: Option<DateTime<Utc>>,
+    pub last_failure_class: Option<String>,
+    pub last_error: Option<String>,
+    pub dead_lettered_at: Option<DateTime<Utc>>,
+    // set by an operator; the scheduler skips the subscription until it is cleared
+    pub relayer_paused_at: Option<DateTime<Utc>>,
+    // consecutive CHAIN_ERROR / RELAYER_ERROR failures; these have their own, larger dead-letter budget
+    pub chain_error_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DeadLetterSubscription {
+    pub id: String,
+    pub subscriber: String,
+    pub merchant: String,
+    pub chain: String,
+    pub token_address: String,
+    pub amount: String,
+    pub executed_payments: i64,
+    pub failure_count: i64,
+    pub last_failure_class: Option<String>,
+    pub last_error: Option<String>,
+    pub dead_lettered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEventType {
+    PaymentExecuted,
+    PaymentFailed,
+    SubscriptionPaused,
+    SubscriptionCancelled,
+    SubscriptionCompleted,
+}
+
+impl WebhookEventType {
+    pub const ALL: [WebhookEventType; 5] = [
+        WebhookEventType::PaymentExecuted,
+        WebhookEventType::PaymentFailed,
+        WebhookEventType::SubscriptionPaused,
+        WebhookEventType::SubscriptionCancelled,
+        WebhookEventType::SubscriptionCompleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::PaymentExecuted => "payment.executed",
+            WebhookEventType::PaymentFailed => "payment.failed",
+            WebhookEventType::SubscriptionPaused => "subscription.paused",
+            WebhookEventType::SubscriptionCancelled => "subscription.cancelled",
+            WebhookEventType::SubscriptionCompleted => "subscription.completed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|event_type| event_type.as_str() == value)
+    }
+}
+
+impl std::fmt::Display for WebhookEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MerchantWebhook {
+    pub id: i64,
+    pub merchant: String,
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<String>, // empty subscribes to every event type
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MerchantWebhook {
+    pub fn accepts(&self, event_type: WebhookEventType) -> bool {
+        self.event_types.is_empty() || self.event_types.iter().any(|t| t == event_type.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    // gave up after the last retry
+    Failed,
+}
+
+impl std::fmt::Display for WebhookDeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebhookDeliveryStatus::Pending => write!(f, "PENDING"),
+            WebhookDeliveryStatus::Delivered => write!(f, "DELIVERED"),
+            WebhookDeliveryStatus::Failed => write!(f, "FAILED"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_id: uuid::Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub status: String, // "PENDING", "DELIVERED", "FAILED"
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// a delivery claimed by the worker, with what it needs to sign and send it
+#[derive(Debug, Clone, FromRow)]
+pub struct ClaimedWebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_id: uuid::Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AvailStatus {
+    // accepted by the api, waiting for the outbox worker
+    Pending,
+    Submitted,
+    // gave up after the last retry
+    Failed,
+    // avail is turned off on this relayer; never stored, only reported for accepted intents
+    Disabled,
+}
+
+impl std::fmt::Display for AvailStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AvailStatus::Pending => write!(f, "PENDING"),
+            AvailStatus::Submitted => write!(f, "SUBMITTED"),
+            AvailStatus::Failed => write!(f, "FAILED"),
+            AvailStatus::Disabled => write!(f, "DISABLED"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AvailSubmission {
+    pub id: i64,
+    pub subscription_id: String,
+    pub payload: serde_json::Value, // AvailIntent
+    pub status: String,             // "PENDING", "SUBMITTED", "FAILED"
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub block_number: Option<i64>,
+    pub extrinsic_index: Option<i64>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    // set once the intent is anchored in a batch
+    pub batch_id: Option<i64>,
+    pub leaf_index: Option<i32>,
+    pub merkle_proof: Option<serde_json::Value>, // Vec<ProofStep>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AvailBatchRecord {
+    pub id: i64,
+    pub merkle_root: String,
+    pub leaf_count: i32,
+    pub block_number: i64,
+    pub extrinsic_index: i64,
+    pub submitted_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// a submission's place in a batch that was just included
+#[derive(Debug, Clone)]
+pub struct AvailBatchMember {
+    pub submission_id: i64,
+    pub leaf_index: i32,
+    pub merkle_proof: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiKeyRole {
+    // reads and manages one merchant's data
+    Merchant,
+    // everything, including the admin routes
+    Admin,
+}
+
+impl ApiKeyRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiKeyRole::Merchant => "MERCHANT",
+            ApiKeyRole::Admin => "ADMIN",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "MERCHANT" => Some(ApiKeyRole::Merchant),
+            "ADMIN" => Some(ApiKeyRole::Admin),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiKeyRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiKey {
+    pub id: i64,
+    pub key_hash: String, // hex sha-256 of the key, never the key itself
+    pub key_prefix: String,
+    pub role: String, // "MERCHANT", "ADMIN"
+    pub merchant: Option<String>,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+// operator actions taken through the admin api, as recorded in admin_audit_log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AdminAction {
+    SyncSubscription,
+    ResetFailureCount,
+    PauseSubscription,
+    ResumeSubscription,
+    RequeueDeadLetter,
+    TriggerSchedulerCycle,
+    UpdateSchedulerSettings,
+    HyperSyncResync,
+    CreateApiKey,
+    RevokeApiKey,
+}
+
+impl AdminAction {
+    pub const ALL: [AdminAction; 10] = [
+        AdminAction::SyncSubscription,
+        AdminAction::ResetFailureCount,
+        AdminAction::PauseSubscription,
+        AdminAction::ResumeSubscription,
+        AdminAction::RequeueDeadLetter,
+        AdminAction::TriggerSchedulerCycle,
+        AdminAction::UpdateSchedulerSettings,
+        AdminAction::HyperSyncResync,
+        AdminAction::CreateApiKey,
+        AdminAction::RevokeApiKey,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.as_str().eq_ignore_ascii_case(value))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdminAction::SyncSubscription => "SYNC_SUBSCRIPTION",
+            AdminAction::ResetFailureCount => "RESET_FAILURE_COUNT",
+            AdminAction::PauseSubscription => "PAUSE_SUBSCRIPTION",
+            AdminAction::ResumeSubscription => "RESUME_SUBSCRIPTION",
+            AdminAction::RequeueDeadLetter => "REQUEUE_DEAD_LETTER",
+            AdminAction::TriggerSchedulerCycle => "TRIGGER_SCHEDULER_CYCLE",
+            AdminAction::UpdateSchedulerSettings => "UPDATE_SCHEDULER_SETTINGS",
+            AdminAction::HyperSyncResync => "HYPERSYNC_RESYNC",
+            AdminAction::CreateApiKey => "CREATE_API_KEY",
+            AdminAction::RevokeApiKey => "REVOKE_API_KEY",
+        }
+    }
+}
+
+impl std::fmt::Display for AdminAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AdminAuditEntry {
+    pub id: i64,
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+// the backend holding a scheduler advisory lock, from pg_locks and pg_stat_activity
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AdvisoryLockHolder {
+    pub pid: i32,
+    pub application_name: Option<String>,
+    pub client_addr: Option<String>,
+    pub backend_start: Option<DateTime<Utc>>,
+    pub state: Option<String>,
+    pub state_change: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PendingOperationKind {
+    CreateSubscription,
+}
+
+impl std::fmt::Display for PendingOperationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PendingOperationKind::CreateSubscription => write!(f, "CREATE_SUBSCRIPTION"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PendingOperationStatus {
+    Pending,
+    Confirmed,
+    Failed,
+}
+
+impl std::fmt::Display for PendingOperationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PendingOperationStatus::Pending => write!(f, "PENDING"),
+            PendingOperationStatus::Confirmed => write!(f, "CONFIRMED"),
+            PendingOperationStatus::Failed => write!(f, "FAILED"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExecutionStatus {
+    // mined, but not yet past the chain's confirmation depth
     Success,
+    Confirmed,
+    // dropped by a reorg; the payment was rolled back
+    Orphaned,
     Failed,
     Pending,
 }
@@ -133,36 +632,74 @@ impl std::fmt::Display for ExecutionStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ExecutionStatus::Success => write!(f, "SUCCESS"),
+            ExecutionStatus::Confirmed => write!(f, "CONFIRMED"),
+            ExecutionStatus::Orphaned => write!(f, "ORPHANED"),
             ExecutionStatus::Failed => write!(f, "FAILED"),
             ExecutionStatus::Pending => write!(f, "PENDING"),
         }
     }
 }
 
-// chain enum
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Chain {
-    Sepolia,
-    Base,
-}
+impl ExecutionStatus {
+    pub const ALL: [ExecutionStatus; 5] = [
+        ExecutionStatus::Success,
+        ExecutionStatus::Confirmed,
+        ExecutionStatus::Orphaned,
+        ExecutionStatus::Failed,
+        ExecutionStatus::Pending,
+    ];
 
-impl std::fmt::Display for Chain {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Chain::Sepolia => write!(f, "sepolia"),
-            Chain::Base => write!(f, "base"),
-        }
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.to_string().eq_ignore_ascii_case(value))
     }
 }
 
-impl std::str::FromStr for Chain {
-    type Err = anyhow::Error;
+// optional filters for a merchant's payment history; addresses are lowercase, `chain` is a
+// registry name. every data source applies the same rules, in sql, graphql or via `matches`
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionFilter {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub token_address: Option<String>,
+    pub chain: Option<String>,
+    pub subscription_id: Option<String>,
+    pub status: Option<ExecutionStatus>,
+    pub min_amount: Option<U256>,
+    // only SUCCESS and CONFIRMED executions, i.e. payments that moved funds
+    pub settled: bool,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "sepolia" => Ok(Chain::Sepolia),
-            "base" => Ok(Chain::Base),
-            _ => Err(anyhow::anyhow!("invalid chain: {}", s)),
-        }
+impl ExecutionFilter {
+    pub fn matches(
+        &self,
+        executed_at: DateTime<Utc>,
+        token_address: &str,
+        chain: &str,
+        subscription_id: &str,
+        status: &str,
+        amount: &str,
+    ) -> bool {
+        self.from.is_none_or(|from| executed_at >= from)
+            && self.to.is_none_or(|to| executed_at <= to)
+            && self
+                .token_address
+                .as_ref()
+                .is_none_or(|token| token == token_address)
+            && self.chain.as_ref().is_none_or(|name| name == chain)
+            && self
+                .subscription_id
+                .as_ref()
+                .is_none_or(|id| id == subscription_id)
+            && self
+                .status
+                .is_none_or(|expected| expected.to_string() == status)
+            && self
+                .min_amount
+                .is_none_or(|min| U256::from_dec_str(amount).is_ok_and(|amount| amount >= min))
+            && (!self.settled
+                || status == ExecutionStatus::Success.to_string()
+                || status == ExecutionStatus::Confirmed.to_string())
     }
 }
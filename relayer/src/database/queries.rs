@@ -2,7 +2,13 @@
 
 use super::{
     models::{
-        Execution, ExecutionRecord, IntentCache, Subscription, SubscriptionStatus, SyncMetadata,
+        AdminAction, AdminAuditEntry, AdvisoryLockHolder, ApiKey, ApiKeyRole, AvailBatchMember,
+        AvailBatchRecord, AvailStatus, AvailSubmission, ClaimedWebhookDelivery,
+        DeadLetterSubscription, Execution, ExecutionFilter, ExecutionRecord, ExecutionStatus,
+        FailureClass, IntentCache, MerchantExecution, MerchantWebhook, OutboundTransaction,
+        OutboundTxStatus, PendingOperation, PendingOperationKind, PendingOperationStatus,
+        Subscription, SubscriptionFilter, SubscriptionRetryState, SubscriptionStatus, SyncMetadata,
+        WebhookDelivery, WebhookDeliveryStatus, WebhookEventType,
     },
     StubStorage,
 };
@@ -12,9 +18,10 @@ use crate::{
 };
 use chrono::{DateTime, Utc};
 use ethers::types::U256;
-use sqlx::{types::BigDecimal, PgPool};
+use sqlx::{types::BigDecimal, PgExecutor, PgPool, Row};
 use std::{convert::TryFrom, str::FromStr, sync::Arc};
 use tracing::{info, warn};
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct Queries {
@@ -37,6 +44,11 @@ impl Queries {
         }
     }
 
+    // private in-memory store, for components that run without a database handle
+    pub(crate) fn in_memory() -> Self {
+        Self::new_stub(Arc::new(StubStorage::default()))
+    }
+
     fn postgres_pool(&self) -> Option<&PgPool> {
         self.pool.as_ref()
     }
@@ -49,11 +61,10 @@ impl Queries {
             let mut subscriptions = storage.subscriptions.lock().unwrap();
             for subscription in subscriptions.values_mut() {
                 if subscription.token_address == ZERO_ADDRESS {
-                    let replacement = match subscription.chain.to_lowercase().as_str() {
-                        "sepolia" => Some(config.pyusd_address_sepolia.clone()),
-                        "base" => Some(config.pyusd_address_base.clone()),
-                        _ => None,
-                    };
+                    let replacement = config
+                        .chains
+                        .by_name(&subscription.chain)
+                        .and_then(|chain| chain.pyusd_address.clone());
 
                     if let Some(address) = replacement {
                         subscription.token_address = address;
@@ -63,10 +74,12 @@ impl Queries {
         }
 
         if let Some(pool) = self.postgres_pool() {
-            let updates = vec![
-                ("sepolia", &config.pyusd_address_sepolia),
-                ("base", &config.pyusd_address_base),
-            ];
+            let updates = config.chains.iter().filter_map(|chain| {
+                chain
+                    .pyusd_address
+                    .as_ref()
+                    .map(|address| (chain.name.as_str(), address))
+            });
 
             for (chain, address) in updates {
                 let result = sqlx::query(
@@ -125,8 +138,16 @@ impl Queries {
         }
 
         let pool = self.require_postgres("insert_subscription")?;
-        let record = subscription.clone();
+        Self::insert_subscription_row(pool, subscription).await?;
+
+        info!("successfully inserted subscription: {}", subscription.id);
+        Ok(subscription.id.clone())
+    }
 
+    async fn insert_subscription_row<'e>(
+        executor: impl PgExecutor<'e>,
+        record: &Subscription,
+    ) -> Result<()> {
         sqlx::query!(
             r#"
             INSERT INTO subscriptions (
@@ -197,11 +218,9 @@ impl Queries {
             record.avail_block_number,
             record.avail_extrinsic_index
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
-
-        info!("successfully inserted subscription: {}", subscription.id);
-        Ok(subscription.id.clone())
+        Ok(())
     }
 
     pub async fn get_subscription(&self, subscription_id: &str) -> Result<Option<Subscription>> {
@@ -260,28 +279,107 @@ impl Queries {
         Ok(subscription)
     }
 
+    // a subscriber's subscriptions, newest first; `after` is the (created_at, id) of the last row
+    // already returned, so pages stay stable while new subscriptions arrive
+    pub async fn get_subscriber_subscriptions(
+        &self,
+        subscriber: &str,
+        filter: &SubscriptionFilter,
+        after: Option<(DateTime<Utc>, &str)>,
+        limit: i64,
+    ) -> Result<Vec<Subscription>> {
+        let subscriber = subscriber.to_lowercase();
+        let status = filter.status.as_ref().map(|status| status.to_string());
+
+        if let Some(storage) = self.stub_storage() {
+            let subscriptions = storage.subscriptions.lock().unwrap();
+            let sort_key = |sub: &Subscription| (sub.created_at.timestamp_micros(), sub.id.clone());
+            let after = after.map(|(at, id)| (at.timestamp_micros(), id.to_string()));
+            let mut results: Vec<Subscription> = subscriptions
+                .values()
+                .filter(|sub| {
+                    sub.subscriber == subscriber
+                        && status.as_ref().is_none_or(|status| &sub.status == status)
+                        && filter
+                            .chain
+                            .as_ref()
+                            .is_none_or(|chain| &sub.chain == chain)
+                        && filter
+                            .token_address
+                            .as_ref()
+                            .is_none_or(|token| &sub.token_address == token)
+                        && filter
+                            .merchant
+                            .as_ref()
+                            .is_none_or(|merchant| &sub.merchant == merchant)
+                        && after.as_ref().is_none_or(|after| &sort_key(sub) < after)
+                })
+                .cloned()
+                .collect();
+            results.sort_by_key(|sub| std::cmp::Reverse(sort_key(sub)));
+            results.truncate(limit.max(0) as usize);
+            return Ok(results);
+        }
+
+        let pool = self.require_postgres("get_subscriber_subscriptions")?;
+        let (after_at, after_id) = after.unzip();
+
+        // served by idx_subscriptions_subscriber
+        let subscriptions = sqlx::query_as::<_, Subscription>(
+            r#"
+            SELECT id, subscriber, merchant, amount, interval_seconds, start_time, max_payments,
+                   max_total_amount, expiry, nonce, token_address, status, executed_payments,
+                   total_paid, next_payment_due, failure_count, created_at, updated_at, chain,
+                   avail_block_number, avail_extrinsic_index
+            FROM subscriptions
+            WHERE subscriber = $1
+                AND ($2::VARCHAR IS NULL OR status = $2)
+                AND ($3::VARCHAR IS NULL OR chain = $3)
+                AND ($4::VARCHAR IS NULL OR token_address = $4)
+                AND ($5::VARCHAR IS NULL OR merchant = $5)
+                AND ($6::TIMESTAMPTZ IS NULL OR (created_at, id) < ($6, $7))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $8
+            "#,
+        )
+        .bind(&subscriber)
+        .bind(status)
+        .bind(&filter.chain)
+        .bind(&filter.token_address)
+        .bind(&filter.merchant)
+        .bind(after_at)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(subscriptions)
+    }
+
     /// Check if a nonce has been used by this subscriber
     /// Returns true if nonce is already used (preventing replay attacks)
-    pub async fn is_nonce_used(&self, subscriber: &str, nonce: i64) -> Result<bool> {
+    // nonces are tracked per subscriber by each chain's SubscriptionManager
+    pub async fn is_nonce_used(&self, subscriber: &str, nonce: i64, chain: &str) -> Result<bool> {
         info!(
-            "checking nonce uniqueness for subscriber: {}, nonce: {}",
-            subscriber, nonce
+            "checking nonce uniqueness for subscriber: {}, nonce: {}, chain: {}",
+            subscriber, nonce, chain
         );
 
         if let Some(storage) = self.stub_storage() {
             let subscriptions = storage.subscriptions.lock().unwrap();
-            let used = subscriptions
-                .values()
-                .any(|sub| sub.subscriber == subscriber && sub.nonce == nonce);
+            let used = subscriptions.values().any(|sub| {
+                sub.subscriber == subscriber && sub.nonce == nonce && sub.chain == chain
+            });
             return Ok(used);
         }
 
         let pool = self.require_postgres("is_nonce_used")?;
 
         let result = sqlx::query!(
-            r#"SELECT COUNT(*) AS "count!" FROM subscriptions WHERE subscriber = $1 AND nonce = $2"#,
+            r#"SELECT COUNT(*) AS "count!" FROM subscriptions WHERE subscriber = $1 AND nonce = $2 AND chain = $3"#,
             subscriber,
-            nonce
+            nonce,
+            chain
         )
         .fetch_one(pool)
         .await?;
@@ -356,15 +454,17 @@ impl Queries {
         &self,
         chain: &str,
         limit: i64,
+        offset: i64,
     ) -> Result<Vec<Subscription>> {
         info!(
-            "fetching due subscriptions for chain: {}, limit: {}",
-            chain, limit
+            "fetching due subscriptions for chain: {}, limit: {}, offset: {}",
+            chain, limit, offset
         );
 
         if let Some(storage) = self.stub_storage() {
             let now = Utc::now();
             let subscriptions = storage.subscriptions.lock().unwrap();
+            let retry_states = storage.retry_states.lock().unwrap();
             let mut results: Vec<Subscription> = subscriptions
                 .values()
                 .filter(|sub| {
@@ -373,11 +473,24 @@ impl Queries {
                         && sub.expiry > now
                         && sub.executed_payments < sub.max_payments
                         && sub.next_payment_due <= now
+                        && sub.id.len() <= 66
+                        && retry_states.get(&sub.id).is_none_or(|state| {
+                            state.relayer_paused_at.is_none()
+                                && state.next_retry_at.is_none_or(|retry_at| retry_at <= now)
+                        })
                 })
                 .cloned()
                 .collect();
-            results.sort_by_key(|sub| sub.next_payment_due);
-            results.truncate(limit as usize);
+            results.sort_by(|a, b| {
+                a.next_payment_due
+                    .cmp(&b.next_payment_due)
+                    .then_with(|| a.id.cmp(&b.id))
+            });
+            let results: Vec<Subscription> = results
+                .into_iter()
+                .skip(offset.max(0) as usize)
+                .take(limit.max(0) as usize)
+                .collect();
             info!(
                 "found {} due subscriptions for chain: {} (stub)",
                 results.len(),
@@ -388,8 +501,7 @@ impl Queries {
 
         let pool = self.require_postgres("get_due_subscriptions_for_chain")?;
 
-        let subscriptions = sqlx::query_as!(
-            Subscription,
+        let subscriptions = sqlx::query_as::<_, Subscription>(
             r#"
             SELECT
                 id,
@@ -419,12 +531,16 @@ impl Queries {
                 AND expiry > NOW()
                 AND executed_payments < max_payments
                 AND next_payment_due <= NOW()
-            ORDER BY next_payment_due ASC
-            LIMIT $2
+                AND (next_retry_at IS NULL OR next_retry_at <= NOW())
+                AND relayer_paused_at IS NULL
+                AND length(id) <= 66
+            ORDER BY next_payment_due ASC, id ASC
+            LIMIT $2 OFFSET $3
             "#,
-            chain,
-            limit
         )
+        .bind(chain)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(pool)
         .await?;
 
@@ -436,6 +552,53 @@ impl Queries {
         Ok(subscriptions)
     }
 
+    // size of the due backlog, same filters as get_due_subscriptions_for_chain
+    pub async fn count_due_subscriptions_for_chain(&self, chain: &str) -> Result<i64> {
+        if let Some(storage) = self.stub_storage() {
+            let now = Utc::now();
+            let subscriptions = storage.subscriptions.lock().unwrap();
+            let retry_states = storage.retry_states.lock().unwrap();
+            let count = subscriptions
+                .values()
+                .filter(|sub| {
+                    sub.status == "ACTIVE"
+                        && sub.chain == chain
+                        && sub.expiry > now
+                        && sub.executed_payments < sub.max_payments
+                        && sub.next_payment_due <= now
+                        && sub.id.len() <= 66
+                        && retry_states.get(&sub.id).is_none_or(|state| {
+                            state.relayer_paused_at.is_none()
+                                && state.next_retry_at.is_none_or(|retry_at| retry_at <= now)
+                        })
+                })
+                .count();
+            return Ok(count as i64);
+        }
+
+        let pool = self.require_postgres("count_due_subscriptions_for_chain")?;
+
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM subscriptions
+            WHERE status = 'ACTIVE'
+                AND chain = $1
+                AND expiry > NOW()
+                AND executed_payments < max_payments
+                AND next_payment_due <= NOW()
+                AND (next_retry_at IS NULL OR next_retry_at <= NOW())
+                AND relayer_paused_at IS NULL
+                AND length(id) <= 66
+            "#,
+        )
+        .bind(chain)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
     pub async fn update_subscription_status(
         &self,
         subscription_id: &str,
@@ -765,7 +928,9 @@ impl Queries {
                 e.nexus_attestation_id,
                 e.nexus_verified,
                 e.nexus_submitted_at,
-                e.token_address
+                e.token_address,
+                e.block_hash,
+                e.confirmed_at
             FROM executions e
             JOIN subscriptions s ON e.subscription_id = s.id
             WHERE s.merchant = $1
@@ -786,6 +951,179 @@ impl Queries {
         Ok(executions)
     }
 
+    // the filters shared by list_merchant_executions and get_merchant_execution_totals
+    const MERCHANT_EXECUTIONS_WHERE: &'static str = r#"
+            FROM executions e
+            JOIN subscriptions s ON e.subscription_id = s.id
+            WHERE s.merchant = $1
+                AND ($2::TIMESTAMPTZ IS NULL OR e.executed_at >= $2)
+                AND ($3::TIMESTAMPTZ IS NULL OR e.executed_at <= $3)
+                AND ($4::VARCHAR IS NULL OR COALESCE(e.token_address, s.token_address) = $4)
+                AND ($5::VARCHAR IS NULL OR e.chain = $5)
+                AND ($6::VARCHAR IS NULL OR e.subscription_id = $6)
+                AND ($7::VARCHAR IS NULL OR e.status = $7)
+                AND ($8::NUMERIC IS NULL OR e.amount_paid::NUMERIC >= $8::NUMERIC)
+                AND (NOT $9::BOOLEAN OR e.status IN ('SUCCESS', 'CONFIRMED'))
+    "#;
+
+    fn stub_merchant_executions(
+        storage: &StubStorage,
+        merchant: &str,
+        filter: &ExecutionFilter,
+    ) -> Vec<MerchantExecution> {
+        let subscriptions = storage.subscriptions.lock().unwrap();
+        let executions = storage.executions.lock().unwrap();
+        executions
+            .iter()
+            .filter_map(|execution| {
+                let sub = subscriptions
+                    .get(&execution.subscription_id)
+                    .filter(|sub| sub.merchant == merchant)?;
+                let mut execution = execution.clone();
+                execution.token_address = execution
+                    .token_address
+                    .or_else(|| Some(sub.token_address.clone()));
+                Some(MerchantExecution {
+                    execution,
+                    subscriber: sub.subscriber.clone(),
+                })
+            })
+            .filter(|row| {
+                let execution = &row.execution;
+                filter.matches(
+                    execution.executed_at,
+                    execution.token_address.as_deref().unwrap_or_default(),
+                    &execution.chain,
+                    &execution.subscription_id,
+                    &execution.status,
+                    &execution.amount_paid,
+                )
+            })
+            .collect()
+    }
+
+    // newest first, keyed on (executed_at, id); `after` is the last row of the previous page
+    pub async fn list_merchant_executions(
+        &self,
+        merchant: &str,
+        filter: &ExecutionFilter,
+        after: Option<(DateTime<Utc>, i64)>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<MerchantExecution>> {
+        let merchant = merchant.to_lowercase();
+
+        if let Some(storage) = self.stub_storage() {
+            let sort_key = |row: &MerchantExecution| {
+                (
+                    row.execution.executed_at.timestamp_micros(),
+                    row.execution.id,
+                )
+            };
+            let after = after.map(|(at, id)| (at.timestamp_micros(), id));
+            let mut results: Vec<MerchantExecution> =
+                Self::stub_merchant_executions(storage, &merchant, filter)
+                    .into_iter()
+                    .filter(|row| after.is_none_or(|after| sort_key(row) < after))
+                    .collect();
+            results.sort_by_key(|row| std::cmp::Reverse(sort_key(row)));
+            return Ok(results
+                .into_iter()
+                .skip(offset.max(0) as usize)
+                .take(limit.max(0) as usize)
+                .collect());
+        }
+
+        let pool = self.require_postgres("list_merchant_executions")?;
+        let (after_at, after_id) = after.unzip();
+
+        let rows = sqlx::query_as::<_, MerchantExecution>(&format!(
+            r#"
+            SELECT e.id, e.subscription_id, e.relayer_address, e.payment_number, e.amount_paid,
+                   e.protocol_fee, e.merchant_amount, e.transaction_hash, e.block_number,
+                   e.gas_used, e.gas_price, e.status, e.error_message, e.executed_at, e.chain,
+                   e.nexus_attestation_id, e.nexus_verified, e.nexus_submitted_at,
+                   COALESCE(e.token_address, s.token_address) AS token_address, e.block_hash,
+                   e.confirmed_at, s.subscriber
+            {}
+                AND ($10::TIMESTAMPTZ IS NULL OR (e.executed_at, e.id) < ($10, $11))
+            ORDER BY e.executed_at DESC, e.id DESC
+            LIMIT $12 OFFSET $13
+            "#,
+            Self::MERCHANT_EXECUTIONS_WHERE
+        ))
+        .bind(&merchant)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(&filter.token_address)
+        .bind(&filter.chain)
+        .bind(&filter.subscription_id)
+        .bind(filter.status.map(|status| status.to_string()))
+        .bind(filter.min_amount.map(|amount| amount.to_string()))
+        .bind(filter.settled)
+        .bind(after_at)
+        .bind(after_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    // (matching executions, wei paid by the successful ones) ignoring pagination
+    pub async fn get_merchant_execution_totals(
+        &self,
+        merchant: &str,
+        filter: &ExecutionFilter,
+    ) -> Result<(u64, U256)> {
+        let merchant = merchant.to_lowercase();
+        let success = ExecutionStatus::Success.to_string();
+        let confirmed = ExecutionStatus::Confirmed.to_string();
+
+        if let Some(storage) = self.stub_storage() {
+            let rows = Self::stub_merchant_executions(storage, &merchant, filter);
+            let revenue = rows
+                .iter()
+                .filter(|row| row.execution.status == success || row.execution.status == confirmed)
+                .filter_map(|row| U256::from_dec_str(&row.execution.amount_paid).ok())
+                .fold(U256::zero(), |total, amount| total.saturating_add(amount));
+            return Ok((rows.len() as u64, revenue));
+        }
+
+        let pool = self.require_postgres("get_merchant_execution_totals")?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT COUNT(*) AS count,
+                   COALESCE(SUM(e.amount_paid::NUMERIC) FILTER (WHERE e.status IN ($10, $11)), 0)::TEXT
+                       AS revenue
+            {}
+            "#,
+            Self::MERCHANT_EXECUTIONS_WHERE
+        ))
+        .bind(&merchant)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(&filter.token_address)
+        .bind(&filter.chain)
+        .bind(&filter.subscription_id)
+        .bind(filter.status.map(|status| status.to_string()))
+        .bind(filter.min_amount.map(|amount| amount.to_string()))
+        .bind(filter.settled)
+        .bind(&success)
+        .bind(&confirmed)
+        .fetch_one(pool)
+        .await?;
+
+        let count: i64 = row.try_get("count")?;
+        let revenue: String = row.try_get("revenue")?;
+        Ok((
+            count.max(0) as u64,
+            U256::from_dec_str(&revenue).unwrap_or(U256::MAX),
+        ))
+    }
+
     pub async fn get_executions_by_subscription(
         &self,
         subscription_id: &str,
@@ -831,7 +1169,9 @@ impl Queries {
                 nexus_attestation_id,
                 nexus_verified,
                 nexus_submitted_at,
-                token_address
+                token_address,
+                block_hash,
+                confirmed_at
             FROM executions
             WHERE subscription_id = $1
             ORDER BY payment_number ASC
@@ -935,8 +1275,15 @@ impl Queries {
         }
 
         let pool = self.require_postgres("cache_intent")?;
-        let record = intent.clone();
+        let id = Self::cache_intent_row(pool, intent).await?;
+        info!("successfully cached intent with id: {}", id);
+        Ok(id)
+    }
 
+    async fn cache_intent_row<'e>(
+        executor: impl PgExecutor<'e>,
+        record: &IntentCache,
+    ) -> Result<i64> {
         // use upsert (ON CONFLICT) to handle duplicate intents
         let row = sqlx::query!(
             r#"
@@ -975,12 +1322,9 @@ impl Queries {
             record.avail_block_number,
             record.avail_extrinsic_index
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
-
-        let id: i64 = row.id;
-        info!("successfully cached intent with id: {}", id);
-        Ok(id)
+        Ok(row.id)
     }
 
     pub async fn get_cached_intent(&self, subscription_id: &str) -> Result<Option<IntentCache>> {
@@ -1462,8 +1806,8 @@ impl Queries {
                 subscription_id, relayer_address, payment_number, amount_paid,
                 protocol_fee, merchant_amount, transaction_hash, block_number,
                 gas_used, gas_price, status, executed_at, chain,
-                nexus_attestation_id, nexus_verified, nexus_submitted_at, token_address
-            ) VALUES ($1, '', $2, $3, $4, '', $5, $6, $7, $8, 'SUCCESS', $9, $10, $11, $12, $13, $14)
+                nexus_attestation_id, nexus_verified, nexus_submitted_at, token_address, block_hash
+            ) VALUES ($1, '', $2, $3, $4, '', $5, $6, $7, $8, 'SUCCESS', $9, $10, $11, $12, $13, $14, $15)
             ",
         )
         .bind(&record.subscription_id)
@@ -1480,6 +1824,7 @@ impl Queries {
         .bind(record.nexus_verified)
         .bind(&record.nexus_submitted_at)
         .bind(record.token_address.as_deref())
+        .bind(record.block_hash.as_deref())
         .execute(pool)
         .await?;
 
@@ -1561,76 +1906,454 @@ impl Queries {
             .await
     }
 
-    // enhanced atomic transaction with better error handling and validation
-    pub async fn record_execution_and_update_subscription(
+    // backs a failing subscription off until next_retry_at
+    pub async fn schedule_subscription_retry(
         &self,
-        execution_record: &ExecutionRecord,
         subscription_id: &str,
-        new_payments_made: i64,
-        next_payment_time: DateTime<Utc>,
         failure_count: i64,
+        chain_error_count: i64,
+        class: FailureClass,
+        error: &str,
+        next_retry_at: DateTime<Utc>,
     ) -> Result<()> {
-        info!(
-            "recording execution and updating subscription in transaction for {}",
-            subscription_id
-        );
+        let error = truncate_error(error);
 
-        // validate inputs to prevent corruption
-        if execution_record.subscription_id != subscription_id {
-            return Err(RelayerError::Validation(
-                "execution record subscription id mismatch".to_string(),
-            ));
+        if let Some(storage) = self.stub_storage() {
+            let mut subscriptions = storage.subscriptions.lock().unwrap();
+            let subscription = subscriptions.get_mut(subscription_id).ok_or_else(|| {
+                RelayerError::NotFound(format!("subscription not found: {}", subscription_id))
+            })?;
+            subscription.failure_count = failure_count;
+            subscription.updated_at = Utc::now();
+
+            let mut retry_states = storage.retry_states.lock().unwrap();
+            let state = retry_states.entry(subscription_id.to_string()).or_default();
+            state.next_retry_at = Some(next_retry_at);
+            state.last_failure_class = Some(class.to_string());
+            state.last_error = Some(error);
+            state.chain_error_count = chain_error_count;
+            return Ok(());
         }
 
-        if new_payments_made < 0 || failure_count < 0 {
-            return Err(RelayerError::Validation(
-                "negative payment count or failure count".to_string(),
-            ));
+        let pool = self.require_postgres("schedule_subscription_retry")?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET failure_count = $2,
+                last_failure_class = $3,
+                last_error = $4,
+                next_retry_at = $5,
+                chain_error_count = $6,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(failure_count)
+        .bind(class.to_string())
+        .bind(&error)
+        .bind(next_retry_at)
+        .bind(chain_error_count)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RelayerError::NotFound(format!(
+                "subscription not found: {}",
+                subscription_id
+            )));
         }
+        Ok(())
+    }
+
+    // parks a subscription that exhausted its retries, keeping the error that put it there
+    pub async fn dead_letter_subscription(
+        &self,
+        subscription_id: &str,
+        failure_count: i64,
+        chain_error_count: i64,
+        class: FailureClass,
+        error: &str,
+    ) -> Result<()> {
+        let error = truncate_error(error);
 
         if let Some(storage) = self.stub_storage() {
             let mut subscriptions = storage.subscriptions.lock().unwrap();
             let subscription = subscriptions.get_mut(subscription_id).ok_or_else(|| {
                 RelayerError::NotFound(format!("subscription not found: {}", subscription_id))
             })?;
+            subscription.failure_count = failure_count;
+            subscription.status = SubscriptionStatus::DeadLetter.to_string();
+            subscription.updated_at = Utc::now();
 
-            if new_payments_made != subscription.executed_payments + 1 {
-                return Err(RelayerError::Validation(
-                    "invalid payment count increment".to_string(),
-                ));
-            }
+            let mut retry_states = storage.retry_states.lock().unwrap();
+            let state = retry_states.entry(subscription_id.to_string()).or_default();
+            state.next_retry_at = None;
+            state.last_failure_class = Some(class.to_string());
+            state.last_error = Some(error);
+            state.dead_lettered_at = Some(Utc::now());
+            state.chain_error_count = chain_error_count;
+            return Ok(());
+        }
 
-            let current_total =
-                U256::from_dec_str(&subscription.total_paid).unwrap_or_else(|_| U256::zero());
-            let payment_amount =
-                U256::from_dec_str(&execution_record.payment_amount).map_err(|_| {
-                    RelayerError::Validation("invalid payment amount format".to_string())
-                })?;
-            let new_total_paid = current_total.checked_add(payment_amount).ok_or_else(|| {
-                RelayerError::Validation("total_paid calculation overflow".to_string())
-            })?;
+        let pool = self.require_postgres("dead_letter_subscription")?;
 
-            subscription.executed_payments = new_payments_made;
-            subscription.total_paid = new_total_paid.to_string();
-            subscription.next_payment_due = next_payment_time;
-            subscription.failure_count = failure_count;
-            subscription.updated_at = Utc::now();
+        let result = sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET status = 'DEAD_LETTER',
+                failure_count = $2,
+                last_failure_class = $3,
+                last_error = $4,
+                next_retry_at = NULL,
+                dead_lettered_at = NOW(),
+                chain_error_count = $5,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(failure_count)
+        .bind(class.to_string())
+        .bind(&error)
+        .bind(chain_error_count)
+        .execute(pool)
+        .await?;
 
-            drop(subscriptions);
+        if result.rows_affected() == 0 {
+            return Err(RelayerError::NotFound(format!(
+                "subscription not found: {}",
+                subscription_id
+            )));
+        }
+        Ok(())
+    }
 
-            // store execution record for observability
-            let mut record_clone = execution_record.clone();
-            if record_clone.id == 0 {
-                record_clone.id = storage.next_execution_id();
-            }
-            storage
-                .execution_records
+    pub async fn get_subscription_retry_state(
+        &self,
+        subscription_id: &str,
+    ) -> Result<SubscriptionRetryState> {
+        if let Some(storage) = self.stub_storage() {
+            return Ok(storage
+                .retry_states
                 .lock()
                 .unwrap()
-                .push(record_clone.clone());
+                .get(subscription_id)
+                .cloned()
+                .unwrap_or_default());
+        }
 
-            // materialise execution entry
-            let execution_entry = Execution {
+        let pool = self.require_postgres("get_subscription_retry_state")?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT next_retry_at, last_failure_class, last_error, dead_lettered_at,
+                relayer_paused_at, chain_error_count
+            FROM subscriptions
+            WHERE id = $1
+            "#,
+        )
+        .bind(subscription_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row
+            .map(|row| SubscriptionRetryState {
+                next_retry_at: row.get("next_retry_at"),
+                last_failure_class: row.get("last_failure_class"),
+                last_error: row.get("last_error"),
+                dead_lettered_at: row.get("dead_lettered_at"),
+                relayer_paused_at: row.get("relayer_paused_at"),
+                chain_error_count: row.get("chain_error_count"),
+            })
+            .unwrap_or_default())
+    }
+
+    // most recently dead-lettered first
+    pub async fn get_dead_letter_subscriptions(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<DeadLetterSubscription>> {
+        if let Some(storage) = self.stub_storage() {
+            let subscriptions = storage.subscriptions.lock().unwrap();
+            let retry_states = storage.retry_states.lock().unwrap();
+            let mut results: Vec<DeadLetterSubscription> = subscriptions
+                .values()
+                .filter(|sub| sub.status == SubscriptionStatus::DeadLetter.to_string())
+                .map(|sub| {
+                    let state = retry_states.get(&sub.id).cloned().unwrap_or_default();
+                    DeadLetterSubscription {
+                        id: sub.id.clone(),
+                        subscriber: sub.subscriber.clone(),
+                        merchant: sub.merchant.clone(),
+                        chain: sub.chain.clone(),
+                        token_address: sub.token_address.clone(),
+                        amount: sub.amount.clone(),
+                        executed_payments: sub.executed_payments,
+                        failure_count: sub.failure_count,
+                        last_failure_class: state.last_failure_class,
+                        last_error: state.last_error,
+                        dead_lettered_at: state.dead_lettered_at,
+                    }
+                })
+                .collect();
+            results.sort_by(|a, b| {
+                b.dead_lettered_at
+                    .cmp(&a.dead_lettered_at)
+                    .then_with(|| a.id.cmp(&b.id))
+            });
+            return Ok(results
+                .into_iter()
+                .skip(offset.max(0) as usize)
+                .take(limit.max(0) as usize)
+                .collect());
+        }
+
+        let pool = self.require_postgres("get_dead_letter_subscriptions")?;
+
+        let subscriptions = sqlx::query_as::<_, DeadLetterSubscription>(
+            r#"
+            SELECT id, subscriber, merchant, chain, token_address, amount,
+                   executed_payments, failure_count, last_failure_class, last_error,
+                   dead_lettered_at
+            FROM subscriptions
+            WHERE status = 'DEAD_LETTER'
+            ORDER BY dead_lettered_at DESC NULLS LAST, id ASC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(subscriptions)
+    }
+
+    // puts a dead-lettered subscription back in the schedule with a fresh failure budget;
+    // returns false when the subscription isn't dead-lettered
+    pub async fn requeue_dead_letter_subscription(&self, subscription_id: &str) -> Result<bool> {
+        if let Some(storage) = self.stub_storage() {
+            let mut subscriptions = storage.subscriptions.lock().unwrap();
+            let Some(subscription) = subscriptions
+                .get_mut(subscription_id)
+                .filter(|sub| sub.status == SubscriptionStatus::DeadLetter.to_string())
+            else {
+                return Ok(false);
+            };
+            subscription.status = SubscriptionStatus::Active.to_string();
+            subscription.failure_count = 0;
+            subscription.updated_at = Utc::now();
+
+            if let Some(state) = storage
+                .retry_states
+                .lock()
+                .unwrap()
+                .get_mut(subscription_id)
+            {
+                state.next_retry_at = None;
+                state.dead_lettered_at = None;
+                state.chain_error_count = 0;
+            }
+            return Ok(true);
+        }
+
+        let pool = self.require_postgres("requeue_dead_letter_subscription")?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET status = 'ACTIVE',
+                failure_count = 0,
+                chain_error_count = 0,
+                next_retry_at = NULL,
+                dead_lettered_at = NULL,
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'DEAD_LETTER'
+            "#,
+        )
+        .bind(subscription_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // clears the failure count and any pending retry backoff; a dead-lettered subscription
+    // stays dead-lettered until requeued. returns false when the subscription doesn't exist
+    pub async fn reset_subscription_failures(&self, subscription_id: &str) -> Result<bool> {
+        if let Some(storage) = self.stub_storage() {
+            let mut subscriptions = storage.subscriptions.lock().unwrap();
+            let Some(subscription) = subscriptions.get_mut(subscription_id) else {
+                return Ok(false);
+            };
+            subscription.failure_count = 0;
+            subscription.updated_at = Utc::now();
+
+            if let Some(state) = storage
+                .retry_states
+                .lock()
+                .unwrap()
+                .get_mut(subscription_id)
+            {
+                state.next_retry_at = None;
+                state.last_failure_class = None;
+                state.last_error = None;
+                state.chain_error_count = 0;
+            }
+            return Ok(true);
+        }
+
+        let pool = self.require_postgres("reset_subscription_failures")?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET failure_count = 0,
+                chain_error_count = 0,
+                next_retry_at = NULL,
+                last_failure_class = NULL,
+                last_error = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(subscription_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // holds a subscription back from the scheduler, or releases it; on-chain state is untouched.
+    // returns false when the subscription doesn't exist
+    pub async fn set_subscription_relayer_pause(
+        &self,
+        subscription_id: &str,
+        paused: bool,
+    ) -> Result<bool> {
+        if let Some(storage) = self.stub_storage() {
+            let mut subscriptions = storage.subscriptions.lock().unwrap();
+            let Some(subscription) = subscriptions.get_mut(subscription_id) else {
+                return Ok(false);
+            };
+            subscription.updated_at = Utc::now();
+
+            let mut retry_states = storage.retry_states.lock().unwrap();
+            let state = retry_states.entry(subscription_id.to_string()).or_default();
+            state.relayer_paused_at = match (paused, state.relayer_paused_at) {
+                (true, Some(paused_at)) => Some(paused_at),
+                (true, None) => Some(Utc::now()),
+                (false, _) => None,
+            };
+            return Ok(true);
+        }
+
+        let pool = self.require_postgres("set_subscription_relayer_pause")?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET relayer_paused_at = CASE
+                    WHEN $2 THEN COALESCE(relayer_paused_at, NOW())
+                    ELSE NULL
+                END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(paused)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // enhanced atomic transaction with better error handling and validation
+    pub async fn record_execution_and_update_subscription(
+        &self,
+        execution_record: &ExecutionRecord,
+        subscription_id: &str,
+        new_payments_made: i64,
+        next_payment_time: DateTime<Utc>,
+        failure_count: i64,
+    ) -> Result<()> {
+        info!(
+            "recording execution and updating subscription in transaction for {}",
+            subscription_id
+        );
+
+        // validate inputs to prevent corruption
+        if execution_record.subscription_id != subscription_id {
+            return Err(RelayerError::Validation(
+                "execution record subscription id mismatch".to_string(),
+            ));
+        }
+
+        if new_payments_made < 0 || failure_count < 0 {
+            return Err(RelayerError::Validation(
+                "negative payment count or failure count".to_string(),
+            ));
+        }
+
+        if let Some(storage) = self.stub_storage() {
+            let mut subscriptions = storage.subscriptions.lock().unwrap();
+            let subscription = subscriptions.get_mut(subscription_id).ok_or_else(|| {
+                RelayerError::NotFound(format!("subscription not found: {}", subscription_id))
+            })?;
+
+            if new_payments_made != subscription.executed_payments + 1 {
+                return Err(RelayerError::Validation(
+                    "invalid payment count increment".to_string(),
+                ));
+            }
+
+            let current_total =
+                U256::from_dec_str(&subscription.total_paid).unwrap_or_else(|_| U256::zero());
+            let payment_amount =
+                U256::from_dec_str(&execution_record.payment_amount).map_err(|_| {
+                    RelayerError::Validation("invalid payment amount format".to_string())
+                })?;
+            let new_total_paid = current_total.checked_add(payment_amount).ok_or_else(|| {
+                RelayerError::Validation("total_paid calculation overflow".to_string())
+            })?;
+
+            subscription.executed_payments = new_payments_made;
+            subscription.total_paid = new_total_paid.to_string();
+            subscription.next_payment_due = next_payment_time;
+            subscription.failure_count = failure_count;
+            subscription.updated_at = Utc::now();
+
+            drop(subscriptions);
+            // a successful payment clears the retry state, but not an operator's pause
+            if let Some(state) = storage
+                .retry_states
+                .lock()
+                .unwrap()
+                .get_mut(subscription_id)
+            {
+                *state = SubscriptionRetryState {
+                    relayer_paused_at: state.relayer_paused_at,
+                    ..SubscriptionRetryState::default()
+                };
+            }
+
+            // store execution record for observability
+            let mut record_clone = execution_record.clone();
+            if record_clone.id == 0 {
+                record_clone.id = storage.next_execution_id();
+            }
+            storage
+                .execution_records
+                .lock()
+                .unwrap()
+                .push(record_clone.clone());
+
+            // materialise execution entry
+            let execution_entry = Execution {
                 id: record_clone.id,
                 subscription_id: subscription_id.to_string(),
                 relayer_address: String::new(),
@@ -1650,6 +2373,8 @@ impl Queries {
                 nexus_verified: record_clone.nexus_verified,
                 nexus_submitted_at: record_clone.nexus_submitted_at,
                 token_address: record_clone.token_address.clone(),
+                block_hash: record_clone.block_hash.clone(),
+                confirmed_at: None,
             };
             storage.executions.lock().unwrap().push(execution_entry);
 
@@ -1718,8 +2443,8 @@ impl Queries {
                 subscription_id, relayer_address, payment_number, amount_paid,
                 protocol_fee, merchant_amount, transaction_hash, block_number,
                 gas_used, gas_price, status, executed_at, chain,
-                nexus_attestation_id, nexus_verified, nexus_submitted_at, token_address
-            ) VALUES ($1, '', $2, $3, $4, '', $5, $6, $7, $8, 'SUCCESS', $9, $10, $11, $12, $13, $14)
+                nexus_attestation_id, nexus_verified, nexus_submitted_at, token_address, block_hash
+            ) VALUES ($1, '', $2, $3, $4, '', $5, $6, $7, $8, 'SUCCESS', $9, $10, $11, $12, $13, $14, $15)
             ",
         )
         .bind(&execution_record.subscription_id)
@@ -1736,42 +2461,43 @@ impl Queries {
         .bind(execution_record.nexus_verified)
         .bind(&execution_record.nexus_submitted_at)
         .bind(&current_subscription.token_address)
+        .bind(execution_record.block_hash.as_deref())
         .execute(&mut *tx)
         .await?;
 
         // update subscription with total_paid calculation
-        let new_total_paid: U256 = current_subscription
-            .total_paid
-            .parse::<U256>()
+        let new_total_paid: U256 = U256::from_dec_str(&current_subscription.total_paid)
             .map_err(|_| RelayerError::Validation("invalid current total_paid format".to_string()))?
             .checked_add(
-                execution_record
-                    .payment_amount
-                    .parse::<U256>()
-                    .map_err(|_| {
-                        RelayerError::Validation("invalid payment amount format".to_string())
-                    })?,
+                U256::from_dec_str(&execution_record.payment_amount).map_err(|_| {
+                    RelayerError::Validation("invalid payment amount format".to_string())
+                })?,
             )
             .ok_or_else(|| {
                 RelayerError::Validation("total_paid calculation overflow".to_string())
             })?;
 
-        let result = sqlx::query!(
+        // a successful payment also ends any retry backoff
+        let result = sqlx::query(
             r#"
-            UPDATE subscriptions 
+            UPDATE subscriptions
             SET executed_payments = $1,
                 total_paid = $2,
                 next_payment_due = $3,
                 failure_count = $4,
+                chain_error_count = 0,
+                next_retry_at = NULL,
+                last_failure_class = NULL,
+                last_error = NULL,
                 updated_at = NOW()
             WHERE id = $5
             "#,
-            new_payments_made,
-            new_total_paid.to_string(),
-            next_payment_time,
-            failure_count,
-            subscription_id
         )
+        .bind(new_payments_made)
+        .bind(new_total_paid.to_string())
+        .bind(next_payment_time)
+        .bind(failure_count)
+        .bind(subscription_id)
         .execute(&mut *tx)
         .await?;
 
@@ -1912,6 +2638,7 @@ impl Queries {
         merchant_amount: &str,
         transaction_hash: &str,
         block_number: i64,
+        block_hash: Option<&str>,
         executed_at: DateTime<Utc>,
         chain: &str,
     ) -> Result<bool> {
@@ -1933,6 +2660,7 @@ impl Queries {
                 nexus_verified: false,
                 nexus_submitted_at: None,
                 token_address: None,
+                block_hash: block_hash.map(str::to_string),
             };
             let existing = records
                 .iter()
@@ -1965,9 +2693,10 @@ impl Queries {
                 nexus_attestation_id,
                 nexus_verified,
                 nexus_submitted_at,
-                token_address
+                token_address,
+                block_hash
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, '0', '0', 'SUCCESS', $9, $10, NULL, false, NULL, NULL)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, '0', '0', 'SUCCESS', $9, $10, NULL, false, NULL, NULL, $11)
             ON CONFLICT (transaction_hash) DO NOTHING
             "#,
         )
@@ -1981,9 +2710,2050 @@ impl Queries {
         .bind(block_number)
         .bind(executed_at)
         .bind(chain)
+        .bind(block_hash)
         .execute(pool)
         .await?;
 
         Ok(result.rows_affected() > 0)
     }
+
+    // executions mined but not yet past their chain's confirmation depth
+    pub async fn get_unconfirmed_executions(
+        &self,
+        chain: &str,
+        limit: i64,
+    ) -> Result<Vec<Execution>> {
+        let success = ExecutionStatus::Success.to_string();
+
+        if let Some(storage) = self.stub_storage() {
+            let executions = storage.executions.lock().unwrap();
+            let mut unconfirmed: Vec<Execution> = executions
+                .iter()
+                .filter(|execution| execution.chain == chain && execution.status == success)
+                .cloned()
+                .collect();
+            unconfirmed.sort_by_key(|execution| (execution.block_number, execution.id));
+            unconfirmed.truncate(limit.max(0) as usize);
+            return Ok(unconfirmed);
+        }
+
+        let pool = self.require_postgres("get_unconfirmed_executions")?;
+
+        let executions = sqlx::query_as::<_, Execution>(
+            "
+            SELECT
+                id,
+                subscription_id,
+                relayer_address,
+                payment_number,
+                amount_paid,
+                protocol_fee,
+                merchant_amount,
+                transaction_hash,
+                block_number,
+                gas_used,
+                gas_price,
+                status,
+                error_message,
+                executed_at,
+                chain,
+                nexus_attestation_id,
+                nexus_verified,
+                nexus_submitted_at,
+                token_address,
+                block_hash,
+                confirmed_at
+            FROM executions
+            WHERE chain = $1 AND status = $2
+            ORDER BY block_number ASC, id ASC
+            LIMIT $3
+            ",
+        )
+        .bind(chain)
+        .bind(&success)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(executions)
+    }
+
+    // records the block an unconfirmed execution currently sits in; a changed hash means it was re-mined
+    pub async fn update_execution_block(
+        &self,
+        execution_id: i64,
+        block_number: i64,
+        block_hash: &str,
+    ) -> Result<()> {
+        if let Some(storage) = self.stub_storage() {
+            let mut executions = storage.executions.lock().unwrap();
+            let execution = executions
+                .iter_mut()
+                .find(|execution| execution.id == execution_id)
+                .ok_or_else(|| {
+                    RelayerError::NotFound(format!("execution not found: {}", execution_id))
+                })?;
+            execution.block_number = block_number;
+            execution.block_hash = Some(block_hash.to_string());
+            return Ok(());
+        }
+
+        let pool = self.require_postgres("update_execution_block")?;
+
+        let result =
+            sqlx::query("UPDATE executions SET block_number = $2, block_hash = $3 WHERE id = $1")
+                .bind(execution_id)
+                .bind(block_number)
+                .bind(block_hash)
+                .execute(pool)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RelayerError::NotFound(format!(
+                "execution not found: {}",
+                execution_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn confirm_execution(
+        &self,
+        execution_id: i64,
+        block_number: i64,
+        block_hash: &str,
+    ) -> Result<bool> {
+        info!(
+            "confirming execution {} in block {} ({})",
+            execution_id, block_number, block_hash
+        );
+        let success = ExecutionStatus::Success.to_string();
+        let confirmed = ExecutionStatus::Confirmed.to_string();
+
+        if let Some(storage) = self.stub_storage() {
+            let mut executions = storage.executions.lock().unwrap();
+            let Some(execution) = executions
+                .iter_mut()
+                .find(|execution| execution.id == execution_id && execution.status == success)
+            else {
+                return Ok(false);
+            };
+            execution.status = confirmed;
+            execution.block_number = block_number;
+            execution.block_hash = Some(block_hash.to_string());
+            execution.confirmed_at = Some(Utc::now());
+            return Ok(true);
+        }
+
+        let pool = self.require_postgres("confirm_execution")?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE executions
+            SET status = $4, block_number = $2, block_hash = $3, confirmed_at = NOW()
+            WHERE id = $1 AND status = $5
+            "#,
+        )
+        .bind(execution_id)
+        .bind(block_number)
+        .bind(block_hash)
+        .bind(&confirmed)
+        .bind(&success)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // marks a reorged-out execution ORPHANED and rolls the payment back off its subscription
+    // so the scheduler picks it up again
+    pub async fn orphan_execution(&self, execution_id: i64, reason: &str) -> Result<bool> {
+        warn!("orphaning execution {}: {}", execution_id, reason);
+        let success = ExecutionStatus::Success.to_string();
+        let orphaned = ExecutionStatus::Orphaned.to_string();
+
+        if let Some(storage) = self.stub_storage() {
+            let mut executions = storage.executions.lock().unwrap();
+            let Some(execution) = executions
+                .iter_mut()
+                .find(|execution| execution.id == execution_id && execution.status == success)
+            else {
+                return Ok(false);
+            };
+            execution.status = orphaned;
+            execution.error_message = Some(reason.to_string());
+            let subscription_id = execution.subscription_id.clone();
+            let amount_paid = execution.amount_paid.clone();
+            drop(executions);
+
+            let mut subscriptions = storage.subscriptions.lock().unwrap();
+            if let Some(subscription) = subscriptions.get_mut(&subscription_id) {
+                let (executed_payments, total_paid) = rolled_back_totals(
+                    subscription.executed_payments,
+                    &subscription.total_paid,
+                    &amount_paid,
+                );
+                subscription.executed_payments = executed_payments;
+                subscription.total_paid = total_paid;
+                subscription.next_payment_due -=
+                    chrono::Duration::seconds(subscription.interval_seconds);
+                subscription.updated_at = Utc::now();
+            }
+            return Ok(true);
+        }
+
+        let pool = self.require_postgres("orphan_execution")?;
+
+        let mut tx = pool.begin().await.map_err(|e| {
+            RelayerError::DatabaseError(format!("failed to begin transaction: {}", e))
+        })?;
+
+        let orphaned_row = sqlx::query_as::<_, (String, String)>(
+            r#"
+            UPDATE executions
+            SET status = $2, error_message = $3
+            WHERE id = $1 AND status = $4
+            RETURNING subscription_id, amount_paid
+            "#,
+        )
+        .bind(execution_id)
+        .bind(&orphaned)
+        .bind(reason)
+        .bind(&success)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some((subscription_id, amount_paid)) = orphaned_row else {
+            return Ok(false);
+        };
+
+        let current = sqlx::query_as::<_, (i64, String)>(
+            "SELECT executed_payments, total_paid FROM subscriptions WHERE id = $1 FOR UPDATE",
+        )
+        .bind(&subscription_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some((executed_payments, total_paid)) = current {
+            let (executed_payments, total_paid) =
+                rolled_back_totals(executed_payments, &total_paid, &amount_paid);
+
+            sqlx::query(
+                r#"
+                UPDATE subscriptions
+                SET executed_payments = $2,
+                    total_paid = $3,
+                    next_payment_due = next_payment_due - make_interval(secs => interval_seconds),
+                    updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(&subscription_id)
+            .bind(executed_payments)
+            .bind(total_paid)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            RelayerError::DatabaseError(format!("failed to commit transaction: {}", e))
+        })?;
+
+        info!(
+            "rolled back orphaned payment for subscription {}",
+            subscription_id
+        );
+        Ok(true)
+    }
+
+    // removes a subscription whose createSubscription never took effect on chain, with its
+    // cached intent, outbox row and pending operations, so the subscriber can sign again with
+    // the same nonce. only PENDING rows are touched; returns false when nothing was removed
+    pub async fn discard_unrelayed_subscription(&self, subscription_id: &str) -> Result<bool> {
+        if let Some(storage) = self.stub_storage() {
+            let mut subscriptions = storage.subscriptions.lock().unwrap();
+            let pending = subscriptions
+                .get(subscription_id)
+                .is_some_and(|sub| sub.status == SubscriptionStatus::Pending.to_string());
+            if !pending {
+                return Ok(false);
+            }
+            subscriptions.remove(subscription_id);
+            storage
+                .intent_cache
+                .lock()
+                .unwrap()
+                .retain(|intent| intent.subscription_id != subscription_id);
+            storage
+                .avail_submissions
+                .lock()
+                .unwrap()
+                .retain(|submission| submission.subscription_id != subscription_id);
+            storage
+                .pending_operations
+                .lock()
+                .unwrap()
+                .retain(|operation| operation.subscription_id != subscription_id);
+            return Ok(true);
+        }
+
+        let pool = self.require_postgres("discard_unrelayed_subscription")?;
+        let mut tx = pool.begin().await.map_err(|e| {
+            RelayerError::DatabaseError(format!("failed to begin transaction: {}", e))
+        })?;
+
+        // the subscription row is locked first so it can't leave PENDING while its operations go
+        let pending: Option<String> = sqlx::query_scalar(
+            "SELECT id FROM subscriptions WHERE id = $1 AND status = $2 FOR UPDATE",
+        )
+        .bind(subscription_id)
+        .bind(SubscriptionStatus::Pending.to_string())
+        .fetch_optional(&mut *tx)
+        .await?;
+        if pending.is_none() {
+            return Ok(false);
+        }
+        sqlx::query("DELETE FROM pending_operations WHERE subscription_id = $1")
+            .bind(subscription_id)
+            .execute(&mut *tx)
+            .await?;
+        // avail_submissions goes with the subscription through ON DELETE CASCADE
+        let deleted = sqlx::query("DELETE FROM subscriptions WHERE id = $1 AND status = $2")
+            .bind(subscription_id)
+            .bind(SubscriptionStatus::Pending.to_string())
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if deleted == 0 {
+            return Ok(false);
+        }
+        sqlx::query("DELETE FROM intent_cache WHERE subscription_id = $1")
+            .bind(subscription_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await.map_err(|e| {
+            RelayerError::DatabaseError(format!("failed to commit transaction: {}", e))
+        })?;
+        Ok(true)
+    }
+
+    // pending on-chain operation queries
+    pub async fn insert_pending_operation(
+        &self,
+        subscription_id: &str,
+        operation: PendingOperationKind,
+        chain: &str,
+        transaction_hash: &str,
+    ) -> Result<i64> {
+        info!(
+            "recording pending {} for subscription {} ({})",
+            operation, subscription_id, transaction_hash
+        );
+
+        if let Some(storage) = self.stub_storage() {
+            let now = Utc::now();
+            let id = storage.next_pending_operation_id();
+            storage
+                .pending_operations
+                .lock()
+                .unwrap()
+                .push(PendingOperation {
+                    id,
+                    subscription_id: subscription_id.to_string(),
+                    operation: operation.to_string(),
+                    chain: chain.to_string(),
+                    transaction_hash: transaction_hash.to_string(),
+                    status: PendingOperationStatus::Pending.to_string(),
+                    error_message: None,
+                    created_at: now,
+                    updated_at: now,
+                });
+            return Ok(id);
+        }
+
+        let pool = self.require_postgres("insert_pending_operation")?;
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO pending_operations (subscription_id, operation, chain, transaction_hash, status)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(operation.to_string())
+        .bind(chain)
+        .bind(transaction_hash)
+        .bind(PendingOperationStatus::Pending.to_string())
+        .fetch_one(pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    pub async fn get_open_pending_operations(&self, limit: i64) -> Result<Vec<PendingOperation>> {
+        let pending = PendingOperationStatus::Pending.to_string();
+
+        if let Some(storage) = self.stub_storage() {
+            let operations = storage.pending_operations.lock().unwrap();
+            return Ok(operations
+                .iter()
+                .filter(|operation| operation.status == pending)
+                .take(limit.max(0) as usize)
+                .cloned()
+                .collect());
+        }
+
+        let pool = self.require_postgres("get_open_pending_operations")?;
+
+        let operations = sqlx::query_as::<_, PendingOperation>(
+            r#"
+            SELECT id, subscription_id, operation, chain, transaction_hash, status,
+                   error_message, created_at, updated_at
+            FROM pending_operations
+            WHERE status = $1
+            ORDER BY created_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(pending)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(operations)
+    }
+
+    pub async fn get_pending_operations_for_subscription(
+        &self,
+        subscription_id: &str,
+    ) -> Result<Vec<PendingOperation>> {
+        if let Some(storage) = self.stub_storage() {
+            let operations = storage.pending_operations.lock().unwrap();
+            return Ok(operations
+                .iter()
+                .filter(|operation| operation.subscription_id == subscription_id)
+                .cloned()
+                .collect());
+        }
+
+        let pool = self.require_postgres("get_pending_operations_for_subscription")?;
+
+        let operations = sqlx::query_as::<_, PendingOperation>(
+            r#"
+            SELECT id, subscription_id, operation, chain, transaction_hash, status,
+                   error_message, created_at, updated_at
+            FROM pending_operations
+            WHERE subscription_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(subscription_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(operations)
+    }
+
+    pub async fn resolve_pending_operation(
+        &self,
+        id: i64,
+        status: PendingOperationStatus,
+        error_message: Option<&str>,
+    ) -> Result<()> {
+        info!("resolving pending operation {} as {}", id, status);
+
+        if let Some(storage) = self.stub_storage() {
+            let mut operations = storage.pending_operations.lock().unwrap();
+            let operation = operations
+                .iter_mut()
+                .find(|operation| operation.id == id)
+                .ok_or_else(|| {
+                    RelayerError::NotFound(format!("pending operation not found: {}", id))
+                })?;
+            operation.status = status.to_string();
+            operation.error_message = error_message.map(str::to_string);
+            operation.updated_at = Utc::now();
+            return Ok(());
+        }
+
+        let pool = self.require_postgres("resolve_pending_operation")?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE pending_operations
+            SET status = $2, error_message = $3, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status.to_string())
+        .bind(error_message)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RelayerError::NotFound(format!(
+                "pending operation not found: {}",
+                id
+            )));
+        }
+
+        Ok(())
+    }
+
+    // outbound transaction queries
+    pub async fn insert_outbound_transaction(&self, tx: &OutboundTransaction) -> Result<i64> {
+        info!(
+            "persisting outbound {} tx {} on {} with nonce {}",
+            tx.purpose, tx.transaction_hash, tx.chain, tx.nonce
+        );
+
+        if let Some(storage) = self.stub_storage() {
+            let mut transactions = storage.outbound_transactions.lock().unwrap();
+            let failed = OutboundTxStatus::Failed.to_string();
+            if transactions.iter().any(|existing| {
+                existing.chain == tx.chain
+                    && existing.from_address == tx.from_address
+                    && existing.nonce == tx.nonce
+                    && existing.status != failed
+            }) {
+                return Err(RelayerError::Duplicate(format!(
+                    "nonce {} already in use on {}",
+                    tx.nonce, tx.chain
+                )));
+            }
+            let mut record = tx.clone();
+            record.id = storage.next_outbound_transaction_id();
+            transactions.push(record.clone());
+            return Ok(record.id);
+        }
+
+        let pool = self.require_postgres("insert_outbound_transaction")?;
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO outbound_transactions (
+                chain, from_address, to_address, nonce, calldata, value, gas_limit,
+                max_fee_per_gas, max_priority_fee_per_gas, transaction_hash, replaced_hashes,
+                attempts, status, purpose, reference
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            RETURNING id
+            "#,
+        )
+        .bind(&tx.chain)
+        .bind(&tx.from_address)
+        .bind(&tx.to_address)
+        .bind(tx.nonce)
+        .bind(&tx.calldata)
+        .bind(&tx.value)
+        .bind(&tx.gas_limit)
+        .bind(&tx.max_fee_per_gas)
+        .bind(&tx.max_priority_fee_per_gas)
+        .bind(&tx.transaction_hash)
+        .bind(&tx.replaced_hashes)
+        .bind(tx.attempts)
+        .bind(&tx.status)
+        .bind(&tx.purpose)
+        .bind(&tx.reference)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    // moves the current hash into replaced_hashes and records the bumped attempt
+    pub async fn record_outbound_replacement(
+        &self,
+        id: i64,
+        transaction_hash: &str,
+        max_fee_per_gas: &str,
+        max_priority_fee_per_gas: &str,
+    ) -> Result<()> {
+        info!(
+            "outbound transaction {} replaced by {} (max fee {})",
+            id, transaction_hash, max_fee_per_gas
+        );
+
+        if let Some(storage) = self.stub_storage() {
+            let mut transactions = storage.outbound_transactions.lock().unwrap();
+            let tx = transactions
+                .iter_mut()
+                .find(|tx| tx.id == id)
+                .ok_or_else(|| {
+                    RelayerError::NotFound(format!("outbound transaction not found: {}", id))
+                })?;
+            let previous =
+                std::mem::replace(&mut tx.transaction_hash, transaction_hash.to_string());
+            tx.replaced_hashes.push(previous);
+            tx.max_fee_per_gas = max_fee_per_gas.to_string();
+            tx.max_priority_fee_per_gas = max_priority_fee_per_gas.to_string();
+            tx.attempts += 1;
+            tx.updated_at = Utc::now();
+            return Ok(());
+        }
+
+        let pool = self.require_postgres("record_outbound_replacement")?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE outbound_transactions
+            SET replaced_hashes = array_append(replaced_hashes, transaction_hash),
+                transaction_hash = $2,
+                max_fee_per_gas = $3,
+                max_priority_fee_per_gas = $4,
+                attempts = attempts + 1,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(transaction_hash)
+        .bind(max_fee_per_gas)
+        .bind(max_priority_fee_per_gas)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RelayerError::NotFound(format!(
+                "outbound transaction not found: {}",
+                id
+            )));
+        }
+
+        Ok(())
+    }
+
+    // final state; transaction_hash is set to whichever attempt was actually mined
+    pub async fn resolve_outbound_transaction(
+        &self,
+        id: i64,
+        status: OutboundTxStatus,
+        mined_hash: Option<&str>,
+        block_number: Option<i64>,
+        error_message: Option<&str>,
+    ) -> Result<()> {
+        info!("resolving outbound transaction {} as {}", id, status);
+
+        if let Some(storage) = self.stub_storage() {
+            let mut transactions = storage.outbound_transactions.lock().unwrap();
+            let tx = transactions
+                .iter_mut()
+                .find(|tx| tx.id == id)
+                .ok_or_else(|| {
+                    RelayerError::NotFound(format!("outbound transaction not found: {}", id))
+                })?;
+            if let Some(hash) = mined_hash {
+                tx.transaction_hash = hash.to_string();
+            }
+            tx.status = status.to_string();
+            tx.block_number = block_number;
+            tx.error_message = error_message.map(str::to_string);
+            tx.updated_at = Utc::now();
+            return Ok(());
+        }
+
+        let pool = self.require_postgres("resolve_outbound_transaction")?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE outbound_transactions
+            SET status = $2,
+                transaction_hash = COALESCE($3, transaction_hash),
+                block_number = $4,
+                error_message = $5,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status.to_string())
+        .bind(mined_hash)
+        .bind(block_number)
+        .bind(error_message)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RelayerError::NotFound(format!(
+                "outbound transaction not found: {}",
+                id
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_pending_outbound_transactions(
+        &self,
+        chain: &str,
+        from_address: &str,
+    ) -> Result<Vec<OutboundTransaction>> {
+        let pending = OutboundTxStatus::Pending.to_string();
+
+        if let Some(storage) = self.stub_storage() {
+            let transactions = storage.outbound_transactions.lock().unwrap();
+            let mut result: Vec<_> = transactions
+                .iter()
+                .filter(|tx| {
+                    tx.chain == chain && tx.from_address == from_address && tx.status == pending
+                })
+                .cloned()
+                .collect();
+            result.sort_by_key(|tx| tx.nonce);
+            return Ok(result);
+        }
+
+        let pool = self.require_postgres("get_pending_outbound_transactions")?;
+
+        let transactions = sqlx::query_as::<_, OutboundTransaction>(
+            r#"
+            SELECT id, chain, from_address, to_address, nonce, calldata, value, gas_limit,
+                   max_fee_per_gas, max_priority_fee_per_gas, transaction_hash, replaced_hashes,
+                   attempts, status, purpose, reference, block_number, error_message,
+                   created_at, updated_at
+            FROM outbound_transactions
+            WHERE chain = $1 AND from_address = $2 AND status = $3
+            ORDER BY nonce ASC
+            "#,
+        )
+        .bind(chain)
+        .bind(from_address)
+        .bind(pending)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(transactions)
+    }
+
+    // highest nonce still occupied by a broadcast transaction, if any
+    pub async fn get_max_outbound_nonce(
+        &self,
+        chain: &str,
+        from_address: &str,
+    ) -> Result<Option<i64>> {
+        let failed = OutboundTxStatus::Failed.to_string();
+
+        if let Some(storage) = self.stub_storage() {
+            let transactions = storage.outbound_transactions.lock().unwrap();
+            return Ok(transactions
+                .iter()
+                .filter(|tx| {
+                    tx.chain == chain && tx.from_address == from_address && tx.status != failed
+                })
+                .map(|tx| tx.nonce)
+                .max());
+        }
+
+        let pool = self.require_postgres("get_max_outbound_nonce")?;
+
+        let nonce = sqlx::query_scalar::<_, Option<i64>>(
+            r#"
+            SELECT MAX(nonce)
+            FROM outbound_transactions
+            WHERE chain = $1 AND from_address = $2 AND status <> $3
+            "#,
+        )
+        .bind(chain)
+        .bind(from_address)
+        .bind(failed)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(nonce)
+    }
+
+    // matches the current hash or any earlier attempt replaced by a fee bump
+    pub async fn get_outbound_transaction_by_hash(
+        &self,
+        transaction_hash: &str,
+    ) -> Result<Option<OutboundTransaction>> {
+        if let Some(storage) = self.stub_storage() {
+            let transactions = storage.outbound_transactions.lock().unwrap();
+            return Ok(transactions
+                .iter()
+                .find(|tx| {
+                    tx.transaction_hash == transaction_hash
+                        || tx
+                            .replaced_hashes
+                            .iter()
+                            .any(|hash| hash == transaction_hash)
+                })
+                .cloned());
+        }
+
+        let pool = self.require_postgres("get_outbound_transaction_by_hash")?;
+
+        let transaction = sqlx::query_as::<_, OutboundTransaction>(
+            r#"
+            SELECT id, chain, from_address, to_address, nonce, calldata, value, gas_limit,
+                   max_fee_per_gas, max_priority_fee_per_gas, transaction_hash, replaced_hashes,
+                   attempts, status, purpose, reference, block_number, error_message,
+                   created_at, updated_at
+            FROM outbound_transactions
+            WHERE transaction_hash = $1 OR $1 = ANY(replaced_hashes)
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(transaction_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(transaction)
+    }
+
+    pub async fn create_merchant_webhook(
+        &self,
+        merchant: &str,
+        url: &str,
+        secret: &str,
+        event_types: &[String],
+    ) -> Result<MerchantWebhook> {
+        let merchant = merchant.to_lowercase();
+
+        if let Some(storage) = self.stub_storage() {
+            let now = Utc::now();
+            let webhook = MerchantWebhook {
+                id: storage.next_webhook_id(),
+                merchant,
+                url: url.to_string(),
+                secret: secret.to_string(),
+                event_types: event_types.to_vec(),
+                active: true,
+                created_at: now,
+                updated_at: now,
+            };
+            storage
+                .merchant_webhooks
+                .lock()
+                .unwrap()
+                .push(webhook.clone());
+            return Ok(webhook);
+        }
+
+        let pool = self.require_postgres("create_merchant_webhook")?;
+
+        let webhook = sqlx::query_as::<_, MerchantWebhook>(
+            r#"
+            INSERT INTO merchant_webhooks (merchant, url, secret, event_types)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, merchant, url, secret, event_types, active, created_at, updated_at
+            "#,
+        )
+        .bind(&merchant)
+        .bind(url)
+        .bind(secret)
+        .bind(event_types)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    pub async fn get_merchant_webhooks(&self, merchant: &str) -> Result<Vec<MerchantWebhook>> {
+        let merchant = merchant.to_lowercase();
+
+        if let Some(storage) = self.stub_storage() {
+            return Ok(storage
+                .merchant_webhooks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|webhook| webhook.merchant == merchant && webhook.active)
+                .cloned()
+                .collect());
+        }
+
+        let pool = self.require_postgres("get_merchant_webhooks")?;
+
+        let webhooks = sqlx::query_as::<_, MerchantWebhook>(
+            r#"
+            SELECT id, merchant, url, secret, event_types, active, created_at, updated_at
+            FROM merchant_webhooks
+            WHERE merchant = $1 AND active
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(&merchant)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    // deactivates rather than deletes so the delivery log stays queryable
+    pub async fn deactivate_merchant_webhook(
+        &self,
+        merchant: &str,
+        webhook_id: i64,
+    ) -> Result<bool> {
+        let merchant = merchant.to_lowercase();
+
+        if let Some(storage) = self.stub_storage() {
+            let mut webhooks = storage.merchant_webhooks.lock().unwrap();
+            let Some(webhook) = webhooks.iter_mut().find(|webhook| {
+                webhook.id == webhook_id && webhook.merchant == merchant && webhook.active
+            }) else {
+                return Ok(false);
+            };
+            webhook.active = false;
+            webhook.updated_at = Utc::now();
+            return Ok(true);
+        }
+
+        let pool = self.require_postgres("deactivate_merchant_webhook")?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE merchant_webhooks
+            SET active = FALSE, updated_at = NOW()
+            WHERE id = $1 AND merchant = $2 AND active
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(&merchant)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // queues one delivery per active webhook of the merchant that wants this event type
+    pub async fn enqueue_webhook_deliveries(
+        &self,
+        merchant: &str,
+        event_type: WebhookEventType,
+        event_id: Uuid,
+        payload: &serde_json::Value,
+    ) -> Result<u64> {
+        let merchant = merchant.to_lowercase();
+
+        if let Some(storage) = self.stub_storage() {
+            let webhooks: Vec<i64> = storage
+                .merchant_webhooks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|webhook| {
+                    webhook.merchant == merchant && webhook.active && webhook.accepts(event_type)
+                })
+                .map(|webhook| webhook.id)
+                .collect();
+
+            let now = Utc::now();
+            let mut deliveries = storage.webhook_deliveries.lock().unwrap();
+            for webhook_id in &webhooks {
+                deliveries.push(WebhookDelivery {
+                    id: storage.next_webhook_delivery_id(),
+                    webhook_id: *webhook_id,
+                    event_id,
+                    event_type: event_type.to_string(),
+                    payload: payload.clone(),
+                    status: WebhookDeliveryStatus::Pending.to_string(),
+                    attempts: 0,
+                    next_attempt_at: now,
+                    response_status: None,
+                    last_error: None,
+                    delivered_at: None,
+                    created_at: now,
+                    updated_at: now,
+                });
+            }
+            return Ok(webhooks.len() as u64);
+        }
+
+        let pool = self.require_postgres("enqueue_webhook_deliveries")?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event_id, event_type, payload)
+            SELECT id, $2, $3, $4
+            FROM merchant_webhooks
+            WHERE merchant = $1
+                AND active
+                AND (cardinality(event_types) = 0 OR $3 = ANY(event_types))
+            ON CONFLICT (webhook_id, event_id) DO NOTHING
+            "#,
+        )
+        .bind(&merchant)
+        .bind(event_id)
+        .bind(event_type.as_str())
+        .bind(sqlx::types::Json(payload))
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // takes due deliveries and pushes their next attempt out by `lease` so other relayer
+    // instances skip them while this one is sending
+    pub async fn claim_due_webhook_deliveries(
+        &self,
+        limit: i64,
+        lease: chrono::Duration,
+    ) -> Result<Vec<ClaimedWebhookDelivery>> {
+        if let Some(storage) = self.stub_storage() {
+            let now = Utc::now();
+            let webhooks = storage.merchant_webhooks.lock().unwrap();
+            let mut deliveries = storage.webhook_deliveries.lock().unwrap();
+            let mut claimed = Vec::new();
+            for delivery in deliveries.iter_mut().filter(|delivery| {
+                delivery.status == WebhookDeliveryStatus::Pending.to_string()
+                    && delivery.next_attempt_at <= now
+            }) {
+                if claimed.len() as i64 >= limit {
+                    break;
+                }
+                let Some(webhook) = webhooks.iter().find(|w| w.id == delivery.webhook_id) else {
+                    continue;
+                };
+                delivery.next_attempt_at = now + lease;
+                delivery.updated_at = now;
+                claimed.push(ClaimedWebhookDelivery {
+                    id: delivery.id,
+                    webhook_id: delivery.webhook_id,
+                    event_id: delivery.event_id,
+                    event_type: delivery.event_type.clone(),
+                    payload: delivery.payload.clone(),
+                    attempts: delivery.attempts,
+                    url: webhook.url.clone(),
+                    secret: webhook.secret.clone(),
+                });
+            }
+            return Ok(claimed);
+        }
+
+        let pool = self.require_postgres("claim_due_webhook_deliveries")?;
+
+        let deliveries = sqlx::query_as::<_, ClaimedWebhookDelivery>(
+            r#"
+            WITH due AS (
+                SELECT id
+                FROM webhook_deliveries
+                WHERE status = 'PENDING' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at ASC, id ASC
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE webhook_deliveries d
+            SET next_attempt_at = NOW() + make_interval(secs => $2),
+                updated_at = NOW()
+            FROM due, merchant_webhooks w
+            WHERE d.id = due.id AND w.id = d.webhook_id
+            RETURNING d.id, d.webhook_id, d.event_id, d.event_type, d.payload, d.attempts,
+                      w.url, w.secret
+            "#,
+        )
+        .bind(limit)
+        .bind(lease.num_seconds() as f64)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    // records one delivery attempt; next_attempt_at is only used while the delivery stays pending
+    pub async fn record_webhook_attempt(
+        &self,
+        delivery_id: i64,
+        status: WebhookDeliveryStatus,
+        response_status: Option<i32>,
+        error: Option<&str>,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let error = error.map(truncate_error);
+
+        if let Some(storage) = self.stub_storage() {
+            let mut deliveries = storage.webhook_deliveries.lock().unwrap();
+            let delivery = deliveries
+                .iter_mut()
+                .find(|delivery| delivery.id == delivery_id)
+                .ok_or_else(|| {
+                    RelayerError::NotFound(format!("webhook delivery not found: {}", delivery_id))
+                })?;
+            let now = Utc::now();
+            delivery.status = status.to_string();
+            delivery.attempts += 1;
+            delivery.response_status = response_status;
+            delivery.last_error = error;
+            delivery.next_attempt_at = next_attempt_at;
+            if status == WebhookDeliveryStatus::Delivered {
+                delivery.delivered_at = Some(now);
+            }
+            delivery.updated_at = now;
+            return Ok(());
+        }
+
+        let pool = self.require_postgres("record_webhook_attempt")?;
+
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2,
+                attempts = attempts + 1,
+                response_status = $3,
+                last_error = $4,
+                next_attempt_at = $5,
+                delivered_at = CASE WHEN $2 = 'DELIVERED' THEN NOW() ELSE delivered_at END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(status.to_string())
+        .bind(response_status)
+        .bind(error)
+        .bind(next_attempt_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // delivery log for one merchant, newest first, optionally narrowed to a webhook or status
+    pub async fn get_webhook_deliveries(
+        &self,
+        merchant: &str,
+        webhook_id: Option<i64>,
+        status: Option<WebhookDeliveryStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let merchant = merchant.to_lowercase();
+        let status = status.map(|status| status.to_string());
+
+        if let Some(storage) = self.stub_storage() {
+            let webhook_ids: Vec<i64> = storage
+                .merchant_webhooks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|webhook| webhook.merchant == merchant)
+                .map(|webhook| webhook.id)
+                .collect();
+            let mut deliveries: Vec<WebhookDelivery> = storage
+                .webhook_deliveries
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|delivery| {
+                    webhook_ids.contains(&delivery.webhook_id)
+                        && webhook_id.is_none_or(|id| delivery.webhook_id == id)
+                        && status
+                            .as_ref()
+                            .is_none_or(|status| &delivery.status == status)
+                })
+                .cloned()
+                .collect();
+            deliveries.sort_by_key(|delivery| std::cmp::Reverse(delivery.id));
+            return Ok(deliveries
+                .into_iter()
+                .skip(offset.max(0) as usize)
+                .take(limit.max(0) as usize)
+                .collect());
+        }
+
+        let pool = self.require_postgres("get_webhook_deliveries")?;
+
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT d.id, d.webhook_id, d.event_id, d.event_type, d.payload, d.status, d.attempts,
+                   d.next_attempt_at, d.response_status, d.last_error, d.delivered_at,
+                   d.created_at, d.updated_at
+            FROM webhook_deliveries d
+            JOIN merchant_webhooks w ON w.id = d.webhook_id
+            WHERE w.merchant = $1
+                AND ($2::BIGINT IS NULL OR d.webhook_id = $2)
+                AND ($3::VARCHAR IS NULL OR d.status = $3)
+            ORDER BY d.id DESC
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(&merchant)
+        .bind(webhook_id)
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    pub async fn create_api_key(
+        &self,
+        role: ApiKeyRole,
+        merchant: Option<&str>,
+        label: Option<&str>,
+        key_hash: &str,
+        key_prefix: &str,
+    ) -> Result<ApiKey> {
+        let merchant = merchant.map(str::to_lowercase);
+
+        if let Some(storage) = self.stub_storage() {
+            let mut keys = storage.api_keys.lock().unwrap();
+            if keys.iter().any(|key| key.key_hash == key_hash) {
+                return Err(RelayerError::Duplicate(
+                    "api key already exists".to_string(),
+                ));
+            }
+            let key = ApiKey {
+                id: storage.next_api_key_id(),
+                key_hash: key_hash.to_string(),
+                key_prefix: key_prefix.to_string(),
+                role: role.to_string(),
+                merchant,
+                label: label.map(str::to_string),
+                created_at: Utc::now(),
+                last_used_at: None,
+                revoked_at: None,
+            };
+            keys.push(key.clone());
+            return Ok(key);
+        }
+
+        let pool = self.require_postgres("create_api_key")?;
+
+        let key = sqlx::query_as::<_, ApiKey>(
+            r#"
+            INSERT INTO api_keys (key_hash, key_prefix, role, merchant, label)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, key_hash, key_prefix, role, merchant, label,
+                created_at, last_used_at, revoked_at
+            "#,
+        )
+        .bind(key_hash)
+        .bind(key_prefix)
+        .bind(role.as_str())
+        .bind(&merchant)
+        .bind(label)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(key)
+    }
+
+    // looks up an unrevoked key by digest and stamps last_used_at in the same round trip
+    pub async fn authenticate_api_key(&self, key_hash: &str) -> Result<Option<ApiKey>> {
+        if let Some(storage) = self.stub_storage() {
+            let mut keys = storage.api_keys.lock().unwrap();
+            let Some(key) = keys
+                .iter_mut()
+                .find(|key| key.key_hash == key_hash && key.revoked_at.is_none())
+            else {
+                return Ok(None);
+            };
+            key.last_used_at = Some(Utc::now());
+            return Ok(Some(key.clone()));
+        }
+
+        let pool = self.require_postgres("authenticate_api_key")?;
+
+        let key = sqlx::query_as::<_, ApiKey>(
+            r#"
+            UPDATE api_keys
+            SET last_used_at = NOW()
+            WHERE key_hash = $1 AND revoked_at IS NULL
+            RETURNING id, key_hash, key_prefix, role, merchant, label,
+                created_at, last_used_at, revoked_at
+            "#,
+        )
+        .bind(key_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(key)
+    }
+
+    // unrevoked keys, optionally only those scoped to one merchant
+    pub async fn get_api_keys(&self, merchant: Option<&str>) -> Result<Vec<ApiKey>> {
+        let merchant = merchant.map(str::to_lowercase);
+
+        if let Some(storage) = self.stub_storage() {
+            return Ok(storage
+                .api_keys
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|key| key.revoked_at.is_none())
+                .filter(|key| merchant.is_none() || key.merchant == merchant)
+                .cloned()
+                .collect());
+        }
+
+        let pool = self.require_postgres("get_api_keys")?;
+
+        let keys = sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, key_hash, key_prefix, role, merchant, label,
+                created_at, last_used_at, revoked_at
+            FROM api_keys
+            WHERE revoked_at IS NULL
+                AND ($1::TEXT IS NULL OR merchant = $1)
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(&merchant)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    pub async fn revoke_api_key(&self, key_id: i64) -> Result<bool> {
+        if let Some(storage) = self.stub_storage() {
+            let mut keys = storage.api_keys.lock().unwrap();
+            let Some(key) = keys
+                .iter_mut()
+                .find(|key| key.id == key_id && key.revoked_at.is_none())
+            else {
+                return Ok(false);
+            };
+            key.revoked_at = Some(Utc::now());
+            return Ok(true);
+        }
+
+        let pool = self.require_postgres("revoke_api_key")?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET revoked_at = NOW()
+            WHERE id = $1 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(key_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // refills the bucket for the time since its last update and takes one token if there is one;
+    // returns whether the request is allowed and the tokens left. postgres only, the in-memory
+    // limiter covers stub mode
+    pub async fn take_rate_limit_token(
+        &self,
+        bucket_key: &str,
+        capacity: f64,
+        refill_per_second: f64,
+    ) -> Result<(bool, f64)> {
+        let pool = self.require_postgres("take_rate_limit_token")?;
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO rate_limit_buckets AS bucket (bucket_key, tokens, last_allowed, updated_at)
+            VALUES ($1, $2 - 1, TRUE, NOW())
+            ON CONFLICT (bucket_key) DO UPDATE SET
+                (tokens, last_allowed, updated_at) = (
+                    SELECT
+                        CASE WHEN refilled.tokens >= 1 THEN refilled.tokens - 1 ELSE refilled.tokens END,
+                        refilled.tokens >= 1,
+                        NOW()
+                    FROM (
+                        SELECT LEAST(
+                            $2,
+                            bucket.tokens
+                                + EXTRACT(EPOCH FROM (NOW() - bucket.updated_at))::DOUBLE PRECISION
+                                    * $3
+                        ) AS tokens
+                    ) AS refilled
+                )
+            RETURNING bucket.last_allowed, bucket.tokens
+            "#,
+        )
+        .bind(bucket_key)
+        .bind(capacity)
+        .bind(refill_per_second)
+        .fetch_one(pool)
+        .await?;
+
+        Ok((row.try_get("last_allowed")?, row.try_get("tokens")?))
+    }
+
+    // buckets idle this long have refilled completely and can be recreated on demand
+    pub async fn prune_rate_limit_buckets(&self, idle_for: chrono::Duration) -> Result<u64> {
+        let pool = self.require_postgres("prune_rate_limit_buckets")?;
+
+        let result = sqlx::query("DELETE FROM rate_limit_buckets WHERE updated_at < $1")
+            .bind(Utc::now() - idle_for)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn record_admin_action(
+        &self,
+        actor: &str,
+        action: AdminAction,
+        target: Option<&str>,
+        details: &serde_json::Value,
+    ) -> Result<AdminAuditEntry> {
+        if let Some(storage) = self.stub_storage() {
+            let entry = AdminAuditEntry {
+                id: storage.next_admin_audit_id(),
+                actor: actor.to_string(),
+                action: action.to_string(),
+                target: target.map(str::to_string),
+                details: details.clone(),
+                created_at: Utc::now(),
+            };
+            storage.admin_audit_log.lock().unwrap().push(entry.clone());
+            return Ok(entry);
+        }
+
+        let pool = self.require_postgres("record_admin_action")?;
+        Self::record_admin_action_row(pool, actor, action, target, details).await
+    }
+
+    async fn record_admin_action_row<'e>(
+        executor: impl PgExecutor<'e>,
+        actor: &str,
+        action: AdminAction,
+        target: Option<&str>,
+        details: &serde_json::Value,
+    ) -> Result<AdminAuditEntry> {
+        let entry = sqlx::query_as::<_, AdminAuditEntry>(
+            r#"
+            INSERT INTO admin_audit_log (actor, action, target, details)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, actor, action, target, details, created_at
+            "#,
+        )
+        .bind(actor)
+        .bind(action.as_str())
+        .bind(target)
+        .bind(sqlx::types::Json(details))
+        .fetch_one(executor)
+        .await?;
+
+        Ok(entry)
+    }
+
+    // moves a chain's sync checkpoint and records who did it in one transaction; details gets the
+    // checkpoint it replaced. returns that previous block and the audit entry
+    pub async fn rewind_sync_metadata(
+        &self,
+        chain_id: i64,
+        last_synced_block: i64,
+        actor: &str,
+        target: &str,
+        details: impl FnOnce(i64) -> serde_json::Value,
+    ) -> Result<(i64, AdminAuditEntry)> {
+        if self.stub_storage().is_some() {
+            let previous = self.get_sync_metadata(chain_id).await?.last_synced_block;
+            self.update_sync_metadata(chain_id, last_synced_block)
+                .await?;
+            let entry = self
+                .record_admin_action(
+                    actor,
+                    AdminAction::HyperSyncResync,
+                    Some(target),
+                    &details(previous),
+                )
+                .await?;
+            return Ok((previous, entry));
+        }
+
+        let pool = self.require_postgres("rewind_sync_metadata")?;
+        let mut tx = pool.begin().await.map_err(|e| {
+            RelayerError::DatabaseError(format!("failed to begin transaction: {}", e))
+        })?;
+
+        // a missing row means nothing was synced yet
+        let previous = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT last_synced_block
+            FROM sync_metadata
+            WHERE chain_id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(chain_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .unwrap_or(0);
+
+        sqlx::query(
+            r#"
+            INSERT INTO sync_metadata (chain_id, last_synced_block, last_synced_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (chain_id) DO UPDATE
+            SET last_synced_block = EXCLUDED.last_synced_block,
+                last_synced_at = NOW()
+            "#,
+        )
+        .bind(chain_id)
+        .bind(last_synced_block)
+        .execute(&mut *tx)
+        .await?;
+
+        let entry = Self::record_admin_action_row(
+            &mut *tx,
+            actor,
+            AdminAction::HyperSyncResync,
+            Some(target),
+            &details(previous),
+        )
+        .await?;
+
+        tx.commit().await.map_err(|e| {
+            RelayerError::DatabaseError(format!("failed to commit transaction: {}", e))
+        })?;
+
+        Ok((previous, entry))
+    }
+
+    // newest first, optionally only one action or one target
+    pub async fn get_admin_audit_log(
+        &self,
+        action: Option<AdminAction>,
+        target: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AdminAuditEntry>> {
+        if let Some(storage) = self.stub_storage() {
+            return Ok(storage
+                .admin_audit_log
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .filter(|entry| action.is_none_or(|action| entry.action == action.as_str()))
+                .filter(|entry| target.is_none_or(|target| entry.target.as_deref() == Some(target)))
+                .skip(offset.max(0) as usize)
+                .take(limit.max(0) as usize)
+                .cloned()
+                .collect());
+        }
+
+        let pool = self.require_postgres("get_admin_audit_log")?;
+
+        let entries = sqlx::query_as::<_, AdminAuditEntry>(
+            r#"
+            SELECT id, actor, action, target, details, created_at
+            FROM admin_audit_log
+            WHERE ($1::TEXT IS NULL OR action = $1)
+                AND ($2::TEXT IS NULL OR target = $2)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(action.map(AdminAction::as_str))
+        .bind(target)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    // the session holding a bigint advisory lock, if any. postgres only, stub mode has no locks
+    pub async fn get_advisory_lock_holder(&self, key: i64) -> Result<Option<AdvisoryLockHolder>> {
+        if self.stub_storage().is_some() {
+            return Ok(None);
+        }
+
+        let pool = self.require_postgres("get_advisory_lock_holder")?;
+
+        // bigint keys are split across classid (high half) and objid (low half), objsubid 1
+        let holder = sqlx::query_as::<_, AdvisoryLockHolder>(
+            r#"
+            SELECT l.pid,
+                a.application_name,
+                host(a.client_addr) AS client_addr,
+                a.backend_start,
+                a.state,
+                a.state_change
+            FROM pg_locks l
+            LEFT JOIN pg_stat_activity a ON a.pid = l.pid
+            WHERE l.locktype = 'advisory'
+                AND l.granted
+                AND l.objsubid = 1
+                AND l.classid = ($1 >> 32)::OID
+                AND l.objid = ($1 & 4294967295)::OID
+            LIMIT 1
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(holder)
+    }
+
+    // queues an intent for the avail outbox worker; one submission per subscription
+    pub async fn enqueue_avail_submission(
+        &self,
+        subscription_id: &str,
+        payload: &serde_json::Value,
+    ) -> Result<AvailSubmission> {
+        if let Some(storage) = self.stub_storage() {
+            let mut submissions = storage.avail_submissions.lock().unwrap();
+            if submissions
+                .iter()
+                .any(|submission| submission.subscription_id == subscription_id)
+            {
+                return Err(RelayerError::Duplicate(format!(
+                    "avail submission already queued for {}",
+                    subscription_id
+                )));
+            }
+            let now = Utc::now();
+            let submission = AvailSubmission {
+                id: storage.next_avail_submission_id(),
+                subscription_id: subscription_id.to_string(),
+                payload: payload.clone(),
+                status: AvailStatus::Pending.to_string(),
+                attempts: 0,
+                next_attempt_at: now,
+                last_error: None,
+                block_number: None,
+                extrinsic_index: None,
+                submitted_at: None,
+                created_at: now,
+                updated_at: now,
+                batch_id: None,
+                leaf_index: None,
+                merkle_proof: None,
+            };
+            submissions.push(submission.clone());
+            return Ok(submission);
+        }
+
+        let pool = self.require_postgres("enqueue_avail_submission")?;
+        Self::enqueue_avail_submission_row(pool, subscription_id, payload).await
+    }
+
+    async fn enqueue_avail_submission_row<'e>(
+        executor: impl PgExecutor<'e>,
+        subscription_id: &str,
+        payload: &serde_json::Value,
+    ) -> Result<AvailSubmission> {
+        let submission = sqlx::query_as::<_, AvailSubmission>(
+            r#"
+            INSERT INTO avail_submissions (subscription_id, payload)
+            VALUES ($1, $2)
+            RETURNING id, subscription_id, payload, status, attempts, next_attempt_at, last_error,
+                      block_number, extrinsic_index, submitted_at, created_at, updated_at,
+                      batch_id, leaf_index, merkle_proof
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(payload)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(submission)
+    }
+
+    // writes an accepted intent, its subscription and its outbox row in one transaction, so a
+    // crash between them can never leave a subscription that is not anchored to avail
+    pub async fn insert_accepted_intent(
+        &self,
+        intent: &IntentCache,
+        subscription: &Subscription,
+        // None when avail is disabled and the intent isn't queued for anchoring
+        avail_payload: Option<&serde_json::Value>,
+    ) -> Result<Option<AvailSubmission>> {
+        info!("inserting accepted intent for: {}", subscription.id);
+
+        if let Some(storage) = self.stub_storage() {
+            // check everything up front so a rejected write leaves nothing behind
+            let exists = storage
+                .subscriptions
+                .lock()
+                .unwrap()
+                .contains_key(&subscription.id)
+                || storage
+                    .avail_submissions
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .any(|submission| submission.subscription_id == subscription.id);
+            if exists {
+                return Err(RelayerError::Duplicate(format!(
+                    "subscription {} already exists",
+                    subscription.id
+                )));
+            }
+            self.cache_intent(intent).await?;
+            self.insert_subscription(subscription).await?;
+            return match avail_payload {
+                Some(payload) => self
+                    .enqueue_avail_submission(&subscription.id, payload)
+                    .await
+                    .map(Some),
+                None => Ok(None),
+            };
+        }
+
+        let pool = self.require_postgres("insert_accepted_intent")?;
+        let mut tx = pool.begin().await.map_err(|e| {
+            RelayerError::DatabaseError(format!("failed to begin transaction: {}", e))
+        })?;
+
+        let intent_id = Self::cache_intent_row(&mut *tx, intent).await?;
+        Self::insert_subscription_row(&mut *tx, subscription).await?;
+        let submission = match avail_payload {
+            Some(payload) => {
+                Some(Self::enqueue_avail_submission_row(&mut *tx, &subscription.id, payload).await?)
+            }
+            None => None,
+        };
+
+        tx.commit().await.map_err(|e| {
+            RelayerError::DatabaseError(format!("failed to commit transaction: {}", e))
+        })?;
+
+        info!(
+            "inserted accepted intent {} for subscription {}",
+            intent_id, subscription.id
+        );
+        Ok(submission)
+    }
+
+    // claims due submissions oldest first; the lease keeps overlapping runs off them
+    pub async fn claim_due_avail_submissions(
+        &self,
+        limit: i64,
+        lease: chrono::Duration,
+    ) -> Result<Vec<AvailSubmission>> {
+        if let Some(storage) = self.stub_storage() {
+            let now = Utc::now();
+            let mut submissions = storage.avail_submissions.lock().unwrap();
+            let mut claimed = Vec::new();
+            for submission in submissions.iter_mut().filter(|submission| {
+                submission.status == AvailStatus::Pending.to_string()
+                    && submission.next_attempt_at <= now
+            }) {
+                if claimed.len() as i64 >= limit {
+                    break;
+                }
+                submission.next_attempt_at = now + lease;
+                submission.updated_at = now;
+                claimed.push(submission.clone());
+            }
+            return Ok(claimed);
+        }
+
+        let pool = self.require_postgres("claim_due_avail_submissions")?;
+
+        let submissions = sqlx::query_as::<_, AvailSubmission>(
+            r#"
+            WITH due AS (
+                SELECT id
+                FROM avail_submissions
+                WHERE status = 'PENDING' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at ASC, id ASC
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE avail_submissions s
+            SET next_attempt_at = NOW() + make_interval(secs => $2),
+                updated_at = NOW()
+            FROM due
+            WHERE s.id = due.id
+            RETURNING s.id, s.subscription_id, s.payload, s.status, s.attempts,
+                      s.next_attempt_at, s.last_error, s.block_number, s.extrinsic_index,
+                      s.submitted_at, s.created_at, s.updated_at, s.batch_id, s.leaf_index,
+                      s.merkle_proof
+            "#,
+        )
+        .bind(limit)
+        .bind(lease.num_seconds() as f64)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(submissions)
+    }
+
+    // records an included batch, marks its members submitted with their proofs and backfills the
+    // references on each subscription and cached intent; members can be a subset of the leaves
+    pub async fn complete_avail_batch(
+        &self,
+        merkle_root: &str,
+        leaf_count: usize,
+        block_number: u64,
+        extrinsic_index: u64,
+        members: &[AvailBatchMember],
+    ) -> Result<AvailBatchRecord> {
+        let block_number = block_number as i64;
+        let extrinsic_index = extrinsic_index as i64;
+
+        if let Some(storage) = self.stub_storage() {
+            let now = Utc::now();
+            let batch = AvailBatchRecord {
+                id: storage.next_avail_batch_id(),
+                merkle_root: merkle_root.to_string(),
+                leaf_count: leaf_count as i32,
+                block_number,
+                extrinsic_index,
+                submitted_at: now,
+                created_at: now,
+            };
+            let mut subscription_ids = Vec::with_capacity(members.len());
+            {
+                let mut submissions = storage.avail_submissions.lock().unwrap();
+                for member in members {
+                    let submission = submissions
+                        .iter_mut()
+                        .find(|submission| submission.id == member.submission_id)
+                        .ok_or_else(|| {
+                            RelayerError::NotFound(format!(
+                                "avail submission not found: {}",
+                                member.submission_id
+                            ))
+                        })?;
+                    submission.status = AvailStatus::Submitted.to_string();
+                    submission.attempts += 1;
+                    submission.last_error = None;
+                    submission.block_number = Some(block_number);
+                    submission.extrinsic_index = Some(extrinsic_index);
+                    submission.submitted_at = Some(now);
+                    submission.updated_at = now;
+                    submission.batch_id = Some(batch.id);
+                    submission.leaf_index = Some(member.leaf_index);
+                    submission.merkle_proof = Some(member.merkle_proof.clone());
+                    subscription_ids.push(submission.subscription_id.clone());
+                }
+            }
+            let mut subscriptions = storage.subscriptions.lock().unwrap();
+            let mut intents = storage.intent_cache.lock().unwrap();
+            for subscription_id in &subscription_ids {
+                if let Some(subscription) = subscriptions.get_mut(subscription_id) {
+                    subscription.avail_block_number = Some(block_number);
+                    subscription.avail_extrinsic_index = Some(extrinsic_index);
+                    subscription.updated_at = now;
+                }
+                for intent in intents
+                    .iter_mut()
+                    .filter(|intent| &intent.subscription_id == subscription_id)
+                {
+                    intent.avail_block_number = Some(block_number);
+                    intent.avail_extrinsic_index = Some(extrinsic_index);
+                }
+            }
+            storage.avail_batches.lock().unwrap().push(batch.clone());
+            return Ok(batch);
+        }
+
+        let pool = self.require_postgres("complete_avail_batch")?;
+
+        let mut tx = pool.begin().await.map_err(|e| {
+            RelayerError::DatabaseError(format!("failed to begin transaction: {}", e))
+        })?;
+
+        let batch = sqlx::query_as::<_, AvailBatchRecord>(
+            r#"
+            INSERT INTO avail_batches (merkle_root, leaf_count, block_number, extrinsic_index)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, merkle_root, leaf_count, block_number, extrinsic_index, submitted_at,
+                      created_at
+            "#,
+        )
+        .bind(merkle_root)
+        .bind(leaf_count as i32)
+        .bind(block_number)
+        .bind(extrinsic_index)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for member in members {
+            let subscription_id: String = sqlx::query_scalar(
+                r#"
+                UPDATE avail_submissions
+                SET status = 'SUBMITTED',
+                    attempts = attempts + 1,
+                    last_error = NULL,
+                    block_number = $2,
+                    extrinsic_index = $3,
+                    batch_id = $4,
+                    leaf_index = $5,
+                    merkle_proof = $6,
+                    submitted_at = NOW(),
+                    updated_at = NOW()
+                WHERE id = $1
+                RETURNING subscription_id
+                "#,
+            )
+            .bind(member.submission_id)
+            .bind(block_number)
+            .bind(extrinsic_index)
+            .bind(batch.id)
+            .bind(member.leaf_index)
+            .bind(&member.merkle_proof)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| {
+                RelayerError::NotFound(format!(
+                    "avail submission not found: {}",
+                    member.submission_id
+                ))
+            })?;
+
+            sqlx::query(
+                r#"
+                UPDATE subscriptions
+                SET avail_block_number = $2, avail_extrinsic_index = $3, updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(&subscription_id)
+            .bind(block_number)
+            .bind(extrinsic_index)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                r#"
+                UPDATE intent_cache
+                SET avail_block_number = $2, avail_extrinsic_index = $3
+                WHERE subscription_id = $1
+                "#,
+            )
+            .bind(&subscription_id)
+            .bind(block_number)
+            .bind(extrinsic_index)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            RelayerError::DatabaseError(format!("failed to commit transaction: {}", e))
+        })?;
+
+        Ok(batch)
+    }
+
+    // records a failed attempt; status is PENDING to retry at next_attempt_at, or FAILED to give up
+    pub async fn record_avail_submission_failure(
+        &self,
+        submission_id: i64,
+        status: AvailStatus,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let error = truncate_error(error);
+
+        if let Some(storage) = self.stub_storage() {
+            let mut submissions = storage.avail_submissions.lock().unwrap();
+            let submission = submissions
+                .iter_mut()
+                .find(|submission| submission.id == submission_id)
+                .ok_or_else(|| {
+                    RelayerError::NotFound(format!("avail submission not found: {}", submission_id))
+                })?;
+            submission.status = status.to_string();
+            submission.attempts += 1;
+            submission.last_error = Some(error);
+            submission.next_attempt_at = next_attempt_at;
+            submission.updated_at = Utc::now();
+            return Ok(());
+        }
+
+        let pool = self.require_postgres("record_avail_submission_failure")?;
+
+        sqlx::query(
+            r#"
+            UPDATE avail_submissions
+            SET status = $2,
+                attempts = attempts + 1,
+                last_error = $3,
+                next_attempt_at = $4,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(submission_id)
+        .bind(status.to_string())
+        .bind(error)
+        .bind(next_attempt_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_avail_submission(
+        &self,
+        subscription_id: &str,
+    ) -> Result<Option<AvailSubmission>> {
+        if let Some(storage) = self.stub_storage() {
+            let submissions = storage.avail_submissions.lock().unwrap();
+            return Ok(submissions
+                .iter()
+                .find(|submission| submission.subscription_id == subscription_id)
+                .cloned());
+        }
+
+        let pool = self.require_postgres("get_avail_submission")?;
+
+        let submission = sqlx::query_as::<_, AvailSubmission>(
+            r#"
+            SELECT id, subscription_id, payload, status, attempts, next_attempt_at, last_error,
+                   block_number, extrinsic_index, submitted_at, created_at, updated_at,
+                   batch_id, leaf_index, merkle_proof
+            FROM avail_submissions
+            WHERE subscription_id = $1
+            "#,
+        )
+        .bind(subscription_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(submission)
+    }
+
+    pub async fn get_avail_batch(&self, batch_id: i64) -> Result<Option<AvailBatchRecord>> {
+        if let Some(storage) = self.stub_storage() {
+            let batches = storage.avail_batches.lock().unwrap();
+            return Ok(batches.iter().find(|batch| batch.id == batch_id).cloned());
+        }
+
+        let pool = self.require_postgres("get_avail_batch")?;
+
+        let batch = sqlx::query_as::<_, AvailBatchRecord>(
+            r#"
+            SELECT id, merkle_root, leaf_count, block_number, extrinsic_index, submitted_at,
+                   created_at
+            FROM avail_batches
+            WHERE id = $1
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(batch)
+    }
+}
+
+// payment count and total after removing one orphaned payment; never goes below zero
+fn rolled_back_totals(
+    executed_payments: i64,
+    total_paid: &str,
+    amount_paid: &str,
+) -> (i64, String) {
+    let total = U256::from_dec_str(total_paid).unwrap_or_else(|_| U256::zero());
+    let amount = U256::from_dec_str(amount_paid).unwrap_or_else(|_| U256::zero());
+    (
+        (executed_payments - 1).max(0),
+        total.saturating_sub(amount).to_string(),
+    )
+}
+
+// keeps stored failure messages bounded
+fn truncate_error(error: &str) -> String {
+    const MAX_ERROR_LENGTH: usize = 1000;
+    match error.char_indices().nth(MAX_ERROR_LENGTH) {
+        Some((index, _)) => error[..index].to_string(),
+        None => error.to_string(),
+    }
 }
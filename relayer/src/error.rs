@@ -1,10 +1,13 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use serde_json::{json, Value};
 use std::fmt;
+use std::time::Duration;
+
+use crate::api::types::ErrorResponse;
 
 #[derive(Debug)]
 pub enum RelayerError {
@@ -21,8 +24,46 @@ pub enum RelayerError {
     RpcConnectionFailed(String),
     Config(anyhow::Error),
     Validation(String),
+    // a request field that failed validation; field uses the request's json name
+    InvalidField {
+        field: String,
+        reason: String,
+    },
+    // malformed or unrecoverable signature
+    InvalidSignature(String),
+    // well-formed signature from someone other than the subscriber
+    SignatureMismatch {
+        expected: String,
+        recovered: String,
+    },
+    NonceAlreadyUsed {
+        subscriber: String,
+        nonce: u64,
+        chain: String,
+    },
+    UnsupportedChain {
+        requested: String,
+        supported: Vec<String>,
+    },
+    UnsupportedToken {
+        token: String,
+        chain: String,
+        supported: Vec<String>,
+    },
+    // the action isn't allowed from the subscription's current status
+    InvalidSubscriptionState {
+        subscription_id: String,
+        status: String,
+        action: String,
+    },
     NotFound(String),
+    Unauthorized(String),
+    Forbidden(String),
+    // with the time until the client's bucket has a token again
+    RateLimited(Duration),
     Duplicate(String),
+    // a backend the request depends on is turned off or not reachable from this relayer
+    Unavailable(String),
     InternalError(String),
 }
 
@@ -46,8 +87,50 @@ impl fmt::Display for RelayerError {
             RelayerError::RpcConnectionFailed(msg) => write!(f, "rpc connection failed: {}", msg),
             RelayerError::Config(err) => write!(f, "config error: {}", err),
             RelayerError::Validation(msg) => write!(f, "validation error: {}", msg),
+            RelayerError::InvalidField { field, reason } => {
+                write!(f, "validation error: {} ({})", reason, field)
+            }
+            RelayerError::InvalidSignature(msg) => write!(f, "invalid signature: {}", msg),
+            RelayerError::SignatureMismatch {
+                expected,
+                recovered,
+            } => write!(
+                f,
+                "signature recovered to {}, expected subscriber {}",
+                recovered, expected
+            ),
+            RelayerError::NonceAlreadyUsed {
+                subscriber,
+                nonce,
+                chain,
+            } => write!(
+                f,
+                "nonce {} already used by {} on {}",
+                nonce, subscriber, chain
+            ),
+            RelayerError::UnsupportedChain { requested, .. } => {
+                write!(f, "unsupported chain: {}", requested)
+            }
+            RelayerError::UnsupportedToken { token, chain, .. } => {
+                write!(f, "unsupported token address {} on {}", token, chain)
+            }
+            RelayerError::InvalidSubscriptionState {
+                subscription_id,
+                status,
+                action,
+            } => write!(
+                f,
+                "cannot {} subscription {} with status {}",
+                action, subscription_id, status
+            ),
             RelayerError::NotFound(msg) => write!(f, "not found: {}", msg),
+            RelayerError::Unauthorized(msg) => write!(f, "unauthorized: {}", msg),
+            RelayerError::Forbidden(msg) => write!(f, "forbidden: {}", msg),
+            RelayerError::RateLimited(retry_after) => {
+                write!(f, "rate limited: retry in {}s", retry_after.as_secs())
+            }
             RelayerError::Duplicate(msg) => write!(f, "duplicate: {}", msg),
+            RelayerError::Unavailable(msg) => write!(f, "unavailable: {}", msg),
             RelayerError::InternalError(msg) => write!(f, "internal error: {}", msg),
         }
     }
@@ -93,84 +176,281 @@ impl From<anyhow::Error> for RelayerError {
     }
 }
 
+impl RelayerError {
+    // every code an error response can carry; clients match on these, so they never change
+    pub const CODES: &'static [&'static str] = &[
+        "DATABASE_ERROR",
+        "DATABASE_CONNECTION_ERROR",
+        "DATABASE_MIGRATION_ERROR",
+        "DATABASE_CONSTRAINT",
+        "ETHEREUM_ERROR",
+        "TRANSACTION_FAILED",
+        "INSUFFICIENT_GAS",
+        "NONCE_ERROR",
+        "CONTRACT_REVERT",
+        "RPC_CONNECTION_FAILED",
+        "CONFIG_ERROR",
+        "VALIDATION_ERROR",
+        "INVALID_FIELD",
+        "INVALID_SIGNATURE",
+        "SIGNATURE_MISMATCH",
+        "NONCE_ALREADY_USED",
+        "UNSUPPORTED_CHAIN",
+        "UNSUPPORTED_TOKEN",
+        "INVALID_SUBSCRIPTION_STATE",
+        "NOT_FOUND",
+        "UNAUTHORIZED",
+        "FORBIDDEN",
+        "RATE_LIMITED",
+        "DUPLICATE",
+        "SERVICE_UNAVAILABLE",
+        "INTERNAL_ERROR",
+    ];
+
+    pub fn invalid_field(field: &str, reason: impl Into<String>) -> Self {
+        RelayerError::InvalidField {
+            field: field.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            RelayerError::Database(_) | RelayerError::DatabaseError(_) => "DATABASE_ERROR",
+            RelayerError::DatabaseConnection(_) => "DATABASE_CONNECTION_ERROR",
+            RelayerError::DatabaseMigration(_) => "DATABASE_MIGRATION_ERROR",
+            RelayerError::DatabaseConstraint(_) => "DATABASE_CONSTRAINT",
+            RelayerError::Ethereum(_) => "ETHEREUM_ERROR",
+            RelayerError::TransactionFailed(_) => "TRANSACTION_FAILED",
+            RelayerError::InsufficientGas(_) => "INSUFFICIENT_GAS",
+            RelayerError::NonceError(_) => "NONCE_ERROR",
+            RelayerError::ContractRevert(_) => "CONTRACT_REVERT",
+            RelayerError::RpcConnectionFailed(_) => "RPC_CONNECTION_FAILED",
+            RelayerError::Config(_) => "CONFIG_ERROR",
+            RelayerError::Validation(_) => "VALIDATION_ERROR",
+            RelayerError::InvalidField { .. } => "INVALID_FIELD",
+            RelayerError::InvalidSignature(_) => "INVALID_SIGNATURE",
+            RelayerError::SignatureMismatch { .. } => "SIGNATURE_MISMATCH",
+            RelayerError::NonceAlreadyUsed { .. } => "NONCE_ALREADY_USED",
+            RelayerError::UnsupportedChain { .. } => "UNSUPPORTED_CHAIN",
+            RelayerError::UnsupportedToken { .. } => "UNSUPPORTED_TOKEN",
+            RelayerError::InvalidSubscriptionState { .. } => "INVALID_SUBSCRIPTION_STATE",
+            RelayerError::NotFound(_) => "NOT_FOUND",
+            RelayerError::Unauthorized(_) => "UNAUTHORIZED",
+            RelayerError::Forbidden(_) => "FORBIDDEN",
+            RelayerError::RateLimited(_) => "RATE_LIMITED",
+            RelayerError::Duplicate(_) => "DUPLICATE",
+            RelayerError::Unavailable(_) => "SERVICE_UNAVAILABLE",
+            RelayerError::InternalError(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            RelayerError::Database(_)
+            | RelayerError::DatabaseError(_)
+            | RelayerError::DatabaseConnection(_)
+            | RelayerError::DatabaseMigration(_)
+            | RelayerError::Config(_)
+            | RelayerError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RelayerError::Ethereum(_) | RelayerError::RpcConnectionFailed(_) => {
+                StatusCode::BAD_GATEWAY
+            }
+            RelayerError::TransactionFailed(_)
+            | RelayerError::InsufficientGas(_)
+            | RelayerError::ContractRevert(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            RelayerError::DatabaseConstraint(_)
+            | RelayerError::Validation(_)
+            | RelayerError::InvalidField { .. }
+            | RelayerError::InvalidSignature(_)
+            | RelayerError::SignatureMismatch { .. }
+            | RelayerError::UnsupportedChain { .. }
+            | RelayerError::UnsupportedToken { .. } => StatusCode::BAD_REQUEST,
+            RelayerError::Forbidden(_) => StatusCode::FORBIDDEN,
+            RelayerError::NonceError(_)
+            | RelayerError::NonceAlreadyUsed { .. }
+            | RelayerError::InvalidSubscriptionState { .. }
+            | RelayerError::Duplicate(_) => StatusCode::CONFLICT,
+            RelayerError::NotFound(_) => StatusCode::NOT_FOUND,
+            RelayerError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            RelayerError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            RelayerError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    // structured fields for the response body, keyed the same way as the api types
+    pub fn details(&self) -> Option<Value> {
+        let details = match self {
+            RelayerError::InvalidField { field, .. } => json!({ "field": field }),
+            RelayerError::SignatureMismatch {
+                expected,
+                recovered,
+            } => json!({ "expected": expected, "recovered": recovered }),
+            RelayerError::NonceAlreadyUsed {
+                subscriber,
+                nonce,
+                chain,
+            } => json!({ "subscriber": subscriber, "nonce": nonce, "chain": chain }),
+            RelayerError::UnsupportedChain {
+                requested,
+                supported,
+            } => json!({ "chain": requested, "supportedChains": supported }),
+            RelayerError::UnsupportedToken {
+                token,
+                chain,
+                supported,
+            } => json!({ "token": token, "chain": chain, "supportedTokens": supported }),
+            RelayerError::InvalidSubscriptionState {
+                subscription_id,
+                status,
+                action,
+            } => json!({
+                "subscriptionId": subscription_id,
+                "status": status,
+                "action": action,
+            }),
+            RelayerError::RateLimited(retry_after) => {
+                json!({ "retryAfterSeconds": retry_after.as_secs().max(1) })
+            }
+            _ => return None,
+        };
+        Some(details)
+    }
+
+    // internal failures get a generic message so nothing about the backend leaks
+    pub(crate) fn public_message(&self) -> String {
+        match self {
+            RelayerError::Database(_) | RelayerError::DatabaseError(_) => {
+                "database error occurred".to_string()
+            }
+            RelayerError::DatabaseConnection(_) => "database connection error".to_string(),
+            RelayerError::DatabaseMigration(_) => "database migration error".to_string(),
+            RelayerError::DatabaseConstraint(_) => "database constraint violation".to_string(),
+            RelayerError::Ethereum(_) => "ethereum rpc error".to_string(),
+            RelayerError::Config(_) => "configuration error".to_string(),
+            RelayerError::InternalError(_) => "internal server error".to_string(),
+            RelayerError::TransactionFailed(msg)
+            | RelayerError::InsufficientGas(msg)
+            | RelayerError::NonceError(msg)
+            | RelayerError::ContractRevert(msg)
+            | RelayerError::RpcConnectionFailed(msg)
+            | RelayerError::Validation(msg)
+            | RelayerError::InvalidSignature(msg)
+            | RelayerError::NotFound(msg)
+            | RelayerError::Unauthorized(msg)
+            | RelayerError::Forbidden(msg)
+            | RelayerError::Duplicate(msg)
+            | RelayerError::Unavailable(msg) => msg.clone(),
+            RelayerError::InvalidField { reason, .. } => reason.clone(),
+            RelayerError::SignatureMismatch { .. } => {
+                "signature does not match subscriber address".to_string()
+            }
+            RelayerError::NonceAlreadyUsed { .. } => {
+                "nonce already used by this subscriber (replay attack prevented)".to_string()
+            }
+            RelayerError::RateLimited(retry_after) => format!(
+                "rate limit exceeded, retry in {}s",
+                retry_after.as_secs().max(1)
+            ),
+            RelayerError::UnsupportedChain { .. }
+            | RelayerError::UnsupportedToken { .. }
+            | RelayerError::InvalidSubscriptionState { .. } => self.to_string(),
+        }
+    }
+}
+
 impl IntoResponse for RelayerError {
     fn into_response(self) -> Response {
-        let (status, code, error_message) = match self {
-            RelayerError::Database(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "DATABASE_ERROR".to_string(),
-                "database error occurred".to_string(),
-            ),
-            RelayerError::DatabaseError(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "DATABASE_ERROR".to_string(),
-                "database error occurred".to_string(),
-            ),
-            RelayerError::DatabaseConnection(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "DATABASE_CONNECTION_ERROR".to_string(),
-                "database connection error".to_string(),
-            ),
-            RelayerError::DatabaseMigration(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "DATABASE_MIGRATION_ERROR".to_string(),
-                "database migration error".to_string(),
-            ),
-            RelayerError::DatabaseConstraint(_) => (
+        let retry_after = match &self {
+            RelayerError::RateLimited(retry_after) => Some(retry_after.as_secs().max(1)),
+            _ => None,
+        };
+
+        let body = ErrorResponse {
+            error: self.public_message(),
+            code: self.code().to_string(),
+            details: self.details(),
+        };
+
+        let mut response = (self.status(), Json(body)).into_response();
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
+    }
+}
+
+pub type Result<T> = std::result::Result<T, RelayerError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_errors_map_to_codes_statuses_and_details() {
+        let cases = [
+            (
+                RelayerError::invalid_field("startTime", "start time cannot be in the past"),
                 StatusCode::BAD_REQUEST,
-                "DATABASE_CONSTRAINT".to_string(),
-                "database constraint violation".to_string(),
-            ),
-            RelayerError::Ethereum(_) => (
-                StatusCode::BAD_GATEWAY,
-                "ETHEREUM_ERROR".to_string(),
-                "ethereum rpc error".to_string(),
-            ),
-            RelayerError::TransactionFailed(msg) => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                "TRANSACTION_FAILED".to_string(),
-                msg,
+                json!({ "field": "startTime" }),
             ),
-            RelayerError::InsufficientGas(msg) => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                "INSUFFICIENT_GAS".to_string(),
-                msg,
+            (
+                RelayerError::SignatureMismatch {
+                    expected: "0xaa".to_string(),
+                    recovered: "0xbb".to_string(),
+                },
+                StatusCode::BAD_REQUEST,
+                json!({ "expected": "0xaa", "recovered": "0xbb" }),
             ),
-            RelayerError::NonceError(msg) => (StatusCode::CONFLICT, "NONCE_ERROR".to_string(), msg),
-            RelayerError::ContractRevert(msg) => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                "CONTRACT_REVERT".to_string(),
-                msg,
+            (
+                RelayerError::NonceAlreadyUsed {
+                    subscriber: "0xaa".to_string(),
+                    nonce: 7,
+                    chain: "base".to_string(),
+                },
+                StatusCode::CONFLICT,
+                json!({ "subscriber": "0xaa", "nonce": 7, "chain": "base" }),
             ),
-            RelayerError::RpcConnectionFailed(msg) => (
-                StatusCode::BAD_GATEWAY,
-                "RPC_CONNECTION_FAILED".to_string(),
-                msg,
+            (
+                RelayerError::UnsupportedToken {
+                    token: "0xdd".to_string(),
+                    chain: "base".to_string(),
+                    supported: vec!["0x00".to_string()],
+                },
+                StatusCode::BAD_REQUEST,
+                json!({ "token": "0xdd", "chain": "base", "supportedTokens": ["0x00"] }),
             ),
-            RelayerError::Config(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "CONFIG_ERROR".to_string(),
-                "configuration error".to_string(),
+            (
+                RelayerError::InvalidSubscriptionState {
+                    subscription_id: "0x01".to_string(),
+                    status: "CANCELLED".to_string(),
+                    action: "resume".to_string(),
+                },
+                StatusCode::CONFLICT,
+                json!({ "subscriptionId": "0x01", "status": "CANCELLED", "action": "resume" }),
             ),
-            RelayerError::Validation(msg) => {
-                (StatusCode::BAD_REQUEST, "VALIDATION_ERROR".to_string(), msg)
-            }
-            RelayerError::NotFound(msg) => (StatusCode::NOT_FOUND, "NOT_FOUND".to_string(), msg),
-            RelayerError::Duplicate(msg) => (StatusCode::CONFLICT, "DUPLICATE".to_string(), msg),
-            RelayerError::InternalError(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "INTERNAL_ERROR".to_string(),
-                "internal server error".to_string(),
+            (
+                RelayerError::RateLimited(Duration::from_millis(200)),
+                StatusCode::TOO_MANY_REQUESTS,
+                json!({ "retryAfterSeconds": 1 }),
             ),
-        };
+        ];
 
-        let body = Json(json!({
-            "error": error_message,
-            "code": code,
-        }));
+        for (error, status, details) in cases {
+            assert!(RelayerError::CODES.contains(&error.code()), "{}", error);
+            assert_eq!(error.status(), status, "{}", error);
+            assert_eq!(error.details(), Some(details), "{}", error);
+        }
 
-        (status, body).into_response()
+        let plain = RelayerError::Validation("bad".to_string());
+        assert_eq!(plain.code(), "VALIDATION_ERROR");
+        assert_eq!(plain.details(), None);
+        // internal detail stays out of the response
+        assert_eq!(
+            RelayerError::InternalError("pool exhausted".to_string()).public_message(),
+            "internal server error"
+        );
     }
 }
-
-pub type Result<T> = std::result::Result<T, RelayerError>;
@@ -8,6 +8,7 @@ use serde_json::{json, Value};
 use tracing::warn;
 
 use crate::api::types::TransactionData;
+use crate::database::models::{ExecutionFilter, ExecutionStatus};
 use crate::error::{RelayerError, Result};
 use crate::utils::tokens;
 
@@ -110,7 +111,7 @@ pub struct MerchantTokenStatsRow {
     pub total_payments: i64,
     #[serde(rename = "chainId", deserialize_with = "deserialize_i64")]
     pub chain_id: i64,
-    #[serde(rename = "averageTransactionValue")]
+    #[serde(rename = "averageTransactionValue", default)]
     pub average_transaction_value: String,
 }
 
@@ -138,11 +139,34 @@ pub struct MerchantTransactionsResult {
     pub transactions: Vec<TransactionData>,
     pub total_count: u64,
     pub total_revenue: String,
-    pub token_totals: HashMap<String, String>,
     pub has_more: bool,
     pub explorer_url: Option<String>,
 }
 
+impl MerchantTransactionsResult {
+    fn empty() -> Self {
+        Self {
+            transactions: Vec::new(),
+            total_count: 0,
+            total_revenue: "0".to_string(),
+            has_more: false,
+            explorer_url: None,
+        }
+    }
+}
+
+// one page of a merchant's payments, newest first
+#[derive(Debug, Clone, Default)]
+pub struct PaymentHistoryQuery {
+    pub filter: ExecutionFilter,
+    // the indexer keys chains by id, so `filter.chain` is resolved by the caller
+    pub chain_id: Option<u64>,
+    // (unix seconds, payment id) of the last payment already returned
+    pub after: Option<(i64, String)>,
+    pub offset: u32,
+    pub limit: u32,
+}
+
 impl EnvioClient {
     pub fn new(graphql_endpoint: String, explorer_base_url: String) -> Result<Self> {
         let client = Client::builder()
@@ -177,21 +201,13 @@ impl EnvioClient {
     pub async fn get_merchant_transactions(
         &self,
         merchant_address: &str,
-        page: u32,
-        page_size: u32,
+        query: &PaymentHistoryQuery,
     ) -> Result<MerchantTransactionsResult> {
         match &self.mode {
-            EnvioClientMode::Stub => Ok(MerchantTransactionsResult {
-                transactions: Vec::new(),
-                total_count: 0,
-                total_revenue: "0".to_string(),
-                token_totals: HashMap::new(),
-                has_more: false,
-                explorer_url: None,
-            }),
+            EnvioClientMode::Stub => Ok(MerchantTransactionsResult::empty()),
             EnvioClientMode::Remote(remote) => {
                 remote
-                    .get_merchant_transactions(merchant_address, page, page_size)
+                    .get_merchant_transactions(merchant_address, query)
                     .await
             }
         }
@@ -303,16 +319,33 @@ impl RemoteEnvioClient {
     async fn get_merchant_transactions(
         &self,
         merchant_address: &str,
-        page: u32,
-        page_size: u32,
+        query: &PaymentHistoryQuery,
     ) -> Result<MerchantTransactionsResult> {
-        let offset = page * page_size;
+        let merchant_lower = merchant_address.to_lowercase();
+        let explorer_url = Some(self.build_explorer_url("payments", &merchant_lower));
+
+        // the indexer only sees payments that landed on chain
+        if query
+            .filter
+            .status
+            .is_some_and(|status| status != ExecutionStatus::Success)
+        {
+            return Ok(MerchantTransactionsResult {
+                explorer_url,
+                ..MerchantTransactionsResult::empty()
+            });
+        }
 
-        let query = r#"
-        query MerchantTransactions($merchant: String!, $limit: Int!, $offset: Int!) {
+        let graphql = r#"
+        query MerchantTransactions(
+            $where: Payment_bool_exp!,
+            $totalsWhere: Payment_bool_exp!,
+            $limit: Int!,
+            $offset: Int!
+        ) {
             Payment(
-                where: { merchant: { _eq: $merchant } }
-                order_by: { timestamp: desc }
+                where: $where
+                order_by: [{ timestamp: desc }, { id: desc }]
                 limit: $limit
                 offset: $offset
             ) {
@@ -333,7 +366,7 @@ impl RemoteEnvioClient {
                 nexusAttestationId
                 nexusVerified
             }
-            Payment_aggregate(where: { merchant: { _eq: $merchant } }) {
+            Payment_aggregate(where: $totalsWhere) {
                 aggregate {
                     count
                     sum {
@@ -344,14 +377,35 @@ impl RemoteEnvioClient {
         }
         "#;
 
+        let totals_where = payment_filter(&merchant_lower, query);
+        let page_where = match &query.after {
+            Some((timestamp, id)) => json!({
+                "_and": [
+                    totals_where,
+                    {
+                        "_or": [
+                            { "timestamp": { "_lt": timestamp.to_string() } },
+                            {
+                                "timestamp": { "_eq": timestamp.to_string() },
+                                "id": { "_lt": id }
+                            }
+                        ]
+                    }
+                ]
+            }),
+            None => totals_where.clone(),
+        };
+
+        // one extra row tells whether another page exists
         let variables = json!({
-            "merchant": merchant_address.to_lowercase(),
-            "limit": page_size,
-            "offset": offset,
+            "where": page_where,
+            "totalsWhere": totals_where,
+            "limit": query.limit + 1,
+            "offset": query.offset,
         });
 
         let response: GraphQlResponse<MerchantTransactionsData> =
-            self.execute_query(query, variables).await?;
+            self.execute_query(graphql, variables).await?;
 
         let data = response.data.ok_or_else(|| {
             RelayerError::InternalError("missing data in Envio response".to_string())
@@ -374,45 +428,21 @@ impl RemoteEnvioClient {
             .cloned()
             .unwrap_or_else(|| "0".to_string());
 
-        let transactions = data
+        let mut transactions = data
             .payment
             .into_iter()
             .map(|event| self.convert_event_to_transaction(event, merchant_address))
             .collect::<Result<Vec<_>>>()?;
 
-        let has_more = ((page + 1) as u64 * page_size as u64) < total_count;
-
-        let mut token_totals_map: HashMap<String, U256> = HashMap::new();
-        for tx in &transactions {
-            let amount = U256::from_dec_str(&tx.amount).unwrap_or_else(|_| U256::zero());
-            let entry = token_totals_map
-                .entry(tx.token_symbol.clone())
-                .or_insert(U256::zero());
-            *entry = entry.checked_add(amount).unwrap_or(U256::MAX);
-        }
-
-        let token_totals = token_totals_map
-            .into_iter()
-            .map(|(symbol, amount)| {
-                let decimals = match symbol.as_str() {
-                    "ETH" => 18,
-                    "PYUSD" => 6,
-                    _ => 18,
-                };
-                let formatted = tokens::format_token_amount(amount, decimals);
-                (symbol, formatted)
-            })
-            .collect::<HashMap<_, _>>();
+        let has_more = transactions.len() > query.limit as usize;
+        transactions.truncate(query.limit as usize);
 
         Ok(MerchantTransactionsResult {
             transactions,
             total_count,
             total_revenue,
-            token_totals,
             has_more,
-            explorer_url: Some(
-                self.build_explorer_url("payments", &merchant_address.to_lowercase()),
-            ),
+            explorer_url,
         })
     }
 
@@ -694,6 +724,7 @@ impl RemoteEnvioClient {
         merchant_address: &str,
     ) -> Result<TransactionData> {
         let PaymentEvent {
+            id,
             subscription_id,
             payment_number,
             amount,
@@ -718,6 +749,7 @@ impl RemoteEnvioClient {
             token_symbol.unwrap_or_else(|| tokens::get_token_symbol(&token_address).to_string());
 
         Ok(TransactionData {
+            id,
             subscription_id,
             subscriber: subscriber
                 .unwrap_or_else(|| "0x0000000000000000000000000000000000000000".to_string()),
@@ -732,6 +764,7 @@ impl RemoteEnvioClient {
             chain: chain_id.to_string(),
             token_address,
             token_symbol,
+            status: ExecutionStatus::Success.to_string(),
         })
     }
 
@@ -797,6 +830,38 @@ struct GraphQlError {
     message: String,
 }
 
+// the indexer's Payment entity id, as paymentEntityId in envio-indexer/src/EventHandlers.ts builds it.
+// payments scanned from HyperSync or RPC carry it too, so their cursors keep working when a page
+// falls back to Envio
+pub fn payment_entity_id(chain_id: u64, subscription_id: &str, payment_number: u64) -> String {
+    format!("{}_{}_{}", chain_id, subscription_id, payment_number)
+}
+
+// the Payment_bool_exp for a merchant's payments matching `query.filter`, without the cursor
+fn payment_filter(merchant: &str, query: &PaymentHistoryQuery) -> Value {
+    let filter = &query.filter;
+    let mut conditions = vec![json!({ "merchant": { "_eq": merchant } })];
+    if let Some(from) = filter.from {
+        conditions.push(json!({ "timestamp": { "_gte": from.timestamp().to_string() } }));
+    }
+    if let Some(to) = filter.to {
+        conditions.push(json!({ "timestamp": { "_lte": to.timestamp().to_string() } }));
+    }
+    if let Some(token) = &filter.token_address {
+        conditions.push(json!({ "token": { "_eq": token } }));
+    }
+    if let Some(chain_id) = query.chain_id {
+        conditions.push(json!({ "chainId": { "_eq": chain_id } }));
+    }
+    if let Some(subscription_id) = &filter.subscription_id {
+        conditions.push(json!({ "subscriptionId": { "_eq": subscription_id } }));
+    }
+    if let Some(min_amount) = filter.min_amount {
+        conditions.push(json!({ "amount": { "_gte": min_amount.to_string() } }));
+    }
+    json!({ "_and": conditions })
+}
+
 #[derive(Debug, Deserialize)]
 struct MerchantTransactionsData {
     #[serde(rename = "Payment")]
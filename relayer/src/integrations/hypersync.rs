@@ -1,8 +1,14 @@
 use crate::{
     blockchain::BlockchainClient,
+    chains::{ChainConfig, ChainRegistry},
     config::Config,
-    database::{models::Subscription, queries::Queries},
+    database::{
+        models::{Subscription, SubscriptionStatus, WebhookEventType},
+        queries::Queries,
+    },
     error::{RelayerError, Result},
+    utils::tokens,
+    webhooks,
 };
 use anyhow::anyhow;
 use chrono::{DateTime, TimeZone, Utc};
@@ -20,14 +26,19 @@ use hypersync_client::{
     simple_types::Event,
     Client as HypClient, ClientConfig, StreamConfig,
 };
+use serde_json::json;
 use std::{
     cmp::{max, min},
+    collections::HashMap,
     convert::TryFrom,
     num::NonZeroU64,
     sync::Arc,
     time::{Duration, Instant},
 };
-use tokio::time::sleep;
+use tokio::{
+    sync::{Mutex, OwnedMutexGuard},
+    time::sleep,
+};
 use tracing::{debug, info, warn};
 use url::Url;
 
@@ -43,16 +54,29 @@ const PAYMENT_EXECUTED_SIGNATURE: &str =
 const SUBSCRIPTION_CREATED_SIGNATURE: &str =
     "SubscriptionCreated(bytes32,address,address,address,uint256,uint256,uint256,uint256,uint256)";
 
+#[derive(Clone)]
+struct HyperSyncEndpoint {
+    chain_name: String,
+    url: String,
+    client: Arc<HypClient>,
+}
+
 #[derive(Clone)]
 pub struct HyperSyncClient {
-    sepolia_client: Arc<HypClient>,
-    base_client: Arc<HypClient>,
-    sepolia_url: String,
-    base_url: String,
+    // keyed by chain id; chains without a HyperSync url are served by RPC fallback
+    endpoints: HashMap<u64, HyperSyncEndpoint>,
+    // one per configured chain, so two syncs never move the same checkpoint at once
+    sync_locks: HashMap<u64, Arc<Mutex<()>>>,
     payment_topic: LogArgument,
     subscription_topic: LogArgument,
 }
 
+// held for the whole of a chain's sync; see HyperSyncClient::try_lock_chain
+pub struct ChainSyncGuard {
+    chain_id: u64,
+    _guard: OwnedMutexGuard<()>,
+}
+
 #[derive(Debug, Clone)]
 pub struct RawPaymentEvent {
     pub chain_id: u64,
@@ -65,6 +89,8 @@ pub struct RawPaymentEvent {
     pub fee: U256,
     pub relayer: String,
     pub block_number: u64,
+    // absent when the source did not return it; the confirmation checker fills it in
+    pub block_hash: Option<String>,
     pub transaction_hash: String,
 }
 
@@ -85,26 +111,46 @@ pub struct RawSubscriptionEvent {
 }
 
 impl HyperSyncClient {
-    pub fn new<S: Into<String>, B: Into<String>>(sepolia_url: S, base_url: B) -> Result<Self> {
+    pub fn new(chains: &ChainRegistry) -> Result<Self> {
         let payment_topic = Self::topic_from_signature(PAYMENT_EXECUTED_SIGNATURE)?;
         let subscription_topic = Self::topic_from_signature(SUBSCRIPTION_CREATED_SIGNATURE)?;
 
-        let sepolia_url = sepolia_url.into();
-        let base_url = base_url.into();
+        let mut endpoints = HashMap::new();
+        for chain in chains.iter() {
+            let Some(url) = chain.hypersync_endpoint() else {
+                continue;
+            };
+            endpoints.insert(
+                chain.chain_id,
+                HyperSyncEndpoint {
+                    chain_name: chain.name.clone(),
+                    url: url.to_string(),
+                    client: Arc::new(Self::build_client(url)?),
+                },
+            );
+        }
 
-        let sepolia_client = Arc::new(Self::build_client(&sepolia_url)?);
-        let base_client = Arc::new(Self::build_client(&base_url)?);
+        if endpoints.is_empty() {
+            return Err(RelayerError::Config(anyhow!(
+                "no HyperSync endpoints configured for any chain"
+            )));
+        }
 
-        info!(
-            "configured HyperSync clients (sepolia: {}, base: {})",
-            sepolia_url, base_url
-        );
+        let mut configured: Vec<String> = endpoints
+            .values()
+            .map(|endpoint| format!("{}: {}", endpoint.chain_name, endpoint.url))
+            .collect();
+        configured.sort();
+        info!("configured HyperSync clients ({})", configured.join(", "));
+
+        let sync_locks = chains
+            .iter()
+            .map(|chain| (chain.chain_id, Arc::new(Mutex::new(()))))
+            .collect();
 
         Ok(Self {
-            sepolia_client,
-            base_client,
-            sepolia_url,
-            base_url,
+            endpoints,
+            sync_locks,
             payment_topic,
             subscription_topic,
         })
@@ -152,39 +198,32 @@ impl HyperSyncClient {
     }
 
     fn client_for_chain(&self, chain_id: u64) -> Result<Arc<HypClient>> {
-        match chain_id {
-            11155111 => {
-                debug!("using HyperSync Sepolia endpoint {}", self.sepolia_url);
-                Ok(Arc::clone(&self.sepolia_client))
-            }
-            84532 => {
-                debug!("using HyperSync Base endpoint {}", self.base_url);
-                Ok(Arc::clone(&self.base_client))
+        match self.endpoints.get(&chain_id) {
+            Some(endpoint) => {
+                debug!(
+                    "using HyperSync {} endpoint {}",
+                    endpoint.chain_name, endpoint.url
+                );
+                Ok(Arc::clone(&endpoint.client))
             }
-            other => Err(RelayerError::Validation(format!(
+            None => Err(RelayerError::Validation(format!(
                 "unsupported chain id for HyperSync: {}",
-                other
+                chain_id
             ))),
         }
     }
 
-    fn chain_name(chain_id: u64) -> Result<&'static str> {
-        match chain_id {
-            11155111 => Ok("sepolia"),
-            84532 => Ok("base"),
-            other => Err(RelayerError::Validation(format!(
-                "unsupported chain id: {}",
-                other
-            ))),
-        }
+    fn chain_name(&self, chain_id: u64) -> Result<&str> {
+        self.endpoints
+            .get(&chain_id)
+            .map(|endpoint| endpoint.chain_name.as_str())
+            .ok_or_else(|| RelayerError::Validation(format!("unsupported chain id: {}", chain_id)))
     }
 
     fn endpoint_for_chain(&self, chain_id: u64) -> Option<&str> {
-        match chain_id {
-            11155111 => Some(self.sepolia_url.as_str()),
-            84532 => Some(self.base_url.as_str()),
-            _ => None,
-        }
+        self.endpoints
+            .get(&chain_id)
+            .map(|endpoint| endpoint.url.as_str())
     }
 
     async fn collect_events(
@@ -218,10 +257,9 @@ impl HyperSyncClient {
         let stream_config = StreamConfig::default();
 
         let response = client.clone().collect_events(query, stream_config).await.map_err(|e| {
-            let mut chain_iter = e.chain();
             let mut flattened = Vec::new();
             let mut not_found = false;
-            while let Some(cause) = chain_iter.next() {
+            for cause in e.chain() {
                 let cause_str = cause.to_string();
                 if cause_str.contains("404 Not Found") {
                     not_found = true;
@@ -236,7 +274,8 @@ impl HyperSyncClient {
             };
 
             if not_found {
-                let chain_label = Self::chain_name(chain_id)
+                let chain_label = self
+                    .chain_name(chain_id)
                     .unwrap_or("unknown chain")
                     .to_string();
                 let endpoint = self
@@ -526,6 +565,11 @@ impl HyperSyncClient {
             fee,
             relayer: format!("0x{:x}", relayer),
             block_number,
+            block_hash: event
+                .log
+                .block_hash
+                .as_ref()
+                .map(|hash| Self::bytes_to_hex(hash.as_ref())),
             transaction_hash: Self::bytes_to_hex(tx_hash.as_ref()),
         })
     }
@@ -563,6 +607,7 @@ impl HyperSyncClient {
             fee,
             relayer: format!("0x{:x}", relayer),
             block_number,
+            block_hash: log.block_hash.map(|hash| format!("{:?}", hash)),
             transaction_hash: Self::bytes_to_hex(tx_hash.as_bytes()),
         })
     }
@@ -809,176 +854,217 @@ impl HyperSyncClient {
         Ok(events)
     }
 
+    // every configured chain in turn; a failing chain doesn't stop the others, the first error is
+    // returned once all of them had their turn
     pub async fn sync_historical_data(
         &self,
         config: &Config,
         queries: Arc<Queries>,
         blockchain_client: Arc<BlockchainClient>,
     ) -> Result<()> {
-        let targets = vec![
-            (
-                11155111u64,
-                config.subscription_manager_address_sepolia.clone(),
-            ),
-            (84532u64, config.subscription_manager_address_base.clone()),
-        ];
+        let mut first_error = None;
+        for chain in config.chains.iter() {
+            let guard = self.lock_chain(chain.chain_id).await?;
+            if let Err(e) = self
+                .sync_chain(&guard, chain, &queries, &blockchain_client)
+                .await
+            {
+                warn!("HyperSync historical sync of {} failed: {}", chain.name, e);
+                first_error.get_or_insert(e);
+            }
+        }
 
-        for (chain_id, contract_address) in targets {
-            let chain_name = match Self::chain_name(chain_id) {
-                Ok(name) => name,
-                Err(err) => {
-                    warn!("skipping unsupported chain {}: {}", chain_id, err);
-                    continue;
-                }
-            };
-            let chain_numeric_id = blockchain_client.chain_id(chain_name)?;
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
 
-            let metadata = queries.get_sync_metadata(chain_id as i64).await?;
-            let from_block = metadata.last_synced_block.max(0) as u64;
-            let current_block = blockchain_client
-                .get_current_block_number(chain_name)
-                .await?;
+    // waits for any sync of the chain that is already running
+    pub async fn lock_chain(&self, chain_id: u64) -> Result<ChainSyncGuard> {
+        let lock = self.sync_lock(chain_id)?;
+        Ok(ChainSyncGuard {
+            chain_id,
+            _guard: lock.lock_owned().await,
+        })
+    }
 
-            if current_block <= from_block {
-                info!(
-                    "HyperSync up-to-date for {} (current block {}, last synced {})",
-                    chain_name, current_block, from_block
-                );
-                continue;
-            }
+    // None while another sync of the chain is running
+    pub fn try_lock_chain(&self, chain_id: u64) -> Result<Option<ChainSyncGuard>> {
+        let lock = self.sync_lock(chain_id)?;
+        Ok(lock.try_lock_owned().ok().map(|guard| ChainSyncGuard {
+            chain_id,
+            _guard: guard,
+        }))
+    }
+
+    fn sync_lock(&self, chain_id: u64) -> Result<Arc<Mutex<()>>> {
+        self.sync_locks.get(&chain_id).cloned().ok_or_else(|| {
+            RelayerError::InternalError(format!("chain {} is not configured", chain_id))
+        })
+    }
+
+    // syncs one chain from its checkpoint to the current block; the guard must be for that chain
+    pub async fn sync_chain(
+        &self,
+        guard: &ChainSyncGuard,
+        chain: &ChainConfig,
+        queries: &Arc<Queries>,
+        blockchain_client: &Arc<BlockchainClient>,
+    ) -> Result<()> {
+        if guard.chain_id != chain.chain_id {
+            return Err(RelayerError::InternalError(format!(
+                "sync guard for chain {} used to sync {}",
+                guard.chain_id, chain.name
+            )));
+        }
+
+        let chain_name = chain.name.as_str();
+        let chain_id = chain.chain_id;
+        let chain_numeric_id = blockchain_client.chain_id(chain_name)?;
+        let contract_address = chain.subscription_manager_address.clone();
+
+        let metadata = queries.get_sync_metadata(chain_id as i64).await?;
+        // a fresh checkpoint starts at 0; nothing before the start block needs scanning
+        let from_block = (metadata.last_synced_block.max(0) as u64)
+            .max(chain.start_block.unwrap_or(0).saturating_sub(1));
+        let current_block = blockchain_client
+            .get_current_block_number(chain_name)
+            .await?;
 
-            let sync_start_block = from_block.saturating_add(1);
+        if current_block <= from_block {
             info!(
-                "starting HyperSync historical sync for {} (from block {} to block {})",
-                chain_name, sync_start_block, current_block
+                "HyperSync up-to-date for {} (current block {}, last synced {})",
+                chain_name, current_block, from_block
             );
+            return Ok(());
+        }
 
-            let mut current_start = sync_start_block;
+        let sync_start_block = from_block.saturating_add(1);
+        info!(
+            "starting HyperSync historical sync for {} (from block {} to block {})",
+            chain_name, sync_start_block, current_block
+        );
 
-            while current_start <= current_block {
-                let chunk_end = current_start.saturating_add(CHUNK_SIZE).min(current_block);
+        let mut current_start = sync_start_block;
 
-                let chunk_timer = Instant::now();
-                let subscription_events = match self
-                    .get_historical_subscriptions(
+        while current_start <= current_block {
+            let chunk_end = current_start.saturating_add(CHUNK_SIZE).min(current_block);
+
+            let chunk_timer = Instant::now();
+            let subscription_events = match self
+                .get_historical_subscriptions(
+                    chain_numeric_id,
+                    &contract_address,
+                    current_start,
+                    chunk_end,
+                )
+                .await
+            {
+                Ok(events) => events,
+                Err(err) => {
+                    warn!(
+                        "HyperSync subscription chunk {}-{} failed for {}: {}, falling back to RPC",
+                        current_start, chunk_end, chain_name, err
+                    );
+                    Self::fetch_subscriptions_via_rpc_static(
+                        blockchain_client.as_ref(),
+                        chain_name,
                         chain_numeric_id,
                         &contract_address,
                         current_start,
                         chunk_end,
                     )
+                    .await?
+                }
+            };
+
+            info!(
+                "retrieved {} subscription events from HyperSync (chain={}, from={}, to={}, duration_ms={})",
+                subscription_events.len(),
+                chain_numeric_id,
+                current_start,
+                chunk_end,
+                chunk_timer.elapsed().as_millis()
+            );
+
+            for event in subscription_events {
+                if let Err(err) = self
+                    .persist_subscription_event(&event, chain_name, queries, blockchain_client)
                     .await
                 {
-                    Ok(events) => events,
-                    Err(err) => {
-                        warn!(
-                            "HyperSync subscription chunk {}-{} failed for {}: {}, falling back to RPC",
-                            current_start, chunk_end, chain_name, err
-                        );
-                        Self::fetch_subscriptions_via_rpc_static(
-                            blockchain_client.as_ref(),
-                            chain_name,
-                            chain_numeric_id,
-                            &contract_address,
-                            current_start,
-                            chunk_end,
-                        )
-                        .await?
-                    }
-                };
+                    warn!(
+                        "failed to persist subscription {} on {}: {}",
+                        event.subscription_id, chain_name, err
+                    );
+                }
+            }
 
-                info!(
-                    "retrieved {} subscription events from HyperSync (chain={}, from={}, to={}, duration_ms={})",
-                    subscription_events.len(),
+            let payment_timer = Instant::now();
+            let payment_events = match self
+                .get_historical_payments(
                     chain_numeric_id,
+                    &contract_address,
                     current_start,
                     chunk_end,
-                    chunk_timer.elapsed().as_millis()
-                );
-
-                for event in subscription_events {
-                    if let Err(err) = self
-                        .persist_subscription_event(
-                            &event,
-                            chain_name,
-                            &queries,
-                            &blockchain_client,
-                        )
-                        .await
-                    {
-                        warn!(
-                            "failed to persist subscription {} on {}: {}",
-                            event.subscription_id, chain_name, err
-                        );
-                    }
-                }
-
-                let payment_timer = Instant::now();
-                let payment_events = match self
-                    .get_historical_payments(
+                )
+                .await
+            {
+                Ok(events) => events,
+                Err(err) => {
+                    warn!(
+                        "HyperSync payment chunk {}-{} failed for {}: {}, falling back to RPC",
+                        current_start, chunk_end, chain_name, err
+                    );
+                    Self::fetch_payments_via_rpc_static(
+                        blockchain_client.as_ref(),
+                        chain_name,
                         chain_numeric_id,
                         &contract_address,
                         current_start,
                         chunk_end,
                     )
-                    .await
-                {
-                    Ok(events) => events,
-                    Err(err) => {
-                        warn!(
-                            "HyperSync payment chunk {}-{} failed for {}: {}, falling back to RPC",
-                            current_start, chunk_end, chain_name, err
-                        );
-                        Self::fetch_payments_via_rpc_static(
-                            blockchain_client.as_ref(),
-                            chain_name,
-                            chain_numeric_id,
-                            &contract_address,
-                            current_start,
-                            chunk_end,
-                        )
-                        .await?
-                    }
-                };
-
-                info!(
-                    "retrieved {} payment events from HyperSync (chain={}, from={}, to={}, duration_ms={})",
-                    payment_events.len(),
-                    chain_numeric_id,
-                    current_start,
-                    chunk_end,
-                    payment_timer.elapsed().as_millis()
-                );
-
-                for event in payment_events {
-                    if let Err(err) = self
-                        .persist_payment_event(&event, chain_name, &queries, &blockchain_client)
-                        .await
-                    {
-                        warn!(
-                            "failed to persist payment {} on {}: {}",
-                            event.transaction_hash, chain_name, err
-                        );
-                    }
+                    .await?
                 }
+            };
 
-                queries
-                    .update_sync_metadata(chain_id as i64, chunk_end as i64)
-                    .await?;
+            info!(
+                "retrieved {} payment events from HyperSync (chain={}, from={}, to={}, duration_ms={})",
+                payment_events.len(),
+                chain_numeric_id,
+                current_start,
+                chunk_end,
+                payment_timer.elapsed().as_millis()
+            );
 
-                if chunk_end == current_block {
-                    break;
+            for event in payment_events {
+                if let Err(err) = self
+                    .persist_payment_event(&event, chain_name, queries, blockchain_client)
+                    .await
+                {
+                    warn!(
+                        "failed to persist payment {} on {}: {}",
+                        event.transaction_hash, chain_name, err
+                    );
                 }
+            }
 
-                current_start = chunk_end.saturating_add(1);
-                sleep(Duration::from_millis(100)).await;
+            queries
+                .update_sync_metadata(chain_id as i64, chunk_end as i64)
+                .await?;
+
+            if chunk_end == current_block {
+                break;
             }
 
-            info!(
-                "HyperSync historical sync complete for {} (synced up to block {})",
-                chain_name, current_block
-            );
+            current_start = chunk_end.saturating_add(1);
+            sleep(Duration::from_millis(100)).await;
         }
 
+        info!(
+            "HyperSync historical sync complete for {} (synced up to block {})",
+            chain_name, current_block
+        );
         Ok(())
     }
 
@@ -1092,6 +1178,7 @@ impl HyperSyncClient {
                 &merchant_amount.to_string(),
                 &event.transaction_hash,
                 i64::try_from(event.block_number).unwrap_or(i64::MAX),
+                event.block_hash.as_deref(),
                 executed_at,
                 chain,
             )
@@ -1102,6 +1189,27 @@ impl HyperSyncClient {
                 "recorded payment {} for subscription {} on {} via HyperSync",
                 event.transaction_hash, event.subscription_id, chain
             );
+            // payments this relayer executed are already recorded, so these came from elsewhere
+            webhooks::publish(
+                queries,
+                &event.merchant,
+                WebhookEventType::PaymentExecuted,
+                json!({
+                    "subscriptionId": event.subscription_id,
+                    "subscriber": event.subscriber,
+                    "merchant": event.merchant,
+                    "chain": chain,
+                    "tokenAddress": event.token,
+                    "tokenSymbol": tokens::get_token_symbol(&event.token),
+                    "paymentNumber": event.payment_number,
+                    "amount": event.amount.to_string(),
+                    "fee": event.fee.to_string(),
+                    "transactionHash": event.transaction_hash,
+                    "blockNumber": event.block_number,
+                    "relayer": event.relayer,
+                }),
+            )
+            .await;
         } else {
             debug!(
                 "payment {} already present in database; skipping HyperSync insert",
@@ -1201,14 +1309,8 @@ impl HyperSyncClient {
     }
 
     fn status_to_string(status: u8) -> String {
-        match status {
-            0 => "ACTIVE",
-            1 => "PAUSED",
-            2 => "CANCELLED",
-            3 => "EXPIRED",
-            4 => "COMPLETED",
-            _ => "UNKNOWN",
-        }
-        .to_string()
+        SubscriptionStatus::from_on_chain(status)
+            .map(|status| status.to_string())
+            .unwrap_or_else(|| "UNKNOWN".to_string())
     }
 }
@@ -8,6 +8,7 @@
 pub mod api;
 pub mod avail;
 pub mod blockchain;
+pub mod chains;
 pub mod config;
 pub mod database;
 pub mod error;
@@ -15,16 +16,21 @@ pub mod integrations;
 pub mod metrics;
 pub mod scheduler;
 pub mod utils;
+pub mod webhooks;
 
 pub use avail::{AvailClient, AvailClientMode};
 pub use blockchain::BlockchainClient;
-pub use config::Config;
+pub use chains::{ChainConfig, ChainRegistry};
+pub use config::{AvailMode, Config};
 pub use database::Database;
 pub use error::{RelayerError, Result};
 pub use integrations::envio::EnvioClient;
 pub use integrations::hypersync::HyperSyncClient;
 pub use metrics::{Metrics, MetricsSnapshot};
-pub use scheduler::{Scheduler, SchedulerContext};
+pub use scheduler::{
+    Scheduler, SchedulerContext, SchedulerSettings, SchedulerSettingsHandle,
+    SchedulerSettingsUpdate,
+};
 
 #[derive(Clone)]
 pub struct AppState {
@@ -35,4 +41,6 @@ pub struct AppState {
     pub envio_client: EnvioClient,
     pub hypersync_client: Option<std::sync::Arc<HyperSyncClient>>,
     pub metrics: std::sync::Arc<Metrics>,
+    pub scheduler_settings: SchedulerSettingsHandle,
+    pub rate_limiter: std::sync::Arc<api::rate_limit::RateLimiter>,
 }
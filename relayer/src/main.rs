@@ -6,10 +6,11 @@ use tokio::signal;
 use tracing::{error, info, Level};
 use tracing_subscriber;
 
+use relayer::api::rate_limit::RateLimiter;
 use relayer::api::ApiServer;
 use relayer::{
     AppState, AvailClient, BlockchainClient, Config, Database, EnvioClient, HyperSyncClient,
-    Metrics, Scheduler, SchedulerContext,
+    Metrics, Scheduler, SchedulerContext, SchedulerSettings, SchedulerSettingsHandle,
 };
 
 #[tokio::main]
@@ -36,13 +37,14 @@ async fn main() -> Result<()> {
         "server will run on {}:{}",
         config.server_host, config.server_port
     );
+    let scheduler_settings = SchedulerSettingsHandle::new(SchedulerSettings::from_config(&config));
     info!(
-        "execution interval: {} seconds",
-        config.execution_interval_seconds
+        "payment cadence: {}",
+        scheduler_settings.current().cadence()
     );
     info!(
-        "max executions per batch: {}",
-        config.max_executions_per_batch
+        "max executions per batch: {}, per cycle: {}",
+        config.max_executions_per_batch, config.max_executions_per_cycle
     );
     if let Some(ref avail_url) = config.avail_rpc_url {
         info!("avail rpc url: {}", avail_url);
@@ -73,21 +75,33 @@ async fn main() -> Result<()> {
             e
         })?;
 
-    // initialize blockchain client
-    let blockchain_client = BlockchainClient::new(&config).await.map_err(|e| {
-        error!("failed to initialize blockchain client: {}", e);
-        e
-    })?;
+    let metrics = Arc::new(Metrics::new());
+
+    // initialize blockchain client; outbound transactions are persisted in the database
+    let blockchain_client = BlockchainClient::with_queries(&config, database.queries())
+        .await
+        .map_err(|e| {
+            error!("failed to initialize blockchain client: {}", e);
+            e
+        })?
+        .with_metrics(metrics.clone());
+
+    // settle anything a previous run left in flight before new nonces are handed out
+    blockchain_client
+        .reconcile_pending_transactions()
+        .await
+        .map_err(|e| {
+            error!("failed to reconcile pending transactions: {}", e);
+            e
+        })?;
 
     let avail_client = AvailClient::new(&config).await.map_err(|e| {
         error!("failed to initialise avail client: {}", e);
         e
     })?;
 
-    let metrics = Arc::new(Metrics::new());
-
-    let hypersync_client = if let Some((sepolia_url, base_url)) = config.hypersync_urls() {
-        match HyperSyncClient::new(sepolia_url.to_string(), base_url.to_string()) {
+    let hypersync_client = if config.hypersync_enabled() {
+        match HyperSyncClient::new(&config.chains) {
             Ok(client) => {
                 info!("hypersync client configured for accelerated historical sync");
                 Some(Arc::new(client))
@@ -120,6 +134,8 @@ async fn main() -> Result<()> {
         }
     };
 
+    let rate_limiter = Arc::new(RateLimiter::new(config.rate_limits.clone(), &database));
+
     // create application state
     let app_state = Arc::new(AppState {
         config: config.clone(),
@@ -129,6 +145,8 @@ async fn main() -> Result<()> {
         envio_client,
         hypersync_client: hypersync_client.clone(),
         metrics: metrics.clone(),
+        scheduler_settings: scheduler_settings.clone(),
+        rate_limiter,
     });
 
     info!("relayer service initialized successfully");
@@ -142,6 +160,7 @@ async fn main() -> Result<()> {
         metrics: metrics.clone(),
         config: config.clone(),
         pool: app_state.database.expect_pool().clone(),
+        settings: scheduler_settings.clone(),
     };
 
     let mut scheduler = Scheduler::new(scheduler_context).await.map_err(|e| {
@@ -176,8 +195,8 @@ async fn main() -> Result<()> {
         config.server_host, config.server_port
     );
     info!(
-        "- payment scheduler running every {} seconds",
-        config.execution_interval_seconds
+        "- payment scheduler running {}",
+        scheduler_settings.current().cadence()
     );
     info!(
         "- health endpoint: http://{}:{}/health",
@@ -1,6 +1,21 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use std::time::Duration;
+use utoipa::ToSchema;
+
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// seconds; rpc and api calls
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+// seconds; a cycle is capped by the scheduler's per-chain time budget
+const CYCLE_BUCKETS: &[f64] = &[0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0];
+
+type LabelSet = Vec<(&'static str, String)>;
 
 #[derive(Debug)]
 pub struct Metrics {
@@ -8,6 +23,15 @@ pub struct Metrics {
     hypersync_queries: AtomicU64,
     envio_total_nanos: AtomicU64,
     envio_queries: AtomicU64,
+    executions_attempted: CounterVec,
+    executions_succeeded: CounterVec,
+    executions_failed: CounterVec,
+    rpc_call_seconds: HistogramVec,
+    scheduler_cycle_seconds: HistogramVec,
+    api_request_seconds: HistogramVec,
+    due_subscriptions: GaugeVec,
+    relayer_native_balance: GaugeVec,
+    hypersync_lag_blocks: GaugeVec,
 }
 
 impl Metrics {
@@ -17,7 +41,155 @@ impl Metrics {
             hypersync_queries: AtomicU64::new(0),
             envio_total_nanos: AtomicU64::new(0),
             envio_queries: AtomicU64::new(0),
+            executions_attempted: CounterVec::default(),
+            executions_succeeded: CounterVec::default(),
+            executions_failed: CounterVec::default(),
+            rpc_call_seconds: HistogramVec::new(LATENCY_BUCKETS),
+            scheduler_cycle_seconds: HistogramVec::new(CYCLE_BUCKETS),
+            api_request_seconds: HistogramVec::new(LATENCY_BUCKETS),
+            due_subscriptions: GaugeVec::default(),
+            relayer_native_balance: GaugeVec::default(),
+            hypersync_lag_blocks: GaugeVec::default(),
+        }
+    }
+
+    // outcome of one due subscription; `failure_reason` is None when the payment went through
+    pub fn record_execution(&self, chain: &str, token: &str, failure_reason: Option<&str>) {
+        let labels = vec![("chain", chain.to_string()), ("token", token.to_string())];
+        self.executions_attempted.inc(labels.clone());
+        match failure_reason {
+            None => self.executions_succeeded.inc(labels),
+            Some(reason) => {
+                let mut labels = labels;
+                labels.push(("reason", reason.to_string()));
+                self.executions_failed.inc(labels);
+            }
+        }
+    }
+
+    pub fn observe_rpc_call(&self, chain: &str, method: &str, duration: Duration, success: bool) {
+        self.rpc_call_seconds.observe(
+            vec![
+                ("chain", chain.to_string()),
+                ("method", method.to_string()),
+                ("outcome", if success { "ok" } else { "error" }.to_string()),
+            ],
+            duration.as_secs_f64(),
+        );
+    }
+
+    pub fn observe_scheduler_cycle(&self, chain: &str, duration: Duration) {
+        self.scheduler_cycle_seconds
+            .observe(vec![("chain", chain.to_string())], duration.as_secs_f64());
+    }
+
+    // `route` is the matched route template, not the raw path, to keep label values bounded
+    pub fn observe_api_request(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        self.api_request_seconds.observe(
+            vec![
+                ("method", method.to_string()),
+                ("route", route.to_string()),
+                ("status", status.to_string()),
+            ],
+            duration.as_secs_f64(),
+        );
+    }
+
+    pub fn set_due_subscriptions(&self, chain: &str, count: i64) {
+        self.due_subscriptions
+            .set(vec![("chain", chain.to_string())], count as f64);
+    }
+
+    pub fn set_relayer_native_balance(&self, chain: &str, balance_ether: f64) {
+        self.relayer_native_balance
+            .set(vec![("chain", chain.to_string())], balance_ether);
+    }
+
+    pub fn set_hypersync_lag(&self, chain: &str, blocks: u64) {
+        self.hypersync_lag_blocks
+            .set(vec![("chain", chain.to_string())], blocks as f64);
+    }
+
+    // prometheus text exposition format, version 0.0.4
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        self.executions_attempted.render(
+            &mut out,
+            "relayer_executions_attempted_total",
+            "Due subscriptions the scheduler tried to charge.",
+        );
+        self.executions_succeeded.render(
+            &mut out,
+            "relayer_executions_succeeded_total",
+            "Payments executed and recorded.",
+        );
+        self.executions_failed.render(
+            &mut out,
+            "relayer_executions_failed_total",
+            "Payments that failed, by failure class.",
+        );
+        self.rpc_call_seconds.render(
+            &mut out,
+            "relayer_rpc_call_duration_seconds",
+            "Latency of blockchain rpc calls.",
+        );
+        self.scheduler_cycle_seconds.render(
+            &mut out,
+            "relayer_scheduler_cycle_duration_seconds",
+            "Duration of one payment cycle per chain.",
+        );
+        self.api_request_seconds.render(
+            &mut out,
+            "relayer_api_request_duration_seconds",
+            "Latency of api requests by route.",
+        );
+        self.due_subscriptions.render(
+            &mut out,
+            "relayer_due_subscriptions",
+            "Subscriptions due for payment and not backing off.",
+        );
+        self.relayer_native_balance.render(
+            &mut out,
+            "relayer_native_balance_ether",
+            "Native gas token balance of the relayer account.",
+        );
+        self.hypersync_lag_blocks.render(
+            &mut out,
+            "relayer_hypersync_lag_blocks",
+            "Blocks between the chain head and the last HyperSync-synced block.",
+        );
+
+        let hypersync_seconds =
+            self.hypersync_total_nanos.load(Ordering::Relaxed) as f64 / 1_000_000_000_f64;
+        let envio_seconds =
+            self.envio_total_nanos.load(Ordering::Relaxed) as f64 / 1_000_000_000_f64;
+        for (name, help, value) in [
+            (
+                "relayer_hypersync_queries_total",
+                "HyperSync historical queries.",
+                self.hypersync_queries.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "relayer_hypersync_query_seconds_total",
+                "Time spent in HyperSync historical queries.",
+                hypersync_seconds,
+            ),
+            (
+                "relayer_envio_queries_total",
+                "Envio GraphQL queries.",
+                self.envio_queries.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "relayer_envio_query_seconds_total",
+                "Time spent in Envio GraphQL queries.",
+                envio_seconds,
+            ),
+        ] {
+            write_header(&mut out, name, help, "counter");
+            let _ = writeln!(out, "{} {}", name, value);
         }
+
+        out
     }
 
     pub fn record_hypersync_query(&self, duration: Duration) {
@@ -36,17 +208,17 @@ impl Metrics {
         let hypersync_queries = self.hypersync_queries.load(Ordering::Relaxed);
         let envio_queries = self.envio_queries.load(Ordering::Relaxed);
 
-        let hypersync_average_ns = if hypersync_queries > 0 {
-            self.hypersync_total_nanos.load(Ordering::Relaxed) / hypersync_queries
-        } else {
-            0
-        };
+        let hypersync_average_ns = self
+            .hypersync_total_nanos
+            .load(Ordering::Relaxed)
+            .checked_div(hypersync_queries)
+            .unwrap_or(0);
 
-        let envio_average_ns = if envio_queries > 0 {
-            self.envio_total_nanos.load(Ordering::Relaxed) / envio_queries
-        } else {
-            0
-        };
+        let envio_average_ns = self
+            .envio_total_nanos
+            .load(Ordering::Relaxed)
+            .checked_div(envio_queries)
+            .unwrap_or(0);
 
         MetricsSnapshot {
             hypersync_queries,
@@ -63,7 +235,7 @@ impl Default for Metrics {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct MetricsSnapshot {
     #[serde(rename = "hypersyncQueries")]
     pub hypersync_queries: u64,
@@ -74,3 +246,203 @@ pub struct MetricsSnapshot {
     #[serde(rename = "envioAverageMs")]
     pub envio_average_ms: f64,
 }
+
+#[derive(Debug, Default)]
+struct CounterVec {
+    series: Mutex<BTreeMap<LabelSet, u64>>,
+}
+
+impl CounterVec {
+    fn inc(&self, labels: LabelSet) {
+        *self.series.lock().unwrap().entry(labels).or_default() += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        write_header(out, name, help, "counter");
+        for (labels, value) in self.series.lock().unwrap().iter() {
+            let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct GaugeVec {
+    series: Mutex<BTreeMap<LabelSet, f64>>,
+}
+
+impl GaugeVec {
+    fn set(&self, labels: LabelSet, value: f64) {
+        self.series.lock().unwrap().insert(labels, value);
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        write_header(out, name, help, "gauge");
+        for (labels, value) in self.series.lock().unwrap().iter() {
+            let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct HistogramVec {
+    buckets: &'static [f64],
+    series: Mutex<BTreeMap<LabelSet, HistogramSeries>>,
+}
+
+#[derive(Debug)]
+struct HistogramSeries {
+    // per-bucket counts, not cumulative; the last slot is +Inf
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl HistogramVec {
+    fn new(buckets: &'static [f64]) -> Self {
+        Self {
+            buckets,
+            series: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn observe(&self, labels: LabelSet, value: f64) {
+        let mut series = self.series.lock().unwrap();
+        let entry = series.entry(labels).or_insert_with(|| HistogramSeries {
+            counts: vec![0; self.buckets.len() + 1],
+            sum: 0.0,
+            count: 0,
+        });
+        let bucket = self
+            .buckets
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.buckets.len());
+        entry.counts[bucket] += 1;
+        entry.sum += value;
+        entry.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        write_header(out, name, help, "histogram");
+        for (labels, series) in self.series.lock().unwrap().iter() {
+            let mut cumulative = 0;
+            for (index, count) in series.counts.iter().enumerate() {
+                cumulative += count;
+                let bound = self
+                    .buckets
+                    .get(index)
+                    .map(|bound| bound.to_string())
+                    .unwrap_or_else(|| "+Inf".to_string());
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{} {}",
+                    name,
+                    format_labels(labels, Some(&bound)),
+                    cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_sum{} {}",
+                name,
+                format_labels(labels, None),
+                series.sum
+            );
+            let _ = writeln!(
+                out,
+                "{}_count{} {}",
+                name,
+                format_labels(labels, None),
+                series.count
+            );
+        }
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn format_labels(labels: &LabelSet, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label_value(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_counters_and_histograms() {
+        let metrics = Metrics::new();
+        metrics.record_execution("base", "USDC", None);
+        metrics.record_execution("base", "USDC", Some("INSUFFICIENT_BALANCE"));
+        metrics.observe_rpc_call("base", "get_block_number", Duration::from_millis(30), true);
+        metrics.observe_rpc_call("base", "get_block_number", Duration::from_secs(60), true);
+        metrics.set_due_subscriptions("base", 7);
+
+        let text = metrics.render_prometheus();
+        assert!(text.contains("# TYPE relayer_executions_attempted_total counter"));
+        assert!(
+            text.contains("relayer_executions_attempted_total{chain=\"base\",token=\"USDC\"} 2")
+        );
+        assert!(
+            text.contains("relayer_executions_succeeded_total{chain=\"base\",token=\"USDC\"} 1")
+        );
+        assert!(text.contains(
+            "relayer_executions_failed_total{chain=\"base\",token=\"USDC\",reason=\"INSUFFICIENT_BALANCE\"} 1"
+        ));
+
+        let series = "chain=\"base\",method=\"get_block_number\",outcome=\"ok\"";
+        assert!(text.contains("# TYPE relayer_rpc_call_duration_seconds histogram"));
+        // buckets are cumulative: 30ms lands in 0.05, 60s only in +Inf
+        assert!(text.contains(&format!(
+            "relayer_rpc_call_duration_seconds_bucket{{{},le=\"0.025\"}} 0",
+            series
+        )));
+        assert!(text.contains(&format!(
+            "relayer_rpc_call_duration_seconds_bucket{{{},le=\"0.05\"}} 1",
+            series
+        )));
+        assert!(text.contains(&format!(
+            "relayer_rpc_call_duration_seconds_bucket{{{},le=\"30\"}} 1",
+            series
+        )));
+        assert!(text.contains(&format!(
+            "relayer_rpc_call_duration_seconds_bucket{{{},le=\"+Inf\"}} 2",
+            series
+        )));
+        assert!(text.contains(&format!(
+            "relayer_rpc_call_duration_seconds_count{{{}}} 2",
+            series
+        )));
+        assert!(text.contains("relayer_due_subscriptions{chain=\"base\"} 7"));
+        assert!(text.contains("relayer_hypersync_queries_total 0"));
+    }
+
+    #[test]
+    fn test_label_values_are_escaped() {
+        assert_eq!(
+            format_labels(&vec![("route", "a\"b\\c\n".to_string())], Some("1")),
+            "{route=\"a\\\"b\\\\c\\n\",le=\"1\"}"
+        );
+    }
+}
@@ -1,21 +1,36 @@
-use crate::avail::{AvailClient, AvailClientMode};
-use crate::blockchain::BlockchainClient;
-use crate::database::models::{ExecutionRecord, IntentCache, Subscription, SubscriptionStatus};
+use crate::avail::{outbox, AvailClient, AvailClientMode};
+use crate::blockchain::{BlockchainClient, PaymentPreflight, PreflightRequest};
+use crate::chains::ChainRegistry;
+use crate::database::models::{
+    Execution, ExecutionRecord, FailureClass, IntentCache, OutboundTxStatus, PendingOperation,
+    PendingOperationStatus, Subscription, SubscriptionStatus, WebhookEventType,
+};
 use crate::database::queries::Queries;
 use crate::error::{RelayerError, Result};
 use crate::integrations::hypersync::HyperSyncClient;
 use crate::metrics::Metrics;
+use crate::utils::backoff::backoff;
 use crate::utils::tokens;
+use crate::webhooks;
 use crate::Config;
-use chrono::Utc;
-use ethers::types::{Address, H256, U256};
-use serde_json::to_value;
-use sqlx::{PgPool, Row};
+use chrono::{DateTime, Utc};
+use ethers::types::{Address, TransactionReceipt, H256, U256};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, to_value};
+use sqlx::pool::PoolConnection;
+use sqlx::{PgPool, Postgres, Row};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio_cron_scheduler::{Job, JobScheduler};
+use tokio::sync::{broadcast, watch};
+use tokio::task::{JoinError, JoinHandle, JoinSet};
+use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
 use tracing::{debug, error, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
 
 // secure subscription id conversion - only accepts contract-format hex strings
 // fixes critical security vulnerability: hash collision attack prevention
@@ -66,6 +81,7 @@ pub enum ValidationResult {
 pub struct ExecutionResult {
     pub transaction_hash: H256,
     pub block_number: u64,
+    pub block_hash: Option<H256>,
     pub gas_used: U256,
     pub gas_price: U256,
     pub fee_paid: U256,
@@ -74,12 +90,241 @@ pub struct ExecutionResult {
 }
 
 // constants for security and performance limits
-const MAX_SUBSCRIPTIONS_PER_BATCH: i64 = 100;
-const MAX_PROCESSING_TIME_SECONDS: u64 = 300; // 5 minutes max per batch
-const MAX_RETRY_ATTEMPTS: u32 = 3;
+const MAX_PROCESSING_TIME_SECONDS: u64 = 300; // 5 minutes max per chain per cycle
+const MAX_CONCURRENT_EXECUTIONS_PER_CHAIN: usize = 4;
+const PROCESSING_LOCK_NAMESPACE: i64 = 12345;
+// admin cycle requests buffered per scheduler before they are coalesced into one full run
+const CYCLE_REQUEST_CAPACITY: usize = 16;
 const BASE_RETRY_DELAY_SECONDS: u64 = 30;
+// chain errors (rpc outages, dropped transactions) and relayer errors clear on their own, so
+// they only dead-letter after this many times the configured failure budget
+const CHAIN_ERROR_BUDGET_MULTIPLIER: i64 = 10;
 const MAX_ID_LENGTH: usize = 66; // 0x + 64 hex chars
 const PROTOCOL_FEE_BPS: u32 = 50; // 0.5% protocol fee
+const MAX_PENDING_OPERATIONS_PER_BATCH: i64 = 100;
+// without an outbound_transactions row to say otherwise, an unmined creation is given up after this
+const PENDING_OPERATION_TIMEOUT_HOURS: i64 = 24;
+const MAX_UNCONFIRMED_EXECUTIONS_PER_BATCH: i64 = 200;
+
+// a failed payment and the class that decides how long to back off before the next attempt
+#[derive(Debug)]
+pub struct PaymentFailure {
+    pub class: FailureClass,
+    pub error: RelayerError,
+}
+
+impl PaymentFailure {
+    pub fn new(class: FailureClass, error: RelayerError) -> Self {
+        Self { class, error }
+    }
+}
+
+impl From<RelayerError> for PaymentFailure {
+    fn from(error: RelayerError) -> Self {
+        Self {
+            class: classify_failure(&error),
+            error,
+        }
+    }
+}
+
+// errors that aren't already classified by pre-flight: only reverts are blamed on the
+// subscription; the relayer's own failures and everything else are treated as transient
+pub fn classify_failure(error: &RelayerError) -> FailureClass {
+    match error {
+        RelayerError::ContractRevert(_) => FailureClass::ContractRevert,
+        RelayerError::TransactionFailed(msg) if msg.to_lowercase().contains("revert") => {
+            FailureClass::ContractRevert
+        }
+        RelayerError::Database(_)
+        | RelayerError::DatabaseError(_)
+        | RelayerError::DatabaseConnection(_)
+        | RelayerError::DatabaseMigration(_)
+        | RelayerError::DatabaseConstraint(_)
+        | RelayerError::Config(_)
+        | RelayerError::NotFound(_)
+        | RelayerError::Validation(_)
+        | RelayerError::InvalidField { .. }
+        | RelayerError::UnsupportedChain { .. }
+        | RelayerError::UnsupportedToken { .. }
+        | RelayerError::InternalError(_) => FailureClass::RelayerError,
+        _ => FailureClass::ChainError,
+    }
+}
+
+// base delay and cap of the retry backoff for each failure class
+fn retry_bounds(class: FailureClass) -> (Duration, Duration) {
+    let (base_seconds, cap_seconds) = match class {
+        FailureClass::InsufficientBalance => (3_600, 86_400),
+        FailureClass::MissingAllowance => (21_600, 172_800),
+        FailureClass::ChainError | FailureClass::RelayerError => (BASE_RETRY_DELAY_SECONDS, 3_600),
+        FailureClass::ContractRevert => (900, 43_200),
+    };
+    (
+        Duration::from_secs(base_seconds),
+        Duration::from_secs(cap_seconds),
+    )
+}
+
+// scheduler knobs seeded from Config; the admin api can change them while the process runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulerSettings {
+    pub execution_interval_seconds: u64,
+    /// cron expression (with a seconds field) that replaces the interval when set
+    pub cron: Option<String>,
+    pub max_executions_per_batch: i64,
+    pub max_executions_per_cycle: i64,
+    pub max_retry_attempts: u32,
+    pub max_failures_before_dead_letter: i64,
+}
+
+/// partial update; omitted fields keep their current value and an empty cron falls back to
+/// the interval
+#[derive(Debug, Clone, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SchedulerSettingsUpdate {
+    pub execution_interval_seconds: Option<u64>,
+    pub cron: Option<String>,
+    pub max_executions_per_batch: Option<i64>,
+    pub max_executions_per_cycle: Option<i64>,
+    pub max_retry_attempts: Option<u32>,
+    pub max_failures_before_dead_letter: Option<i64>,
+}
+
+impl SchedulerSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            execution_interval_seconds: config.execution_interval_seconds,
+            cron: config.scheduler_cron.clone(),
+            max_executions_per_batch: config.max_executions_per_batch,
+            max_executions_per_cycle: config.max_executions_per_cycle,
+            max_retry_attempts: config.max_retry_attempts,
+            max_failures_before_dead_letter: config.max_failures_before_dead_letter,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: &str| Err(RelayerError::Validation(message.to_string()));
+
+        if !(1..=86_400).contains(&self.execution_interval_seconds) {
+            return invalid("executionIntervalSeconds must be between 1 and 86400");
+        }
+        if let Some(cron) = &self.cron {
+            Job::new_async(cron.as_str(), |_uuid, _l| Box::pin(async {})).map_err(|e| {
+                RelayerError::Validation(format!("invalid cron expression '{}': {}", cron, e))
+            })?;
+        }
+        if !(1..=1_000).contains(&self.max_executions_per_batch) {
+            return invalid("maxExecutionsPerBatch must be between 1 and 1000");
+        }
+        if self.max_executions_per_cycle < self.max_executions_per_batch
+            || self.max_executions_per_cycle > 100_000
+        {
+            return invalid(
+                "maxExecutionsPerCycle must be between maxExecutionsPerBatch and 100000",
+            );
+        }
+        if !(1..=10).contains(&self.max_retry_attempts) {
+            return invalid("maxRetryAttempts must be between 1 and 10");
+        }
+        if !(1..=100).contains(&self.max_failures_before_dead_letter) {
+            return invalid("maxFailuresBeforeDeadLetter must be between 1 and 100");
+        }
+
+        Ok(())
+    }
+
+    pub fn apply(&self, update: &SchedulerSettingsUpdate) -> Result<Self> {
+        let mut next = self.clone();
+        if let Some(interval) = update.execution_interval_seconds {
+            next.execution_interval_seconds = interval;
+        }
+        if let Some(cron) = &update.cron {
+            next.cron = Some(cron.trim().to_string()).filter(|cron| !cron.is_empty());
+        }
+        if let Some(batch) = update.max_executions_per_batch {
+            next.max_executions_per_batch = batch;
+        }
+        if let Some(cycle) = update.max_executions_per_cycle {
+            next.max_executions_per_cycle = cycle;
+        }
+        if let Some(retries) = update.max_retry_attempts {
+            next.max_retry_attempts = retries;
+        }
+        if let Some(failures) = update.max_failures_before_dead_letter {
+            next.max_failures_before_dead_letter = failures;
+        }
+
+        next.validate()?;
+        Ok(next)
+    }
+
+    pub fn cadence(&self) -> String {
+        match &self.cron {
+            Some(cron) => format!("cron '{}'", cron),
+            None => format!("every {} seconds", self.execution_interval_seconds),
+        }
+    }
+
+    fn same_cadence(&self, other: &Self) -> bool {
+        self.execution_interval_seconds == other.execution_interval_seconds
+            && self.cron == other.cron
+    }
+}
+
+// shared between the api and the scheduler; payment jobs are rescheduled when the cadence changes
+#[derive(Clone)]
+pub struct SchedulerSettingsHandle {
+    sender: Arc<watch::Sender<SchedulerSettings>>,
+    // immediate cycle requests from the admin api, for one chain or (None) all of them
+    cycle_requests: broadcast::Sender<Option<String>>,
+}
+
+impl SchedulerSettingsHandle {
+    pub fn new(settings: SchedulerSettings) -> Self {
+        Self {
+            sender: Arc::new(watch::Sender::new(settings)),
+            cycle_requests: broadcast::channel(CYCLE_REQUEST_CAPACITY).0,
+        }
+    }
+
+    pub fn current(&self) -> SchedulerSettings {
+        self.sender.borrow().clone()
+    }
+
+    pub fn update(&self, update: &SchedulerSettingsUpdate) -> Result<SchedulerSettings> {
+        let mut result = None;
+        self.sender
+            .send_if_modified(|current| match current.apply(update) {
+                Ok(next) => {
+                    let changed = next != *current;
+                    *current = next.clone();
+                    result = Some(Ok(next));
+                    changed
+                }
+                Err(e) => {
+                    result = Some(Err(e));
+                    false
+                }
+            });
+        result.expect("settings update closure always runs")
+    }
+
+    fn subscribe(&self) -> watch::Receiver<SchedulerSettings> {
+        self.sender.subscribe()
+    }
+
+    // asks the scheduler in this process for a payment cycle now, outside the regular cadence;
+    // false when no scheduler is running. the cycle still needs the chain's processing lock
+    pub fn request_cycle(&self, chain: Option<&str>) -> bool {
+        self.cycle_requests.send(chain.map(str::to_string)).is_ok()
+    }
+
+    fn subscribe_cycle_requests(&self) -> broadcast::Receiver<Option<String>> {
+        self.cycle_requests.subscribe()
+    }
+}
 
 pub struct Scheduler {
     queries: Arc<Queries>,
@@ -90,6 +335,9 @@ pub struct Scheduler {
     config: Config,
     job_scheduler: JobScheduler,
     pool: PgPool, // for distributed locking
+    settings: SchedulerSettingsHandle,
+    settings_watcher: Option<JoinHandle<()>>,
+    cycle_request_watcher: Option<JoinHandle<()>>,
 }
 
 pub struct SchedulerContext {
@@ -100,6 +348,7 @@ pub struct SchedulerContext {
     pub metrics: Arc<Metrics>,
     pub config: Config,
     pub pool: PgPool,
+    pub settings: SchedulerSettingsHandle,
 }
 
 impl Scheduler {
@@ -114,6 +363,7 @@ impl Scheduler {
             metrics,
             config,
             pool,
+            settings,
         } = context;
 
         let job_scheduler = JobScheduler::new().await.map_err(|e| {
@@ -129,10 +379,21 @@ impl Scheduler {
             config,
             job_scheduler,
             pool,
+            settings,
+            settings_watcher: None,
+            cycle_request_watcher: None,
         };
 
         scheduler.launch_initial_historical_sync();
         scheduler.setup_payment_job().await?;
+        scheduler.setup_pending_operations_job().await?;
+        scheduler.setup_confirmation_job().await?;
+        scheduler.setup_webhook_delivery_job().await?;
+        // with avail disabled nothing is queued, so there is no outbox to drain
+        if scheduler.avail_client.mode() != AvailClientMode::Disabled {
+            scheduler.setup_avail_submission_job().await?;
+        }
+        scheduler.setup_metrics_job().await?;
 
         info!("payment scheduler initialized successfully");
         Ok(scheduler)
@@ -171,69 +432,217 @@ impl Scheduler {
         }
     }
 
+    // one job per chain, each with its own lock and time budget so a slow chain can't stall the rest
     async fn setup_payment_job(&mut self) -> Result<()> {
+        let context = PaymentJobContext {
+            queries: Arc::clone(&self.queries),
+            blockchain_client: Arc::clone(&self.blockchain_client),
+            avail_client: Arc::clone(&self.avail_client),
+            metrics: Arc::clone(&self.metrics),
+            pool: self.pool.clone(),
+            settings: self.settings.clone(),
+        };
+        let chains: Vec<(String, u64)> = self
+            .config
+            .chains
+            .iter()
+            .map(|chain| (chain.name.clone(), chain.chain_id))
+            .collect();
+
+        self.cycle_request_watcher = Some(spawn_cycle_request_watcher(
+            self.settings.subscribe_cycle_requests(),
+            chains.clone(),
+            context.clone(),
+        ));
+
+        let mut receiver = self.settings.subscribe();
+        let settings = receiver.borrow_and_update().clone();
+        let job_ids = add_payment_jobs(&self.job_scheduler, &chains, &context, &settings).await?;
+        info!("payment jobs scheduled {}", settings.cadence());
+
+        let job_scheduler = self.job_scheduler.clone();
+        self.settings_watcher = Some(tokio::spawn(async move {
+            let mut active = settings;
+            let mut job_ids = job_ids;
+
+            while receiver.changed().await.is_ok() {
+                let next = receiver.borrow_and_update().clone();
+                if next.same_cadence(&active) {
+                    continue;
+                }
+
+                for job_id in job_ids.drain(..) {
+                    if let Err(e) = job_scheduler.remove(&job_id).await {
+                        error!("failed to remove payment job {}: {}", job_id, e);
+                    }
+                }
+                match add_payment_jobs(&job_scheduler, &chains, &context, &next).await {
+                    Ok(ids) => {
+                        info!("payment jobs rescheduled {}", next.cadence());
+                        job_ids = ids;
+                    }
+                    Err(e) => error!("failed to reschedule payment jobs: {}", e),
+                }
+                active = next;
+            }
+        }));
+
+        Ok(())
+    }
+
+    async fn setup_pending_operations_job(&mut self) -> Result<()> {
         let queries = Arc::clone(&self.queries);
         let blockchain_client = Arc::clone(&self.blockchain_client);
-        let avail_client = Arc::clone(&self.avail_client);
-        let pool = self.pool.clone();
 
-        // use safer cron expression: every 60 seconds with proper timing
-        let job = Job::new_async("0 */1 * * * *", move |_uuid, _l| {
+        let job = Job::new_async("*/15 * * * * *", move |_uuid, _l| {
             let queries = Arc::clone(&queries);
             let blockchain_client = Arc::clone(&blockchain_client);
-            let avail_client = Arc::clone(&avail_client);
-            let pool = pool.clone();
 
             Box::pin(async move {
-                info!("starting payment processing cycle with distributed lock");
-
-                // implement distributed locking to prevent concurrent execution
-                match acquire_processing_lock(&pool).await {
-                    Ok(lock_acquired) => {
-                        if lock_acquired {
-                            info!("acquired processing lock, starting payment processing");
-
-                            // set processing timeout
-                            let processing_result = tokio::time::timeout(
-                                Duration::from_secs(MAX_PROCESSING_TIME_SECONDS),
-                                process_payments_job_safe(
-                                    queries,
-                                    blockchain_client,
-                                    avail_client,
-                                    pool.clone(),
-                                ),
-                            )
-                            .await;
-
-                            match processing_result {
-                                Ok(Ok(())) => info!("payment processing completed successfully"),
-                                Ok(Err(e)) => error!("payment processing failed: {}", e),
-                                Err(_) => error!(
-                                    "payment processing timed out after {} seconds",
-                                    MAX_PROCESSING_TIME_SECONDS
-                                ),
-                            }
-
-                            // always release lock
-                            if let Err(e) = release_processing_lock(&pool).await {
-                                error!("failed to release processing lock: {}", e);
-                            }
-                        } else {
-                            debug!(
-                                "processing lock already held by another instance, skipping cycle"
-                            );
-                        }
+                match confirm_pending_operations(&queries, &blockchain_client).await {
+                    Ok(0) => debug!("no pending operations resolved"),
+                    Ok(resolved) => info!("resolved {} pending operations", resolved),
+                    Err(e) => error!("pending operation check failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| {
+            RelayerError::InternalError(format!("failed to create pending operations job: {}", e))
+        })?;
+
+        self.job_scheduler.add(job).await.map_err(|e| {
+            RelayerError::InternalError(format!("failed to add pending operations job: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    async fn setup_confirmation_job(&mut self) -> Result<()> {
+        let queries = Arc::clone(&self.queries);
+        let blockchain_client = Arc::clone(&self.blockchain_client);
+        let chains = self.config.chains.clone();
+
+        let job = Job::new_async("*/30 * * * * *", move |_uuid, _l| {
+            let queries = Arc::clone(&queries);
+            let blockchain_client = Arc::clone(&blockchain_client);
+            let chains = chains.clone();
+
+            Box::pin(async move {
+                match check_execution_confirmations(&queries, &blockchain_client, &chains).await {
+                    Ok(summary) if summary == ConfirmationSummary::default() => {
+                        debug!("no executions changed confirmation state")
                     }
-                    Err(e) => error!("failed to acquire processing lock: {}", e),
+                    Ok(summary) => info!(
+                        "confirmation check: {} confirmed, {} orphaned, {} reorged",
+                        summary.confirmed, summary.orphaned, summary.reorged
+                    ),
+                    Err(e) => error!("confirmation check failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| {
+            RelayerError::InternalError(format!("failed to create confirmation job: {}", e))
+        })?;
+
+        self.job_scheduler.add(job).await.map_err(|e| {
+            RelayerError::InternalError(format!("failed to add confirmation job: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    async fn setup_webhook_delivery_job(&mut self) -> Result<()> {
+        let queries = Arc::clone(&self.queries);
+        let allow_private_targets = self.config.webhook_allow_private_targets;
+
+        let job = Job::new_async("*/10 * * * * *", move |_uuid, _l| {
+            let queries = Arc::clone(&queries);
+
+            Box::pin(async move {
+                match webhooks::deliver_due_webhooks(&queries, allow_private_targets).await {
+                    Ok(0) => debug!("no webhooks delivered"),
+                    Ok(delivered) => info!("delivered {} webhooks", delivered),
+                    Err(e) => error!("webhook delivery run failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| {
+            RelayerError::InternalError(format!("failed to create webhook delivery job: {}", e))
+        })?;
+
+        self.job_scheduler.add(job).await.map_err(|e| {
+            RelayerError::InternalError(format!("failed to add webhook delivery job: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    async fn setup_avail_submission_job(&mut self) -> Result<()> {
+        let queries = Arc::clone(&self.queries);
+        let avail_client = Arc::clone(&self.avail_client);
+        let max_intents = self.config.avail_batch_max_intents;
+        // each run anchors whatever arrived during the last window
+        let window = Duration::from_secs(self.config.avail_batch_window_seconds);
+
+        let job = Job::new_repeated_async(window, move |_uuid, _l| {
+            let queries = Arc::clone(&queries);
+            let avail_client = Arc::clone(&avail_client);
+
+            Box::pin(async move {
+                match outbox::submit_pending_intents(&queries, &avail_client, max_intents).await {
+                    Ok(0) => debug!("no intents anchored on avail"),
+                    Ok(submitted) => info!("anchored {} intents on avail", submitted),
+                    Err(e) => error!("avail submission run failed: {}", e),
                 }
+            })
+        })
+        .map_err(|e| {
+            RelayerError::InternalError(format!("failed to create avail submission job: {}", e))
+        })?;
+
+        self.job_scheduler.add(job).await.map_err(|e| {
+            RelayerError::InternalError(format!("failed to add avail submission job: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    async fn setup_metrics_job(&mut self) -> Result<()> {
+        let queries = Arc::clone(&self.queries);
+        let blockchain_client = Arc::clone(&self.blockchain_client);
+        let metrics = Arc::clone(&self.metrics);
+        let chains: Vec<(String, u64)> = self
+            .config
+            .chains
+            .iter()
+            .map(|chain| (chain.name.clone(), chain.chain_id))
+            .collect();
+        let track_hypersync = self.hypersync_client.is_some();
+
+        let job = Job::new_async("*/30 * * * * *", move |_uuid, _l| {
+            let queries = Arc::clone(&queries);
+            let blockchain_client = Arc::clone(&blockchain_client);
+            let metrics = Arc::clone(&metrics);
+            let chains = chains.clone();
 
-                info!("payment processing cycle completed");
+            Box::pin(async move {
+                for (chain_name, chain_id) in &chains {
+                    refresh_chain_gauges(
+                        chain_name,
+                        *chain_id,
+                        track_hypersync,
+                        &queries,
+                        &blockchain_client,
+                        &metrics,
+                    )
+                    .await;
+                }
             })
         })
-        .map_err(|e| RelayerError::InternalError(format!("failed to create payment job: {}", e)))?;
+        .map_err(|e| RelayerError::InternalError(format!("failed to create metrics job: {}", e)))?;
 
         self.job_scheduler.add(job).await.map_err(|e| {
-            RelayerError::InternalError(format!("failed to add payment job: {}", e))
+            RelayerError::InternalError(format!("failed to add metrics job: {}", e))
         })?;
 
         Ok(())
@@ -249,6 +658,12 @@ impl Scheduler {
 
     pub async fn stop(&mut self) -> Result<()> {
         info!("stopping payment scheduler");
+        if let Some(watcher) = self.settings_watcher.take() {
+            watcher.abort();
+        }
+        if let Some(watcher) = self.cycle_request_watcher.take() {
+            watcher.abort();
+        }
         self.job_scheduler
             .shutdown()
             .await
@@ -280,7 +695,7 @@ impl Scheduler {
 
             if let Err(e) = self.process_single_subscription(&subscription).await {
                 error!("failed to process subscription {}: {}", subscription.id, e);
-                self.handle_subscription_failure(&subscription, &e).await?;
+                self.handle_subscription_failure(&subscription, e).await?;
             }
         }
 
@@ -596,10 +1011,11 @@ impl Scheduler {
         let chain = &subscription.chain;
         let subscription_id_bytes = subscription_id_to_bytes(&subscription.id)?;
 
+        let max_retries = self.settings.current().max_retry_attempts;
         let mut retry_count = 0;
         let mut last_error = None;
 
-        while retry_count < MAX_RETRY_ATTEMPTS {
+        while retry_count < max_retries {
             match tokio::time::timeout(
                 Duration::from_secs(120), // 2 minute timeout per attempt
                 self.blockchain_client
@@ -637,6 +1053,7 @@ impl Scheduler {
                     let execution_result = ExecutionResult {
                         transaction_hash: result.transaction_hash,
                         block_number: result.block_number,
+                        block_hash: result.block_hash,
                         gas_used: result.gas_used,
                         gas_price: result.gas_price,
                         fee_paid: protocol_fee,
@@ -671,7 +1088,7 @@ impl Scheduler {
                         _ => retry_count < 2,                     // retry once for unknown errors
                     };
 
-                    if !should_retry || retry_count >= MAX_RETRY_ATTEMPTS {
+                    if !should_retry || retry_count >= max_retries {
                         warn!(
                             "permanent error or max retries reached, not retrying: {}",
                             last_error.as_ref().unwrap()
@@ -699,7 +1116,7 @@ impl Scheduler {
                         "blockchain request timeout".to_string(),
                     ));
 
-                    if retry_count < MAX_RETRY_ATTEMPTS {
+                    if retry_count < max_retries {
                         let backoff_seconds =
                             BASE_RETRY_DELAY_SECONDS * (2_u64.pow(retry_count - 1));
                         warn!(
@@ -712,10 +1129,7 @@ impl Scheduler {
             }
         }
 
-        error!(
-            "payment execution failed after {} attempts",
-            MAX_RETRY_ATTEMPTS
-        );
+        error!("payment execution failed after {} attempts", max_retries);
         Err(last_error.unwrap())
     }
 
@@ -746,6 +1160,9 @@ impl Scheduler {
             nexus_verified: false,
             nexus_submitted_at: None,
             token_address: Some(subscription.token_address.clone()),
+            block_hash: execution_result
+                .block_hash
+                .map(|hash| format!("{:?}", hash)),
         };
 
         self.queries
@@ -776,89 +1193,584 @@ impl Scheduler {
     async fn handle_subscription_failure(
         &self,
         subscription: &Subscription,
-        error: &RelayerError,
+        error: RelayerError,
     ) -> Result<()> {
-        let new_failure_count = subscription.failure_count + 1;
+        handle_subscription_failure_job_safe(
+            subscription,
+            &PaymentFailure::from(error),
+            &self.queries,
+            self.settings.current().max_failures_before_dead_letter,
+        )
+        .await
+    }
+}
 
-        warn!(
-            "subscription {} failed (attempt {}): {}",
-            subscription.id, new_failure_count, error
-        );
+// backlog, relayer balance and indexer lag for one chain; failures only skip that gauge
+async fn refresh_chain_gauges(
+    chain_name: &str,
+    chain_id: u64,
+    track_hypersync: bool,
+    queries: &Queries,
+    blockchain_client: &BlockchainClient,
+    metrics: &Metrics,
+) {
+    match queries.count_due_subscriptions_for_chain(chain_name).await {
+        Ok(count) => metrics.set_due_subscriptions(chain_name, count),
+        Err(e) => warn!("failed to count due subscriptions on {}: {}", chain_name, e),
+    }
 
-        if new_failure_count > 3 {
-            warn!(
-                "subscription {} exceeded maximum failures, pausing",
-                subscription.id
-            );
+    match blockchain_client.get_relayer_balance(chain_name).await {
+        Ok(balance) => {
+            let ether = ethers::utils::format_ether(balance)
+                .parse::<f64>()
+                .unwrap_or_default();
+            metrics.set_relayer_native_balance(chain_name, ether);
+        }
+        Err(e) => warn!("failed to read relayer balance on {}: {}", chain_name, e),
+    }
 
-            self.queries
-                .update_subscription_status_enum(&subscription.id, SubscriptionStatus::Paused)
-                .await?;
-        } else {
-            self.queries
-                .update_subscription_after_payment(
-                    &subscription.id,
-                    subscription.executed_payments,
-                    subscription.next_payment_due,
-                    new_failure_count,
-                )
-                .await?;
+    if !track_hypersync {
+        return;
+    }
+    let current_block = match blockchain_client.get_current_block_number(chain_name).await {
+        Ok(block) => block,
+        Err(e) => {
+            warn!("failed to read block number on {}: {}", chain_name, e);
+            return;
+        }
+    };
+    match queries.get_sync_metadata(chain_id as i64).await {
+        Ok(metadata) => {
+            let synced = u64::try_from(metadata.last_synced_block).unwrap_or_default();
+            metrics.set_hypersync_lag(chain_name, current_block.saturating_sub(synced));
         }
+        Err(e) => debug!("no hypersync sync metadata for {}: {}", chain_name, e),
+    }
+}
 
-        Ok(())
+#[derive(Clone)]
+struct PaymentJobContext {
+    queries: Arc<Queries>,
+    blockchain_client: Arc<BlockchainClient>,
+    avail_client: Arc<AvailClient>,
+    metrics: Arc<Metrics>,
+    pool: PgPool,
+    settings: SchedulerSettingsHandle,
+}
+
+async fn add_payment_jobs(
+    job_scheduler: &JobScheduler,
+    chains: &[(String, u64)],
+    context: &PaymentJobContext,
+    settings: &SchedulerSettings,
+) -> Result<Vec<Uuid>> {
+    let mut job_ids = Vec::with_capacity(chains.len());
+
+    for (chain_name, chain_id) in chains {
+        let job =
+            payment_job(chain_name.clone(), *chain_id, context.clone(), settings).map_err(|e| {
+                RelayerError::InternalError(format!(
+                    "failed to create payment job for {}: {}",
+                    chain_name, e
+                ))
+            })?;
+
+        let job_id = job_scheduler.add(job).await.map_err(|e| {
+            RelayerError::InternalError(format!(
+                "failed to add payment job for {}: {}",
+                chain_name, e
+            ))
+        })?;
+        job_ids.push(job_id);
+    }
+
+    Ok(job_ids)
+}
+
+fn payment_job(
+    chain_name: String,
+    chain_id: u64,
+    context: PaymentJobContext,
+    settings: &SchedulerSettings,
+) -> std::result::Result<Job, JobSchedulerError> {
+    let run = move |_uuid, _l| {
+        let chain_name = chain_name.clone();
+        let context = context.clone();
+        Box::pin(async move { run_payment_cycle(&chain_name, chain_id, &context).await })
+            as Pin<Box<dyn Future<Output = ()> + Send>>
+    };
+
+    match &settings.cron {
+        Some(cron) => Job::new_async(cron.as_str(), run),
+        None => Job::new_repeated_async(
+            Duration::from_secs(settings.execution_interval_seconds),
+            run,
+        ),
+    }
+}
+
+// runs requested cycles right away, each chain on its own task like the scheduled jobs
+fn spawn_cycle_request_watcher(
+    mut requests: broadcast::Receiver<Option<String>>,
+    chains: Vec<(String, u64)>,
+    context: PaymentJobContext,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let requested = match requests.recv().await {
+                Ok(requested) => requested,
+                // requests were dropped, so cover every chain
+                Err(broadcast::error::RecvError::Lagged(_)) => None,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            for (chain_name, chain_id) in &chains {
+                if requested.as_ref().is_some_and(|name| name != chain_name) {
+                    continue;
+                }
+                info!("running requested {} payment cycle", chain_name);
+                let chain_name = chain_name.clone();
+                let chain_id = *chain_id;
+                let context = context.clone();
+                tokio::spawn(
+                    async move { run_payment_cycle(&chain_name, chain_id, &context).await },
+                );
+            }
+        }
+    })
+}
+
+async fn run_payment_cycle(chain_name: &str, chain_id: u64, context: &PaymentJobContext) {
+    info!(
+        "starting {} payment processing cycle with distributed lock",
+        chain_name
+    );
+
+    // implement distributed locking to prevent concurrent execution
+    let lock = match acquire_processing_lock(&context.pool, chain_id).await {
+        Ok(Some(lock)) => lock,
+        Ok(None) => {
+            debug!(
+                "{} processing lock already held by another instance, skipping cycle",
+                chain_name
+            );
+            return;
+        }
+        Err(e) => {
+            error!("failed to acquire {} processing lock: {}", chain_name, e);
+            return;
+        }
+    };
+
+    // settings are read per cycle so admin changes apply from the next run
+    let settings = Arc::new(context.settings.current());
+
+    // set processing timeout
+    let started = Instant::now();
+    let processing_result = tokio::time::timeout(
+        Duration::from_secs(MAX_PROCESSING_TIME_SECONDS),
+        process_payments_job_safe(chain_name, settings, context.clone()),
+    )
+    .await;
+    context
+        .metrics
+        .observe_scheduler_cycle(chain_name, started.elapsed());
+
+    match processing_result {
+        Ok(Ok(())) => info!("{} payment processing completed successfully", chain_name),
+        Ok(Err(e)) => error!("{} payment processing failed: {}", chain_name, e),
+        Err(_) => error!(
+            "{} payment processing timed out after {} seconds",
+            chain_name, MAX_PROCESSING_TIME_SECONDS
+        ),
     }
+
+    // always release lock
+    if let Err(e) = release_processing_lock(lock, chain_id).await {
+        error!("failed to release {} processing lock: {}", chain_name, e);
+    }
+
+    info!("{} payment processing cycle completed", chain_name);
 }
 
-// distributed locking functions for concurrency safety
-async fn acquire_processing_lock(pool: &PgPool) -> Result<bool> {
-    let result = sqlx::query("SELECT pg_try_advisory_lock(12345) as acquired")
-        .fetch_one(pool)
+// distributed locking functions for concurrency safety.
+// advisory locks belong to a session, so the connection that took the lock is held until release
+async fn acquire_processing_lock(
+    pool: &PgPool,
+    chain_id: u64,
+) -> Result<Option<PoolConnection<Postgres>>> {
+    let mut conn = pool.acquire().await.map_err(|e| {
+        RelayerError::DatabaseError(format!("failed to acquire lock connection: {}", e))
+    })?;
+
+    let result = sqlx::query("SELECT pg_try_advisory_lock($1) as acquired")
+        .bind(processing_lock_key(chain_id))
+        .fetch_one(&mut *conn)
         .await
         .map_err(|e| RelayerError::DatabaseError(format!("failed to acquire lock: {}", e)))?;
 
     let acquired: bool = result.get("acquired");
-    Ok(acquired)
+    Ok(acquired.then_some(conn))
 }
 
-async fn release_processing_lock(pool: &PgPool) -> Result<()> {
-    sqlx::query("SELECT pg_advisory_unlock(12345)")
-        .execute(pool)
+async fn release_processing_lock(mut conn: PoolConnection<Postgres>, chain_id: u64) -> Result<()> {
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(processing_lock_key(chain_id))
+        .execute(&mut *conn)
         .await
         .map_err(|e| RelayerError::DatabaseError(format!("failed to release lock: {}", e)))?;
 
     Ok(())
 }
 
-// safe payment processing with resource limits and proper error handling
-async fn process_payments_job_safe(
-    queries: Arc<Queries>,
-    blockchain_client: Arc<BlockchainClient>,
-    avail_client: Arc<AvailClient>,
-    pool: PgPool,
-) -> Result<()> {
-    info!("starting safe payment processing with resource limits");
+// per-chain advisory lock key: the old global key in the high half, the chain id in the low half
+pub fn processing_lock_key(chain_id: u64) -> i64 {
+    (PROCESSING_LOCK_NAMESPACE << 32) | (chain_id & 0xffff_ffff) as i64
+}
 
-    // process subscriptions in batches to prevent memory exhaustion
-    let mut offset = 0;
-    let mut total_processed = 0;
+// checks receipts for relayed operations; confirmed creations become ACTIVE. creations that
+// reverted, failed to send or were never mined are discarded so their nonce can be signed again
+pub async fn confirm_pending_operations(
+    queries: &Queries,
+    blockchain_client: &BlockchainClient,
+) -> Result<usize> {
+    let operations = queries
+        .get_open_pending_operations(MAX_PENDING_OPERATIONS_PER_BATCH)
+        .await?;
+    let mut resolved = 0;
 
-    loop {
-        // get limited batch of due subscriptions
-        let due_subscriptions = queries
-            .get_due_subscriptions_with_limit(MAX_SUBSCRIPTIONS_PER_BATCH, offset)
+    for operation in operations {
+        // the tx manager may have replaced the original hash with fee-bumped attempts
+        let outbound = queries
+            .get_outbound_transaction_by_hash(&operation.transaction_hash)
             .await?;
+        let candidates = match &outbound {
+            Some(outbound) => outbound.candidate_hashes(),
+            None => vec![operation.transaction_hash.clone()],
+        };
+
+        let mut receipt = None;
+        for candidate in &candidates {
+            let Ok(tx_hash) = H256::from_str(candidate) else {
+                warn!(
+                    "pending operation {} has malformed tx hash {}",
+                    operation.id, candidate
+                );
+                continue;
+            };
+
+            match blockchain_client
+                .get_transaction_receipt(tx_hash, &operation.chain)
+                .await
+            {
+                Ok(Some(found)) => {
+                    receipt = Some(found);
+                    break;
+                }
+                Ok(None) => {}
+                Err(e) => warn!(
+                    "failed to fetch receipt for pending operation {}: {}",
+                    operation.id, e
+                ),
+            }
+        }
+
+        let Some(receipt) = receipt else {
+            let abandoned = match &outbound {
+                // every candidate was checked above, so none of them can still be mined
+                Some(outbound) => {
+                    (outbound.status == OutboundTxStatus::Failed.to_string()).then(|| {
+                        outbound
+                            .error_message
+                            .as_deref()
+                            .unwrap_or("transaction failed")
+                    })
+                }
+                None => (Utc::now() - operation.created_at
+                    > chrono::Duration::hours(PENDING_OPERATION_TIMEOUT_HOURS))
+                .then_some("transaction was never mined"),
+            };
+            if let Some(reason) = abandoned {
+                abandon_creation(queries, &operation, reason).await?;
+                resolved += 1;
+            }
+            continue;
+        };
+
+        if receipt.status == Some(1u64.into()) {
+            queries
+                .resolve_pending_operation(operation.id, PendingOperationStatus::Confirmed, None)
+                .await?;
+            queries
+                .update_subscription_status_enum(
+                    &operation.subscription_id,
+                    SubscriptionStatus::Active,
+                )
+                .await?;
+            info!(
+                "{} confirmed for subscription {} in block {:?}",
+                operation.operation, operation.subscription_id, receipt.block_number
+            );
+        } else {
+            abandon_creation(queries, &operation, "transaction reverted").await?;
+        }
+
+        resolved += 1;
+    }
+
+    Ok(resolved)
+}
+
+// the contract's nonce only advances when createSubscription succeeds, so the rows go too;
+// otherwise the subscriber could never sign again with the same nonce
+async fn abandon_creation(
+    queries: &Queries,
+    operation: &PendingOperation,
+    reason: &str,
+) -> Result<()> {
+    queries
+        .resolve_pending_operation(operation.id, PendingOperationStatus::Failed, Some(reason))
+        .await?;
+    let discarded = queries
+        .discard_unrelayed_subscription(&operation.subscription_id)
+        .await?;
+    warn!(
+        "{} for subscription {} failed ({}): {}; {}",
+        operation.operation,
+        operation.subscription_id,
+        operation.transaction_hash,
+        reason,
+        if discarded {
+            "discarded the subscription"
+        } else {
+            "subscription is no longer PENDING, left in place"
+        }
+    );
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfirmationCheck {
+    // not deep enough yet and nothing new to record
+    Wait,
+    // still shallow, but now sits in a block we have not recorded (first sighting or re-mined)
+    Moved { block_number: u64, block_hash: H256 },
+    Confirm { block_number: u64, block_hash: H256 },
+    Orphan(String),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmationSummary {
+    pub confirmed: usize,
+    pub orphaned: usize,
+    // executions whose recorded block hash was replaced by a reorg
+    pub reorged: usize,
+}
+
+// decides what to do with a SUCCESS execution given its current receipt and the chain head.
+// a missing receipt is only treated as a reorg once the chain has moved a full confirmation
+// depth past the recorded block, so a briefly re-queued transaction is not orphaned early
+pub fn assess_execution(
+    execution: &Execution,
+    receipt: Option<&TransactionReceipt>,
+    head: u64,
+    confirmations: u64,
+) -> ConfirmationCheck {
+    let recorded_block = u64::try_from(execution.block_number).unwrap_or_default();
+
+    let Some(receipt) = receipt else {
+        if head >= recorded_block.saturating_add(confirmations) {
+            return ConfirmationCheck::Orphan(
+                "transaction no longer in the canonical chain".to_string(),
+            );
+        }
+        return ConfirmationCheck::Wait;
+    };
+
+    if receipt.status != Some(1u64.into()) {
+        return ConfirmationCheck::Orphan("transaction reverted after reorg".to_string());
+    }
+
+    let (Some(block_number), Some(block_hash)) = (receipt.block_number, receipt.block_hash) else {
+        return ConfirmationCheck::Wait;
+    };
+    let block_number = block_number.as_u64();
+
+    let depth = if head >= block_number {
+        head - block_number + 1
+    } else {
+        0
+    };
+    if depth >= confirmations.max(1) {
+        return ConfirmationCheck::Confirm {
+            block_number,
+            block_hash,
+        };
+    }
+
+    let same_block = block_number == recorded_block
+        && execution
+            .block_hash
+            .as_deref()
+            .is_some_and(|recorded| recorded.eq_ignore_ascii_case(&format!("{:?}", block_hash)));
+    if same_block {
+        ConfirmationCheck::Wait
+    } else {
+        ConfirmationCheck::Moved {
+            block_number,
+            block_hash,
+        }
+    }
+}
+
+// re-verifies recorded executions against each chain; buried ones become CONFIRMED and
+// reorged-out ones ORPHANED with their payment rolled back
+pub async fn check_execution_confirmations(
+    queries: &Queries,
+    blockchain_client: &BlockchainClient,
+    chains: &ChainRegistry,
+) -> Result<ConfirmationSummary> {
+    let mut summary = ConfirmationSummary::default();
+
+    for chain in chains.iter() {
+        let executions = queries
+            .get_unconfirmed_executions(&chain.name, MAX_UNCONFIRMED_EXECUTIONS_PER_BATCH)
+            .await?;
+        if executions.is_empty() {
+            continue;
+        }
+
+        let head = match blockchain_client
+            .get_current_block_number(&chain.name)
+            .await
+        {
+            Ok(head) => head,
+            Err(e) => {
+                warn!(
+                    "skipping confirmation check on {}: failed to read head: {}",
+                    chain.name, e
+                );
+                continue;
+            }
+        };
+
+        for execution in executions {
+            let Ok(tx_hash) = H256::from_str(&execution.transaction_hash) else {
+                warn!(
+                    "execution {} has malformed tx hash {}",
+                    execution.id, execution.transaction_hash
+                );
+                continue;
+            };
+
+            let receipt = match blockchain_client
+                .get_transaction_receipt(tx_hash, &chain.name)
+                .await
+            {
+                Ok(receipt) => receipt,
+                Err(e) => {
+                    warn!(
+                        "failed to fetch receipt for execution {}: {}",
+                        execution.id, e
+                    );
+                    continue;
+                }
+            };
+
+            match assess_execution(&execution, receipt.as_ref(), head, chain.confirmations) {
+                ConfirmationCheck::Wait => {}
+                ConfirmationCheck::Moved {
+                    block_number,
+                    block_hash,
+                } => {
+                    if execution.block_hash.is_some() {
+                        warn!(
+                            "execution {} moved from block {} to {} on {}",
+                            execution.id, execution.block_number, block_number, chain.name
+                        );
+                        summary.reorged += 1;
+                    }
+                    queries
+                        .update_execution_block(
+                            execution.id,
+                            block_number as i64,
+                            &format!("{:?}", block_hash),
+                        )
+                        .await?;
+                }
+                ConfirmationCheck::Confirm {
+                    block_number,
+                    block_hash,
+                } => {
+                    if queries
+                        .confirm_execution(
+                            execution.id,
+                            block_number as i64,
+                            &format!("{:?}", block_hash),
+                        )
+                        .await?
+                    {
+                        summary.confirmed += 1;
+                    }
+                }
+                ConfirmationCheck::Orphan(reason) => {
+                    if queries.orphan_execution(execution.id, &reason).await? {
+                        warn!(
+                            "payment {} for subscription {} orphaned on {}: {}",
+                            execution.transaction_hash,
+                            execution.subscription_id,
+                            chain.name,
+                            reason
+                        );
+                        summary.orphaned += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+// safe payment processing for one chain with resource limits and proper error handling
+async fn process_payments_job_safe(
+    chain: &str,
+    settings: Arc<SchedulerSettings>,
+    context: PaymentJobContext,
+) -> Result<()> {
+    info!(
+        "starting safe payment processing on {} with resource limits",
+        chain
+    );
+
+    // process subscriptions in batches to prevent memory exhaustion
+    let mut offset = 0;
+    let mut total_processed = 0;
+
+    loop {
+        // get limited batch of due subscriptions
+        let due_subscriptions = context
+            .queries
+            .get_due_subscriptions_for_chain(chain, settings.max_executions_per_batch, offset)
+            .await?;
+
+        if due_subscriptions.is_empty() {
+            info!("no more due subscriptions to process on {}", chain);
+            break;
+        }
 
-        if due_subscriptions.is_empty() {
-            info!("no more due subscriptions to process");
-            break;
-        }
-
         info!(
-            "processing batch of {} subscriptions (offset: {})",
+            "processing batch of {} subscriptions on {} (offset: {})",
             due_subscriptions.len(),
+            chain,
             offset
         );
 
+        let mut preflights =
+            preflight_batch_job_safe(&due_subscriptions, &context.blockchain_client).await;
+
+        // bounded fan-out; nonces stay ordered because the tx manager serialises sends per chain
+        let mut workers = JoinSet::new();
         for subscription in due_subscriptions {
             // validate subscription id length for DoS protection
             if subscription.id.len() > MAX_ID_LENGTH {
@@ -869,54 +1781,112 @@ async fn process_payments_job_safe(
                 continue;
             }
 
-            match process_single_subscription_job_safe(
-                &subscription,
-                &queries,
-                &blockchain_client,
-                &avail_client,
-                &pool,
-            )
-            .await
-            {
-                Ok(processed) => {
-                    if processed {
-                        total_processed += 1;
-                    }
-                }
-                Err(e) => {
-                    error!("failed to process subscription {}: {}", subscription.id, e);
-                    if let Err(failure_err) =
-                        handle_subscription_failure_job_safe(&subscription, &e, &queries).await
-                    {
-                        error!("failed to handle subscription failure: {}", failure_err);
-                    }
-                }
+            while workers.len() >= MAX_CONCURRENT_EXECUTIONS_PER_CHAIN {
+                total_processed += count_processed(workers.join_next().await);
             }
+
+            let preflight = preflights.remove(&subscription.id);
+            let settings = Arc::clone(&settings);
+            let context = context.clone();
+            workers.spawn(async move {
+                process_due_subscription_job_safe(subscription, preflight, settings, context).await
+            });
+        }
+
+        while !workers.is_empty() {
+            total_processed += count_processed(workers.join_next().await);
         }
 
-        offset += MAX_SUBSCRIPTIONS_PER_BATCH;
+        offset += settings.max_executions_per_batch;
 
-        // safety limit: don't process more than 1000 subscriptions per cycle
-        if total_processed >= 1000 {
-            warn!("reached maximum subscriptions per cycle limit (1000), stopping");
+        // safety limit on subscriptions processed per cycle
+        if total_processed as i64 >= settings.max_executions_per_cycle {
+            warn!(
+                "reached maximum subscriptions per cycle limit ({}) on {}, stopping",
+                settings.max_executions_per_cycle, chain
+            );
             break;
         }
     }
 
     info!(
-        "completed safe payment processing, processed {} subscriptions",
-        total_processed
+        "completed safe payment processing on {}, processed {} subscriptions",
+        chain, total_processed
     );
     Ok(())
 }
 
+fn count_processed(joined: Option<std::result::Result<bool, JoinError>>) -> usize {
+    match joined {
+        Some(Ok(processed)) => usize::from(processed),
+        Some(Err(e)) => {
+            error!("subscription worker panicked: {}", e);
+            0
+        }
+        None => 0,
+    }
+}
+
+async fn process_due_subscription_job_safe(
+    subscription: Subscription,
+    preflight: Option<PaymentPreflight>,
+    settings: Arc<SchedulerSettings>,
+    context: PaymentJobContext,
+) -> bool {
+    let token_symbol = tokens::get_token_symbol(&subscription.token_address);
+    match process_single_subscription_job_safe(
+        &subscription,
+        preflight.as_ref(),
+        &settings,
+        &context.queries,
+        &context.blockchain_client,
+        &context.avail_client,
+        &context.pool,
+    )
+    .await
+    {
+        Ok(processed) => {
+            if processed {
+                context
+                    .metrics
+                    .record_execution(&subscription.chain, token_symbol, None);
+            }
+            processed
+        }
+        Err(failure) => {
+            context.metrics.record_execution(
+                &subscription.chain,
+                token_symbol,
+                Some(&failure.class.to_string()),
+            );
+            error!(
+                "failed to process subscription {} ({}): {}",
+                subscription.id, failure.class, failure.error
+            );
+            if let Err(failure_err) = handle_subscription_failure_job_safe(
+                &subscription,
+                &failure,
+                &context.queries,
+                settings.max_failures_before_dead_letter,
+            )
+            .await
+            {
+                error!("failed to handle subscription failure: {}", failure_err);
+            }
+            false
+        }
+    }
+}
+
 async fn process_single_subscription_job_safe(
     subscription: &Subscription,
+    preflight: Option<&PaymentPreflight>,
+    settings: &SchedulerSettings,
     queries: &Arc<Queries>,
     blockchain_client: &Arc<BlockchainClient>,
     avail_client: &Arc<AvailClient>,
     pool: &PgPool,
-) -> Result<bool> {
+) -> std::result::Result<bool, PaymentFailure> {
     info!(
         "processing subscription {} for subscriber {}",
         subscription.id, subscription.subscriber
@@ -948,12 +1918,15 @@ async fn process_single_subscription_job_safe(
             );
             return Ok(false);
         }
-        Err(e) => return Err(RelayerError::Database(e)),
+        Err(e) => return Err(RelayerError::Database(e).into()),
     }
 
     ensure_intent_cached_job_safe(subscription, queries, avail_client.as_ref()).await?;
 
-    let validation_result = validate_payment_job_safe(subscription, blockchain_client).await?;
+    let validation_result = match preflight {
+        Some(preflight) => evaluate_preflight(subscription, preflight, Utc::now())?,
+        None => validate_payment_job_safe(subscription, blockchain_client).await?,
+    };
 
     match validation_result {
         ValidationResult::Valid => {
@@ -962,8 +1935,12 @@ async fn process_single_subscription_job_safe(
                 subscription.id
             );
 
-            let execution_result =
-                execute_payment_on_chain_job_safe(subscription, blockchain_client).await?;
+            let execution_result = execute_payment_on_chain_job_safe(
+                subscription,
+                blockchain_client,
+                settings.max_retry_attempts,
+            )
+            .await?;
 
             record_successful_execution_job_safe(subscription, &execution_result, queries).await?;
 
@@ -985,7 +1962,10 @@ async fn process_single_subscription_job_safe(
                 "PYUSD" => "Insufficient PYUSD balance".to_string(),
                 other => format!("insufficient {} balance", other),
             };
-            return Err(RelayerError::Validation(message));
+            return Err(PaymentFailure::new(
+                FailureClass::InsufficientBalance,
+                RelayerError::Validation(message),
+            ));
         }
         ValidationResult::InsufficientAllowance { token_address } => {
             let symbol = tokens::get_token_symbol(&token_address);
@@ -998,7 +1978,10 @@ async fn process_single_subscription_job_safe(
                 "PYUSD" => "PYUSD allowance required".to_string(),
                 other => format!("{} allowance required", other),
             };
-            return Err(RelayerError::Validation(message));
+            return Err(PaymentFailure::new(
+                FailureClass::MissingAllowance,
+                RelayerError::Validation(message),
+            ));
         }
         ValidationResult::NotDue => {
             debug!("subscription {} is not due yet", subscription.id);
@@ -1006,14 +1989,16 @@ async fn process_single_subscription_job_safe(
         }
         ValidationResult::SubscriptionNotActive => {
             warn!("subscription {} is not active", subscription.id);
-            return Err(RelayerError::Validation(
-                "subscription not active".to_string(),
+            return Err(PaymentFailure::new(
+                FailureClass::ContractRevert,
+                RelayerError::Validation("subscription not active".to_string()),
             ));
         }
         ValidationResult::SubscriptionNotFound => {
             warn!("subscription {} not found on chain", subscription.id);
-            return Err(RelayerError::NotFound(
-                "subscription not found on chain".to_string(),
+            return Err(PaymentFailure::new(
+                FailureClass::ContractRevert,
+                RelayerError::NotFound("subscription not found on chain".to_string()),
             ));
         }
         ValidationResult::ChainError(msg) => {
@@ -1022,14 +2007,135 @@ async fn process_single_subscription_job_safe(
                 "chain error for subscription {} on token {} ({}): {}",
                 subscription.id, symbol, subscription.token_address, msg
             );
-            return Err(RelayerError::ContractRevert(msg));
+            return Err(PaymentFailure::new(
+                FailureClass::ChainError,
+                RelayerError::ContractRevert(msg),
+            ));
         }
     }
 }
 
+// resolves on-chain state for a whole batch with one multicall per chain, keyed by subscription id.
+// chains whose multicall fails are left out so those subscriptions fall back to single calls
+async fn preflight_batch_job_safe(
+    subscriptions: &[Subscription],
+    blockchain_client: &Arc<BlockchainClient>,
+) -> HashMap<String, PaymentPreflight> {
+    let mut by_chain: HashMap<&str, (Vec<&Subscription>, Vec<PreflightRequest>)> = HashMap::new();
+
+    for subscription in subscriptions {
+        let Some(request) = preflight_request(subscription) else {
+            continue;
+        };
+        let (pending, requests) = by_chain.entry(subscription.chain.as_str()).or_default();
+        pending.push(subscription);
+        requests.push(request);
+    }
+
+    let mut preflights = HashMap::with_capacity(subscriptions.len());
+    for (chain, (pending, requests)) in by_chain {
+        match tokio::time::timeout(
+            Duration::from_secs(30),
+            blockchain_client.preflight_payments(chain, &requests),
+        )
+        .await
+        {
+            Ok(Ok(results)) => {
+                for (subscription, preflight) in pending.into_iter().zip(results) {
+                    preflights.insert(subscription.id.clone(), preflight);
+                }
+            }
+            Ok(Err(e)) => warn!(
+                "multicall pre-flight failed on {}, falling back to single calls: {}",
+                chain, e
+            ),
+            Err(_) => warn!(
+                "multicall pre-flight timed out on {}, falling back to single calls",
+                chain
+            ),
+        }
+    }
+
+    preflights
+}
+
+fn preflight_request(subscription: &Subscription) -> Option<PreflightRequest> {
+    Some(PreflightRequest {
+        subscription_id: subscription_id_to_bytes(&subscription.id).ok()?,
+        subscriber: Address::from_str(&subscription.subscriber).ok()?,
+        token: Address::from_str(&subscription.token_address).ok()?,
+    })
+}
+
+// single-subscription path, used when the batched pre-flight is unavailable for a chain
 async fn validate_payment_job_safe(
     subscription: &Subscription,
     blockchain_client: &Arc<BlockchainClient>,
+) -> Result<ValidationResult> {
+    let chain = &subscription.chain;
+    let request = preflight_request(subscription).ok_or_else(|| {
+        RelayerError::Validation(format!(
+            "subscription {} has an invalid id or address",
+            subscription.id
+        ))
+    })?;
+
+    let on_chain_subscription = match tokio::time::timeout(
+        Duration::from_secs(30),
+        blockchain_client.get_subscription(request.subscription_id, chain),
+    )
+    .await
+    {
+        Ok(Ok(sub)) => Ok(sub),
+        Ok(Err(e)) => Err(format!("blockchain error: {}", e)),
+        Err(_) => Err("blockchain request timeout".to_string()),
+    };
+
+    // later lookups are only consulted once the subscription itself checks out
+    let skipped = "skipped".to_string();
+    let mut preflight = PaymentPreflight {
+        subscription: on_chain_subscription,
+        payment_count: Err(skipped.clone()),
+        balance: Err(skipped.clone()),
+        allowance: Err(skipped),
+    };
+
+    if matches!(preflight.subscription, Ok(Some(_))) {
+        preflight.payment_count = blockchain_client
+            .get_payment_count(request.subscription_id, chain)
+            .await
+            .map_err(|e| e.to_string());
+        preflight.balance = blockchain_client
+            .check_balance(request.subscriber, request.token, chain)
+            .await
+            .map_err(|e| e.to_string());
+        preflight.allowance = if request.token == Address::zero() {
+            Ok(None)
+        } else {
+            let payment_amount = U256::from_dec_str(&subscription.amount).unwrap_or_default();
+            // check_allowance only reports sufficiency, so express it against the amount due
+            blockchain_client
+                .check_allowance(request.subscriber, request.token, payment_amount, chain)
+                .await
+                .map(|sufficient| {
+                    Some(if sufficient {
+                        payment_amount
+                    } else {
+                        U256::zero()
+                    })
+                })
+                .map_err(|e| e.to_string())
+        };
+    }
+
+    evaluate_preflight(subscription, &preflight, Utc::now())
+}
+
+// derives the scheduler's decision for one subscription from its pre-fetched on-chain state
+pub fn evaluate_preflight(
+    subscription: &Subscription,
+    preflight: &PaymentPreflight,
+    now: DateTime<Utc>,
 ) -> Result<ValidationResult> {
     let token_address_str = subscription.token_address.clone();
     let token_symbol = tokens::get_token_symbol(&token_address_str);
@@ -1045,31 +2151,13 @@ async fn validate_payment_job_safe(
         ));
     }
 
-    let chain = &subscription.chain;
-    let subscription_id_bytes = subscription_id_to_bytes(&subscription.id)?;
     let token_address = Address::from_str(&token_address_str)
         .map_err(|_| RelayerError::Validation("invalid token address".to_string()))?;
 
-    // get current blockchain state with timeout
-    let on_chain_subscription = match tokio::time::timeout(
-        Duration::from_secs(30),
-        blockchain_client.get_subscription(subscription_id_bytes, chain),
-    )
-    .await
-    {
-        Ok(Ok(Some(sub))) => sub,
-        Ok(Ok(None)) => return Ok(ValidationResult::SubscriptionNotFound),
-        Ok(Err(e)) => {
-            return Ok(ValidationResult::ChainError(format!(
-                "blockchain error: {}",
-                e
-            )))
-        }
-        Err(_) => {
-            return Ok(ValidationResult::ChainError(
-                "blockchain request timeout".to_string(),
-            ))
-        }
+    let on_chain_subscription = match &preflight.subscription {
+        Ok(Some(sub)) => sub,
+        Ok(None) => return Ok(ValidationResult::SubscriptionNotFound),
+        Err(e) => return Ok(ValidationResult::ChainError(e.clone())),
     };
 
     if on_chain_subscription.status != 0 {
@@ -1084,7 +2172,6 @@ async fn validate_payment_job_safe(
     }
 
     // check if subscription has expired
-    let now = Utc::now();
     if now > subscription.expiry {
         return Ok(ValidationResult::SubscriptionNotActive);
     }
@@ -1095,14 +2182,14 @@ async fn validate_payment_job_safe(
     }
 
     // secure arithmetic with overflow protection
-    let payment_amount: U256 = subscription.amount.parse().map_err(|_| {
+    let payment_amount = U256::from_dec_str(&subscription.amount).map_err(|_| {
         RelayerError::Validation("invalid payment amount in subscription".to_string())
     })?;
 
-    // get payment count from contract (source of truth)
-    let on_chain_payments = blockchain_client
-        .get_payment_count(subscription_id_bytes, chain)
-        .await
+    // payment count from contract (source of truth)
+    let on_chain_payments = preflight
+        .payment_count
+        .clone()
         .map_err(|e| RelayerError::ContractRevert(format!("failed to get payment count: {}", e)))?;
 
     // calculate total paid from contract state
@@ -1125,20 +2212,13 @@ async fn validate_payment_job_safe(
         return Ok(ValidationResult::SubscriptionNotActive);
     }
 
-    let next_payment_time = subscription.next_payment_due;
-
-    if now < next_payment_time {
+    if now < subscription.next_payment_due {
         return Ok(ValidationResult::NotDue);
     }
 
-    let subscriber_address = subscription
-        .subscriber
-        .parse()
-        .map_err(|_| RelayerError::Validation("invalid subscriber address".to_string()))?;
-
-    let balance = blockchain_client
-        .check_balance(subscriber_address, token_address, chain)
-        .await
+    let balance = preflight
+        .balance
+        .clone()
         .map_err(|e| RelayerError::ContractRevert(format!("failed to check balance: {}", e)))?;
 
     // verify amounts match between database and chain
@@ -1163,10 +2243,11 @@ async fn validate_payment_job_safe(
         });
     }
 
-    let has_allowance = blockchain_client
-        .check_allowance(subscriber_address, token_address, payment_amount, chain)
-        .await
+    let allowance = preflight
+        .allowance
+        .clone()
         .map_err(|e| RelayerError::ContractRevert(format!("failed to check allowance: {}", e)))?;
+    let has_allowance = allowance.is_none_or(|allowance| allowance >= payment_amount);
 
     info!(
         "subscription {} allowance check: {} for token {} ({})",
@@ -1189,6 +2270,7 @@ async fn validate_payment_job_safe(
 async fn execute_payment_on_chain_job_safe(
     subscription: &Subscription,
     blockchain_client: &Arc<BlockchainClient>,
+    max_retries: u32,
 ) -> Result<ExecutionResult> {
     let token_symbol = tokens::get_token_symbol(&subscription.token_address);
     info!(
@@ -1199,7 +2281,6 @@ async fn execute_payment_on_chain_job_safe(
     let chain = &subscription.chain;
     let subscription_id_bytes = subscription_id_to_bytes(&subscription.id)?;
 
-    let max_retries = MAX_RETRY_ATTEMPTS;
     let mut retry_count = 0;
     let mut last_error = None;
 
@@ -1225,6 +2306,7 @@ async fn execute_payment_on_chain_job_safe(
                 let execution_result = ExecutionResult {
                     transaction_hash: result.transaction_hash,
                     block_number: result.block_number,
+                    block_hash: result.block_hash,
                     gas_used: result.gas_used,
                     gas_price: result.gas_price,
                     fee_paid: protocol_fee,
@@ -1308,6 +2390,9 @@ async fn record_successful_execution_job_safe(
         nexus_verified: false,
         nexus_submitted_at: None,
         token_address: Some(subscription.token_address.clone()),
+        block_hash: execution_result
+            .block_hash
+            .map(|hash| format!("{:?}", hash)),
     };
     let new_payments_made = subscription
         .executed_payments
@@ -1327,6 +2412,44 @@ async fn record_successful_execution_job_safe(
         .await?;
 
     info!("execution record saved, subscription updated for next payment");
+
+    let mut data = webhooks::subscription_event_data(subscription);
+    data["executedPayments"] = json!(new_payments_made);
+    data["paymentNumber"] = json!(execution_record.payment_number);
+    data["amount"] = json!(execution_record.payment_amount);
+    data["fee"] = json!(execution_record.fee_paid);
+    data["transactionHash"] = json!(execution_record.transaction_hash);
+    data["blockNumber"] = json!(execution_record.block_number);
+    webhooks::publish(
+        queries,
+        &subscription.merchant,
+        WebhookEventType::PaymentExecuted,
+        data,
+    )
+    .await;
+
+    if new_payments_made >= subscription.max_payments {
+        queries
+            .update_subscription_status_enum(&subscription.id, SubscriptionStatus::Completed)
+            .await?;
+        info!(
+            "subscription {} completed after {} payments",
+            subscription.id, new_payments_made
+        );
+        let completed = Subscription {
+            executed_payments: new_payments_made,
+            status: SubscriptionStatus::Completed.to_string(),
+            ..subscription.clone()
+        };
+        webhooks::publish_status_change(
+            queries,
+            &completed,
+            WebhookEventType::SubscriptionCompleted,
+            Some(&execution_record.transaction_hash),
+        )
+        .await;
+    }
+
     Ok(())
 }
 
@@ -1335,11 +2458,6 @@ async fn ensure_intent_cached_job_safe(
     queries: &Arc<Queries>,
     avail_client: &AvailClient,
 ) -> Result<()> {
-    // skip remote fetch when running in stub mode to avoid noisy logs
-    if matches!(avail_client.mode(), AvailClientMode::Stub) {
-        return Ok(());
-    }
-
     if queries.get_cached_intent(&subscription.id).await?.is_some() {
         return Ok(());
     }
@@ -1360,7 +2478,7 @@ async fn ensure_intent_cached_job_safe(
     );
 
     match avail_client
-        .fetch_intent_if_available(block_number, extrinsic_index)
+        .fetch_intent_if_available(block_number, extrinsic_index, &subscription.id)
         .await?
     {
         Some(avail_intent) => {
@@ -1424,48 +2542,120 @@ async fn ensure_intent_cached_job_safe(
 
 async fn handle_subscription_failure_job_safe(
     subscription: &Subscription,
-    error: &RelayerError,
+    failure: &PaymentFailure,
     queries: &Arc<Queries>,
+    max_failures: i64,
 ) -> Result<()> {
-    let new_failure_count = subscription.failure_count.saturating_add(1); // prevent overflow
+    let error_message = failure.error.to_string();
+    let retry_state = queries
+        .get_subscription_retry_state(&subscription.id)
+        .await?;
 
-    warn!(
-        "subscription {} failed (attempt {}): {}",
-        subscription.id, new_failure_count, error
-    );
+    // chain and relayer errors run on their own counter and budget; any other failure means
+    // the chain answered, which ends the streak
+    let (new_failure_count, chain_error_count, attempts, budget) = match failure.class {
+        FailureClass::ChainError | FailureClass::RelayerError => {
+            let chain_error_count = retry_state.chain_error_count.saturating_add(1);
+            (
+                subscription.failure_count,
+                chain_error_count,
+                chain_error_count,
+                max_failures.saturating_mul(CHAIN_ERROR_BUDGET_MULTIPLIER),
+            )
+        }
+        _ => {
+            let new_failure_count = subscription.failure_count.saturating_add(1); // prevent overflow
+            (new_failure_count, 0, new_failure_count, max_failures)
+        }
+    };
 
-    if new_failure_count > 3 {
+    if attempts > budget {
         warn!(
-            "subscription {} exceeded maximum failures, pausing",
-            subscription.id
+            "subscription {} exceeded maximum failures ({} {}), moving to dead letter",
+            subscription.id, attempts, failure.class
         );
 
         queries
-            .update_subscription_status_enum(&subscription.id, SubscriptionStatus::Paused)
-            .await?;
-    } else {
-        queries
-            .update_subscription_after_payment(
+            .dead_letter_subscription(
                 &subscription.id,
-                subscription.executed_payments,
-                subscription.next_payment_due,
                 new_failure_count,
+                chain_error_count,
+                failure.class,
+                &error_message,
             )
             .await?;
+        publish_payment_failed(queries, subscription, failure, attempts, None).await;
+        return Ok(());
     }
 
+    let (base, cap) = retry_bounds(failure.class);
+    let delay = backoff(base, cap, attempts);
+    warn!(
+        "subscription {} failed with {} (attempt {}), retrying in {}s: {}",
+        subscription.id,
+        failure.class,
+        attempts,
+        delay.as_secs(),
+        error_message
+    );
+
+    let next_retry_at = Utc::now()
+        + chrono::Duration::from_std(delay)
+            .map_err(|e| RelayerError::InternalError(format!("invalid retry delay: {}", e)))?;
+    queries
+        .schedule_subscription_retry(
+            &subscription.id,
+            new_failure_count,
+            chain_error_count,
+            failure.class,
+            &error_message,
+            next_retry_at,
+        )
+        .await?;
+    publish_payment_failed(
+        queries,
+        subscription,
+        failure,
+        attempts,
+        Some(next_retry_at),
+    )
+    .await;
+
     Ok(())
 }
 
+// no next retry means the subscription was dead-lettered
+async fn publish_payment_failed(
+    queries: &Queries,
+    subscription: &Subscription,
+    failure: &PaymentFailure,
+    failure_count: i64,
+    next_retry_at: Option<DateTime<Utc>>,
+) {
+    let mut data = webhooks::subscription_event_data(subscription);
+    data["reason"] = json!(failure.class.to_string());
+    // merchants only see the sanitized message, never the raw error
+    data["error"] = json!(failure.error.public_message());
+    data["failureCount"] = json!(failure_count);
+    data["nextRetryAt"] = json!(next_retry_at);
+    data["deadLettered"] = json!(next_retry_at.is_none());
+    webhooks::publish(
+        queries,
+        &subscription.merchant,
+        WebhookEventType::PaymentFailed,
+        data,
+    )
+    .await;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::database::{models::*, queries::Queries, StubStorage};
-    use crate::Config;
+    use crate::api::rate_limit::RateLimitSettings;
+    use crate::blockchain::SubscriptionData;
+    use crate::database::models::*;
+    use crate::{AvailMode, Config};
     use chrono::Utc;
-    use mockito::Server;
-    use serde_json::json;
-    use tokio;
 
     fn create_test_subscription() -> Subscription {
         Subscription {
@@ -1494,7 +2684,7 @@ mod tests {
     }
 
     fn stub_config() -> Config {
-        let config = Config {
+        let mut config = Config {
             database_url: "stub".to_string(),
             ethereum_rpc_url: "stub".to_string(),
             base_rpc_url: "stub".to_string(),
@@ -1518,15 +2708,29 @@ mod tests {
             server_port: 3000,
             execution_interval_seconds: 30,
             max_executions_per_batch: 10,
+            scheduler_cron: None,
+            max_executions_per_cycle: 1000,
+            max_retry_attempts: 3,
+            max_failures_before_dead_letter: 3,
+            admin_api_token: None,
+            cors_allowed_origins: Vec::new(),
+            rate_limits: RateLimitSettings::default(),
             max_gas_price_gwei: 50,
             relayer_address: "0x5555555555555555555555555555555555555555".to_string(),
             envio_graphql_endpoint: None,
             envio_explorer_url: None,
+            avail_mode: AvailMode::Mock,
             avail_rpc_url: None,
             avail_application_id: None,
+            avail_batch_window_seconds: 30,
+            avail_batch_max_intents: 100,
             hypersync_url_sepolia: None,
             hypersync_url_base: None,
+            relay_subscription_creation: false,
+            webhook_allow_private_targets: false,
+            chains: ChainRegistry::default(),
         };
+        config.chains = ChainRegistry::new(config.legacy_chains()).unwrap();
 
         tokens::register_pyusd_addresses(&[
             config.pyusd_address_sepolia.clone(),
@@ -1570,6 +2774,7 @@ mod tests {
         let execution_result = ExecutionResult {
             transaction_hash: H256::zero(),
             block_number: 12345,
+            block_hash: None,
             gas_used: U256::from(21000),
             gas_price: U256::from(20000000000u64),
             fee_paid: U256::from(420000000000000u64),
@@ -1624,4 +2829,760 @@ mod tests {
             }
         }
     }
+
+    fn receipt_in(block_number: u64, block_hash: H256, status: u64) -> TransactionReceipt {
+        TransactionReceipt {
+            block_number: Some(block_number.into()),
+            block_hash: Some(block_hash),
+            status: Some(status.into()),
+            ..Default::default()
+        }
+    }
+
+    fn recorded_execution(block_number: i64, block_hash: Option<H256>) -> Execution {
+        Execution {
+            id: 1,
+            subscription_id: create_test_subscription().id,
+            relayer_address: String::new(),
+            payment_number: 1,
+            amount_paid: "1000000".to_string(),
+            protocol_fee: "5000".to_string(),
+            merchant_amount: "1000000".to_string(),
+            transaction_hash: format!("{:?}", H256::repeat_byte(0xaa)),
+            block_number,
+            gas_used: "21000".to_string(),
+            gas_price: "1000000000".to_string(),
+            status: "SUCCESS".to_string(),
+            error_message: None,
+            executed_at: Utc::now(),
+            chain: "sepolia".to_string(),
+            nexus_attestation_id: None,
+            nexus_verified: false,
+            nexus_submitted_at: None,
+            token_address: None,
+            block_hash: block_hash.map(|hash| format!("{:?}", hash)),
+            confirmed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_assess_execution_waits_for_depth() {
+        let hash = H256::repeat_byte(1);
+        let execution = recorded_execution(100, Some(hash));
+        let receipt = receipt_in(100, hash, 1);
+
+        assert_eq!(
+            assess_execution(&execution, Some(&receipt), 101, 3),
+            ConfirmationCheck::Wait
+        );
+        assert_eq!(
+            assess_execution(&execution, Some(&receipt), 102, 3),
+            ConfirmationCheck::Confirm {
+                block_number: 100,
+                block_hash: hash
+            }
+        );
+    }
+
+    #[test]
+    fn test_assess_execution_tracks_remined_blocks() {
+        let original = H256::repeat_byte(1);
+        let replacement = H256::repeat_byte(2);
+        let execution = recorded_execution(100, Some(original));
+
+        assert_eq!(
+            assess_execution(&execution, Some(&receipt_in(101, replacement, 1)), 101, 3),
+            ConfirmationCheck::Moved {
+                block_number: 101,
+                block_hash: replacement
+            }
+        );
+
+        // executions recorded without a hash get one on first sighting
+        let unanchored = recorded_execution(100, None);
+        assert_eq!(
+            assess_execution(&unanchored, Some(&receipt_in(100, original, 1)), 100, 3),
+            ConfirmationCheck::Moved {
+                block_number: 100,
+                block_hash: original
+            }
+        );
+    }
+
+    #[test]
+    fn test_assess_execution_orphans_dropped_and_reverted() {
+        let hash = H256::repeat_byte(1);
+        let execution = recorded_execution(100, Some(hash));
+
+        // a missing receipt is tolerated until the chain moves a full depth past the block
+        assert_eq!(
+            assess_execution(&execution, None, 102, 3),
+            ConfirmationCheck::Wait
+        );
+        assert!(matches!(
+            assess_execution(&execution, None, 103, 3),
+            ConfirmationCheck::Orphan(_)
+        ));
+        assert!(matches!(
+            assess_execution(&execution, Some(&receipt_in(101, hash, 0)), 101, 3),
+            ConfirmationCheck::Orphan(_)
+        ));
+    }
+
+    fn on_chain_copy(subscription: &Subscription) -> SubscriptionData {
+        SubscriptionData {
+            id: [0x12; 32],
+            subscriber: Address::from_str(&subscription.subscriber).unwrap(),
+            merchant: Address::from_str(&subscription.merchant).unwrap(),
+            amount: U256::from_dec_str(&subscription.amount).unwrap(),
+            interval: U256::from(subscription.interval_seconds),
+            start_time: U256::zero(),
+            max_payments: U256::from(subscription.max_payments),
+            max_total_amount: U256::from_dec_str(&subscription.max_total_amount).unwrap(),
+            expiry: U256::from(subscription.expiry.timestamp()),
+            nonce: U256::one(),
+            token: Address::from_str(&subscription.token_address).unwrap(),
+            status: 0,
+            executed_payments: U256::zero(),
+            total_paid: U256::zero(),
+        }
+    }
+
+    fn funded_preflight(subscription: &Subscription) -> PaymentPreflight {
+        PaymentPreflight {
+            subscription: Ok(Some(on_chain_copy(subscription))),
+            payment_count: Ok(0),
+            balance: Ok(U256::from(10_000_000u64)),
+            allowance: Ok(None),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_preflight_native_token() {
+        let subscription = create_test_subscription();
+        let preflight = funded_preflight(&subscription);
+
+        // native token has no allowance to check
+        assert!(matches!(
+            evaluate_preflight(&subscription, &preflight, Utc::now()).unwrap(),
+            ValidationResult::Valid
+        ));
+
+        let broke = PaymentPreflight {
+            balance: Ok(U256::from(999_999u64)),
+            ..funded_preflight(&subscription)
+        };
+        assert!(matches!(
+            evaluate_preflight(&subscription, &broke, Utc::now()).unwrap(),
+            ValidationResult::InsufficientBalance { .. }
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_preflight_erc20_allowance() {
+        let mut subscription = create_test_subscription();
+        subscription.token_address = "0x1c7d4b196cb0c7b01d743fbc6116a902379c7238".to_string();
+
+        let short = PaymentPreflight {
+            allowance: Ok(Some(U256::from(500_000u64))),
+            ..funded_preflight(&subscription)
+        };
+        assert!(matches!(
+            evaluate_preflight(&subscription, &short, Utc::now()).unwrap(),
+            ValidationResult::InsufficientAllowance { .. }
+        ));
+
+        let approved = PaymentPreflight {
+            allowance: Ok(Some(U256::from(1_000_000u64))),
+            ..funded_preflight(&subscription)
+        };
+        assert!(matches!(
+            evaluate_preflight(&subscription, &approved, Utc::now()).unwrap(),
+            ValidationResult::Valid
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_preflight_subscription_state() {
+        let subscription = create_test_subscription();
+
+        let missing = PaymentPreflight {
+            subscription: Ok(None),
+            ..funded_preflight(&subscription)
+        };
+        assert!(matches!(
+            evaluate_preflight(&subscription, &missing, Utc::now()).unwrap(),
+            ValidationResult::SubscriptionNotFound
+        ));
+
+        let failed = PaymentPreflight {
+            subscription: Err("execution reverted".to_string()),
+            ..funded_preflight(&subscription)
+        };
+        assert!(matches!(
+            evaluate_preflight(&subscription, &failed, Utc::now()).unwrap(),
+            ValidationResult::ChainError(_)
+        ));
+
+        // eleven payments already made on-chain leaves room for exactly one more
+        let last = PaymentPreflight {
+            payment_count: Ok(11),
+            ..funded_preflight(&subscription)
+        };
+        assert!(matches!(
+            evaluate_preflight(&subscription, &last, Utc::now()).unwrap(),
+            ValidationResult::Valid
+        ));
+        let exhausted = PaymentPreflight {
+            payment_count: Ok(12),
+            ..funded_preflight(&subscription)
+        };
+        assert!(matches!(
+            evaluate_preflight(&subscription, &exhausted, Utc::now()).unwrap(),
+            ValidationResult::SubscriptionNotActive
+        ));
+
+        let count_failed = PaymentPreflight {
+            payment_count: Err("call reverted".to_string()),
+            ..funded_preflight(&subscription)
+        };
+        assert!(evaluate_preflight(&subscription, &count_failed, Utc::now()).is_err());
+    }
+
+    #[test]
+    fn test_scheduler_settings_follow_config() {
+        let config = stub_config();
+        let settings = SchedulerSettings::from_config(&config);
+
+        assert_eq!(settings.execution_interval_seconds, 30);
+        assert_eq!(settings.max_executions_per_batch, 10);
+        assert_eq!(settings.max_executions_per_cycle, 1000);
+        assert_eq!(settings.cadence(), "every 30 seconds");
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_scheduler_settings_updates_are_validated() {
+        let settings = SchedulerSettings::from_config(&stub_config());
+
+        let cron = settings
+            .apply(&SchedulerSettingsUpdate {
+                cron: Some("0 */5 * * * *".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(cron.cadence(), "cron '0 */5 * * * *'");
+        assert!(!cron.same_cadence(&settings));
+
+        // an empty cron goes back to the interval
+        let cleared = cron
+            .apply(&SchedulerSettingsUpdate {
+                cron: Some(String::new()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(cleared.same_cadence(&settings));
+
+        for update in [
+            SchedulerSettingsUpdate {
+                cron: Some("every minute".to_string()),
+                ..Default::default()
+            },
+            SchedulerSettingsUpdate {
+                execution_interval_seconds: Some(0),
+                ..Default::default()
+            },
+            SchedulerSettingsUpdate {
+                max_executions_per_cycle: Some(5),
+                ..Default::default()
+            },
+            SchedulerSettingsUpdate {
+                max_retry_attempts: Some(0),
+                ..Default::default()
+            },
+        ] {
+            assert!(settings.apply(&update).is_err(), "{:?}", update);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_settings_handle_notifies_on_change() {
+        let handle = SchedulerSettingsHandle::new(SchedulerSettings::from_config(&stub_config()));
+        let mut receiver = handle.subscribe();
+
+        // a no-op update doesn't wake the job watcher
+        handle.update(&SchedulerSettingsUpdate::default()).unwrap();
+        assert!(!receiver.has_changed().unwrap());
+
+        assert!(handle
+            .update(&SchedulerSettingsUpdate {
+                max_executions_per_batch: Some(5000),
+                ..Default::default()
+            })
+            .is_err());
+        assert!(!receiver.has_changed().unwrap());
+
+        let updated = handle
+            .update(&SchedulerSettingsUpdate {
+                execution_interval_seconds: Some(120),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(receiver.borrow_and_update().execution_interval_seconds, 120);
+        assert_eq!(handle.current(), updated);
+    }
+
+    #[tokio::test]
+    async fn test_cycle_requests_reach_a_running_scheduler() {
+        let handle = SchedulerSettingsHandle::new(SchedulerSettings::from_config(&stub_config()));
+        // nothing is listening without a scheduler
+        assert!(!handle.request_cycle(None));
+
+        let mut requests = handle.subscribe_cycle_requests();
+        assert!(handle.request_cycle(Some("sepolia")));
+        assert!(handle.request_cycle(None));
+        assert_eq!(requests.recv().await.unwrap(), Some("sepolia".to_string()));
+        assert_eq!(requests.recv().await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_processing_lock_keys_are_per_chain() {
+        let sepolia = processing_lock_key(11155111);
+        let base = processing_lock_key(84532);
+
+        assert_ne!(sepolia, base);
+        assert_ne!(sepolia, PROCESSING_LOCK_NAMESPACE);
+        assert_eq!(sepolia >> 32, PROCESSING_LOCK_NAMESPACE);
+    }
+
+    #[tokio::test]
+    async fn test_due_subscriptions_are_paged_per_chain() {
+        let database = crate::database::Database::new("stub").await.unwrap();
+        let queries = database.queries();
+
+        for (index, chain) in ["sepolia", "sepolia", "sepolia", "base"].iter().enumerate() {
+            let mut subscription = create_test_subscription();
+            subscription.id = format!("0x{:064x}", index);
+            subscription.nonce = index as i64;
+            subscription.chain = chain.to_string();
+            subscription.next_payment_due =
+                Utc::now() - chrono::Duration::minutes(10 - index as i64);
+            queries.insert_subscription(&subscription).await.unwrap();
+        }
+
+        let first = queries
+            .get_due_subscriptions_for_chain("sepolia", 2, 0)
+            .await
+            .unwrap();
+        let second = queries
+            .get_due_subscriptions_for_chain("sepolia", 2, 2)
+            .await
+            .unwrap();
+        let ids: Vec<String> = first
+            .iter()
+            .chain(&second)
+            .map(|sub| sub.id.clone())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                format!("0x{:064x}", 0),
+                format!("0x{:064x}", 1),
+                format!("0x{:064x}", 2)
+            ]
+        );
+
+        let base = queries
+            .get_due_subscriptions_for_chain("base", 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(base.len(), 1);
+    }
+
+    #[test]
+    fn test_classify_failure() {
+        assert_eq!(
+            classify_failure(&RelayerError::ContractRevert("bad state".to_string())),
+            FailureClass::ContractRevert
+        );
+        assert_eq!(
+            classify_failure(&RelayerError::TransactionFailed(
+                "execution reverted".to_string()
+            )),
+            FailureClass::ContractRevert
+        );
+        assert_eq!(
+            classify_failure(&RelayerError::TransactionFailed(
+                "nonce too low".to_string()
+            )),
+            FailureClass::ChainError
+        );
+        assert_eq!(
+            classify_failure(&RelayerError::RpcConnectionFailed("timeout".to_string())),
+            FailureClass::ChainError
+        );
+        // the relayer's own failures aren't reverts
+        assert_eq!(
+            classify_failure(&RelayerError::Database(sqlx::Error::RowNotFound)),
+            FailureClass::RelayerError
+        );
+        assert_eq!(
+            classify_failure(&RelayerError::NotFound("subscription".to_string())),
+            FailureClass::RelayerError
+        );
+        assert_eq!(
+            classify_failure(&RelayerError::UnsupportedChain {
+                requested: "mainnet".to_string(),
+                supported: vec!["sepolia".to_string()],
+            }),
+            FailureClass::RelayerError
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failing_subscription_backs_off_then_dead_letters() {
+        let database = crate::database::Database::new("stub").await.unwrap();
+        let queries = Arc::new(database.queries());
+
+        let mut subscription = create_test_subscription();
+        subscription.id = format!("0x{:064x}", 7);
+        subscription.next_payment_due = Utc::now() - chrono::Duration::minutes(5);
+        queries.insert_subscription(&subscription).await.unwrap();
+
+        let failure = PaymentFailure::new(
+            FailureClass::InsufficientBalance,
+            RelayerError::Validation("Insufficient PYUSD balance".to_string()),
+        );
+        handle_subscription_failure_job_safe(&subscription, &failure, &queries, 2)
+            .await
+            .unwrap();
+
+        // backed off, so no longer due even though next_payment_due has passed
+        let due = queries
+            .get_due_subscriptions_for_chain(&subscription.chain, 10, 0)
+            .await
+            .unwrap();
+        assert!(due.is_empty());
+        let state = queries
+            .get_subscription_retry_state(&subscription.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            state.last_failure_class.as_deref(),
+            Some("INSUFFICIENT_BALANCE")
+        );
+        assert!(state.next_retry_at.unwrap() > Utc::now() + chrono::Duration::minutes(59));
+
+        subscription.failure_count = 2;
+        handle_subscription_failure_job_safe(&subscription, &failure, &queries, 2)
+            .await
+            .unwrap();
+
+        let dead = queries.get_dead_letter_subscriptions(10, 0).await.unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].failure_count, 3);
+        assert_eq!(
+            dead[0].last_error.as_deref(),
+            Some("validation error: Insufficient PYUSD balance")
+        );
+
+        assert!(queries
+            .requeue_dead_letter_subscription(&subscription.id)
+            .await
+            .unwrap());
+        assert!(!queries
+            .requeue_dead_letter_subscription(&subscription.id)
+            .await
+            .unwrap());
+        let requeued = queries
+            .get_due_subscriptions_for_chain(&subscription.chain, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(requeued.len(), 1);
+        assert_eq!(requeued[0].failure_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_chain_errors_have_their_own_dead_letter_budget() {
+        let database = crate::database::Database::new("stub").await.unwrap();
+        let queries = Arc::new(database.queries());
+
+        let mut subscription = create_test_subscription();
+        subscription.id = format!("0x{:064x}", 8);
+        queries.insert_subscription(&subscription).await.unwrap();
+
+        let outage = PaymentFailure::new(
+            FailureClass::ChainError,
+            RelayerError::RpcConnectionFailed("connection refused".to_string()),
+        );
+        let max_failures = 2;
+        let budget = max_failures * CHAIN_ERROR_BUDGET_MULTIPLIER;
+        for _ in 0..budget {
+            handle_subscription_failure_job_safe(&subscription, &outage, &queries, max_failures)
+                .await
+                .unwrap();
+        }
+        let stored = queries
+            .get_subscription(&subscription.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, "ACTIVE");
+        assert_eq!(stored.failure_count, 0);
+        let state = queries
+            .get_subscription_retry_state(&subscription.id)
+            .await
+            .unwrap();
+        assert_eq!(state.chain_error_count, budget);
+
+        // a failure the chain actually answered ends the outage streak and uses the normal budget
+        let revert = PaymentFailure::new(
+            FailureClass::ContractRevert,
+            RelayerError::ContractRevert("Payment not due".to_string()),
+        );
+        handle_subscription_failure_job_safe(&subscription, &revert, &queries, max_failures)
+            .await
+            .unwrap();
+        let state = queries
+            .get_subscription_retry_state(&subscription.id)
+            .await
+            .unwrap();
+        assert_eq!(state.chain_error_count, 0);
+        assert!(queries
+            .get_dead_letter_subscriptions(10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // an outage that never ends is still dead-lettered eventually
+        for _ in 0..=budget {
+            handle_subscription_failure_job_safe(&subscription, &outage, &queries, max_failures)
+                .await
+                .unwrap();
+        }
+        let dead = queries.get_dead_letter_subscriptions(10, 0).await.unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].last_failure_class.as_deref(), Some("CHAIN_ERROR"));
+    }
+
+    #[tokio::test]
+    async fn test_payment_failed_webhook_carries_only_the_public_error() {
+        let database = crate::database::Database::new("stub").await.unwrap();
+        let queries = Arc::new(database.queries());
+
+        let mut subscription = create_test_subscription();
+        subscription.id = format!("0x{:064x}", 9);
+        queries.insert_subscription(&subscription).await.unwrap();
+        queries
+            .create_merchant_webhook(
+                &subscription.merchant,
+                "https://93.184.215.14/hooks",
+                "s1",
+                &[],
+            )
+            .await
+            .unwrap();
+
+        let failure = PaymentFailure::new(
+            FailureClass::ChainError,
+            RelayerError::InternalError("pool exhausted on 10.0.3.7:5432".to_string()),
+        );
+        handle_subscription_failure_job_safe(&subscription, &failure, &queries, 2)
+            .await
+            .unwrap();
+
+        let deliveries = queries
+            .get_webhook_deliveries(&subscription.merchant, None, None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(deliveries.len(), 1);
+        let data = &deliveries[0].payload["data"];
+        assert_eq!(data["reason"], "CHAIN_ERROR");
+        assert_eq!(data["error"], "internal server error");
+        // the raw error is still kept for operators
+        let state = queries
+            .get_subscription_retry_state(&subscription.id)
+            .await
+            .unwrap();
+        assert!(state.last_error.unwrap().contains("10.0.3.7"));
+    }
+
+    #[tokio::test]
+    async fn test_preflight_batch_keys_by_subscription() {
+        let config = stub_config();
+        let blockchain_client = Arc::new(BlockchainClient::new(&config).await.unwrap());
+
+        let valid = create_test_subscription();
+        let mut invalid = create_test_subscription();
+        invalid.id = "not-hex".to_string();
+
+        let preflights =
+            preflight_batch_job_safe(&[valid.clone(), invalid.clone()], &blockchain_client).await;
+        assert_eq!(preflights.len(), 1);
+        assert!(preflights.contains_key(&valid.id));
+        assert!(!preflights.contains_key(&invalid.id));
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_check_confirms_and_rolls_back_orphans() {
+        let config = stub_config();
+        let database = crate::database::Database::new("stub").await.unwrap();
+        let queries = Arc::new(database.queries());
+        let blockchain_client = BlockchainClient::new(&config).await.unwrap();
+        let with_depth = |confirmations: u64| {
+            ChainRegistry::new(
+                config
+                    .chains
+                    .iter()
+                    .cloned()
+                    .map(|mut chain| {
+                        chain.confirmations = confirmations;
+                        chain
+                    })
+                    .collect(),
+            )
+            .unwrap()
+        };
+
+        let mut subscription = create_test_subscription();
+        queries.insert_subscription(&subscription).await.unwrap();
+
+        // first payment goes through the stub chain, which keeps a receipt for it
+        let mined = blockchain_client
+            .execute_subscription(
+                subscription_id_to_bytes(&subscription.id).unwrap(),
+                &subscription.chain,
+            )
+            .await
+            .unwrap();
+        let first = ExecutionResult {
+            transaction_hash: mined.transaction_hash,
+            block_number: mined.block_number,
+            block_hash: mined.block_hash,
+            gas_used: mined.gas_used,
+            gas_price: mined.gas_price,
+            fee_paid: U256::from(5_000u64),
+            payment_amount: U256::from(1_000_000u64),
+            payment_number: 1,
+        };
+        record_successful_execution_job_safe(&subscription, &first, &queries)
+            .await
+            .unwrap();
+
+        let summary = check_execution_confirmations(&queries, &blockchain_client, &with_depth(1))
+            .await
+            .unwrap();
+        assert_eq!(summary.confirmed, 1);
+        assert_eq!(summary.orphaned, 0);
+
+        // second payment was never seen by the chain, as if a reorg dropped it
+        subscription = queries
+            .get_subscription(&subscription.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let dropped = ExecutionResult {
+            transaction_hash: H256::repeat_byte(0xdd),
+            payment_number: 2,
+            ..first
+        };
+        record_successful_execution_job_safe(&subscription, &dropped, &queries)
+            .await
+            .unwrap();
+
+        let summary = check_execution_confirmations(&queries, &blockchain_client, &with_depth(0))
+            .await
+            .unwrap();
+        assert_eq!(summary.confirmed, 0);
+        assert_eq!(summary.orphaned, 1);
+
+        let rolled_back = queries
+            .get_subscription(&subscription.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(rolled_back.executed_payments, 1);
+        assert_eq!(rolled_back.total_paid, "1000000");
+        assert_eq!(rolled_back.next_payment_due, subscription.next_payment_due);
+
+        let statuses: Vec<String> = queries
+            .get_executions_by_subscription(&subscription.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|execution| execution.status)
+            .collect();
+        assert_eq!(statuses, vec!["CONFIRMED", "ORPHANED"]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_miss_is_refilled_from_avail() {
+        let database = crate::database::Database::new("stub").await.unwrap();
+        let queries = Arc::new(database.queries());
+        let avail_client = AvailClient::new_stub();
+
+        let intent = crate::api::types::SubscriptionIntent {
+            subscriber: "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266".to_string(),
+            merchant: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+            amount: "1000000".to_string(),
+            interval: 86_400,
+            start_time: 1_700_000_000,
+            max_payments: 12,
+            max_total_amount: "12000000".to_string(),
+            expiry: 1_800_000_000,
+            nonce: 1,
+            token: "0x0000000000000000000000000000000000000000".to_string(),
+        };
+        let signature = format!("0x{}", "11".repeat(65));
+        let subscription_id = crate::api::validation::ValidationService::generate_subscription_id(
+            &intent, &signature,
+        )
+        .unwrap();
+        let leaf = crate::avail::merkle::leaf_hash(&subscription_id).unwrap();
+        let batch = crate::avail::AvailBatch {
+            version: outbox::BATCH_VERSION,
+            merkle_root: crate::avail::merkle::encode_hash(&leaf),
+            leaves: vec![crate::avail::AvailBatchLeaf {
+                subscription_id: subscription_id.clone(),
+                record: avail_client.intent_payload(&intent, &signature, "0x01", 11_155_111),
+            }],
+        };
+        let included = avail_client.submit_batch(&batch).await.unwrap();
+
+        let mut subscription = create_test_subscription();
+        subscription.id = subscription_id.clone();
+        subscription.avail_block_number = Some(included.block_number as i64);
+        subscription.avail_extrinsic_index = Some(included.extrinsic_index as i64);
+        queries.insert_subscription(&subscription).await.unwrap();
+
+        ensure_intent_cached_job_safe(&subscription, &queries, &avail_client)
+            .await
+            .unwrap();
+        let cached = queries
+            .get_cached_intent(&subscription_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.signature, signature);
+        assert_eq!(cached.nonce, 1);
+        assert_eq!(
+            cached.avail_block_number,
+            Some(included.block_number as i64)
+        );
+
+        // an extrinsic avail no longer has is logged and left for the next run
+        let mut missing = create_test_subscription();
+        missing.id = format!("0x{:064x}", 8);
+        missing.avail_block_number = Some(included.block_number as i64 + 1);
+        queries.insert_subscription(&missing).await.unwrap();
+        ensure_intent_cached_job_safe(&missing, &queries, &avail_client)
+            .await
+            .unwrap();
+        assert!(queries
+            .get_cached_intent(&missing.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+// delay before the next retry after `attempts` consecutive failures: base, doubling on every
+// further failure, never more than cap
+pub fn backoff(base: Duration, cap: Duration, attempts: i64) -> Duration {
+    let exponent = attempts.clamp(1, 32) as u32 - 1;
+    base.saturating_mul(2_u32.saturating_pow(exponent)).min(cap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let base = Duration::from_secs(30);
+        let cap = Duration::from_secs(3_600);
+
+        assert_eq!(backoff(base, cap, 0), base);
+        assert_eq!(backoff(base, cap, 1), base);
+        assert_eq!(backoff(base, cap, 2), Duration::from_secs(60));
+        assert_eq!(backoff(base, cap, 5), Duration::from_secs(480));
+        assert_eq!(backoff(base, cap, 20), cap);
+        assert_eq!(backoff(base, cap, i64::MAX), cap);
+    }
+}
@@ -51,6 +51,15 @@ pub fn get_token_symbol(address: &str) -> &'static str {
     }
 }
 
+// PYUSD has 6 decimals; ETH and anything unrecognised are treated as 18
+pub fn get_token_decimals(address: &str) -> u8 {
+    if is_pyusd(address) {
+        6
+    } else {
+        18
+    }
+}
+
 pub fn normalize_token_address(address: &str) -> String {
     canonicalize(address)
 }
@@ -0,0 +1,555 @@
+use crate::database::models::{
+    ClaimedWebhookDelivery, Subscription, WebhookDeliveryStatus, WebhookEventType,
+};
+use crate::database::queries::Queries;
+use crate::error::{RelayerError, Result};
+use crate::utils::backoff::backoff;
+use crate::utils::tokens;
+use chrono::Utc;
+use ethers::core::rand::{thread_rng, RngCore};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tracing::{debug, info, warn};
+use url::{Host, Url};
+use uuid::Uuid;
+
+pub const SIGNATURE_HEADER: &str = "X-Aurum-Signature";
+pub const EVENT_HEADER: &str = "X-Aurum-Event";
+pub const DELIVERY_HEADER: &str = "X-Aurum-Delivery";
+
+const MAX_DELIVERY_ATTEMPTS: i32 = 10;
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(6 * 3_600);
+const DELIVERIES_PER_RUN: i64 = 25;
+const DELIVERY_TIMEOUT_SECONDS: u64 = 10;
+// longer than a delivery can take, so a claimed delivery isn't picked up twice
+const DELIVERY_LEASE_SECONDS: i64 = 60;
+// names that only resolve inside a private network
+const INTERNAL_HOST_SUFFIXES: [&str; 6] = [
+    ".localhost",
+    ".local",
+    ".internal",
+    ".localdomain",
+    ".lan",
+    ".home.arpa",
+];
+
+// queues an event for every webhook the merchant registered for it. never fails the caller:
+// the payment or status change already happened, a missed notification is only logged
+pub async fn publish(queries: &Queries, merchant: &str, event_type: WebhookEventType, data: Value) {
+    let event_id = Uuid::new_v4();
+    let payload = json!({
+        "id": event_id,
+        "type": event_type.as_str(),
+        "createdAt": Utc::now(),
+        "data": data,
+    });
+
+    match queries
+        .enqueue_webhook_deliveries(merchant, event_type, event_id, &payload)
+        .await
+    {
+        Ok(0) => debug!("no webhooks registered for {} on {}", event_type, merchant),
+        Ok(queued) => debug!(
+            "queued {} {} deliveries for merchant {}",
+            queued, event_type, merchant
+        ),
+        Err(e) => warn!(
+            "failed to queue {} webhook for merchant {}: {}",
+            event_type, merchant, e
+        ),
+    }
+}
+
+// fields every subscription event carries
+pub fn subscription_event_data(subscription: &Subscription) -> Value {
+    json!({
+        "subscriptionId": subscription.id,
+        "subscriber": subscription.subscriber,
+        "merchant": subscription.merchant,
+        "chain": subscription.chain,
+        "tokenAddress": subscription.token_address,
+        "tokenSymbol": tokens::get_token_symbol(&subscription.token_address),
+        "amount": subscription.amount,
+        "executedPayments": subscription.executed_payments,
+        "maxPayments": subscription.max_payments,
+    })
+}
+
+// publishes subscription.paused / cancelled / completed for a status the subscription just entered
+pub async fn publish_status_change(
+    queries: &Queries,
+    subscription: &Subscription,
+    event_type: WebhookEventType,
+    transaction_hash: Option<&str>,
+) {
+    let mut data = subscription_event_data(subscription);
+    data["transactionHash"] = json!(transaction_hash);
+    publish(queries, &subscription.merchant, event_type, data).await;
+}
+
+// hex hmac-sha256 of "<timestamp>.<body>"; receivers recompute it and reject stale timestamps
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    thread_rng().fill_bytes(&mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+// whether an address is reachable on the public internet; anything else (loopback, rfc1918,
+// link-local such as the cloud metadata endpoint, cgnat, reserved ranges, and ipv6 addresses
+// wrapping any of them) is off limits
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 198 && (b == 18 || b == 19))
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(mapped));
+            }
+            let segments = ip.segments();
+            let [first, second, ..] = segments;
+            let embedded = |high: u16, low: u16| {
+                let [a, b] = high.to_be_bytes();
+                let [c, d] = low.to_be_bytes();
+                IpAddr::V4(Ipv4Addr::new(a, b, c, d))
+            };
+            // nat64 and ipv4-compatible addresses carry the ipv4 target in the low 32 bits,
+            // 6to4 right after the prefix; they are only as public as that target. ::1 and ::
+            // embed 0.0.0.x, which isn't public either
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] || segments[..6] == [0; 6] {
+                return is_public_ip(embedded(segments[6], segments[7]));
+            }
+            if first == 0x2002 {
+                return is_public_ip(embedded(segments[1], segments[2]));
+            }
+            !(ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                // deprecated site-local
+                || (first & 0xffc0) == 0xfec0
+                // local-use nat64
+                || (first == 0x64 && second == 0xff9b && segments[2] == 0x0001)
+                || (first == 0x2001 && second == 0x0db8))
+        }
+    }
+}
+
+// single-label names and internal suffixes are resolved by the relayer's own resolver, which is
+// exactly what a merchant-supplied url must not reach
+fn is_internal_host_name(name: &str) -> bool {
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+    name == "localhost"
+        || !name.contains('.')
+        || INTERNAL_HOST_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+// resolves a webhook url and refuses it unless every address is public. run at registration and
+// again before every delivery, since the name can be repointed after it was registered
+pub async fn resolve_public_target(url: &Url) -> Result<Vec<SocketAddr>> {
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addresses: Vec<SocketAddr> = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![SocketAddr::new(ip.into(), port)],
+        Some(Host::Ipv6(ip)) => vec![SocketAddr::new(ip.into(), port)],
+        Some(Host::Domain(name)) => {
+            if is_internal_host_name(name) {
+                return Err(RelayerError::Validation(format!(
+                    "webhook host {} is not publicly resolvable",
+                    name
+                )));
+            }
+            tokio::net::lookup_host((name, port))
+                .await
+                .map_err(|e| {
+                    RelayerError::Validation(format!(
+                        "webhook host {} does not resolve: {}",
+                        name, e
+                    ))
+                })?
+                .collect()
+        }
+        None => {
+            return Err(RelayerError::Validation(
+                "webhook url has no host".to_string(),
+            ))
+        }
+    };
+
+    if addresses.is_empty() || addresses.iter().any(|addr| !is_public_ip(addr.ip())) {
+        return Err(RelayerError::Validation(
+            "webhook url must point to a public address".to_string(),
+        ));
+    }
+    Ok(addresses)
+}
+
+// a client pinned to the addresses that were just checked, so the connection can't be sent
+// anywhere else by a second lookup. allow_private_targets skips the check for local development
+async fn delivery_client(url: &str, allow_private_targets: bool) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(DELIVERY_TIMEOUT_SECONDS))
+        .redirect(reqwest::redirect::Policy::none());
+
+    if !allow_private_targets {
+        let url = Url::parse(url)
+            .map_err(|e| RelayerError::Validation(format!("invalid webhook url: {}", e)))?;
+        let addresses = resolve_public_target(&url).await?;
+        if let Some(Host::Domain(name)) = url.host() {
+            builder = builder.resolve_to_addrs(name, &addresses);
+        }
+    }
+
+    builder.build().map_err(|e| {
+        RelayerError::InternalError(format!("failed to build webhook http client: {}", e))
+    })
+}
+
+// sends every due delivery once; returns how many were delivered
+pub async fn deliver_due_webhooks(
+    queries: &Arc<Queries>,
+    allow_private_targets: bool,
+) -> Result<usize> {
+    let deliveries = queries
+        .claim_due_webhook_deliveries(
+            DELIVERIES_PER_RUN,
+            chrono::Duration::seconds(DELIVERY_LEASE_SECONDS),
+        )
+        .await?;
+    if deliveries.is_empty() {
+        return Ok(0);
+    }
+
+    let mut workers = JoinSet::new();
+    for delivery in deliveries {
+        let queries = Arc::clone(queries);
+        workers.spawn(async move { deliver_one(&queries, allow_private_targets, delivery).await });
+    }
+
+    let mut delivered = 0;
+    while let Some(joined) = workers.join_next().await {
+        match joined {
+            Ok(Ok(true)) => delivered += 1,
+            Ok(Ok(false)) => {}
+            Ok(Err(e)) => warn!("failed to record webhook delivery attempt: {}", e),
+            Err(e) => warn!("webhook delivery worker panicked: {}", e),
+        }
+    }
+    Ok(delivered)
+}
+
+async fn deliver_one(
+    queries: &Queries,
+    allow_private_targets: bool,
+    delivery: ClaimedWebhookDelivery,
+) -> Result<bool> {
+    let body = serde_json::to_vec(&delivery.payload)
+        .map_err(|e| RelayerError::InternalError(format!("invalid webhook payload: {}", e)))?;
+    let timestamp = Utc::now().timestamp();
+    let signature = sign_payload(&delivery.secret, timestamp, &body);
+
+    let outcome = match delivery_client(&delivery.url, allow_private_targets).await {
+        Ok(http) => http
+            .post(&delivery.url)
+            .header("content-type", "application/json")
+            .header(EVENT_HEADER, &delivery.event_type)
+            .header(DELIVERY_HEADER, delivery.event_id.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                format!("t={},v1={}", timestamp, signature),
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("request failed: {}", e)),
+        Err(e) => Err(format!("target rejected: {}", e)),
+    };
+
+    let (response_status, error) = match outcome {
+        Ok(response) if response.status().is_success() => {
+            let status = response.status().as_u16();
+            queries
+                .record_webhook_attempt(
+                    delivery.id,
+                    WebhookDeliveryStatus::Delivered,
+                    Some(status as i32),
+                    None,
+                    Utc::now(),
+                )
+                .await?;
+            info!(
+                "delivered {} webhook {} to {} ({})",
+                delivery.event_type, delivery.id, delivery.url, status
+            );
+            return Ok(true);
+        }
+        // the body is never kept: the delivery log is merchant-facing
+        Ok(response) => {
+            let status = response.status().as_u16();
+            (
+                Some(status as i32),
+                format!("endpoint responded {}", status),
+            )
+        }
+        Err(error) => (None, error),
+    };
+
+    let attempts = delivery.attempts + 1;
+    let (status, next_attempt_at) = if attempts >= MAX_DELIVERY_ATTEMPTS {
+        warn!(
+            "giving up on {} webhook {} to {} after {} attempts: {}",
+            delivery.event_type, delivery.id, delivery.url, attempts, error
+        );
+        (WebhookDeliveryStatus::Failed, Utc::now())
+    } else {
+        let delay = backoff(BASE_RETRY_DELAY, MAX_RETRY_DELAY, attempts.into());
+        warn!(
+            "{} webhook {} to {} failed (attempt {}), retrying in {}s: {}",
+            delivery.event_type,
+            delivery.id,
+            delivery.url,
+            attempts,
+            delay.as_secs(),
+            error
+        );
+        (
+            WebhookDeliveryStatus::Pending,
+            Utc::now() + chrono::Duration::seconds(delay.as_secs() as i64),
+        )
+    };
+
+    queries
+        .record_webhook_attempt(
+            delivery.id,
+            status,
+            response_status,
+            Some(&error),
+            next_attempt_at,
+        )
+        .await?;
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    #[test]
+    fn test_sign_payload_matches_reference_hmac() {
+        // openssl: printf '1700000000.{"a":1}' | openssl dgst -sha256 -hmac whsec_test
+        assert_eq!(
+            sign_payload("whsec_test", 1_700_000_000, br#"{"a":1}"#),
+            "38877139021993b830af32feea6e18a8da83eb2f6e49ee50bd9e4cf4ca4d3789"
+        );
+    }
+
+    #[test]
+    fn test_only_public_addresses_are_webhook_targets() {
+        for private in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+            "64:ff9b:1::1",
+            "2002:7f00:1::1",
+            "2002:a00:1::",
+            "::10.0.0.1",
+            "::127.0.0.1",
+            "fec0::1",
+        ] {
+            assert!(!is_public_ip(private.parse().unwrap()), "{}", private);
+        }
+        for public in [
+            "93.184.215.14",
+            "8.8.8.8",
+            "2606:4700::1111",
+            "64:ff9b::808:808",
+            "2002:808:808::1",
+        ] {
+            assert!(is_public_ip(public.parse().unwrap()), "{}", public);
+        }
+
+        assert!(is_internal_host_name("localhost"));
+        assert!(is_internal_host_name("metadata"));
+        assert!(is_internal_host_name("billing.svc.internal."));
+        assert!(is_internal_host_name("printer.LOCAL"));
+        assert!(!is_internal_host_name("hooks.merchant.example"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_internal_targets_without_resolving_them() {
+        for url in [
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]:8080/hook",
+            "http://localhost:3000/hook",
+            "https://vault.internal/hook",
+        ] {
+            let url = Url::parse(url).unwrap();
+            assert!(
+                matches!(
+                    resolve_public_target(&url).await,
+                    Err(RelayerError::Validation(_))
+                ),
+                "{}",
+                url
+            );
+        }
+        let public = Url::parse("https://93.184.215.14/hook").unwrap();
+        assert_eq!(
+            resolve_public_target(&public).await.unwrap(),
+            vec!["93.184.215.14:443".parse().unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_private_target_is_refused_at_delivery() {
+        let database = Database::new("stub").await.unwrap();
+        let queries = Arc::new(database.queries());
+        let merchant = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+
+        let mut server = mockito::Server::new_async().await;
+        let never = server.mock("POST", "/hook").expect(0).create_async().await;
+        let webhook = queries
+            .create_merchant_webhook(merchant, &format!("{}/hook", server.url()), "s1", &[])
+            .await
+            .unwrap();
+        publish(
+            &queries,
+            merchant,
+            WebhookEventType::PaymentExecuted,
+            json!({ "subscriptionId": "0x01" }),
+        )
+        .await;
+
+        assert_eq!(deliver_due_webhooks(&queries, false).await.unwrap(), 0);
+        never.assert_async().await;
+        let log = queries
+            .get_webhook_deliveries(merchant, Some(webhook.id), None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(log[0].status, "PENDING");
+        assert!(log[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .starts_with("target rejected"));
+    }
+
+    #[tokio::test]
+    async fn test_delivers_signed_events_and_retries_failures() {
+        let database = Database::new("stub").await.unwrap();
+        let queries = Arc::new(database.queries());
+        let merchant = "0xAbCdEfabcdefabcdefabcdefabcdefabcdefabcd";
+
+        let mut server = mockito::Server::new_async().await;
+        let ok = server
+            .mock("POST", "/ok")
+            .match_header(EVENT_HEADER, "payment.executed")
+            .match_header(
+                SIGNATURE_HEADER,
+                mockito::Matcher::Regex("^t=[0-9]+,v1=[0-9a-f]{64}$".to_string()),
+            )
+            .with_status(204)
+            .create_async()
+            .await;
+        let failing = server
+            .mock("POST", "/down")
+            .with_status(503)
+            .with_body("maintenance")
+            .create_async()
+            .await;
+
+        let all_events = queries
+            .create_merchant_webhook(merchant, &format!("{}/ok", server.url()), "s1", &[])
+            .await
+            .unwrap();
+        let down = queries
+            .create_merchant_webhook(
+                merchant,
+                &format!("{}/down", server.url()),
+                "s2",
+                &["payment.executed".to_string()],
+            )
+            .await
+            .unwrap();
+        // filtered out by event type
+        queries
+            .create_merchant_webhook(
+                merchant,
+                &format!("{}/paused-only", server.url()),
+                "s3",
+                &["subscription.paused".to_string()],
+            )
+            .await
+            .unwrap();
+
+        publish(
+            &queries,
+            &merchant.to_lowercase(),
+            WebhookEventType::PaymentExecuted,
+            json!({ "subscriptionId": "0x01" }),
+        )
+        .await;
+
+        let delivered = deliver_due_webhooks(&queries, true).await.unwrap();
+        assert_eq!(delivered, 1);
+        ok.assert_async().await;
+        failing.assert_async().await;
+
+        let log = queries
+            .get_webhook_deliveries(merchant, None, None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(log.len(), 2);
+        let sent = log.iter().find(|d| d.webhook_id == all_events.id).unwrap();
+        assert_eq!(sent.status, "DELIVERED");
+        assert_eq!(sent.response_status, Some(204));
+        let retrying = log.iter().find(|d| d.webhook_id == down.id).unwrap();
+        assert_eq!(retrying.status, "PENDING");
+        assert_eq!(retrying.attempts, 1);
+        assert_eq!(retrying.response_status, Some(503));
+        assert_eq!(
+            retrying.last_error.as_deref(),
+            Some("endpoint responded 503")
+        );
+        assert!(retrying.next_attempt_at > Utc::now() + chrono::Duration::seconds(20));
+
+        // the failed delivery isn't due again yet
+        assert_eq!(deliver_due_webhooks(&queries, true).await.unwrap(), 0);
+    }
+}
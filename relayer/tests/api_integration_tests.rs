@@ -2,15 +2,39 @@ use axum::{
     body::Body,
     http::{Request, StatusCode},
 };
+use ethers::abi::{encode, Token};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, H256, U256};
+use ethers::utils::keccak256;
+use relayer::api::auth;
+use relayer::api::client::RelayerApiClient;
+use relayer::api::handlers::{
+    ApiKeyQueryParams, AuditLogQueryParams, DeadLetterQueryParams, ExportQueryParams,
+    TransactionQueryParams, WebhookDeliveryQueryParams,
+};
+use relayer::api::rate_limit::{RateLimitPolicy, RateLimitSettings, RateLimiter};
 use relayer::api::types::*;
+use relayer::avail::{
+    merkle, mock::MOCK_FIRST_BLOCK, AppExtrinsic, AvailBatch, AvailBatchLeaf, AvailIntent,
+    AvailMetadata, AvailPayload, MockFault, Recovery, RecoveryReport,
+};
+use relayer::database::models::{
+    ApiKeyRole, Execution, FailureClass, IntentCache, OutboundTransaction, OutboundTxStatus,
+    PendingOperationKind, Subscription, WebhookEventType,
+};
+use relayer::integrations::envio::PaymentHistoryQuery;
 use relayer::utils::tokens;
-use relayer::{AppState, AvailClient, BlockchainClient, Config, Database, EnvioClient, Metrics};
+use relayer::{
+    AppState, AvailClient, AvailMode, BlockchainClient, ChainRegistry, Config, Database,
+    EnvioClient, Metrics, RelayerError, SchedulerSettings, SchedulerSettingsHandle,
+    SchedulerSettingsUpdate,
+};
 use std::sync::Arc;
 use tower::util::ServiceExt;
 
 // helper function to create test app state
 async fn create_test_app_state() -> Arc<AppState> {
-    let config = Config {
+    let mut config = Config {
         database_url: std::env::var("TEST_DATABASE_URL").unwrap_or_else(|_| "stub".to_string()),
         ethereum_rpc_url: std::env::var("TEST_ETHEREUM_RPC_URL")
             .unwrap_or_else(|_| "stub".to_string()),
@@ -34,15 +58,29 @@ async fn create_test_app_state() -> Arc<AppState> {
         server_port: 3000,
         execution_interval_seconds: 60,
         max_executions_per_batch: 100,
+        scheduler_cron: None,
+        max_executions_per_cycle: 1000,
+        max_retry_attempts: 3,
+        max_failures_before_dead_letter: 3,
+        admin_api_token: None,
+        cors_allowed_origins: Vec::new(),
+        rate_limits: RateLimitSettings::default(),
         max_gas_price_gwei: 100,
         relayer_address: "0x1234567890123456789012345678901234567890".to_string(),
+        avail_mode: AvailMode::Mock,
         avail_rpc_url: Some("stub".to_string()),
         avail_application_id: None,
         envio_graphql_endpoint: None,
         envio_explorer_url: None,
+        avail_batch_window_seconds: 30,
+        avail_batch_max_intents: 100,
         hypersync_url_sepolia: None,
         hypersync_url_base: None,
+        relay_subscription_creation: false,
+        webhook_allow_private_targets: false,
+        chains: ChainRegistry::default(),
     };
+    config.chains = ChainRegistry::new(config.legacy_chains()).unwrap();
 
     tokens::register_pyusd_addresses(&[
         config.pyusd_address_sepolia.clone(),
@@ -68,6 +106,8 @@ async fn create_test_app_state() -> Arc<AppState> {
         .expect("failed to create avail client");
 
     let envio_client = EnvioClient::new_stub();
+    let scheduler_settings = SchedulerSettingsHandle::new(SchedulerSettings::from_config(&config));
+    let rate_limiter = Arc::new(RateLimiter::new(config.rate_limits.clone(), &database));
 
     Arc::new(AppState {
         config,
@@ -77,6 +117,8 @@ async fn create_test_app_state() -> Arc<AppState> {
         envio_client,
         hypersync_client: None,
         metrics: Arc::new(Metrics::new()),
+        scheduler_settings,
+        rate_limiter,
     })
 }
 
@@ -142,7 +184,11 @@ async fn test_submit_intent_success() {
     let intent = create_test_intent();
     let signature = "0x1234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890".to_string();
 
-    let request_body = SubmitIntentRequest { intent, signature };
+    let request_body = SubmitIntentRequest {
+        intent,
+        signature,
+        chain: None,
+    };
 
     let response = app
         .oneshot(
@@ -160,20 +206,91 @@ async fn test_submit_intent_success() {
     assert!(response.status().is_success() || response.status() == StatusCode::BAD_REQUEST);
 }
 
+// signs a SubscriptionIntent with the contract's EIP-712 domain
+fn sign_intent(
+    wallet: &LocalWallet,
+    intent: &SubscriptionIntent,
+    verifying_contract: Address,
+    chain_id: u64,
+) -> String {
+    let struct_hash = keccak256(encode(&[
+        Token::FixedBytes(
+            keccak256(
+                b"SubscriptionIntent(address subscriber,address merchant,uint256 amount,uint256 interval,uint256 startTime,uint256 maxPayments,uint256 maxTotalAmount,uint256 expiry,uint256 nonce,address token)",
+            )
+            .to_vec(),
+        ),
+        Token::Address(intent.subscriber.parse().unwrap()),
+        Token::Address(intent.merchant.parse().unwrap()),
+        Token::Uint(U256::from_dec_str(&intent.amount).unwrap()),
+        Token::Uint(U256::from(intent.interval)),
+        Token::Uint(U256::from(intent.start_time)),
+        Token::Uint(U256::from(intent.max_payments)),
+        Token::Uint(U256::from_dec_str(&intent.max_total_amount).unwrap()),
+        Token::Uint(U256::from(intent.expiry)),
+        Token::Uint(U256::from(intent.nonce)),
+        Token::Address(intent.token.parse().unwrap()),
+    ]));
+    sign_typed_digest(wallet, struct_hash, verifying_contract, chain_id)
+}
+
+fn sign_typed_digest(
+    wallet: &LocalWallet,
+    struct_hash: [u8; 32],
+    verifying_contract: Address,
+    chain_id: u64,
+) -> String {
+    let domain_separator = keccak256(encode(&[
+        Token::FixedBytes(
+            keccak256(
+                b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+            )
+            .to_vec(),
+        ),
+        Token::FixedBytes(keccak256(b"Aurum").to_vec()),
+        Token::FixedBytes(keccak256(b"1").to_vec()),
+        Token::Uint(U256::from(chain_id)),
+        Token::Address(verifying_contract),
+    ]));
+
+    let mut digest_input = b"\x19\x01".to_vec();
+    digest_input.extend_from_slice(&domain_separator);
+    digest_input.extend_from_slice(&struct_hash);
+
+    let signature = wallet
+        .sign_hash(H256::from(keccak256(digest_input)))
+        .unwrap();
+    format!("0x{}", hex::encode(signature.to_vec()))
+}
+
 #[tokio::test]
-async fn test_submit_intent_validation_errors() {
-    let app_state = create_test_app_state().await;
-    let app = relayer::api::ApiServer::create(app_state).await;
+async fn test_submit_intent_relays_subscription_creation() {
+    let mut state = (*create_test_app_state().await).clone();
+    state.config.relay_subscription_creation = true;
+    let app_state = Arc::new(state);
 
-    // test invalid address format
-    let mut invalid_intent = create_test_intent();
-    invalid_intent.subscriber = "invalid_address".to_string();
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        .parse()
+        .unwrap();
+    let mut intent = create_test_intent();
+    intent.subscriber = format!("{:?}", wallet.address());
+
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+    let signature = sign_intent(&wallet, &intent, verifying_contract, chain_id);
 
     let request_body = SubmitIntentRequest {
-        intent: invalid_intent,
-        signature: "0x1234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890".to_string(),
+        intent,
+        signature,
+        chain: None,
     };
 
+    let app = relayer::api::ApiServer::create(app_state.clone()).await;
     let response = app
         .oneshot(
             Request::builder()
@@ -186,199 +303,2956 @@ async fn test_submit_intent_validation_errors() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "PENDING");
+    assert!(json["creationTxHash"].as_str().unwrap().starts_with("0x"));
+
+    let subscription_id = json["subscriptionId"].as_str().unwrap();
+    let queries = app_state.database.queries();
+    let stored = queries
+        .get_subscription(subscription_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.status, "PENDING");
+
+    // the stub chain confirms every transaction it accepted
+    let resolved =
+        relayer::scheduler::confirm_pending_operations(&queries, &app_state.blockchain_client)
+            .await
+            .unwrap();
+    assert_eq!(resolved, 1);
+
+    let stored = queries
+        .get_subscription(subscription_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.status, "ACTIVE");
 }
 
 #[tokio::test]
-async fn test_submit_intent_rejects_unsupported_token() {
-    let app_state = create_test_app_state().await;
-    let app = relayer::api::ApiServer::create(app_state).await;
+async fn test_failed_creation_relay_frees_the_nonce() {
+    let mut state = (*create_test_app_state().await).clone();
+    state.config.relay_subscription_creation = true;
+    let app_state = Arc::new(state);
 
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        .parse()
+        .unwrap();
     let mut intent = create_test_intent();
-    intent.token = "0xdeaddeaddeaddeaddeaddeaddeaddeaddeaddead".to_string();
-    let payload = SubmitIntentRequest {
+    intent.subscriber = format!("{:?}", wallet.address());
+    intent.nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap() as u64;
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+    let signature = sign_intent(&wallet, &intent, verifying_contract, chain_id);
+    let request_body = SubmitIntentRequest {
         intent,
-        signature: "0x1234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890".to_string(),
+        signature,
+        chain: None,
     };
 
-    let response = app
-        .clone()
-        .oneshot(
+    let app = relayer::api::ApiServer::create(app_state.clone()).await;
+    let submit = || {
+        app.clone().oneshot(
             Request::builder()
                 .method("POST")
                 .uri("/api/v1/intent")
                 .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_vec(&payload).unwrap()))
+                .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
                 .unwrap(),
         )
+    };
+
+    assert!(app_state
+        .blockchain_client
+        .revert_next_stub_creation("Token not supported"));
+    let response = submit().await.unwrap();
+    assert!(!response.status().is_success());
+
+    let subscription_id = relayer::api::validation::ValidationService::generate_subscription_id(
+        &request_body.intent,
+        &request_body.signature,
+    )
+    .unwrap();
+    let queries = app_state.database.queries();
+    assert!(queries
+        .get_subscription(&subscription_id)
         .await
-        .unwrap();
+        .unwrap()
+        .is_none());
+    assert!(queries
+        .get_cached_intent(&subscription_id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(queries
+        .get_avail_submission(&subscription_id)
+        .await
+        .unwrap()
+        .is_none());
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    // the contract never consumed the nonce, so the same signed intent goes through now
+    let response = submit().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["subscriptionId"], subscription_id.as_str());
+    assert_eq!(json["status"], "PENDING");
 }
 
 #[tokio::test]
-async fn test_get_subscription_not_found() {
+async fn test_failed_creation_transaction_discards_the_pending_subscription() {
     let app_state = create_test_app_state().await;
-    let app = relayer::api::ApiServer::create(app_state).await;
-
-    let subscription_id = "0x1234567890123456789012345678901234567890123456789012345678901234";
-
-    let response = app
-        .oneshot(
-            Request::builder()
-                .uri(format!("/api/v1/subscription/{}", subscription_id))
-                .body(Body::empty())
-                .unwrap(),
+    let queries = app_state.database.queries();
+    let nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap();
+    let subscription_id = format!("0x{:064x}", nanos);
+    let subscriber = "0x1234567890123456789012345678901234567890";
+    let now = chrono::Utc::now();
+    let subscription = Subscription {
+        id: subscription_id.clone(),
+        subscriber: subscriber.to_string(),
+        merchant: "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd".to_string(),
+        amount: "1000".to_string(),
+        interval_seconds: 3600,
+        start_time: now,
+        max_payments: 5,
+        max_total_amount: "5000".to_string(),
+        expiry: now + chrono::Duration::days(30),
+        nonce: nanos,
+        token_address: "0x0000000000000000000000000000000000000000".to_string(),
+        status: "PENDING".to_string(),
+        executed_payments: 0,
+        total_paid: "0".to_string(),
+        next_payment_due: now,
+        failure_count: 0,
+        chain: "sepolia".to_string(),
+        created_at: now,
+        updated_at: now,
+        avail_block_number: None,
+        avail_extrinsic_index: None,
+    };
+    queries.insert_subscription(&subscription).await.unwrap();
+
+    // a hash the stub chain never handed out, so there is no receipt for it
+    let hash = format!("{:?}", H256::random());
+    queries
+        .insert_pending_operation(
+            &subscription_id,
+            PendingOperationKind::CreateSubscription,
+            "sepolia",
+            &hash,
         )
         .await
         .unwrap();
+    let outbound_id = queries
+        .insert_outbound_transaction(&OutboundTransaction {
+            id: 0,
+            chain: "sepolia".to_string(),
+            from_address: format!("{:?}", Address::random()),
+            to_address: format!("{:?}", Address::random()),
+            nonce: 0,
+            calldata: "0x".to_string(),
+            value: "0".to_string(),
+            gas_limit: "120000".to_string(),
+            max_fee_per_gas: "1".to_string(),
+            max_priority_fee_per_gas: "1".to_string(),
+            transaction_hash: hash.clone(),
+            replaced_hashes: Vec::new(),
+            attempts: 1,
+            status: OutboundTxStatus::Pending.to_string(),
+            purpose: "CREATE_SUBSCRIPTION".to_string(),
+            reference: Some(subscription_id.clone()),
+            block_number: None,
+            error_message: None,
+            created_at: now,
+            updated_at: now,
+        })
+        .await
+        .unwrap();
+    queries
+        .resolve_outbound_transaction(
+            outbound_id,
+            OutboundTxStatus::Failed,
+            None,
+            None,
+            Some("nonce consumed by another transaction"),
+        )
+        .await
+        .unwrap();
+    assert!(queries
+        .is_nonce_used(subscriber, nanos, "sepolia")
+        .await
+        .unwrap());
 
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    relayer::scheduler::confirm_pending_operations(&queries, &app_state.blockchain_client)
+        .await
+        .unwrap();
+
+    assert!(queries
+        .get_subscription(&subscription_id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(!queries
+        .is_nonce_used(subscriber, nanos, "sepolia")
+        .await
+        .unwrap());
 }
 
 #[tokio::test]
-async fn test_get_subscription_invalid_format() {
-    let app_state = create_test_app_state().await;
-    let app = relayer::api::ApiServer::create(app_state).await;
+async fn test_disabled_avail_accepts_intents_without_queueing_them() {
+    let mut state = (*create_test_app_state().await).clone();
+    state.avail_client = AvailClient::disabled();
+    let app_state = Arc::new(state);
 
-    let invalid_id = "invalid_subscription_id";
+    let wallet: LocalWallet = "0x7c852118294e51e653712a81e05800f419141751be58f605c371e15141b007a6"
+        .parse()
+        .unwrap();
+    let mut intent = create_test_intent();
+    intent.subscriber = format!("{:?}", wallet.address());
+    intent.nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap() as u64;
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+    let signature = sign_intent(&wallet, &intent, verifying_contract, chain_id);
+    let request_body = SubmitIntentRequest {
+        intent,
+        signature,
+        chain: None,
+    };
 
+    let app = relayer::api::ApiServer::create(app_state.clone()).await;
     let response = app
         .oneshot(
             Request::builder()
-                .uri(format!("/api/v1/subscription/{}", invalid_id))
-                .body(Body::empty())
+                .method("POST")
+                .uri("/api/v1/intent")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["availStatus"], "DISABLED");
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let subscription_id = json["subscriptionId"].as_str().unwrap();
+    let queries = app_state.database.queries();
+    assert!(queries
+        .get_avail_submission(subscription_id)
+        .await
+        .unwrap()
+        .is_none());
+    let subscription = queries
+        .get_subscription(subscription_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(subscription.avail_block_number.is_none());
+    assert!(matches!(
+        app_state.avail_client.retrieve_payload(1, 0).await,
+        Err(RelayerError::Unavailable(_))
+    ));
 }
 
 #[tokio::test]
-async fn test_merchant_transactions_invalid_address() {
+async fn test_rejected_accepted_intent_write_leaves_nothing_behind() {
     let app_state = create_test_app_state().await;
-    let app = relayer::api::ApiServer::create(app_state).await;
-
-    let invalid_address = "invalid_merchant_address";
+    let queries = app_state.database.queries();
+    // unique per run so a shared postgres doesn't already hold the id
+    let subscription_id = format!(
+        "0x{:064x}",
+        chrono::Utc::now().timestamp_nanos_opt().unwrap()
+    );
+    let now = chrono::Utc::now();
+    let subscription = Subscription {
+        id: subscription_id.clone(),
+        subscriber: "0x1234567890123456789012345678901234567890".to_string(),
+        merchant: "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd".to_string(),
+        amount: "1000".to_string(),
+        interval_seconds: 3600,
+        start_time: now,
+        max_payments: 5,
+        max_total_amount: "5000".to_string(),
+        expiry: now + chrono::Duration::days(30),
+        nonce: 1,
+        token_address: "0x0000000000000000000000000000000000000000".to_string(),
+        status: "ACTIVE".to_string(),
+        executed_payments: 0,
+        total_paid: "0".to_string(),
+        next_payment_due: now,
+        failure_count: 0,
+        chain: "sepolia".to_string(),
+        created_at: now,
+        updated_at: now,
+        avail_block_number: None,
+        avail_extrinsic_index: None,
+    };
+    let intent = IntentCache {
+        id: 0,
+        subscription_intent: serde_json::json!({}),
+        signature: "0x01".to_string(),
+        subscription_id: subscription_id.clone(),
+        subscriber: subscription.subscriber.clone(),
+        merchant: subscription.merchant.clone(),
+        amount: subscription.amount.clone(),
+        interval_seconds: subscription.interval_seconds,
+        start_time: now,
+        max_payments: subscription.max_payments,
+        max_total_amount: subscription.max_total_amount.clone(),
+        expiry: subscription.expiry,
+        nonce: 1,
+        processed: false,
+        created_at: now,
+        processed_at: None,
+        chain: "sepolia".to_string(),
+        avail_block_number: None,
+        avail_extrinsic_index: None,
+    };
+    let payload = serde_json::json!({ "intent": 1 });
 
-    let response = app
-        .oneshot(
-            Request::builder()
-                .uri(format!("/api/v1/merchant/{}/transactions", invalid_address))
-                .body(Body::empty())
-                .unwrap(),
-        )
+    let submission = queries
+        .insert_accepted_intent(&intent, &subscription, Some(&payload))
         .await
+        .unwrap()
         .unwrap();
+    assert_eq!(submission.subscription_id, subscription_id);
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    // the subscription insert fails after the intent was written, which must roll it back
+    let retry = IntentCache {
+        signature: "0x02".to_string(),
+        created_at: now + chrono::Duration::seconds(1),
+        ..intent.clone()
+    };
+    assert!(queries
+        .insert_accepted_intent(&retry, &subscription, Some(&payload))
+        .await
+        .is_err());
+    let cached = queries
+        .get_cached_intent(&subscription_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(cached.signature, "0x01");
 }
 
 #[tokio::test]
-async fn test_merchant_stats_valid_address() {
+async fn test_submit_intent_queues_avail_submission() {
     let app_state = create_test_app_state().await;
-    let app = relayer::api::ApiServer::create(app_state).await;
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        .parse()
+        .unwrap();
+    let mut intent = create_test_intent();
+    intent.subscriber = format!("{:?}", wallet.address());
+    // unique per run so a shared postgres doesn't already hold the nonce
+    intent.nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap() as u64;
 
-    let merchant_address = "0x1234567890123456789012345678901234567890";
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+    let signature = sign_intent(&wallet, &intent, verifying_contract, chain_id);
+    let request_body = SubmitIntentRequest {
+        intent,
+        signature,
+        chain: None,
+    };
 
+    let app = relayer::api::ApiServer::create(app_state.clone()).await;
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .uri(format!("/api/v1/merchant/{}/stats", merchant_address))
-                .body(Body::empty())
+                .method("POST")
+                .uri("/api/v1/intent")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
                 .unwrap(),
         )
         .await
         .unwrap();
-
     assert_eq!(response.status(), StatusCode::OK);
-
     let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
-    let parsed: MerchantStatsResponse = serde_json::from_slice(&body).unwrap();
-
-    assert_eq!(parsed.merchant, merchant_address.to_lowercase());
-    assert_eq!(parsed.total.token_symbol, "TOTAL");
-    assert!(parsed.by_token.is_empty());
+    let accepted: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(accepted["availStatus"], "PENDING");
+    assert!(accepted["availBlock"].is_null());
+    let subscription_id = accepted["subscriptionId"].as_str().unwrap().to_string();
+
+    let get_subscription = || async {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/v1/subscription/{}", subscription_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+    };
+    let pending = get_subscription().await;
+    assert_eq!(pending["availStatus"], "PENDING");
+    assert!(pending["availBlock"].is_null());
+
+    let queries = app_state.database.queries();
+    let submitted =
+        relayer::avail::outbox::submit_pending_intents(&queries, &app_state.avail_client, 100)
+            .await
+            .unwrap();
+    assert!(submitted >= 1);
+
+    let included = get_subscription().await;
+    assert_eq!(included["availStatus"], "SUBMITTED");
+    let block = included["availBlock"].as_i64().unwrap();
+    let cached = queries
+        .get_cached_intent(&subscription_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(cached.avail_block_number, Some(block));
+    assert_eq!(
+        cached.avail_extrinsic_index,
+        included["availExtrinsic"].as_i64()
+    );
 }
 
 #[tokio::test]
-async fn test_cross_chain_attestations_endpoint() {
+async fn test_inclusion_proof_verifies_against_batch_root() {
     let app_state = create_test_app_state().await;
-    let app = relayer::api::ApiServer::create(app_state).await;
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        .parse()
+        .unwrap();
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+    let app = relayer::api::ApiServer::create(app_state.clone()).await;
+
+    let get_proof = |subscription_id: String| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .uri(format!(
+                            "/api/v1/subscription/{}/inclusion-proof",
+                            subscription_id
+                        ))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            )
+        }
+    };
 
-    let subscription_id = "0x1234567890123456789012345678901234567890123456789012345678901234";
+    let mut subscription_ids = Vec::new();
+    for offset in 0..3 {
+        let mut intent = create_test_intent();
+        intent.subscriber = format!("{:?}", wallet.address());
+        intent.nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap() as u64 + offset;
+        let signature = sign_intent(&wallet, &intent, verifying_contract, chain_id);
+        let request_body = SubmitIntentRequest {
+            intent,
+            signature,
+            chain: None,
+        };
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/intent")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let accepted: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        subscription_ids.push(accepted["subscriptionId"].as_str().unwrap().to_string());
+    }
 
-    let response = app
-        .oneshot(
-            Request::builder()
-                .uri(format!("/api/v1/cross-chain/{}", subscription_id))
-                .body(Body::empty())
-                .unwrap(),
-        )
+    // nothing to prove until the batch is posted
+    let (status, body) = get_proof(subscription_ids[0].clone()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NOT_FOUND");
+
+    let queries = app_state.database.queries();
+    relayer::avail::outbox::submit_pending_intents(
+        &queries,
+        &app_state.avail_client,
+        app_state.config.avail_batch_max_intents,
+    )
+    .await
+    .unwrap();
+
+    for subscription_id in subscription_ids {
+        let (status, body) = get_proof(subscription_id.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        let proof: InclusionProofResponse = serde_json::from_value(body).unwrap();
+        assert_eq!(proof.subscription_id, subscription_id);
+
+        let leaf = merkle::leaf_hash(&subscription_id).unwrap();
+        assert_eq!(proof.leaf, merkle::encode_hash(&leaf));
+        let root = merkle::decode_hash(&proof.merkle_root).unwrap();
+        assert!(merkle::verify(&leaf, &proof.proof, &root).unwrap());
+
+        let subscription = queries
+            .get_subscription(&subscription_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            subscription.avail_block_number,
+            Some(proof.avail_block as i64)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_recovery_rebuilds_subscriptions_from_avail_extrinsics() {
+    let app_state = create_test_app_state().await;
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        .parse()
+        .unwrap();
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+    let nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap() as u64;
+
+    // signing_chain_id other than chain_id gives a well-formed intent the contract would refuse
+    let record = |offset: u64, signing_chain_id: u64| {
+        let mut intent = create_test_intent();
+        intent.subscriber = format!("{:?}", wallet.address());
+        intent.nonce = nonce + offset;
+        let signature = sign_intent(&wallet, &intent, verifying_contract, signing_chain_id);
+        let subscription_id =
+            relayer::api::validation::ValidationService::generate_subscription_id(
+                &intent, &signature,
+            )
+            .unwrap();
+        let record = AvailIntent {
+            intent,
+            signature,
+            metadata: AvailMetadata {
+                application_id: "1".to_string(),
+                chain_id,
+                submitted_at: chrono::Utc::now(),
+                relayer: "0x0000000000000000000000000000000000000000".to_string(),
+            },
+        };
+        (subscription_id, record)
+    };
+    let (legacy_id, legacy) = record(0, chain_id);
+    let (batched_id, batched) = record(1, chain_id);
+    let (forged_id, forged) = record(2, chain_id + 1);
+
+    let leaves = [&batched_id, &forged_id]
+        .iter()
+        .map(|id| merkle::leaf_hash(id).unwrap())
+        .collect::<Vec<_>>();
+    let batch = AvailBatch {
+        version: 1,
+        merkle_root: merkle::encode_hash(&merkle::root(&leaves).unwrap()),
+        leaves: vec![
+            AvailBatchLeaf {
+                subscription_id: batched_id.clone(),
+                record: batched,
+            },
+            AvailBatchLeaf {
+                subscription_id: forged_id.clone(),
+                record: forged,
+            },
+        ],
+    };
+    let extrinsics = [
+        AppExtrinsic {
+            block_number: 700,
+            extrinsic_index: 1,
+            data: serde_json::to_vec(&legacy).unwrap(),
+        },
+        AppExtrinsic {
+            block_number: 701,
+            extrinsic_index: 3,
+            data: b"not an intent".to_vec(),
+        },
+        AppExtrinsic {
+            block_number: 702,
+            extrinsic_index: 2,
+            data: serde_json::to_vec(&batch).unwrap(),
+        },
+    ];
+
+    let queries = app_state.database.queries();
+    let recovery = Recovery {
+        queries: &queries,
+        chains: &app_state.config.chains,
+        blockchain_client: None,
+        dry_run: false,
+    };
+    let mut report = RecoveryReport::default();
+    for extrinsic in &extrinsics {
+        recovery
+            .restore_extrinsic(extrinsic, &mut report)
+            .await
+            .unwrap();
+    }
+    assert_eq!(report.extrinsics, 3);
+    assert_eq!(report.undecodable, 1);
+    assert_eq!(report.intents_found, 3);
+    assert_eq!(report.restored, vec![legacy_id.clone(), batched_id.clone()]);
+    assert_eq!(report.rejected, 1);
+    assert!(queries
+        .get_subscription(&forged_id)
         .await
+        .unwrap()
+        .is_none());
+
+    // without a blockchain client nothing confirms the contract ever created it
+    let restored = queries.get_subscription(&legacy_id).await.unwrap().unwrap();
+    assert_eq!(restored.status, "PENDING");
+    assert_eq!(restored.chain, "sepolia");
+    assert_eq!(restored.nonce, nonce as i64);
+    assert_eq!(restored.avail_block_number, Some(700));
+    assert_eq!(restored.avail_extrinsic_index, Some(1));
+    let cached = queries
+        .get_cached_intent(&legacy_id)
+        .await
+        .unwrap()
         .unwrap();
+    assert_eq!(cached.signature, legacy.signature);
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+    // batched intents get their outbox row back, so inclusion proofs still verify
+    let submission = queries
+        .get_avail_submission(&batched_id)
         .await
+        .unwrap()
         .unwrap();
-    let attestations: Vec<CrossChainAttestationResponse> = serde_json::from_slice(&body).unwrap();
-
-    assert!(attestations.is_empty());
+    assert_eq!(submission.leaf_index, Some(0));
+    let proof: Vec<merkle::ProofStep> =
+        serde_json::from_value(submission.merkle_proof.unwrap()).unwrap();
+    let root = merkle::decode_hash(&batch.merkle_root).unwrap();
+    assert!(merkle::verify(&leaves[0], &proof, &root).unwrap());
+    let restored = queries
+        .get_subscription(&batched_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(restored.avail_block_number, Some(702));
+
+    // a second pass over the same blocks changes nothing
+    let mut rerun = RecoveryReport::default();
+    for extrinsic in &extrinsics {
+        recovery
+            .restore_extrinsic(extrinsic, &mut rerun)
+            .await
+            .unwrap();
+    }
+    assert!(rerun.restored.is_empty());
+    assert_eq!(rerun.already_present, 2);
+
+    // the stub contract knows none of them, so nothing comes back once it is asked
+    let fresh = Database::new("stub").await.unwrap().queries();
+    let checked = Recovery {
+        queries: &fresh,
+        chains: &app_state.config.chains,
+        blockchain_client: Some(&app_state.blockchain_client),
+        dry_run: false,
+    };
+    let mut report = RecoveryReport::default();
+    for extrinsic in &extrinsics {
+        checked
+            .restore_extrinsic(extrinsic, &mut report)
+            .await
+            .unwrap();
+    }
+    assert!(report.restored.is_empty());
+    assert_eq!(report.not_on_chain, 2);
+    assert_eq!(report.rejected, 1);
+    assert!(fresh.get_subscription(&legacy_id).await.unwrap().is_none());
+    assert!(fresh
+        .get_avail_submission(&batched_id)
+        .await
+        .unwrap()
+        .is_none());
 }
 
 #[tokio::test]
-async fn test_api_documentation() {
+async fn test_avail_mock_round_trip_through_outbox_proof_and_recovery() {
     let app_state = create_test_app_state().await;
-    let app = relayer::api::ApiServer::create(app_state).await;
+    let mock = app_state.avail_client.mock().unwrap().clone();
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        .parse()
+        .unwrap();
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+    let app = relayer::api::ApiServer::create(app_state.clone()).await;
+
+    let submit = |offset: u64| {
+        let app = app.clone();
+        let mut intent = create_test_intent();
+        intent.subscriber = format!("{:?}", wallet.address());
+        intent.nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap() as u64 + offset;
+        let signature = sign_intent(&wallet, &intent, verifying_contract, chain_id);
+        async move {
+            let request_body = SubmitIntentRequest {
+                intent,
+                signature,
+                chain: None,
+            };
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/api/v1/intent")
+                        .header("content-type", "application/json")
+                        .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let accepted: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            accepted["subscriptionId"].as_str().unwrap().to_string()
+        }
+    };
+    // the endpoint won't vouch for the mock, so the proof is checked the way it would check it
+    let get_proof = |subscription_id: String| {
+        let app_state = app_state.clone();
+        async move {
+            let subscription = app_state
+                .database
+                .queries()
+                .get_subscription(&subscription_id)
+                .await
+                .unwrap()
+                .unwrap();
+            let payload = match app_state
+                .avail_client
+                .retrieve_payload(
+                    subscription.avail_block_number.unwrap() as u64,
+                    subscription.avail_extrinsic_index.unwrap() as u64,
+                )
+                .await
+            {
+                Ok(payload) => Some(payload),
+                Err(RelayerError::NotFound(_)) => None,
+                Err(e) => panic!("unexpected avail error: {}", e),
+            };
+            relayer::api::proof::verify_anchored_intent(
+                &subscription,
+                payload,
+                verifying_contract,
+                chain_id,
+            )
+        }
+    };
+
+    let anchored_id = submit(0).await;
+    let queries = app_state.database.queries();
+    relayer::avail::outbox::submit_pending_intents(&queries, &app_state.avail_client, 100)
+        .await
+        .unwrap();
+    let anchored_extrinsics = mock.extrinsic_count();
+    assert!(anchored_extrinsics >= 1);
 
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .uri("/api/v1/docs")
+                .uri(format!("/api/v1/subscription/{}/proof", anchored_id))
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
 
-    assert_eq!(response.status(), StatusCode::OK);
+    // the batch reads back out of the mock
+    let proof = get_proof(anchored_id.clone()).await;
+    assert_eq!(proof.verdict, ProofVerdict::Verified);
+    assert_eq!(proof.merkle_root_valid, Some(true));
 
-    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+    // a dropped submission leaves the intent queued and nothing on avail
+    let dropped_id = submit(1).await;
+    mock.fail_next_submission(MockFault::Drop);
+    relayer::avail::outbox::submit_pending_intents(&queries, &app_state.avail_client, 100)
         .await
         .unwrap();
-    let body_str = String::from_utf8(body.to_vec()).unwrap();
-
-    assert!(body_str.contains("Aurum Relayer API"));
-    assert!(body_str.contains("/api/v1/intent"));
+    let queued = queries
+        .get_avail_submission(&dropped_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(queued.status, "PENDING");
+    assert_eq!(queued.attempts, 1);
+    assert!(queued.block_number.is_none());
+    assert_eq!(mock.extrinsic_count(), anchored_extrinsics);
+
+    // recovery into an empty database finds everything the mock holds
+    let fresh = Database::new("stub").await.unwrap().queries();
+    let report = Recovery {
+        queries: &fresh,
+        chains: &app_state.config.chains,
+        blockchain_client: None,
+        dry_run: false,
+    }
+    .scan(
+        &app_state.avail_client,
+        MOCK_FIRST_BLOCK,
+        app_state
+            .avail_client
+            .finalized_block_number()
+            .await
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+    assert!(report.restored.contains(&anchored_id));
+    assert!(!report.restored.contains(&dropped_id));
+    let restored = fresh.get_subscription(&anchored_id).await.unwrap().unwrap();
+    assert_eq!(restored.avail_block_number, Some(proof.avail_block as i64));
+
+    // once avail loses the extrinsic there is nothing left to verify against
+    assert!(mock.forget(proof.avail_block, proof.avail_extrinsic));
+    let proof = get_proof(anchored_id).await;
+    assert_eq!(proof.verdict, ProofVerdict::NotAnchored);
 }
 
 #[tokio::test]
-async fn test_cors_headers() {
+async fn test_subscription_proof_compares_avail_intent_with_stored_terms() {
     let app_state = create_test_app_state().await;
-    let app = relayer::api::ApiServer::create(app_state).await;
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        .parse()
+        .unwrap();
+    let mut intent = create_test_intent();
+    intent.subscriber = format!("{:?}", wallet.address());
+    intent.nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap() as u64;
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+    let signature = sign_intent(&wallet, &intent, verifying_contract, chain_id);
+    let request_body = SubmitIntentRequest {
+        intent,
+        signature,
+        chain: None,
+    };
 
+    let app = relayer::api::ApiServer::create(app_state.clone()).await;
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .method("OPTIONS")
-                .uri("/health")
-                .header("Origin", "https://example.com")
-                .header("Access-Control-Request-Method", "GET")
-                .body(Body::empty())
-                .unwrap(),
+                .method("POST")
+                .uri("/api/v1/intent")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let accepted: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let subscription_id = accepted["subscriptionId"].as_str().unwrap().to_string();
+
+    // the mock is the relayer's own memory, so the endpoint refuses to call anything verified
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/subscription/{}/proof", subscription_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let queries = app_state.database.queries();
+    relayer::avail::outbox::submit_pending_intents(&queries, &app_state.avail_client, 100)
+        .await
+        .unwrap();
+    let subscription = queries
+        .get_subscription(&subscription_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let submission = queries
+        .get_avail_submission(&subscription_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let anchored: AvailIntent = serde_json::from_value(submission.payload).unwrap();
+    let batch = AvailBatch {
+        version: 1,
+        merkle_root: merkle::encode_hash(&merkle::leaf_hash(&subscription_id).unwrap()),
+        leaves: vec![AvailBatchLeaf {
+            subscription_id: subscription_id.clone(),
+            record: anchored.clone(),
+        }],
+    };
+
+    let verified = relayer::api::proof::verify_anchored_intent(
+        &subscription,
+        Some(AvailPayload::Batch(batch.clone())),
+        verifying_contract,
+        chain_id,
+    );
+    assert_eq!(verified.verdict, ProofVerdict::Verified);
+    assert_eq!(verified.merkle_root_valid, Some(true));
+    assert!(verified.signature_valid);
+    assert!(verified.subscription_id_matches);
+    assert!(verified.differences.is_empty());
+
+    // the relayer's row no longer matches what the subscriber signed
+    let mut tampered = subscription.clone();
+    tampered.amount = "999".to_string();
+    let mismatch = relayer::api::proof::verify_anchored_intent(
+        &tampered,
+        Some(AvailPayload::Intent(anchored.clone())),
+        verifying_contract,
+        chain_id,
+    );
+    assert_eq!(mismatch.verdict, ProofVerdict::Mismatch);
+    assert!(mismatch.signature_valid);
+    assert_eq!(
+        mismatch.differences,
+        vec![FieldDifference {
+            field: "amount".to_string(),
+            anchored: anchored.intent.amount.clone(),
+            stored: "999".to_string(),
+        }]
+    );
+
+    // terms edited on avail's copy break both the signature and the id
+    let mut forged = anchored.clone();
+    forged.intent.amount = "999".to_string();
+    let forged = relayer::api::proof::verify_anchored_intent(
+        &tampered,
+        Some(AvailPayload::Intent(forged)),
+        verifying_contract,
+        chain_id,
+    );
+    assert_eq!(forged.verdict, ProofVerdict::Mismatch);
+    assert!(!forged.signature_valid);
+    assert!(!forged.subscription_id_matches);
+
+    let mut unrelated = batch;
+    unrelated.leaves[0].subscription_id = format!("0x{}", "11".repeat(32));
+    let missing = relayer::api::proof::verify_anchored_intent(
+        &subscription,
+        Some(AvailPayload::Batch(unrelated)),
+        verifying_contract,
+        chain_id,
+    );
+    assert_eq!(missing.verdict, ProofVerdict::Mismatch);
+    assert_eq!(missing.merkle_root_valid, Some(false));
+}
+
+#[tokio::test]
+async fn test_submit_intent_validation_errors() {
+    let app_state = create_test_app_state().await;
+    let app = relayer::api::ApiServer::create(app_state).await;
+
+    // test invalid address format
+    let mut invalid_intent = create_test_intent();
+    invalid_intent.subscriber = "invalid_address".to_string();
+
+    let request_body = SubmitIntentRequest {
+        intent: invalid_intent,
+        signature: "0x1234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890".to_string(),
+        chain: None,
+    };
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/intent")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_submit_intent_rejects_unsupported_token() {
+    let app_state = create_test_app_state().await;
+    let app = relayer::api::ApiServer::create(app_state).await;
+
+    let mut intent = create_test_intent();
+    intent.token = "0xdeaddeaddeaddeaddeaddeaddeaddeaddeaddead".to_string();
+    let payload = SubmitIntentRequest {
+        intent,
+        signature: "0x1234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890".to_string(),
+        chain: None,
+    };
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/intent")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&payload).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_submit_intent_rejects_unknown_chain() {
+    let app_state = create_test_app_state().await;
+    let app = relayer::api::ApiServer::create(app_state).await;
+
+    for chain in [
+        ChainSelector::Name("optimism".to_string()),
+        ChainSelector::Id(10),
+    ] {
+        let payload = SubmitIntentRequest {
+            intent: create_test_intent(),
+            signature: "0x1234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890".to_string(),
+            chain: Some(chain),
+        };
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/intent")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&payload).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(error["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("unsupported chain"));
+        assert_eq!(error["code"], "UNSUPPORTED_CHAIN");
+        assert_eq!(
+            error["details"]["supportedChains"],
+            serde_json::json!(["sepolia", "base"])
+        );
+    }
+}
+
+#[test]
+fn chain_selector_accepts_name_or_id() {
+    let by_name: ChainSelector = serde_json::from_str("\"base\"").unwrap();
+    assert_eq!(by_name, ChainSelector::Name("base".to_string()));
+
+    let by_id: ChainSelector = serde_json::from_str("84532").unwrap();
+    assert_eq!(by_id, ChainSelector::Id(84532));
+}
+
+#[tokio::test]
+async fn test_get_subscription_not_found() {
+    let app_state = create_test_app_state().await;
+    let app = relayer::api::ApiServer::create(app_state).await;
+
+    let subscription_id = "0x1234567890123456789012345678901234567890123456789012345678901234";
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/subscription/{}", subscription_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+// signs a PauseRequest/ResumeRequest the way SubscriptionManager verifies it
+fn sign_subscription_action(
+    wallet: &LocalWallet,
+    type_string: &str,
+    subscription_id: &str,
+    nonce: u64,
+    verifying_contract: Address,
+    chain_id: u64,
+) -> String {
+    let id_bytes = hex::decode(&subscription_id[2..]).unwrap();
+    let struct_hash = keccak256(encode(&[
+        Token::FixedBytes(keccak256(type_string.as_bytes()).to_vec()),
+        Token::FixedBytes(id_bytes),
+        Token::Uint(U256::from(nonce)),
+    ]));
+    sign_typed_digest(wallet, struct_hash, verifying_contract, chain_id)
+}
+
+async fn post_subscription_action(
+    app_state: Arc<AppState>,
+    subscription_id: &str,
+    action: &str,
+    signature: &str,
+) -> axum::response::Response {
+    let app = relayer::api::ApiServer::create(app_state).await;
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri(format!(
+                "/api/v1/subscription/{}/{}",
+                subscription_id, action
+            ))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_vec(&serde_json::json!({ "signature": signature })).unwrap(),
+            ))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_pause_and_resume_subscription() {
+    let app_state = create_test_app_state().await;
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        .parse()
+        .unwrap();
+    let subscription_id = "0x5555555555555555555555555555555555555555555555555555555555555555";
+
+    app_state
+        .database
+        .queries()
+        .insert_subscription(&Subscription {
+            id: subscription_id.to_string(),
+            subscriber: format!("{:?}", wallet.address()),
+            merchant: "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd".to_string(),
+            amount: "1000".to_string(),
+            interval_seconds: 3600,
+            start_time: chrono::Utc::now(),
+            max_payments: 5,
+            max_total_amount: "5000".to_string(),
+            expiry: chrono::Utc::now() + chrono::Duration::days(30),
+            nonce: 1,
+            token_address: "0x0000000000000000000000000000000000000000".to_string(),
+            status: "ACTIVE".to_string(),
+            executed_payments: 0,
+            total_paid: "0".to_string(),
+            next_payment_due: chrono::Utc::now(),
+            failure_count: 0,
+            chain: "sepolia".to_string(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            avail_block_number: None,
+            avail_extrinsic_index: None,
+        })
+        .await
+        .unwrap();
+
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+
+    // resuming an active subscription is rejected before anything is relayed
+    let resume_signature = sign_subscription_action(
+        &wallet,
+        "ResumeRequest(bytes32 subscriptionId,uint256 nonce)",
+        subscription_id,
+        0,
+        verifying_contract,
+        chain_id,
+    );
+    let response = post_subscription_action(
+        app_state.clone(),
+        subscription_id,
+        "resume",
+        &resume_signature,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error["code"], "INVALID_SUBSCRIPTION_STATE");
+    assert_eq!(error["details"]["status"], "ACTIVE");
+    assert_eq!(error["details"]["action"], "resume");
+
+    // a signature from anyone but the subscriber is rejected
+    let other_wallet: LocalWallet =
+        "0x5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a"
+            .parse()
+            .unwrap();
+    let forged_signature = sign_subscription_action(
+        &other_wallet,
+        "PauseRequest(bytes32 subscriptionId,uint256 nonce)",
+        subscription_id,
+        0,
+        verifying_contract,
+        chain_id,
+    );
+    let response = post_subscription_action(
+        app_state.clone(),
+        subscription_id,
+        "pause",
+        &forged_signature,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error["code"], "SIGNATURE_MISMATCH");
+    assert_eq!(
+        error["details"]["expected"],
+        format!("{:?}", wallet.address())
+    );
+    assert_eq!(
+        error["details"]["recovered"],
+        format!("{:?}", other_wallet.address())
+    );
+
+    // the stub chain reports nonce 0 for every subscriber
+    let pause_signature = sign_subscription_action(
+        &wallet,
+        "PauseRequest(bytes32 subscriptionId,uint256 nonce)",
+        subscription_id,
+        0,
+        verifying_contract,
+        chain_id,
+    );
+    let response = post_subscription_action(
+        app_state.clone(),
+        subscription_id,
+        "pause",
+        &pause_signature,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "PAUSED");
+    assert!(json["transactionHash"].as_str().unwrap().starts_with("0x"));
+
+    let stored = app_state
+        .database
+        .queries()
+        .get_subscription(subscription_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.status, "PAUSED");
+
+    let response = post_subscription_action(
+        app_state.clone(),
+        subscription_id,
+        "resume",
+        &resume_signature,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let stored = app_state
+        .database
+        .queries()
+        .get_subscription(subscription_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.status, "ACTIVE");
+}
+
+#[tokio::test]
+async fn test_subscriber_subscriptions_filters_and_cursor() {
+    let app_state = create_test_app_state().await;
+    let subscriber = "0x1234567890123456789012345678901234567890";
+    let merchant = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+    let other_merchant = "0x9999999999999999999999999999999999999999";
+    let queries = app_state.database.queries();
+    let created = chrono::Utc::now() - chrono::Duration::hours(1);
+
+    // five subscriptions a minute apart; the newest is paused, one belongs to another wallet
+    for i in 0..5i64 {
+        queries
+            .insert_subscription(&Subscription {
+                id: format!("0x{:064x}", i + 1),
+                subscriber: if i == 3 {
+                    "0x5555555555555555555555555555555555555555".to_string()
+                } else {
+                    subscriber.to_string()
+                },
+                merchant: if i == 0 { other_merchant } else { merchant }.to_string(),
+                amount: "1000".to_string(),
+                interval_seconds: 3600,
+                start_time: created,
+                max_payments: 5,
+                max_total_amount: "5000".to_string(),
+                expiry: created + chrono::Duration::days(30),
+                nonce: i + 1,
+                token_address: "0x0000000000000000000000000000000000000000".to_string(),
+                status: if i == 4 { "PAUSED" } else { "ACTIVE" }.to_string(),
+                executed_payments: i,
+                total_paid: (1000 * i).to_string(),
+                next_payment_due: created + chrono::Duration::hours(i),
+                failure_count: 0,
+                chain: "sepolia".to_string(),
+                created_at: created + chrono::Duration::minutes(i),
+                updated_at: created,
+                avail_block_number: None,
+                avail_extrinsic_index: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let list = |query: String| {
+        let app_state = app_state.clone();
+        async move {
+            admin_request(
+                app_state,
+                "GET",
+                &format!("/api/v1/subscriber/{}/subscriptions{}", subscriber, query),
+                None,
+                None,
+            )
+            .await
+        }
+    };
+
+    let (status, first) = list("?limit=2".to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    let ids: Vec<&str> = first["subscriptions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|sub| sub["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec![format!("0x{:064x}", 5), format!("0x{:064x}", 3)]);
+    // paused: no payment is due, but the remaining schedule and budget still show
+    let paused = &first["subscriptions"][0];
+    assert_eq!(paused["status"], "PAUSED");
+    assert!(paused["nextPaymentDue"].is_null());
+    assert_eq!(paused["remainingPayments"], 1);
+    assert_eq!(paused["remainingBudget"], "1000");
+    let active = &first["subscriptions"][1];
+    assert_eq!(active["remainingPayments"], 3);
+    assert_eq!(active["remainingBudget"], "3000");
+    assert_eq!(
+        active["nextPaymentDue"],
+        (created + chrono::Duration::hours(2)).timestamp()
+    );
+
+    let cursor = first["nextCursor"].as_str().unwrap();
+    let (_, second) = list(format!("?limit=2&cursor={}", cursor)).await;
+    assert_eq!(second["count"], 2);
+    assert_eq!(second["subscriptions"][0]["id"], format!("0x{:064x}", 2));
+    assert_eq!(second["subscriptions"][1]["id"], format!("0x{:064x}", 1));
+    assert!(second["nextCursor"].is_null());
+
+    let (_, filtered) = list(format!("?status=active&merchant={}", merchant)).await;
+    assert_eq!(filtered["count"], 2);
+    let (_, filtered) = list("?chain=sepolia&token=0x0&status=PAUSED".to_string()).await;
+    assert_eq!(filtered["count"], 1);
+
+    let (status, body) = list("?status=sleeping".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION_ERROR");
+    let (status, _) = list("?cursor=not-a-cursor".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_merchant_transactions_filters_and_cursor() {
+    let app_state = create_test_app_state().await;
+    let merchant = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+    let base = seed_merchant_executions(&app_state, merchant).await;
+
+    let merchant_key = issue_api_key(&app_state, ApiKeyRole::Merchant, Some(merchant)).await;
+    let list = |query: String| {
+        let app_state = app_state.clone();
+        let merchant_key = merchant_key.clone();
+        async move {
+            admin_request(
+                app_state,
+                "GET",
+                &format!("/api/v1/merchant/{}/transactions?{}", merchant, query),
+                Some(&merchant_key),
+                None,
+            )
+            .await
+        }
+    };
+    let payments = |body: &serde_json::Value| -> Vec<u64> {
+        body["transactions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tx| tx["paymentNumber"].as_u64().unwrap())
+            .collect()
+    };
+
+    let (status, first) = list("source=postgres&size=2".to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(first["dataSource"], "postgres");
+    assert_eq!(payments(&first), vec![5, 4]);
+    assert_eq!(first["count"], 5);
+    // failed attempts are listed but never counted as revenue
+    assert_eq!(first["totalRevenue"], "1200");
+    assert_eq!(first["hasMore"], true);
+    assert_eq!(first["transactions"][0]["status"], "SUCCESS");
+    assert_eq!(first["transactions"][0]["tokenSymbol"], "ETH");
+
+    let cursor = first["nextCursor"].as_str().unwrap();
+    let (_, second) = list(format!("source=postgres&size=2&cursor={}", cursor)).await;
+    assert_eq!(payments(&second), vec![3, 2]);
+    let cursor = second["nextCursor"].as_str().unwrap();
+    let (_, last) = list(format!("source=postgres&size=2&cursor={}", cursor)).await;
+    assert_eq!(payments(&last), vec![1]);
+    assert!(last["nextCursor"].is_null());
+    assert_eq!(last["hasMore"], false);
+
+    let (_, failed) = list("source=postgres&status=failed".to_string()).await;
+    assert_eq!(payments(&failed), vec![3]);
+    let (_, large) = list("source=postgres&min_amount=400".to_string()).await;
+    assert_eq!(payments(&large), vec![5, 4]);
+    let window = format!(
+        "source=postgres&from_time={}&to_time={}",
+        (base + chrono::Duration::minutes(2)).timestamp(),
+        (base + chrono::Duration::minutes(3)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    );
+    let (_, window) = list(window).await;
+    assert_eq!(payments(&window), vec![3, 2]);
+    let (_, scoped) = list(format!(
+        "source=postgres&chain=sepolia&token=0x0&subscription_id=0x{:064x}",
+        2
+    ))
+    .await;
+    assert_eq!(scoped["count"], 0);
+
+    // the default source is the indexer, a stub here
+    let (status, indexed) = list("size=2".to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(indexed["dataSource"], "envio");
+
+    for query in [
+        "source=mongo".to_string(),
+        "source=postgres&use_hypersync=true".to_string(),
+        "source=postgres&status=sleeping".to_string(),
+        "source=postgres&min_amount=lots".to_string(),
+        "source=postgres&from_time=20&to_time=10".to_string(),
+        "source=postgres&from_time=yesterday".to_string(),
+        // an indexer cursor doesn't key postgres rows
+        format!(
+            "source=postgres&cursor={}",
+            hex::encode("1700000000000000:abc")
+        ),
+    ] {
+        let (status, body) = list(query.clone()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+        assert_eq!(body["code"], "VALIDATION_ERROR");
+    }
+}
+
+// one subscription for `merchant` paid five times a minute apart from `base`: payments 4
+// and 5 share a timestamp and 3 failed. another merchant's payment lands at minute 5
+async fn seed_merchant_executions(
+    app_state: &AppState,
+    merchant: &str,
+) -> chrono::DateTime<chrono::Utc> {
+    let queries = app_state.database.queries();
+    let base = chrono::DateTime::from_timestamp(chrono::Utc::now().timestamp() - 3600, 0).unwrap();
+
+    for (i, owner) in [
+        (1i64, merchant),
+        (2, "0x9999999999999999999999999999999999999999"),
+    ] {
+        queries
+            .insert_subscription(&Subscription {
+                id: format!("0x{:064x}", i),
+                subscriber: "0x1234567890123456789012345678901234567890".to_string(),
+                merchant: owner.to_string(),
+                amount: "100".to_string(),
+                interval_seconds: 60,
+                start_time: base,
+                max_payments: 10,
+                max_total_amount: "1000".to_string(),
+                expiry: base + chrono::Duration::days(30),
+                nonce: i,
+                token_address: "0x0000000000000000000000000000000000000000".to_string(),
+                status: "ACTIVE".to_string(),
+                executed_payments: 0,
+                total_paid: "0".to_string(),
+                next_payment_due: base,
+                failure_count: 0,
+                chain: "sepolia".to_string(),
+                created_at: base,
+                updated_at: base,
+                avail_block_number: None,
+                avail_extrinsic_index: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let execution = |subscription: i64, payment: i64, minute: i64, status: &str| Execution {
+        id: 0,
+        subscription_id: format!("0x{:064x}", subscription),
+        relayer_address: "0x3333333333333333333333333333333333333333".to_string(),
+        payment_number: payment,
+        amount_paid: (payment * 100).to_string(),
+        protocol_fee: "1".to_string(),
+        merchant_amount: (payment * 100 - 1).to_string(),
+        transaction_hash: format!("0x{:064x}", subscription * 100 + payment),
+        block_number: payment,
+        gas_used: "21000".to_string(),
+        gas_price: "1".to_string(),
+        status: status.to_string(),
+        error_message: None,
+        executed_at: base + chrono::Duration::minutes(minute),
+        chain: "sepolia".to_string(),
+        nexus_attestation_id: None,
+        nexus_verified: false,
+        nexus_submitted_at: None,
+        token_address: None,
+        block_hash: None,
+        confirmed_at: None,
+    };
+    for (payment, minute, status) in [
+        (1, 1, "CONFIRMED"),
+        (2, 2, "SUCCESS"),
+        (3, 3, "FAILED"),
+        (4, 4, "SUCCESS"),
+        (5, 4, "SUCCESS"),
+    ] {
+        queries
+            .insert_execution(&execution(1, payment, minute, status))
+            .await
+            .unwrap();
+    }
+    queries
+        .insert_execution(&execution(2, 1, 5, "SUCCESS"))
+        .await
+        .unwrap();
+
+    base
+}
+
+#[tokio::test]
+async fn test_merchant_settlement_export_streams_csv_and_jsonl() {
+    let app_state = create_test_app_state().await;
+    let merchant = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+    let base = seed_merchant_executions(&app_state, merchant).await;
+    let merchant_key = issue_api_key(&app_state, ApiKeyRole::Merchant, Some(merchant)).await;
+
+    let export = |query: String| {
+        let app_state = app_state.clone();
+        let merchant_key = merchant_key.clone();
+        async move {
+            let app = relayer::api::ApiServer::create(app_state).await;
+            let request = Request::builder()
+                .uri(format!("/api/v1/merchant/{}/export{}", merchant, query))
+                .header(auth::API_KEY_HEADER, merchant_key)
+                .body(Body::empty())
+                .unwrap();
+            let response = app.oneshot(request).await.unwrap();
+            let status = response.status();
+            let content_type = response
+                .headers()
+                .get("content-type")
+                .map(|value| value.to_str().unwrap().to_string())
+                .unwrap_or_default();
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (
+                status,
+                content_type,
+                String::from_utf8(bytes.to_vec()).unwrap(),
+            )
+        }
+    };
+
+    let (status, content_type, csv) = export(String::new()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(content_type.starts_with("text/csv"));
+    let mut lines = csv.lines();
+    let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+    let column = |name: &str| header.iter().position(|column| *column == name).unwrap();
+    let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+    // settled payments only, newest first
+    let payments: Vec<&str> = rows
+        .iter()
+        .map(|row| row[column("payment_number")])
+        .collect();
+    assert_eq!(payments, vec!["5", "4", "2", "1"]);
+    assert_eq!(rows[0][column("amount_paid")], "500");
+    assert_eq!(rows[0][column("protocol_fee")], "1");
+    assert_eq!(rows[0][column("merchant_amount")], "499");
+    assert_eq!(rows[0][column("token_symbol")], "ETH");
+    assert_eq!(rows[0][column("token_decimals")], "18");
+    assert_eq!(rows[0][column("amount_formatted")], "0.0000000000000005");
+    assert_eq!(rows[3][column("status")], "CONFIRMED");
+
+    let (status, content_type, jsonl) = export(format!(
+        "?format=jsonl&from={}&to={}",
+        (base + chrono::Duration::minutes(2)).timestamp(),
+        (base + chrono::Duration::minutes(4)).timestamp()
+    ))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "application/x-ndjson");
+    let rows: Vec<serde_json::Value> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let payments: Vec<i64> = rows
+        .iter()
+        .map(|row| row["payment_number"].as_i64().unwrap())
+        .collect();
+    assert_eq!(payments, vec![5, 4, 2]);
+    assert_eq!(rows[0]["chain"], "sepolia");
+    assert_eq!(rows[0]["block_number"], 5);
+
+    let (status, _, _) = export("?format=xlsx".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _, _) = export("?from=20&to=10".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_pause_subscription_not_found() {
+    let app_state = create_test_app_state().await;
+    let signature = format!("0x{}", "11".repeat(65));
+
+    let response = post_subscription_action(
+        app_state,
+        "0x1234567890123456789012345678901234567890123456789012345678901234",
+        "pause",
+        &signature,
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+async fn scheduler_settings_request(
+    app_state: Arc<AppState>,
+    method: &str,
+    token: Option<&str>,
+    body: Option<serde_json::Value>,
+) -> (StatusCode, serde_json::Value) {
+    admin_request(app_state, method, "/api/v1/admin/scheduler", token, body).await
+}
+
+async fn admin_request(
+    app_state: Arc<AppState>,
+    method: &str,
+    uri: &str,
+    token: Option<&str>,
+    body: Option<serde_json::Value>,
+) -> (StatusCode, serde_json::Value) {
+    let app = relayer::api::ApiServer::create(app_state).await;
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        request = request.header("authorization", format!("Bearer {}", token));
+    }
+    let body = body
+        .map(|body| Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap_or_else(Body::empty);
+
+    let response = app.oneshot(request.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+// stores a key straight through the queries and returns the plaintext
+async fn issue_api_key(app_state: &AppState, role: ApiKeyRole, merchant: Option<&str>) -> String {
+    let key = auth::generate_api_key();
+    app_state
+        .database
+        .queries()
+        .create_api_key(
+            role,
+            merchant,
+            None,
+            &auth::hash_api_key(&key),
+            &auth::key_prefix(&key),
+        )
+        .await
+        .unwrap();
+    key
+}
+
+#[tokio::test]
+async fn test_api_keys_scope_merchant_routes() {
+    let token = "test-admin-token-0123456789abcdef";
+    let mut state = (*create_test_app_state().await).clone();
+    state.config.admin_api_token = Some(token.to_string());
+    let app_state = Arc::new(state);
+    let merchant = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+    let other_merchant = "0x1234567890123456789012345678901234567890";
+
+    let (status, created) = admin_request(
+        app_state.clone(),
+        "POST",
+        "/api/v1/admin/api-keys",
+        Some(token),
+        Some(serde_json::json!({ "role": "MERCHANT", "merchant": merchant, "label": "shop" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let merchant_key = created["key"].as_str().unwrap().to_string();
+    assert_eq!(created["merchant"], merchant);
+    assert_eq!(
+        created["keyPrefix"],
+        merchant_key[..created["keyPrefix"].as_str().unwrap().len()]
+    );
+
+    // only the digest is stored
+    let stored = app_state
+        .database
+        .queries()
+        .get_api_keys(Some(merchant))
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].key_hash, auth::hash_api_key(&merchant_key));
+    assert_ne!(stored[0].key_hash, merchant_key);
+
+    let stats = |address: &str| format!("/api/v1/merchant/{}/stats", address);
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "GET",
+        &stats(merchant),
+        Some(&merchant_key),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "GET",
+        &stats(other_merchant),
+        Some(&merchant_key),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "FORBIDDEN");
+    let (status, _) = admin_request(app_state.clone(), "GET", &stats(merchant), None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "GET",
+        &stats(merchant),
+        Some("ak_not-a-key"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // merchant keys can't reach the admin routes
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "GET",
+        "/api/v1/admin/api-keys",
+        Some(&merchant_key),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "POST",
+        "/api/v1/admin/api-keys",
+        Some(token),
+        Some(serde_json::json!({ "role": "MERCHANT" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let key_id = created["id"].as_i64().unwrap();
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "DELETE",
+        &format!("/api/v1/admin/api-keys/{}", key_id),
+        Some(token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["keys"].as_array().unwrap().len(), 0);
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "GET",
+        &stats(merchant),
+        Some(&merchant_key),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_rate_limits_analytics_and_intents() {
+    let token = "test-admin-token-0123456789abcdef";
+    let mut state = (*create_test_app_state().await).clone();
+    state.config.admin_api_token = Some(token.to_string());
+    state.rate_limiter = Arc::new(RateLimiter::new(
+        RateLimitSettings {
+            analytics: RateLimitPolicy::new(60, 2),
+            intent_per_subscriber: RateLimitPolicy::new(60, 1),
+            ..RateLimitSettings::default()
+        },
+        &state.database,
+    ));
+    let app_state = Arc::new(state);
+    let merchant = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+    let merchant_key = issue_api_key(&app_state, ApiKeyRole::Merchant, Some(merchant)).await;
+    let stats = format!("/api/v1/merchant/{}/stats", merchant);
+
+    for _ in 0..2 {
+        let (status, _) =
+            admin_request(app_state.clone(), "GET", &stats, Some(&merchant_key), None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let app = relayer::api::ApiServer::create(app_state.clone()).await;
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(&stats)
+                .header("x-api-key", &merchant_key)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["code"], "RATE_LIMITED");
+
+    // admin keys aren't limited
+    for _ in 0..3 {
+        let (status, _) = admin_request(app_state.clone(), "GET", &stats, Some(token), None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    // forged signatures don't touch the subscriber's bucket
+    let forged = serde_json::to_value(SubmitIntentRequest {
+        intent: create_test_intent(),
+        signature: format!("0x{}", "11".repeat(65)),
+        chain: None,
+    })
+    .unwrap();
+    for _ in 0..2 {
+        let (status, body) = admin_request(
+            app_state.clone(),
+            "POST",
+            "/api/v1/intent",
+            None,
+            Some(forged.clone()),
+        )
+        .await;
+        assert_ne!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_ne!(body["code"], "RATE_LIMITED");
+    }
+
+    // signed intents are limited per recovered signer
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        .parse()
+        .unwrap();
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+    let signed = |offset: u64| {
+        let mut intent = create_test_intent();
+        intent.subscriber = format!("{:?}", wallet.address());
+        intent.nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap() as u64 + offset;
+        let signature = sign_intent(&wallet, &intent, verifying_contract, chain_id);
+        serde_json::to_value(SubmitIntentRequest {
+            intent,
+            signature,
+            chain: None,
+        })
+        .unwrap()
+    };
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "POST",
+        "/api/v1/intent",
+        None,
+        Some(signed(0)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "POST",
+        "/api/v1/intent",
+        None,
+        Some(signed(1)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(body["code"], "RATE_LIMITED");
+}
+
+#[tokio::test]
+async fn test_scheduler_settings_admin_endpoint() {
+    let token = "test-admin-token-0123456789abcdef";
+
+    // without ADMIN_API_TOKEN the token is just an unknown api key
+    let unconfigured = create_test_app_state().await;
+    let (status, _) = scheduler_settings_request(unconfigured, "GET", Some(token), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let mut state = (*create_test_app_state().await).clone();
+    state.config.admin_api_token = Some(token.to_string());
+    let app_state = Arc::new(state);
+
+    let (status, _) = scheduler_settings_request(app_state.clone(), "GET", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) =
+        scheduler_settings_request(app_state.clone(), "GET", Some("wrong"), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) =
+        scheduler_settings_request(app_state.clone(), "GET", Some(token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["executionIntervalSeconds"], 60);
+    assert_eq!(body["maxExecutionsPerBatch"], 100);
+
+    let (status, body) = scheduler_settings_request(
+        app_state.clone(),
+        "PUT",
+        Some(token),
+        Some(serde_json::json!({ "cron": "0 */5 * * * *", "maxRetryAttempts": 5 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["cron"], "0 */5 * * * *");
+    assert_eq!(body["maxRetryAttempts"], 5);
+    assert_eq!(app_state.scheduler_settings.current().max_retry_attempts, 5);
+
+    // rejected updates leave the running settings untouched
+    let (status, _) = scheduler_settings_request(
+        app_state.clone(),
+        "PUT",
+        Some(token),
+        Some(serde_json::json!({ "cron": "not a cron", "maxRetryAttempts": 7 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = scheduler_settings_request(
+        app_state.clone(),
+        "PUT",
+        Some(token),
+        Some(serde_json::json!({ "maxExecutionsPerBatch": 0 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(app_state.scheduler_settings.current().max_retry_attempts, 5);
+    assert_eq!(
+        app_state
+            .scheduler_settings
+            .current()
+            .max_executions_per_batch,
+        100
+    );
+}
+
+#[tokio::test]
+async fn test_dead_letter_list_and_requeue() {
+    let token = "test-admin-token-0123456789abcdef";
+    let mut state = (*create_test_app_state().await).clone();
+    state.config.admin_api_token = Some(token.to_string());
+    let app_state = Arc::new(state);
+    let subscription_id = "0x7777777777777777777777777777777777777777777777777777777777777777";
+
+    let queries = app_state.database.queries();
+    queries
+        .insert_subscription(&Subscription {
+            id: subscription_id.to_string(),
+            subscriber: "0x1234567890123456789012345678901234567890".to_string(),
+            merchant: "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd".to_string(),
+            amount: "1000".to_string(),
+            interval_seconds: 3600,
+            start_time: chrono::Utc::now(),
+            max_payments: 5,
+            max_total_amount: "5000".to_string(),
+            expiry: chrono::Utc::now() + chrono::Duration::days(30),
+            nonce: 7,
+            token_address: "0x0000000000000000000000000000000000000000".to_string(),
+            status: "ACTIVE".to_string(),
+            executed_payments: 1,
+            total_paid: "1000".to_string(),
+            next_payment_due: chrono::Utc::now(),
+            failure_count: 8,
+            chain: "sepolia".to_string(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            avail_block_number: None,
+            avail_extrinsic_index: None,
+        })
+        .await
+        .unwrap();
+    queries
+        .dead_letter_subscription(
+            subscription_id,
+            9,
+            0,
+            FailureClass::MissingAllowance,
+            "PYUSD allowance required",
+        )
+        .await
+        .unwrap();
+
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "GET",
+        "/api/v1/admin/dead-letter",
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "GET",
+        "/api/v1/admin/dead-letter?limit=10",
+        Some(token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["count"], 1);
+    assert_eq!(body["subscriptions"][0]["id"], subscription_id);
+    assert_eq!(body["subscriptions"][0]["failureCount"], 9);
+    assert_eq!(
+        body["subscriptions"][0]["lastFailureClass"],
+        "MISSING_ALLOWANCE"
+    );
+    assert_eq!(
+        body["subscriptions"][0]["lastError"],
+        "PYUSD allowance required"
+    );
+
+    let requeue_uri = format!("/api/v1/admin/dead-letter/{}/requeue", subscription_id);
+    let (status, body) =
+        admin_request(app_state.clone(), "POST", &requeue_uri, Some(token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ACTIVE");
+
+    let subscription = queries
+        .get_subscription(subscription_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(subscription.status, "ACTIVE");
+    assert_eq!(subscription.failure_count, 0);
+
+    // only dead-lettered subscriptions can be requeued
+    let (status, _) =
+        admin_request(app_state.clone(), "POST", &requeue_uri, Some(token), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_admin_subscription_and_scheduler_operations_are_audited() {
+    let app_state = create_test_app_state().await;
+    let token = issue_api_key(&app_state, ApiKeyRole::Admin, None).await;
+    let merchant_key = issue_api_key(
+        &app_state,
+        ApiKeyRole::Merchant,
+        Some("0xabcdefabcdefabcdefabcdefabcdefabcdefabcd"),
+    )
+    .await;
+    let subscription_id = "0x8888888888888888888888888888888888888888888888888888888888888888";
+
+    let queries = app_state.database.queries();
+    let due = chrono::Utc::now() - chrono::Duration::minutes(5);
+    queries
+        .insert_subscription(&Subscription {
+            id: subscription_id.to_string(),
+            subscriber: "0x1234567890123456789012345678901234567890".to_string(),
+            merchant: "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd".to_string(),
+            amount: "1000".to_string(),
+            interval_seconds: 3600,
+            start_time: due,
+            max_payments: 5,
+            max_total_amount: "5000".to_string(),
+            expiry: due + chrono::Duration::days(30),
+            nonce: 3,
+            token_address: "0x0000000000000000000000000000000000000000".to_string(),
+            status: "ACTIVE".to_string(),
+            executed_payments: 1,
+            total_paid: "1000".to_string(),
+            next_payment_due: due,
+            failure_count: 0,
+            chain: "sepolia".to_string(),
+            created_at: due,
+            updated_at: due,
+            avail_block_number: None,
+            avail_extrinsic_index: None,
+        })
+        .await
+        .unwrap();
+    let due_ids = || async {
+        queries
+            .get_due_subscriptions_for_chain("sepolia", 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|subscription| subscription.id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(due_ids().await, vec![subscription_id.to_string()]);
+
+    // merchant keys can't reach the admin routes
+    let pause_uri = format!("/api/v1/admin/subscriptions/{}/pause", subscription_id);
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "POST",
+        &pause_uri,
+        Some(&merchant_key),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // a relayer-side pause keeps it out of the scheduler until resumed
+    let (status, body) =
+        admin_request(app_state.clone(), "POST", &pause_uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ACTIVE");
+    assert!(body["relayerPausedAt"].is_string());
+    assert!(due_ids().await.is_empty());
+
+    let resume_uri = format!("/api/v1/admin/subscriptions/{}/resume", subscription_id);
+    let (status, body) =
+        admin_request(app_state.clone(), "POST", &resume_uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["relayerPausedAt"].is_null());
+    assert_eq!(due_ids().await, vec![subscription_id.to_string()]);
+
+    // resetting failures also lifts the retry backoff
+    queries
+        .schedule_subscription_retry(
+            subscription_id,
+            2,
+            0,
+            FailureClass::InsufficientBalance,
+            "Insufficient ETH balance",
+            chrono::Utc::now() + chrono::Duration::hours(1),
+        )
+        .await
+        .unwrap();
+    assert!(due_ids().await.is_empty());
+    let reset_uri = format!(
+        "/api/v1/admin/subscriptions/{}/reset-failures",
+        subscription_id
+    );
+    let (status, body) =
+        admin_request(app_state.clone(), "POST", &reset_uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["failureCount"], 0);
+    assert!(body["nextRetryAt"].is_null());
+    assert_eq!(due_ids().await, vec![subscription_id.to_string()]);
+
+    // the stub chain has no copy of the subscription to sync from
+    let sync_uri = format!("/api/v1/admin/subscriptions/{}/sync", subscription_id);
+    let (status, _) = admin_request(app_state.clone(), "POST", &sync_uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let missing_uri = format!("/api/v1/admin/subscriptions/0x{:064x}/pause", 1);
+    let (status, _) =
+        admin_request(app_state.clone(), "POST", &missing_uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // no scheduler runs in the test process, so the request isn't queued
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "POST",
+        "/api/v1/admin/scheduler/run",
+        Some(&token),
+        Some(serde_json::json!({ "chain": "11155111" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["chains"], serde_json::json!(["sepolia"]));
+    assert_eq!(body["queued"], false);
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "POST",
+        "/api/v1/admin/scheduler/run",
+        Some(&token),
+        Some(serde_json::json!({ "chain": "mainnet" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "GET",
+        "/api/v1/admin/scheduler/locks",
+        Some(&token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let locks = body["locks"].as_array().unwrap();
+    assert_eq!(locks.len(), app_state.config.chains.len());
+    assert!(locks.iter().all(|lock| lock["held"] == false));
+
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "POST",
+        "/api/v1/admin/hypersync/resync",
+        Some(&token),
+        Some(serde_json::json!({ "chain": "sepolia", "fromBlock": 100 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // successful actions only, newest first, attributed to the key that made them
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "GET",
+        "/api/v1/admin/audit-log",
+        Some(&token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let actions: Vec<&str> = body["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            "TRIGGER_SCHEDULER_CYCLE",
+            "RESET_FAILURE_COUNT",
+            "RESUME_SUBSCRIPTION",
+            "PAUSE_SUBSCRIPTION",
+        ]
+    );
+    let reset = &body["entries"][1];
+    assert!(reset["actor"].as_str().unwrap().starts_with("api-key:"));
+    assert_eq!(reset["target"], subscription_id);
+    assert_eq!(reset["details"]["previousFailureCount"], 2);
+
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "GET",
+        "/api/v1/admin/audit-log?action=pause_subscription",
+        Some(&token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["entries"].as_array().unwrap().len(), 1);
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "GET",
+        "/api/v1/admin/audit-log?action=DROP_TABLES",
+        Some(&token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_merchant_webhook_registration_and_delivery_log() {
+    let token = "test-admin-token-0123456789abcdef";
+    let mut state = (*create_test_app_state().await).clone();
+    state.config.admin_api_token = Some(token.to_string());
+    let app_state = Arc::new(state);
+    let merchant = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+    let webhooks_uri = format!("/api/v1/merchant/{}/webhooks", merchant);
+
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "POST",
+        &webhooks_uri,
+        Some(token),
+        Some(serde_json::json!({ "url": "https://93.184.215.14/hooks", "events": ["payment.refunded"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "POST",
+        &webhooks_uri,
+        Some(token),
+        Some(serde_json::json!({ "url": "ftp://merchant.example/hooks" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    for internal in [
+        "http://169.254.169.254/latest/meta-data",
+        "http://10.0.0.5/hooks",
+        "http://localhost:8080/hooks",
+        "https://billing.internal/hooks",
+    ] {
+        let (status, _) = admin_request(
+            app_state.clone(),
+            "POST",
+            &webhooks_uri,
+            Some(token),
+            Some(serde_json::json!({ "url": internal })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", internal);
+    }
+
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "POST",
+        &webhooks_uri,
+        Some(token),
+        Some(serde_json::json!({
+            "url": "https://93.184.215.14/hooks",
+            "events": ["payment.failed", "payment.failed"]
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["secret"].as_str().unwrap().starts_with("whsec_"));
+    assert_eq!(body["events"], serde_json::json!(["payment.failed"]));
+    let webhook_id = body["id"].as_i64().unwrap();
+
+    // the secret is never shown again
+    let (status, body) =
+        admin_request(app_state.clone(), "GET", &webhooks_uri, Some(token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["webhooks"].as_array().unwrap().len(), 1);
+    assert!(body["webhooks"][0].get("secret").is_none());
+
+    let queries = app_state.database.queries();
+    relayer::webhooks::publish(
+        &queries,
+        merchant,
+        WebhookEventType::PaymentFailed,
+        serde_json::json!({ "subscriptionId": "0x01", "reason": "INSUFFICIENT_BALANCE" }),
+    )
+    .await;
+    relayer::webhooks::publish(
+        &queries,
+        merchant,
+        WebhookEventType::PaymentExecuted,
+        serde_json::json!({ "subscriptionId": "0x01" }),
+    )
+    .await;
+
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "GET",
+        &format!("{}/deliveries?status=pending", webhooks_uri),
+        Some(token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["count"], 1);
+    assert_eq!(body["deliveries"][0]["eventType"], "payment.failed");
+    assert_eq!(
+        body["deliveries"][0]["payload"]["data"]["reason"],
+        "INSUFFICIENT_BALANCE"
+    );
+
+    let (status, body) = admin_request(
+        app_state.clone(),
+        "DELETE",
+        &format!("{}/{}", webhooks_uri, webhook_id),
+        Some(token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["webhooks"].as_array().unwrap().is_empty());
+    let (status, _) = admin_request(
+        app_state.clone(),
+        "DELETE",
+        &format!("{}/{}", webhooks_uri, webhook_id),
+        Some(token),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_get_subscription_invalid_format() {
+    let app_state = create_test_app_state().await;
+    let app = relayer::api::ApiServer::create(app_state).await;
+
+    let invalid_id = "invalid_subscription_id";
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/subscription/{}", invalid_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_merchant_transactions_invalid_address() {
+    let app_state = create_test_app_state().await;
+    let admin_key = issue_api_key(&app_state, ApiKeyRole::Admin, None).await;
+    let app = relayer::api::ApiServer::create(app_state).await;
+
+    let invalid_address = "invalid_merchant_address";
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/merchant/{}/transactions", invalid_address))
+                .header("x-api-key", admin_key)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_merchant_stats_valid_address() {
+    let app_state = create_test_app_state().await;
+    let merchant_address = "0x1234567890123456789012345678901234567890";
+    let merchant_key =
+        issue_api_key(&app_state, ApiKeyRole::Merchant, Some(merchant_address)).await;
+    let app = relayer::api::ApiServer::create(app_state).await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/merchant/{}/stats", merchant_address))
+                .header("x-api-key", merchant_key)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let parsed: MerchantStatsResponse = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(parsed.merchant, merchant_address.to_lowercase());
+    assert_eq!(parsed.total.token_symbol, "TOTAL");
+    assert!(parsed.by_token.is_empty());
+}
+
+#[tokio::test]
+async fn test_cross_chain_attestations_endpoint() {
+    let app_state = create_test_app_state().await;
+    let app = relayer::api::ApiServer::create(app_state).await;
+
+    let subscription_id = "0x1234567890123456789012345678901234567890123456789012345678901234";
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/cross-chain/{}", subscription_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let attestations: Vec<CrossChainAttestationResponse> = serde_json::from_slice(&body).unwrap();
+
+    assert!(attestations.is_empty());
+}
+
+#[tokio::test]
+async fn test_api_documentation() {
+    let app_state = create_test_app_state().await;
+    let app = relayer::api::ApiServer::create(app_state).await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/docs")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_str = String::from_utf8(body.to_vec()).unwrap();
+
+    assert!(body_str.contains("Aurum Relayer API"));
+    assert!(body_str.contains("/api/v1/intent"));
+}
+
+#[tokio::test]
+async fn test_typed_client_against_served_api() {
+    let app_state = create_test_app_state().await;
+    let merchant = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+    let merchant_key = issue_api_key(&app_state, ApiKeyRole::Merchant, Some(merchant)).await;
+    let admin_key = issue_api_key(&app_state, ApiKeyRole::Admin, None).await;
+
+    let app = relayer::api::ApiServer::create(app_state).await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    let client = RelayerApiClient::new(&base_url);
+    let document = client.openapi_document().await.unwrap();
+    assert_eq!(document["openapi"], "3.1.0");
+    assert!(document["paths"]["/api/v1/cross-chain/{subscription_id}"]["get"].is_object());
+    assert!(
+        document["components"]["schemas"]["SubscriptionIntent"]["properties"]["token"].is_object()
+    );
+
+    let missing = format!("0x{}", "ab".repeat(32));
+    assert!(matches!(
+        client.get_subscription(&missing).await,
+        Err(RelayerError::NotFound(_))
+    ));
+    assert!(matches!(
+        client.get_merchant_stats(merchant).await,
+        Err(RelayerError::Unauthorized(_))
+    ));
+
+    let client = client.with_api_key(merchant_key);
+    let stats = client.get_merchant_stats(merchant).await.unwrap();
+    assert_eq!(stats.merchant, merchant);
+    let transactions = client
+        .get_merchant_transactions(
+            merchant,
+            &TransactionQueryParams {
+                page: Some(0),
+                size: Some(5),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(transactions.page, 0);
+    assert!(matches!(
+        client
+            .get_merchant_stats("0x1234567890123456789012345678901234567890")
+            .await,
+        Err(RelayerError::Forbidden(_))
+    ));
+    let export = client
+        .export_merchant_settlements(merchant, &ExportQueryParams::default())
+        .await
+        .unwrap();
+    assert!(export.starts_with("execution_id,"));
+    let webhooks = client.list_webhooks(merchant).await.unwrap();
+    assert!(webhooks
+        .webhooks
+        .iter()
+        .all(|hook| hook.merchant == merchant));
+    let deliveries = client
+        .list_webhook_deliveries(merchant, &WebhookDeliveryQueryParams::default())
+        .await
+        .unwrap();
+    assert_eq!(deliveries.count, deliveries.deliveries.len());
+    assert!(matches!(
+        client.delete_webhook(merchant, i64::MAX).await,
+        Err(RelayerError::NotFound(_))
+    ));
+    assert!(matches!(
+        client.get_scheduler_settings().await,
+        Err(RelayerError::Forbidden(_))
+    ));
+
+    let client = RelayerApiClient::new(&base_url).with_api_key(admin_key);
+    assert!(matches!(
+        client.get_subscription_proof(&missing).await,
+        Err(RelayerError::Unavailable(_))
+    ));
+    assert!(matches!(
+        client.get_inclusion_proof(&missing).await,
+        Err(RelayerError::NotFound(_))
+    ));
+    assert!(matches!(
+        client.admin_sync_subscription(&missing).await,
+        Err(RelayerError::NotFound(_))
+    ));
+    assert!(matches!(
+        client.requeue_dead_letter(&missing).await,
+        Err(RelayerError::NotFound(_))
+    ));
+    let settings = client.get_scheduler_settings().await.unwrap();
+    assert_eq!(
+        client
+            .update_scheduler_settings(&SchedulerSettingsUpdate::default())
+            .await
+            .unwrap(),
+        settings
+    );
+    assert!(!client.get_scheduler_locks().await.unwrap().locks.is_empty());
+    client
+        .list_dead_letter(&DeadLetterQueryParams::default())
+        .await
+        .unwrap();
+    let created = client
+        .create_api_key(&CreateApiKeyRequest {
+            role: "MERCHANT".to_string(),
+            merchant: Some(merchant.to_string()),
+            label: Some("typed client".to_string()),
+        })
+        .await
+        .unwrap();
+    assert!(created.key.is_some());
+    let keys = client
+        .list_api_keys(&ApiKeyQueryParams {
+            merchant: Some(merchant.to_string()),
+        })
+        .await
+        .unwrap();
+    assert!(keys.keys.iter().any(|key| key.id == created.id));
+    let keys = client.revoke_api_key(created.id).await.unwrap();
+    assert!(keys.keys.iter().all(|key| key.id != created.id));
+    let audit = client
+        .list_admin_audit_log(&AuditLogQueryParams::default())
+        .await
+        .unwrap();
+    assert!(!audit.entries.is_empty());
+    assert_eq!(client.status().await.unwrap().service, "aurum-relayer");
+    client.metrics_json().await.unwrap();
+    assert!(client.metrics().await.unwrap().contains("# TYPE"));
+    assert!(client
+        .api_documentation()
+        .await
+        .unwrap()
+        .contains("Aurum Relayer API"));
+}
+
+#[tokio::test]
+async fn test_typed_client_decodes_structured_intent_errors() {
+    let app_state = create_test_app_state().await;
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        .parse()
+        .unwrap();
+    let verifying_contract: Address = app_state
+        .config
+        .subscription_manager_address_for_chain("sepolia")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let chain_id = app_state.config.chain("sepolia").unwrap().chain_id;
+    let signed = |intent: SubscriptionIntent| SubmitIntentRequest {
+        signature: sign_intent(&wallet, &intent, verifying_contract, chain_id),
+        intent,
+        chain: None,
+    };
+
+    let app = relayer::api::ApiServer::create(app_state.clone()).await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    let client = RelayerApiClient::new(&base_url);
+
+    let mut intent = create_test_intent();
+    intent.subscriber = format!("{:?}", wallet.address());
+    // unique per run so a shared postgres doesn't already hold the nonce
+    intent.nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap() as u64;
+
+    let mut unsupported = intent.clone();
+    unsupported.token = "0xdeaddeaddeaddeaddeaddeaddeaddeaddeaddead".to_string();
+    match client.submit_intent(&signed(unsupported)).await {
+        Err(RelayerError::UnsupportedToken {
+            token,
+            chain,
+            supported,
+        }) => {
+            assert_eq!(token, "0xdeaddeaddeaddeaddeaddeaddeaddeaddeaddead");
+            assert_eq!(chain, "sepolia");
+            assert_eq!(supported, app_state.config.supported_tokens_sepolia);
+        }
+        other => panic!("expected UNSUPPORTED_TOKEN, got {:?}", other),
+    }
+
+    let mut late = intent.clone();
+    late.start_time = 1;
+    match client.submit_intent(&signed(late)).await {
+        Err(RelayerError::InvalidField { field, .. }) => assert_eq!(field, "startTime"),
+        other => panic!("expected INVALID_FIELD, got {:?}", other),
+    }
+
+    let mut forged = signed(intent.clone());
+    forged.intent.subscriber = "0x1234567890123456789012345678901234567890".to_string();
+    match client.submit_intent(&forged).await {
+        Err(RelayerError::SignatureMismatch {
+            expected,
+            recovered,
+        }) => {
+            assert_eq!(expected, "0x1234567890123456789012345678901234567890");
+            assert_ne!(recovered, expected);
+        }
+        other => panic!("expected SIGNATURE_MISMATCH, got {:?}", other),
+    }
+
+    client.submit_intent(&signed(intent.clone())).await.unwrap();
+    // same nonce, different terms: a new subscription id but a replayed nonce
+    let mut replay = intent.clone();
+    replay.amount = "2000000000000000000".to_string();
+    replay.max_total_amount = "24000000000000000000".to_string();
+    match client.submit_intent(&signed(replay)).await {
+        Err(RelayerError::NonceAlreadyUsed {
+            subscriber,
+            nonce,
+            chain,
+        }) => {
+            assert_eq!(subscriber, intent.subscriber);
+            assert_eq!(nonce, intent.nonce);
+            assert_eq!(chain, "sepolia");
+        }
+        other => panic!("expected NONCE_ALREADY_USED, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_metrics_prometheus_and_json() {
+    let app_state = create_test_app_state().await;
+    let app = relayer::api::ApiServer::create(app_state).await;
+
+    // one request first so the api latency histogram has a series
+    let health = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(health.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/metrics")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let content_type = response.headers()["content-type"].to_str().unwrap();
+    assert!(content_type.starts_with("text/plain; version=0.0.4"));
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_str = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body_str.contains("# TYPE relayer_api_request_duration_seconds histogram"));
+    assert!(body_str.contains(
+        "relayer_api_request_duration_seconds_count{method=\"GET\",route=\"/health\",status=\"200\"} 1"
+    ));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/metrics.json")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let snapshot: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(snapshot["hypersyncQueries"], 0);
+}
+
+#[tokio::test]
+async fn test_cors_headers() {
+    let mut state = (*create_test_app_state().await).clone();
+    state.config.cors_allowed_origins = Config::parse_cors_origins("https://example.com/");
+    let app = relayer::api::ApiServer::create(Arc::new(state)).await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("OPTIONS")
+                .uri("/health")
+                .header("Origin", "https://example.com")
+                .header("Access-Control-Request-Method", "GET")
+                .body(Body::empty())
+                .unwrap(),
         )
         .await
         .unwrap();
 
-    // CORS should be enabled
-    assert!(response
+    // allowed origins are echoed back
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "https://example.com"
+    );
+    assert!(response.headers()["access-control-allow-headers"]
+        .to_str()
+        .unwrap()
+        .contains("x-api-key"));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .header("Origin", "https://evil.example")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert!(!response
         .headers()
         .contains_key("access-control-allow-origin"));
 }
@@ -419,9 +3293,41 @@ async fn test_envio_client_stub_mode() {
     let client = EnvioClient::new_stub();
     assert!(client.health_check().await.unwrap());
     assert!(client
-        .get_merchant_transactions("0x123", 0, 10)
+        .get_merchant_transactions("0x123", &PaymentHistoryQuery::default())
         .await
         .unwrap()
         .transactions
         .is_empty());
 }
+
+#[tokio::test]
+async fn test_sync_checkpoint_rewind_is_audited_with_the_previous_block() {
+    let app_state = create_test_app_state().await;
+    let queries = app_state.database.queries();
+    // a chain id of its own so a shared postgres doesn't interfere
+    let chain_id = 900_000 + (chrono::Utc::now().timestamp_subsec_nanos() % 100_000) as i64;
+    queries.update_sync_metadata(chain_id, 5_000).await.unwrap();
+
+    let (previous, entry) = queries
+        .rewind_sync_metadata(
+            chain_id,
+            99,
+            "admin-test",
+            "testchain",
+            |previous| serde_json::json!({ "fromBlock": 100, "previousSyncedBlock": previous }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(previous, 5_000);
+    assert_eq!(entry.action, "HYPERSYNC_RESYNC");
+    assert_eq!(entry.target.as_deref(), Some("testchain"));
+    assert_eq!(entry.details["previousSyncedBlock"], 5_000);
+    assert_eq!(
+        queries
+            .get_sync_metadata(chain_id)
+            .await
+            .unwrap()
+            .last_synced_block,
+        99
+    );
+}
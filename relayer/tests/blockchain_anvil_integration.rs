@@ -246,6 +246,8 @@ async fn test_blockchain_client_with_anvil() {
         "SUPPORTED_TOKENS_BASE",
         format!("0x0000000000000000000000000000000000000000,{}", pyusd_base),
     );
+    // anvil runs base as chain 8453 rather than the registry default (base sepolia)
+    std::env::set_var("CHAIN_BASE_CHAIN_ID", "8453");
     std::env::set_var("AVAIL_RPC_URL", "stub");
     std::env::remove_var("AVAIL_APPLICATION_ID");
     std::env::remove_var("ENVIO_GRAPHQL_ENDPOINT");
@@ -273,6 +275,7 @@ async fn test_blockchain_client_with_anvil() {
 
     let sepolia_chain_id = client.chain_id("sepolia").expect("chain id");
     assert_eq!(sepolia_chain_id, 11155111u64);
+    assert_eq!(client.chain_id("base").expect("chain id"), 8453u64);
 
     drop(client);
     drop(anvil_sepolia);
@@ -82,6 +82,8 @@ fn sample_execution(subscription: &Subscription) -> Execution {
         nexus_verified: false,
         nexus_submitted_at: None,
         token_address: Some(subscription.token_address.clone()),
+        block_hash: None,
+        confirmed_at: None,
     }
 }
 
@@ -188,7 +190,11 @@ async fn test_queries_against_postgres() {
     assert_eq!(fetched.subscriber, subscription.subscriber);
 
     let nonce_used = queries
-        .is_nonce_used(&subscription.subscriber, subscription.nonce)
+        .is_nonce_used(
+            &subscription.subscriber,
+            subscription.nonce,
+            &subscription.chain,
+        )
         .await
         .expect("nonce check");
     assert!(nonce_used);
@@ -210,6 +216,17 @@ async fn test_queries_against_postgres() {
         .expect("due subscriptions");
     assert_eq!(due_subs.len(), 1);
 
+    let chain_due = queries
+        .get_due_subscriptions_for_chain(&subscription.chain, 10, 0)
+        .await
+        .expect("due subscriptions for chain");
+    assert_eq!(chain_due.len(), 1);
+    let other_chain_due = queries
+        .get_due_subscriptions_for_chain("not-a-chain", 10, 0)
+        .await
+        .expect("due subscriptions for other chain");
+    assert!(other_chain_due.is_empty());
+
     queries
         .increment_payment_count(&subscription.id, "1000")
         .await
@@ -239,6 +256,25 @@ async fn test_queries_against_postgres() {
         .expect("subscription executions");
     assert_eq!(subscription_execs.len(), 1);
 
+    let unconfirmed = queries
+        .get_unconfirmed_executions(&subscription.chain, 10)
+        .await
+        .expect("unconfirmed executions");
+    assert_eq!(unconfirmed.len(), 1);
+
+    queries
+        .update_execution_block(execution_id, 2, "0xblock")
+        .await
+        .expect("update execution block");
+    assert!(queries
+        .confirm_execution(execution_id, 2, "0xblock")
+        .await
+        .expect("confirm execution"));
+    assert!(!queries
+        .orphan_execution(execution_id, "reorg")
+        .await
+        .expect("confirmed executions cannot be orphaned"));
+
     queries
         .update_execution_status(execution_id, "FAILED", Some("manual"))
         .await
@@ -281,6 +317,34 @@ async fn test_queries_against_postgres() {
         .await
         .expect("update subscription after payment");
 
+    let operation_id = queries
+        .insert_pending_operation(
+            &subscription.id,
+            PendingOperationKind::CreateSubscription,
+            &subscription.chain,
+            "0xcreatehash",
+        )
+        .await
+        .expect("insert pending operation");
+
+    let open_operations = queries
+        .get_open_pending_operations(10)
+        .await
+        .expect("open pending operations");
+    assert_eq!(open_operations.len(), 1);
+    assert_eq!(open_operations[0].operation, "CREATE_SUBSCRIPTION");
+
+    queries
+        .resolve_pending_operation(operation_id, PendingOperationStatus::Confirmed, None)
+        .await
+        .expect("resolve pending operation");
+
+    let open_operations = queries
+        .get_open_pending_operations(10)
+        .await
+        .expect("open pending operations after resolve");
+    assert!(open_operations.is_empty());
+
     drop(pool);
 
     pg.stop_db().await.expect("stop postgres");
@@ -1,4 +1,5 @@
-use relayer::integrations::envio::EnvioClient;
+use relayer::database::models::ExecutionFilter;
+use relayer::integrations::envio::{self, EnvioClient, PaymentHistoryQuery};
 use serde_json::json;
 
 #[tokio::test]
@@ -6,13 +7,28 @@ async fn test_envio_client_stub() {
     let client = EnvioClient::new_stub();
     assert!(client.health_check().await.unwrap());
     let result = client
-        .get_merchant_transactions("0xdeadbeef", 0, 10)
+        .get_merchant_transactions(
+            "0xdeadbeef",
+            &PaymentHistoryQuery {
+                limit: 10,
+                ..Default::default()
+            },
+        )
         .await
         .expect("stub call should succeed");
     assert!(result.transactions.is_empty());
     assert_eq!(result.total_count, 0);
 }
 
+#[test]
+fn test_payment_entity_id_matches_the_indexer() {
+    // envio-indexer/src/EventHandlers.ts paymentEntityId
+    assert_eq!(
+        envio::payment_entity_id(11155111, "0x01", 12),
+        "11155111_0x01_12"
+    );
+}
+
 #[tokio::test]
 async fn test_envio_client_remote_queries() {
     if std::net::TcpListener::bind("127.0.0.1:0").is_err() {
@@ -26,10 +42,24 @@ async fn test_envio_client_remote_queries() {
 
     let client = EnvioClient::new(graphql_endpoint, explorer_url).expect("client");
 
+    // filters and the cursor travel as graphql variables; one extra row is asked for
     let mock_transactions = server
         .mock("POST", "/v1/graphql")
         .match_header("content-type", "application/json")
-        .match_body(mockito::Matcher::Any)
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "variables": {
+                "limit": 11,
+                "offset": 0,
+                "totalsWhere": {
+                    "_and": [
+                        { "merchant": { "_eq": "0xmerchant" } },
+                        { "chainId": { "_eq": 11155111 } },
+                        { "subscriptionId": { "_eq": "0x01" } },
+                        { "amount": { "_gte": "50" } }
+                    ]
+                }
+            }
+        })))
         .with_status(200)
         .with_body(
             json!({
@@ -67,13 +97,27 @@ async fn test_envio_client_remote_queries() {
         .create_async()
         .await;
 
+    let query = PaymentHistoryQuery {
+        filter: ExecutionFilter {
+            subscription_id: Some("0x01".to_string()),
+            min_amount: Some(50.into()),
+            ..Default::default()
+        },
+        chain_id: Some(11155111),
+        after: Some((1700000100, "9".to_string())),
+        offset: 0,
+        limit: 10,
+    };
     let result = client
-        .get_merchant_transactions("0xmerchant", 0, 10)
+        .get_merchant_transactions("0xMerchant", &query)
         .await
         .expect("remote call should succeed");
     mock_transactions.assert();
     assert_eq!(result.transactions.len(), 1);
     assert_eq!(result.total_count, 1);
+    assert!(!result.has_more);
+    assert_eq!(result.transactions[0].id, "1");
+    assert_eq!(result.transactions[0].status, "SUCCESS");
     assert_eq!(result.transactions[0].token_symbol, "ETH");
 
     let mock_stats = server
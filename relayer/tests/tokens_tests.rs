@@ -26,6 +26,11 @@ fn test_get_token_symbol_with_registration() {
         tokens::get_token_symbol("0x9999999999999999999999999999999999999999"),
         "UNKNOWN"
     );
+    assert_eq!(
+        tokens::get_token_decimals("0x3333333333333333333333333333333333333333"),
+        6
+    );
+    assert_eq!(tokens::get_token_decimals("0x0"), 18);
 }
 
 #[test]
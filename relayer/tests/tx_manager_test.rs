@@ -0,0 +1,159 @@
+use chrono::Utc;
+use ethers::types::U256;
+use relayer::blockchain::tx_manager::{bump_fees, cap_fees, fee_cap_wei, FeeQuote};
+use relayer::chains::GasPolicy;
+use relayer::database::models::{OutboundTransaction, OutboundTxStatus};
+use relayer::Database;
+
+fn gwei(value: u64) -> U256 {
+    U256::from(value) * U256::exp10(9)
+}
+
+fn sample_outbound(nonce: i64, hash: &str) -> OutboundTransaction {
+    OutboundTransaction {
+        id: 0,
+        chain: "sepolia".to_string(),
+        from_address: "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266".to_string(),
+        to_address: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+        nonce,
+        calldata: "0x".to_string(),
+        value: "0".to_string(),
+        gas_limit: "120000".to_string(),
+        max_fee_per_gas: gwei(20).to_string(),
+        max_priority_fee_per_gas: gwei(1).to_string(),
+        transaction_hash: hash.to_string(),
+        replaced_hashes: Vec::new(),
+        attempts: 1,
+        status: OutboundTxStatus::Pending.to_string(),
+        purpose: "EXECUTE_SUBSCRIPTION".to_string(),
+        reference: None,
+        block_number: None,
+        error_message: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn fees_are_capped_by_max_gas_price() {
+    let policy = GasPolicy::new(50);
+    assert_eq!(fee_cap_wei(&policy), gwei(50));
+
+    let quote = cap_fees(gwei(80), gwei(60), &policy);
+    assert_eq!(quote.max_fee_per_gas, gwei(50));
+    assert_eq!(quote.max_priority_fee_per_gas, gwei(50));
+
+    let quote = cap_fees(gwei(30), gwei(2), &policy);
+    assert_eq!(quote.max_fee_per_gas, gwei(30));
+    assert_eq!(quote.max_priority_fee_per_gas, gwei(2));
+}
+
+#[test]
+fn bumps_stay_under_cap_and_stop_when_no_room() {
+    let policy = GasPolicy::new(50);
+    let previous = FeeQuote {
+        max_fee_per_gas: gwei(20),
+        max_priority_fee_per_gas: gwei(2),
+    };
+
+    // quiet market: +25% over the previous attempt
+    let quiet = cap_fees(gwei(10), gwei(1), &policy);
+    let bumped = bump_fees(&previous, &quiet, &policy).unwrap();
+    assert_eq!(bumped.max_fee_per_gas, gwei(25));
+    assert_eq!(
+        bumped.max_priority_fee_per_gas,
+        U256::from(2_500_000_000u64)
+    );
+
+    // busy market: follow the network if it moved further than the bump
+    let busy = cap_fees(gwei(40), gwei(5), &policy);
+    let bumped = bump_fees(&previous, &busy, &policy).unwrap();
+    assert_eq!(bumped.max_fee_per_gas, gwei(40));
+    assert_eq!(bumped.max_priority_fee_per_gas, gwei(5));
+
+    // already at the cap: a replacement would be rejected, so don't try
+    let at_cap = FeeQuote {
+        max_fee_per_gas: gwei(48),
+        max_priority_fee_per_gas: gwei(2),
+    };
+    assert!(bump_fees(&at_cap, &busy, &policy).is_none());
+}
+
+#[tokio::test]
+async fn outbound_transactions_track_nonces_and_replacements() {
+    let database = Database::new("stub").await.unwrap();
+    let queries = database.queries();
+    let chain = "sepolia";
+    let from = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+
+    assert_eq!(
+        queries.get_max_outbound_nonce(chain, from).await.unwrap(),
+        None
+    );
+
+    let first_id = queries
+        .insert_outbound_transaction(&sample_outbound(7, "0xaaa"))
+        .await
+        .unwrap();
+    assert!(queries
+        .insert_outbound_transaction(&sample_outbound(7, "0xbbb"))
+        .await
+        .is_err());
+
+    queries
+        .record_outbound_replacement(first_id, "0xccc", &gwei(25).to_string(), "2500000000")
+        .await
+        .unwrap();
+
+    let by_old_hash = queries
+        .get_outbound_transaction_by_hash("0xaaa")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_old_hash.id, first_id);
+    assert_eq!(by_old_hash.transaction_hash, "0xccc");
+    assert_eq!(by_old_hash.attempts, 2);
+    assert_eq!(by_old_hash.candidate_hashes(), vec!["0xccc", "0xaaa"]);
+
+    // a failed broadcast frees its nonce
+    let second_id = queries
+        .insert_outbound_transaction(&sample_outbound(8, "0xddd"))
+        .await
+        .unwrap();
+    queries
+        .resolve_outbound_transaction(
+            second_id,
+            OutboundTxStatus::Failed,
+            None,
+            None,
+            Some("rejected"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        queries.get_max_outbound_nonce(chain, from).await.unwrap(),
+        Some(7)
+    );
+
+    let pending = queries
+        .get_pending_outbound_transactions(chain, from)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+
+    queries
+        .resolve_outbound_transaction(
+            first_id,
+            OutboundTxStatus::Confirmed,
+            Some("0xaaa"),
+            Some(42),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(queries
+        .get_pending_outbound_transactions(chain, from)
+        .await
+        .unwrap()
+        .is_empty());
+}